@@ -0,0 +1,22 @@
+//! Checks a capture file (one raw JSON frame per line) against the current response structs.
+//!
+//! Usage: `cargo run --example migrate_capture -- <capture> [<output>]`; when an output path is
+//! given the capture is converted, otherwise it is only checked.
+
+use std::env;
+use std::path::Path;
+
+use binance_connect::futures_usd::migration::{check_capture, migrate_capture, MigrationReport};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input: &str = args
+        .get(1)
+        .expect("usage: migrate_capture <capture> [<output>]");
+    let report: MigrationReport = match args.get(2) {
+        Some(output) => migrate_capture(Path::new(input), Path::new(output), &[]),
+        None => check_capture(Path::new(input)),
+    }
+    .unwrap_or_else(|err| panic!("{}", err));
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
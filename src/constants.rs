@@ -1,8 +1,43 @@
+#[cfg(feature = "native")]
+use once_cell::sync::Lazy;
+#[cfg(feature = "native")]
+use url::Url;
+
 /* --- FUTURES --- */
 /** BASE_URI **/
 pub const BASE_URL_FUTURES: &str = "https://fapi.binance.com";
 pub const WS_URL_FUTURES: &str = "wss://fstream.binance.com";
 pub const BASE_URL_FUTURES_TESTNET: &str = "https://testnet.binancefuture.com";
 pub const WS_URL_FUTURES_TESTNET: &str = "wss://stream.binancefuture.com";
+#[cfg(feature = "native")]
+pub const WS_URL_FUTURES_AUTH: &str = "wss://fstream-auth.binance.com";
+/** PRE-VALIDATED URLS **/
+#[cfg(feature = "native")]
+pub static WS_FUTURES_URL: Lazy<Url> =
+    Lazy::new(|| Url::parse(WS_URL_FUTURES).expect("WS_URL_FUTURES is a valid URL"));
+#[cfg(feature = "native")]
+pub static WS_FUTURES_TESTNET_URL: Lazy<Url> = Lazy::new(|| {
+    Url::parse(WS_URL_FUTURES_TESTNET).expect("WS_URL_FUTURES_TESTNET is a valid URL")
+});
+#[cfg(feature = "native")]
+pub static WS_FUTURES_AUTH_URL: Lazy<Url> =
+    Lazy::new(|| Url::parse(WS_URL_FUTURES_AUTH).expect("WS_URL_FUTURES_AUTH is a valid URL"));
 /** ENDPOINTS **/
+#[cfg(feature = "native")]
 pub const FUTURES_LISTEN_KEY: &str = "/fapi/v1/listenKey";
+#[cfg(feature = "native")]
+pub const FUTURES_POSITION_RISK: &str = "/fapi/v2/positionRisk";
+#[cfg(feature = "native")]
+pub const FUTURES_OPEN_ORDERS: &str = "/fapi/v1/openOrders";
+#[cfg(feature = "native")]
+pub const FUTURES_ACCOUNT: &str = "/fapi/v2/account";
+#[cfg(feature = "native")]
+pub const FUTURES_LEVERAGE: &str = "/fapi/v1/leverage";
+#[cfg(feature = "native")]
+pub const FUTURES_MARGIN_TYPE: &str = "/fapi/v1/marginType";
+pub const FUTURES_EXCHANGE_INFO: &str = "/fapi/v1/exchangeInfo";
+pub const FUTURES_TIME: &str = "/fapi/v1/time";
+#[cfg(feature = "native")]
+pub const FUTURES_OPEN_INTEREST: &str = "/fapi/v1/openInterest";
+#[cfg(feature = "native")]
+pub const FUTURES_FUNDING_RATE: &str = "/fapi/v1/fundingRate";
@@ -1,53 +1,152 @@
 use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::response::BinanceErrorMessage;
+use serde_json::Value;
 use std::sync::mpsc::SendError;
+use std::time::Duration;
 use thiserror::Error;
 use url::ParseError;
 
+/// The maximum number of payload characters kept in a `BinanceConnectError::JsonError`.
+pub const PAYLOAD_SNIPPET_LEN: usize = 256;
+
 /// Holder for the various errors that can occur when interacting with the futures_usd crate.
 #[derive(Error, Debug)]
 pub enum BinanceConnectError {
     #[error("Url Parse error: {0}")]
     UrlParseError(ParseError),
+    /// The error is boxed, a `tungstenite::Error` is far larger than the other variants.
+    #[cfg(feature = "native")]
     #[error("Socket error: {0}")]
-    SocketError(tungstenite::Error),
+    SocketError(Box<tungstenite::Error>),
+    #[error("Connection closed: {code} {reason}")]
+    ConnectionClosed { code: u16, reason: String },
+    #[error("Stale connection: no message received for {0:?}")]
+    StaleConnection(Duration),
+    #[error("Pong timeout: no pong received for {0:?}")]
+    PongTimeout(Duration),
+    #[error("Listen key expired: the user data stream is renewed with a fresh listen key")]
+    ListenKeyExpired,
+    /// The unsent event is boxed, an `Event` is far larger than the other variants.
     #[error("Mpsc send error: {0}")]
-    MpscSendError(SendError<Event>),
-    #[error("JSON error: {0}")]
-    JsonError(serde_json::Error),
+    MpscSendError(Box<SendError<Event>>),
+    #[error(
+        "JSON error: {source} (event type: {event_type:?}, stream: {stream:?}, payload: {payload})"
+    )]
+    JsonError {
+        source: serde_json::Error,
+        /// The event type (`e` field) of the payload, if it could be determined.
+        event_type: Option<String>,
+        /// The stream name of the payload when received in combined mode.
+        stream: Option<String>,
+        /// The payload, truncated to `PAYLOAD_SNIPPET_LEN` characters.
+        payload: String,
+    },
+    #[cfg(feature = "native")]
     #[error("HTTP error: {0}")]
     HttpError(reqwest::Error),
     #[error("HTTP Response error: {0}")]
     HttpResponseError(String),
+    #[error("IO error: {0}")]
+    IoError(std::io::Error),
+    #[error("Filter violation on {symbol} {filter}: {reason}")]
+    FilterViolation {
+        symbol: String,
+        filter: String,
+        reason: String,
+    },
+    #[error("Invalid {stream} stream configuration: {reason}")]
+    InvalidStreamConfig { stream: String, reason: String },
+    #[error("Invalid configuration setting {key}: {reason}")]
+    InvalidConfig { key: String, reason: String },
+    #[error("Exchange error {code}: {msg}")]
+    ExchangeError { code: i64, msg: String },
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl From<BinanceErrorMessage> for BinanceConnectError {
+    fn from(err: BinanceErrorMessage) -> Self {
+        BinanceConnectError::ExchangeError {
+            code: err.code,
+            msg: err.msg,
+        }
+    }
+}
+
 impl From<ParseError> for BinanceConnectError {
     fn from(err: ParseError) -> Self {
         BinanceConnectError::UrlParseError(err)
     }
 }
 
+#[cfg(feature = "native")]
 impl From<tungstenite::Error> for BinanceConnectError {
     fn from(err: tungstenite::Error) -> Self {
-        BinanceConnectError::SocketError(err)
+        BinanceConnectError::SocketError(Box::new(err))
     }
 }
 
 impl From<serde_json::Error> for BinanceConnectError {
     fn from(err: serde_json::Error) -> Self {
-        BinanceConnectError::JsonError(err)
+        BinanceConnectError::JsonError {
+            source: err,
+            event_type: None,
+            stream: None,
+            payload: String::new(),
+        }
     }
 }
 
+#[cfg(feature = "native")]
 impl From<reqwest::Error> for BinanceConnectError {
     fn from(err: reqwest::Error) -> Self {
         BinanceConnectError::HttpError(err)
     }
 }
 
+impl From<std::io::Error> for BinanceConnectError {
+    fn from(err: std::io::Error) -> Self {
+        BinanceConnectError::IoError(err)
+    }
+}
+
 impl From<SendError<Event>> for BinanceConnectError {
     fn from(err: SendError<Event>) -> Self {
-        BinanceConnectError::MpscSendError(err)
+        BinanceConnectError::MpscSendError(Box::new(err))
+    }
+}
+
+impl BinanceConnectError {
+    /// Creates a `BinanceConnectError::JsonError` with the context of the payload that failed to
+    /// deserialize: the guessed event type, the stream name (combined mode) and a payload snippet.
+    pub fn json_error(err: serde_json::Error, payload: &str) -> BinanceConnectError {
+        let value: Option<Value> = serde_json::from_str(payload).ok();
+        // Combined mode wraps the event as `{"stream": .., "data": ..}`
+        let stream: Option<String> = value
+            .as_ref()
+            .and_then(|value| value.get("stream"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let event_type: Option<String> = value
+            .as_ref()
+            .map(|value| value.get("data").unwrap_or(value))
+            .map(|data| data.get(0).unwrap_or(data))
+            .and_then(|data| data.get("e"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        BinanceConnectError::JsonError {
+            source: err,
+            event_type,
+            stream,
+            payload: payload_snippet(payload),
+        }
+    }
+}
+
+/// Truncates the payload to `PAYLOAD_SNIPPET_LEN` characters.
+fn payload_snippet(payload: &str) -> String {
+    match payload.char_indices().nth(PAYLOAD_SNIPPET_LEN) {
+        Some((index, _)) => format!("{}...", &payload[..index]),
+        None => payload.to_string(),
     }
 }
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::MarkPriceUpdate;
+
+/// A condition evaluated against the incoming events of its symbol.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum AlertCondition {
+    /// The last price of the symbol, from the aggregate trades and (mini) tickers, crosses the
+    /// price in either direction.
+    PriceCrosses { symbol: String, price: f64 },
+    /// The funding rate of the symbol, from the mark price updates, rises above the rate.
+    FundingRateAbove { symbol: String, funding_rate: f64 },
+    /// The absolute spread between the mark and index price of the symbol, relative to the index
+    /// price (e.g. `0.001` for 0.1%), rises above the spread.
+    MarkIndexSpreadAbove { symbol: String, spread: f64 },
+}
+
+impl AlertCondition {
+    /// Returns the symbol the condition is evaluated for.
+    pub fn symbol(&self) -> &str {
+        match self {
+            AlertCondition::PriceCrosses { symbol, .. } => symbol,
+            AlertCondition::FundingRateAbove { symbol, .. } => symbol,
+            AlertCondition::MarkIndexSpreadAbove { symbol, .. } => symbol,
+        }
+    }
+
+    /// Returns the condition with the symbol in upper case, as it is received.
+    fn normalized(mut self) -> AlertCondition {
+        match &mut self {
+            AlertCondition::PriceCrosses { symbol, .. }
+            | AlertCondition::FundingRateAbove { symbol, .. }
+            | AlertCondition::MarkIndexSpreadAbove { symbol, .. } => {
+                *symbol = symbol.to_uppercase()
+            }
+        }
+        self
+    }
+}
+
+/// Raised when a registered condition is triggered.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Alert {
+    /// The id of the condition, returned by `AlertEngine::register`.
+    pub id: usize,
+    pub condition: AlertCondition,
+    /// The value that triggered the condition: the price, funding rate or relative spread.
+    pub value: f64,
+    /// A flag indicating whether the value is above the threshold of the condition, the direction
+    /// of a crossed price.
+    pub above: bool,
+    /// The event time (UNIX millis) of the triggering event.
+    pub event_time: i64,
+}
+
+/// A registered condition with the last evaluated side of its threshold.
+#[derive(Debug, Clone)]
+struct Registration {
+    condition: AlertCondition,
+    /// Whether the value was above the threshold, `None` until the first value is evaluated.
+    #[cfg(feature = "market-data")]
+    above: Option<bool>,
+}
+
+/// Evaluates the registered conditions against the incoming events and raises an `Alert` whenever
+/// one is triggered.
+///
+/// The conditions are edge-triggered: a price alert is raised every time the price crosses, the
+/// funding rate and spread alerts once the value rises above the threshold, after which they are
+/// re-armed when the value falls back. A crossed price requires a previous price, the first price
+/// received only sets the side.
+#[derive(Debug, Clone, Default)]
+pub struct AlertEngine {
+    registrations: Vec<Registration>,
+}
+
+impl AlertEngine {
+    /// Creates an engine without conditions.
+    pub fn new() -> AlertEngine {
+        Self::default()
+    }
+
+    /// Registers the condition, see `register`.
+    ///
+    /// # Arguments
+    ///
+    /// - `condition`: The condition to evaluate.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the condition registered.
+    ///
+    pub fn with_condition(mut self, condition: AlertCondition) -> Self {
+        self.register(condition);
+        self
+    }
+
+    /// Registers the condition.
+    ///
+    /// # Arguments
+    ///
+    /// - `condition`: The condition to evaluate.
+    ///
+    /// # Returns
+    ///
+    /// The id of the condition, set on the alerts it raises.
+    ///
+    pub fn register(&mut self, condition: AlertCondition) -> usize {
+        self.registrations.push(Registration {
+            condition: condition.normalized(),
+            #[cfg(feature = "market-data")]
+            above: None,
+        });
+        self.registrations.len() - 1
+    }
+
+    /// Returns the registered conditions, indexed by their id.
+    pub fn conditions(&self) -> Vec<AlertCondition> {
+        self.registrations
+            .iter()
+            .map(|registration| registration.condition.clone())
+            .collect()
+    }
+
+    /// Evaluates the conditions against the event, events without a price, funding rate or
+    /// spread are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// - `event`: The event, aggregate trades, (mini) tickers and mark price updates are evaluated.
+    ///
+    /// # Returns
+    ///
+    /// The alerts raised by the event.
+    ///
+    pub fn observe(&mut self, event: &Event) -> Vec<Alert> {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::AggTradeEvent(agg_trade) => self.price(
+                &agg_trade.symbol,
+                agg_trade.price,
+                agg_trade.event_time as i64,
+            ),
+            #[cfg(feature = "market-data")]
+            Event::MiniTickerEvent(mini_ticker) => self.price(
+                &mini_ticker.symbol,
+                mini_ticker.close_price,
+                mini_ticker.event_time as i64,
+            ),
+            #[cfg(feature = "market-data")]
+            Event::MiniTickersEvent(mini_tickers) => mini_tickers
+                .data
+                .iter()
+                .flat_map(|mini_ticker| {
+                    self.price(
+                        &mini_ticker.symbol,
+                        mini_ticker.close_price,
+                        mini_ticker.event_time as i64,
+                    )
+                })
+                .collect(),
+            #[cfg(feature = "market-data")]
+            Event::TickerEvent(ticker) => {
+                self.price(&ticker.symbol, ticker.last_price, ticker.event_time as i64)
+            }
+            #[cfg(feature = "market-data")]
+            Event::TickersEvent(tickers) => tickers
+                .data
+                .iter()
+                .flat_map(|ticker| {
+                    self.price(&ticker.symbol, ticker.last_price, ticker.event_time as i64)
+                })
+                .collect(),
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdateEvent(mark_price_update) => self.mark_price(mark_price_update),
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdatesEvent(mark_price_updates) => mark_price_updates
+                .data
+                .iter()
+                .flat_map(|mark_price_update| self.mark_price(mark_price_update))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Evaluates the price conditions of the symbol.
+    #[cfg(feature = "market-data")]
+    fn price(&mut self, symbol: &str, price: f64, event_time: i64) -> Vec<Alert> {
+        self.evaluate(symbol, event_time, |condition| match condition {
+            AlertCondition::PriceCrosses {
+                price: threshold, ..
+            } => Some((price, price >= *threshold)),
+            _ => None,
+        })
+    }
+
+    /// Evaluates the funding rate and spread conditions of the symbol.
+    #[cfg(feature = "market-data")]
+    fn mark_price(&mut self, mark_price_update: &MarkPriceUpdate) -> Vec<Alert> {
+        let spread: f64 = match mark_price_update.index_price {
+            index_price if index_price > 0.0 => {
+                (mark_price_update.mark_price - index_price).abs() / index_price
+            }
+            _ => 0.0,
+        };
+        self.evaluate(
+            &mark_price_update.symbol,
+            mark_price_update.event_time,
+            |condition| match condition {
+                AlertCondition::FundingRateAbove { funding_rate, .. } => Some((
+                    mark_price_update.funding_rate,
+                    mark_price_update.funding_rate > *funding_rate,
+                )),
+                AlertCondition::MarkIndexSpreadAbove {
+                    spread: threshold, ..
+                } => Some((spread, spread > *threshold)),
+                _ => None,
+            },
+        )
+    }
+
+    /// Evaluates the conditions of the symbol for which `value` returns the value and whether it
+    /// is above the threshold.
+    #[cfg(feature = "market-data")]
+    fn evaluate(
+        &mut self,
+        symbol: &str,
+        event_time: i64,
+        value: impl Fn(&AlertCondition) -> Option<(f64, bool)>,
+    ) -> Vec<Alert> {
+        let mut alerts: Vec<Alert> = Vec::new();
+        for (id, registration) in self.registrations.iter_mut().enumerate() {
+            if registration.condition.symbol() != symbol {
+                continue;
+            }
+            let (value, above): (f64, bool) = match value(&registration.condition) {
+                Some(value) => value,
+                None => continue,
+            };
+            let triggered: bool = match (&registration.condition, registration.above) {
+                (AlertCondition::PriceCrosses { .. }, previous) => {
+                    previous.map_or(false, |previous| previous != above)
+                }
+                (_, previous) => above && previous != Some(true),
+            };
+            registration.above = Some(above);
+            if triggered {
+                alerts.push(Alert {
+                    id,
+                    condition: registration.condition.clone(),
+                    value,
+                    above,
+                    event_time,
+                });
+            }
+        }
+        alerts
+    }
+}
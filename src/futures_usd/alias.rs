@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "native")]
+use serde_json::Value;
+
+/// Maps in-house instrument identifiers to Binance symbols and back.
+///
+/// When set on the `FuturesWebSocketConfig`, aliases can be used instead of Binance symbols in
+/// the stream builders, and the symbols of emitted events are replaced by their alias, so no
+/// translation is needed around the call sites.
+///
+/// The symbols are replaced at a single point of the frame processing: once a frame passed the
+/// filters of the subscriptions (multi-symbol and closed-only kline streams), which match the
+/// Binance symbols. Everything after it sees the aliases: the fill callback, the event script,
+/// the latest-value cache, the recorder, the state store, the trackers, the event filter, the
+/// sinks and the receiver. Only the raw JSON of `with_raw_json` keeps the payload as received.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolAliases {
+    /// The Binance symbol (uppercase) per alias (uppercase).
+    to_binance: HashMap<String, String>,
+    /// The alias (as registered) per Binance symbol (uppercase).
+    to_alias: HashMap<String, String>,
+    /// The symbol fields (`"s":"BTCUSDT"`) of the payloads and their aliased replacement.
+    #[cfg(feature = "native")]
+    replacements: Vec<(String, String)>,
+}
+
+impl SymbolAliases {
+    /// Creates a new instance without aliases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an alias for a Binance symbol, replacing an earlier alias of the symbol.
+    ///
+    /// # Arguments
+    ///
+    /// - `alias`: The in-house identifier, e.g. `INST-42`. Aliases are case-insensitive.
+    /// - `symbol`: The Binance symbol, e.g. `BTCUSDT`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the alias added.
+    ///
+    pub fn with_alias(mut self, alias: &str, symbol: &str) -> Self {
+        let symbol: String = symbol.to_uppercase();
+        if let Some(previous) = self.to_alias.insert(symbol.clone(), alias.to_string()) {
+            self.to_binance.remove(&previous.to_uppercase());
+        }
+        self.to_binance.insert(alias.to_uppercase(), symbol);
+        #[cfg(feature = "native")]
+        {
+            self.replacements = self
+                .to_alias
+                .iter()
+                .map(|(symbol, alias)| {
+                    (
+                        format!("\"s\":\"{}\"", symbol),
+                        format!("\"s\":{}", Value::String(alias.clone())),
+                    )
+                })
+                .collect();
+        }
+        self
+    }
+
+    /// Returns the Binance symbol of the alias, or the input when it is not an alias.
+    pub fn to_binance(&self, symbol: &str) -> String {
+        self.to_binance
+            .get(&symbol.to_uppercase())
+            .cloned()
+            .unwrap_or_else(|| symbol.to_string())
+    }
+
+    /// Returns the alias of the Binance symbol, or the input when the symbol has no alias.
+    pub fn to_alias(&self, symbol: &str) -> String {
+        self.to_alias
+            .get(&symbol.to_uppercase())
+            .cloned()
+            .unwrap_or_else(|| symbol.to_string())
+    }
+
+    /// Replaces an alias in the symbol part of a stream name (`symbol@stream`) by its symbol.
+    #[cfg(feature = "native")]
+    pub(crate) fn stream_to_binance(&self, stream: &str) -> String {
+        match stream.split_once('@') {
+            Some((symbol, rest)) if !self.to_binance.is_empty() => {
+                format!("{}@{}", self.to_binance(symbol).to_lowercase(), rest)
+            }
+            _ => stream.to_string(),
+        }
+    }
+
+    /// Replaces the Binance symbols of the JSON payload by their aliases, `None` when the payload
+    /// contains no aliased symbol.
+    ///
+    /// The symbol fields (`s`, also nested, e.g. `o.s` and `a.P[].s`) are replaced in the text,
+    /// the payload is not parsed.
+    #[cfg(feature = "native")]
+    pub(crate) fn apply(&self, json_response: &str) -> Option<String> {
+        let mut aliased: Option<String> = None;
+        for (field, replacement) in &self.replacements {
+            let current: &str = aliased.as_deref().unwrap_or(json_response);
+            if current.contains(field.as_str()) {
+                aliased = Some(current.replace(field.as_str(), replacement));
+            }
+        }
+        aliased
+    }
+}
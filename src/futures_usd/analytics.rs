@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+#[cfg(any(feature = "market-data", feature = "klines"))]
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::error::BinanceConnectError;
+
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::AggTrade;
+#[cfg(feature = "klines")]
+use crate::futures_usd::response::KlineData;
+
+/// The traded volume of an aggregate trade or closed kline.
+#[cfg(any(feature = "market-data", feature = "klines"))]
+#[derive(Debug, Clone)]
+struct Sample {
+    /// The trade time or kline close time (UNIX millis).
+    time: u64,
+    base_volume: f64,
+    quote_volume: f64,
+    taker_buy_base_volume: f64,
+    trades: u64,
+}
+
+/// Incrementally computes the VWAP, traded volume and trade count over a rolling time window,
+/// fed with `AggTrade` events or closed `Kline`s.
+///
+/// The window is based on the trade (or kline close) times, samples older than the window
+/// relative to the latest sample are evicted on every update.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    #[cfg(any(feature = "market-data", feature = "klines"))]
+    window_ms: u64,
+    #[cfg(any(feature = "market-data", feature = "klines"))]
+    samples: VecDeque<Sample>,
+    base_volume: f64,
+    quote_volume: f64,
+    taker_buy_base_volume: f64,
+    trades: u64,
+}
+
+impl RollingStats {
+    /// Creates an empty calculator for the window.
+    ///
+    /// # Arguments
+    ///
+    /// - `window`: The duration of the window, e.g. `Duration::from_secs(60)`.
+    ///
+    /// # Returns
+    ///
+    /// The `RollingStats` or a `BinanceConnectError` when the window is zero, which would evict
+    /// every sample.
+    ///
+    pub fn new(window: Duration) -> Result<RollingStats, BinanceConnectError> {
+        validate_window(window)?;
+        Ok(Self {
+            #[cfg(any(feature = "market-data", feature = "klines"))]
+            window_ms: window.as_millis() as u64,
+            #[cfg(any(feature = "market-data", feature = "klines"))]
+            samples: VecDeque::new(),
+            base_volume: 0.0,
+            quote_volume: 0.0,
+            taker_buy_base_volume: 0.0,
+            trades: 0,
+        })
+    }
+
+    /// Adds an aggregate trade.
+    #[cfg(feature = "market-data")]
+    pub fn update_agg_trade(&mut self, agg_trade: &AggTrade) {
+        let taker_buy: bool = !agg_trade.buyer_is_market_maker;
+        self.push(Sample {
+            time: agg_trade.trade_time,
+            base_volume: agg_trade.quantity,
+            quote_volume: agg_trade.price * agg_trade.quantity,
+            taker_buy_base_volume: if taker_buy { agg_trade.quantity } else { 0.0 },
+            trades: agg_trade
+                .last_trade_id
+                .saturating_sub(agg_trade.first_trade_id)
+                + 1,
+        });
+    }
+
+    /// Adds a kline, the updates of open klines are ignored so every kline is counted once.
+    #[cfg(feature = "klines")]
+    pub fn update_kline(&mut self, kline_data: &KlineData) {
+        if !kline_data.is_kline_closed {
+            return;
+        }
+        self.push(Sample {
+            time: kline_data.kline_close_time.max(0) as u64,
+            base_volume: kline_data.base_asset_volume,
+            quote_volume: kline_data.quote_asset_volume,
+            taker_buy_base_volume: kline_data.taker_buy_base_asset_volume,
+            trades: kline_data.number_of_trades.max(0) as u64,
+        });
+    }
+
+    /// Returns the volume weighted average price of the window, `None` without volume.
+    pub fn vwap(&self) -> Option<f64> {
+        match self.base_volume > 0.0 {
+            true => Some(self.quote_volume / self.base_volume),
+            false => None,
+        }
+    }
+
+    /// Returns the traded volume of the window in the base asset.
+    pub fn volume(&self) -> f64 {
+        self.base_volume
+    }
+
+    /// Returns the traded volume of the window in the quote asset.
+    pub fn quote_volume(&self) -> f64 {
+        self.quote_volume
+    }
+
+    /// Returns the traded volume of the window bought by takers, in the base asset.
+    pub fn taker_buy_volume(&self) -> f64 {
+        self.taker_buy_base_volume
+    }
+
+    /// Returns the number of trades of the window.
+    pub fn trade_count(&self) -> u64 {
+        self.trades
+    }
+
+    /// Adds the sample and evicts the samples that fell out of the window.
+    #[cfg(any(feature = "market-data", feature = "klines"))]
+    fn push(&mut self, sample: Sample) {
+        self.base_volume += sample.base_volume;
+        self.quote_volume += sample.quote_volume;
+        self.taker_buy_base_volume += sample.taker_buy_base_volume;
+        self.trades += sample.trades;
+        let latest: u64 = self
+            .samples
+            .back()
+            .map_or(sample.time, |last| last.time.max(sample.time));
+        self.samples.push_back(sample);
+        while let Some(oldest) = self.samples.front() {
+            if oldest.time + self.window_ms > latest {
+                break;
+            }
+            self.base_volume -= oldest.base_volume;
+            self.quote_volume -= oldest.quote_volume;
+            self.taker_buy_base_volume -= oldest.taker_buy_base_volume;
+            self.trades -= oldest.trades;
+            self.samples.pop_front();
+        }
+        // Reset the sums when the window holds a single sample, so floating point drift does not
+        // accumulate.
+        if self.samples.len() == 1 {
+            self.base_volume = self.samples[0].base_volume;
+            self.quote_volume = self.samples[0].quote_volume;
+            self.taker_buy_base_volume = self.samples[0].taker_buy_base_volume;
+        }
+    }
+}
+
+/// Incrementally computes the traded volume per price level over a rolling time window, fed
+/// with `AggTrade` events.
+#[derive(Debug, Clone)]
+pub struct VolumeProfile {
+    bucket_size: f64,
+    #[cfg(feature = "market-data")]
+    window_ms: u64,
+    /// The trade time, price level and quantity of the trades in the window.
+    #[cfg(feature = "market-data")]
+    trades: VecDeque<(u64, i64, f64)>,
+    levels: BTreeMap<i64, f64>,
+}
+
+impl VolumeProfile {
+    /// Creates an empty profile grouping the prices in buckets of the size.
+    ///
+    /// # Arguments
+    ///
+    /// - `bucket_size`: The size of the price levels, e.g. the tick size.
+    /// - `window`: The duration of the window, e.g. `Duration::from_secs(60)`.
+    ///
+    /// # Returns
+    ///
+    /// The `VolumeProfile` or a `BinanceConnectError` when the bucket size is not positive or the
+    /// window is zero.
+    ///
+    pub fn new(bucket_size: f64, window: Duration) -> Result<VolumeProfile, BinanceConnectError> {
+        if !(bucket_size > 0.0 && bucket_size.is_finite()) {
+            return Err(BinanceConnectError::InvalidConfig {
+                key: "bucket_size".to_string(),
+                reason: format!("{} is not a positive price", bucket_size),
+            });
+        }
+        validate_window(window)?;
+        Ok(Self {
+            bucket_size,
+            #[cfg(feature = "market-data")]
+            window_ms: window.as_millis() as u64,
+            #[cfg(feature = "market-data")]
+            trades: VecDeque::new(),
+            levels: BTreeMap::new(),
+        })
+    }
+
+    /// Adds an aggregate trade.
+    #[cfg(feature = "market-data")]
+    pub fn update_agg_trade(&mut self, agg_trade: &AggTrade) {
+        let level: i64 = (agg_trade.price / self.bucket_size).floor() as i64;
+        *self.levels.entry(level).or_default() += agg_trade.quantity;
+        let latest: u64 = self.trades.back().map_or(agg_trade.trade_time, |last| {
+            last.0.max(agg_trade.trade_time)
+        });
+        self.trades
+            .push_back((agg_trade.trade_time, level, agg_trade.quantity));
+        while let Some((time, level, quantity)) = self.trades.front().cloned() {
+            if time + self.window_ms > latest {
+                break;
+            }
+            if let Some(volume) = self.levels.get_mut(&level) {
+                *volume -= quantity;
+                if *volume <= f64::EPSILON {
+                    self.levels.remove(&level);
+                }
+            }
+            self.trades.pop_front();
+        }
+    }
+
+    /// Returns the traded volume per price level (the lower bound of the bucket), ascending.
+    pub fn levels(&self) -> Vec<(f64, f64)> {
+        self.levels
+            .iter()
+            .map(|(level, volume)| (*level as f64 * self.bucket_size, *volume))
+            .collect()
+    }
+
+    /// Returns the traded volume of the price level containing the price.
+    pub fn volume_at(&self, price: f64) -> f64 {
+        let level: i64 = (price / self.bucket_size).floor() as i64;
+        self.levels.get(&level).copied().unwrap_or_default()
+    }
+
+    /// Returns the price level with the highest traded volume (point of control).
+    pub fn point_of_control(&self) -> Option<f64> {
+        self.levels
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(level, _)| *level as f64 * self.bucket_size)
+    }
+}
+
+/// Validates that the window of a rolling calculation is not zero.
+fn validate_window(window: Duration) -> Result<(), BinanceConnectError> {
+    match window.is_zero() {
+        true => Err(BinanceConnectError::InvalidConfig {
+            key: "window".to_string(),
+            reason: "the window must be longer than zero".to_string(),
+        }),
+        false => Ok(()),
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+use std::sync::Arc;
+
+use zeroize::Zeroizing;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::signer::Signer;
+
+/// Supplies the API key and the signatures of `TRADE` and `USER_DATA` requests on demand.
+///
+/// Implementations backed by a key-management system (HSM, Vault, AWS KMS, ...) fetch the API key
+/// and sign the payload when asked, so the secrets never live in process memory as plain strings.
+pub trait AuthProvider: fmt::Debug + Send + Sync {
+    /// Returns the API key sent in the `X-MBX-APIKEY` header, zeroized once the request is sent.
+    fn api_key(&self) -> Result<Zeroizing<String>, BinanceConnectError>;
+
+    /// Returns the signature of the payload as expected in the `signature` parameter.
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError>;
+}
+
+/// The default `AuthProvider`, holding the API key and signing with a local `Signer`.
+///
+/// The API key is zeroized when the provider is dropped.
+pub struct StaticAuthProvider {
+    api_key: Zeroizing<String>,
+    signer: Arc<dyn Signer>,
+}
+
+impl StaticAuthProvider {
+    /// Creates a new `StaticAuthProvider` for the API key and its `Signer`.
+    pub fn new(api_key: String, signer: impl Signer + 'static) -> StaticAuthProvider {
+        Self {
+            api_key: Zeroizing::new(api_key),
+            signer: Arc::new(signer),
+        }
+    }
+}
+
+impl AuthProvider for StaticAuthProvider {
+    fn api_key(&self) -> Result<Zeroizing<String>, BinanceConnectError> {
+        Ok(self.api_key.clone())
+    }
+
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError> {
+        self.signer.sign(payload)
+    }
+}
+
+impl fmt::Debug for StaticAuthProvider {
+    /// Formats the `StaticAuthProvider` without revealing the API key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticAuthProvider")
+            .field("signer", &self.signer)
+            .finish_non_exhaustive()
+    }
+}
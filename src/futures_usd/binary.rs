@@ -0,0 +1,146 @@
+#[cfg(feature = "native")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "native")]
+use std::io::BufWriter;
+use std::io::{ErrorKind, Read, Write};
+#[cfg(feature = "native")]
+use std::path::Path;
+
+#[cfg(feature = "native")]
+use tracing::warn;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "native")]
+use crate::futures_usd::sink::EventSink;
+#[cfg(feature = "native")]
+use crate::futures_usd::status::now_millis;
+
+/// The size of the length prefix of a frame.
+const LENGTH_PREFIX_LEN: usize = 4;
+
+/// Encodes the event into the compact binary format (bincode), e.g. for inter-process transport.
+///
+/// # Arguments
+///
+/// - `event`: The event to encode.
+///
+/// # Returns
+///
+/// The encoded bytes or a `BinanceConnectError` if the event can not be encoded.
+///
+pub fn encode(event: &Event) -> Result<Vec<u8>, BinanceConnectError> {
+    bincode::serialize(event).map_err(bincode_error)
+}
+
+/// Decodes an event encoded with `encode`.
+///
+/// # Arguments
+///
+/// - `bytes`: The encoded event.
+///
+/// # Returns
+///
+/// The `Event` or a `BinanceConnectError` if the bytes are not an encoded event.
+///
+pub fn decode(bytes: &[u8]) -> Result<Event, BinanceConnectError> {
+    bincode::deserialize(bytes).map_err(bincode_error)
+}
+
+/// Writes the event with its receive time as a length-prefixed frame, the format of the files
+/// written by the `BinaryFileSink` and replayed by `ReplayStream::with_binary_frames`.
+///
+/// # Arguments
+///
+/// - `writer`: The writer, e.g. a file.
+/// - `received`: The receive time (UNIX millis).
+/// - `event`: The event to write.
+///
+/// # Returns
+///
+/// `Ok(())` or a `BinanceConnectError` if the event can not be encoded or written.
+///
+pub fn write_frame(
+    writer: &mut impl Write,
+    received: u64,
+    event: &Event,
+) -> Result<(), BinanceConnectError> {
+    let frame: Vec<u8> = bincode::serialize(&(received, event)).map_err(bincode_error)?;
+    writer.write_all(&(frame.len() as u32).to_le_bytes())?;
+    writer.write_all(&frame)?;
+    Ok(())
+}
+
+/// Reads a frame written by `write_frame`.
+///
+/// # Arguments
+///
+/// - `reader`: The reader, e.g. a buffered file.
+///
+/// # Returns
+///
+/// The receive time and event, `None` at the end of the input, or a `BinanceConnectError` if the
+/// input is truncated or the frame can not be decoded.
+///
+pub fn read_frame(reader: &mut impl Read) -> Result<Option<(u64, Event)>, BinanceConnectError> {
+    let mut length: [u8; LENGTH_PREFIX_LEN] = [0; LENGTH_PREFIX_LEN];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let mut frame: Vec<u8> = vec![0; u32::from_le_bytes(length) as usize];
+    reader.read_exact(&mut frame)?;
+    bincode::deserialize(&frame)
+        .map(Some)
+        .map_err(bincode_error)
+}
+
+/// Converts a bincode error into a `BinanceConnectError`.
+fn bincode_error(err: bincode::Error) -> BinanceConnectError {
+    BinanceConnectError::Other(format!("Binary encoding error: {}", err))
+}
+
+/// Writes every event as a frame (see `write_frame`) to a file, stamped with the time it is
+/// published, for compact storage replayed with `ReplayStream::with_binary_frames`.
+///
+/// The frames are buffered, they are flushed when the sink is dropped.
+#[cfg(feature = "native")]
+#[derive(Debug)]
+pub struct BinaryFileSink {
+    writer: BufWriter<File>,
+}
+
+#[cfg(feature = "native")]
+impl BinaryFileSink {
+    /// Creates a sink appending to the file, which is created when it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the file, e.g. `events.bin`.
+    ///
+    /// # Returns
+    ///
+    /// The `BinaryFileSink` or a `BinanceConnectError` if the file can not be opened.
+    ///
+    pub fn create(path: impl AsRef<Path>) -> Result<BinaryFileSink, BinanceConnectError> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Flushes the buffered frames to the file.
+    pub fn flush(&mut self) -> Result<(), BinanceConnectError> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(feature = "native")]
+impl EventSink for BinaryFileSink {
+    fn publish(&mut self, event: &Event) {
+        if let Err(err) = write_frame(&mut self.writer, now_millis(), event) {
+            warn!(error = %err, "futures_usd binary sink write failed");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use crate::futures_usd::enums::binance::KlineInterval;
+
+/// The number of milliseconds in a day.
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// The offset of the weekly candles from the UNIX epoch (a Thursday), they open on Monday.
+const WEEK_OFFSET_MS: i64 = 4 * DAY_MS;
+
+/// Emitted at the close of every candle of a scheduled interval, whether or not a kline update
+/// was received, see `FuturesWebSocketConfig::with_candle_boundaries`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CandleBoundary {
+    pub interval: KlineInterval,
+    /// The open time (UNIX millis, server time) of the candle that closed.
+    pub open_time: i64,
+    /// The close time (UNIX millis, server time) of the candle, the open time of the next one.
+    pub close_time: i64,
+}
+
+impl CandleBoundary {
+    /// Returns the candle of the interval closing at the first boundary after the time.
+    ///
+    /// The boundaries are aligned as the klines of Binance: to the UNIX epoch up to the 3 day
+    /// interval, to Monday for the weekly interval and to the first day of the (UTC) calendar
+    /// month for the monthly interval.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The interval of the candle.
+    /// - `time`: The time (UNIX millis, server time).
+    ///
+    /// # Returns
+    ///
+    /// The `CandleBoundary` of the candle open at the time.
+    ///
+    pub fn after(interval: KlineInterval, time: i64) -> CandleBoundary {
+        let (open_time, close_time): (i64, i64) = match interval {
+            KlineInterval::Months1 => {
+                let (year, month): (i64, i64) = year_month(time);
+                let (next_year, next_month): (i64, i64) = match month {
+                    12 => (year + 1, 1),
+                    _ => (year, month + 1),
+                };
+                (month_start(year, month), month_start(next_year, next_month))
+            }
+            _ => {
+                let length: i64 = interval.as_duration().as_millis() as i64;
+                let offset: i64 = match interval {
+                    KlineInterval::Weeks1 => WEEK_OFFSET_MS,
+                    _ => 0,
+                };
+                let open_time: i64 = (time - offset).div_euclid(length) * length + offset;
+                (open_time, open_time + length)
+            }
+        };
+        CandleBoundary {
+            interval,
+            open_time,
+            close_time,
+        }
+    }
+}
+
+/// Returns the (UTC) year and month (1-12) of the time (UNIX millis).
+fn year_month(time: i64) -> (i64, i64) {
+    // The civil date of the days since the epoch, counting years from March.
+    let days: i64 = time.div_euclid(DAY_MS) + 719_468;
+    let era: i64 = days.div_euclid(146_097);
+    let day_of_era: i64 = days - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month: i64 = (5 * day_of_year + 2) / 153;
+    let month: i64 = match month < 10 {
+        true => month + 3,
+        false => month - 9,
+    };
+    let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month)
+}
+
+/// Returns the time (UNIX millis) of the start of the first day of the (UTC) month (1-12).
+fn month_start(year: i64, month: i64) -> i64 {
+    let year: i64 = match month <= 2 {
+        true => year - 1,
+        false => year,
+    };
+    let era: i64 = year.div_euclid(400);
+    let year_of_era: i64 = year - era * 400;
+    let month_from_march: i64 = match month > 2 {
+        true => month - 3,
+        false => month + 9,
+    };
+    let day_of_year: i64 = (153 * month_from_march + 2) / 5;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era - 719_468) * DAY_MS
+}
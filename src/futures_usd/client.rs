@@ -1,77 +1,474 @@
 use std::io::ErrorKind;
 use std::net::TcpStream;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::Sender;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use log::{debug, info};
+use tracing::{debug, error, info, trace_span, warn};
+use tungstenite::error::UrlError;
+use tungstenite::handshake::HandshakeError;
+use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::stream::MaybeTlsStream;
-use tungstenite::{connect, Message, WebSocket};
+use tungstenite::{client_tls_with_config, Connector, Message, WebSocket};
 use url::Url;
 
 use crate::error::BinanceConnectError;
-use crate::futures_usd::deserializer::deserialize;
+use crate::futures_usd::alert::{Alert, AlertEngine};
+use crate::futures_usd::alias::SymbolAliases;
+use crate::futures_usd::conflation::Conflator;
+use crate::futures_usd::dedup::{DuplicateFilter, RedundantFilter, UpdateIdFilter};
+use crate::futures_usd::deserializer::deserialize_slice;
+use crate::futures_usd::downtime::DowntimeTracker;
 use crate::futures_usd::enums::events::Event;
-use crate::futures_usd::stream::WouldBlockConfig;
+use crate::futures_usd::event_filter::EventFilter;
+use crate::futures_usd::fill_notice::FillCallback;
+use crate::futures_usd::funding::{FundingAlert, FundingCountdown, FundingTracker};
+use crate::futures_usd::kline_filter::ClosedKlineFilter;
+use crate::futures_usd::latest_cache::LatestCache;
+use crate::futures_usd::listen_key::{ApiAuth, ListenKeyRenewal};
+use crate::futures_usd::listing::{ListingDetector, NewListing};
+use crate::futures_usd::multi_symbol::SymbolFilter;
+use crate::futures_usd::ordering::{OrderingGuard, Sequence, SequenceGenerator};
+use crate::futures_usd::outbox::{Outbox, TokenBucket};
+use crate::futures_usd::ping::{ClientPing, PingMonitor};
+use crate::futures_usd::pnl::PnlTracker;
+use crate::futures_usd::pool::Router;
+use crate::futures_usd::quote_cache::QuoteCache;
+use crate::futures_usd::reconnect::ConnectionClosed;
+use crate::futures_usd::recorder::Recorder;
+use crate::futures_usd::response::{AccountSnapshot, WithRaw};
+use crate::futures_usd::rest::get_account_from;
+use crate::futures_usd::rotation::{MigrationRequest, Rotation};
+#[cfg(feature = "scripting")]
+use crate::futures_usd::script::EventScript;
+use crate::futures_usd::sink::EventSender;
+use crate::futures_usd::skew::{event_times, SkewAlert, SkewMonitor};
+use crate::futures_usd::standby::Standby;
+use crate::futures_usd::state_store::StateStore;
+use crate::futures_usd::stats::ConnectionStats;
+use crate::futures_usd::status::ConnectorStatus;
+use crate::futures_usd::stream::{Shards, WouldBlockConfig};
+use crate::futures_usd::ticker_cache::TickerCache;
+use crate::futures_usd::time_sync::server_now;
+use crate::futures_usd::tls;
+use crate::futures_usd::transport::Transport;
+
+/// The maximum read timeout of a watched connection, bounding how late a stale connection is noticed.
+const STALE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The read timeout of an expiring connection, bounding how late the end of the overlap is noticed.
+const OVERLAP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A WebSocket connection to Binance.
+pub(crate) type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// Holds everything a connection needs to process and forward its events.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionContext {
+    /// The `EventSender` publishing the events to the sinks and the receiver.
+    pub sender: EventSender,
+    /// A flag that stops the client when set.
+    pub stop_signal: Arc<AtomicBool>,
+    /// The shared `ConnectorStatus` updated by the client.
+    pub status: Arc<ConnectorStatus>,
+    /// The `ConnectionStats` of this connection, updated by the client.
+    pub stats: Arc<ConnectionStats>,
+    /// Configuration for handling WouldBlock errors.
+    pub would_block_config: WouldBlockConfig,
+    /// The duration without any message after which the connection is treated as dead.
+    pub stale_timeout: Option<Duration>,
+    /// The client pings sent on the connection.
+    pub client_ping: Option<ClientPing>,
+    /// The replacement of the connection before the 24 hour limit, set per connection.
+    pub rotation: Option<Rotation>,
+    /// The migration requests of the stream, replacing its connections on demand.
+    pub migration: Arc<MigrationRequest>,
+    /// The optional event time skew monitor.
+    pub skew_monitor: Option<Arc<Mutex<SkewMonitor>>>,
+    /// A flag indicating whether the receive lag of the events is recorded in the stats.
+    pub receive_latency: bool,
+    /// A flag indicating whether the events are emitted with the JSON they were decoded from.
+    pub raw_json: bool,
+    /// The optional funding tracker fed by the mark price events.
+    pub funding_tracker: Option<Arc<Mutex<FundingTracker>>>,
+    /// The optional alert engine evaluating the registered conditions.
+    pub alert_engine: Option<Arc<Mutex<AlertEngine>>>,
+    /// The optional detector of new listings fed by the contract info events.
+    pub listing_detector: Option<Arc<Mutex<ListingDetector>>>,
+    /// The optional latest-value cache.
+    pub latest_cache: Option<Arc<LatestCache>>,
+    /// The optional account state store maintained from the user data events.
+    pub state_store: Option<Arc<StateStore>>,
+    /// The optional PnL tracker maintained from the user data and mark price events.
+    pub pnl_tracker: Option<Arc<PnlTracker>>,
+    /// The optional best bid and ask cache maintained from the book ticker events.
+    pub quote_cache: Option<Arc<QuoteCache>>,
+    /// The optional most recent ticker cache maintained from the ticker events.
+    pub ticker_cache: Option<Arc<TickerCache>>,
+    /// The optional recorder of the received event payloads.
+    pub recorder: Option<Arc<Recorder>>,
+    /// The optional conflator keeping only the latest value of the conflated stream types.
+    pub conflator: Option<Arc<Conflator>>,
+    /// The optional filter dropping the all-market stream events of symbols that were not requested.
+    pub symbol_filter: Option<Arc<SymbolFilter>>,
+    /// The optional filter dropping the updates of open klines of closed-only kline streams.
+    pub kline_filter: Option<Arc<ClosedKlineFilter>>,
+    /// The optional aliases replacing the symbols of the events.
+    pub symbol_aliases: Option<Arc<SymbolAliases>>,
+    /// The optional urgent callback invoked for order updates before deserialization.
+    pub fill_callback: Option<FillCallback>,
+    /// The optional tracker reporting the event gaps of connectivity incidents.
+    pub downtime: Option<Arc<DowntimeTracker>>,
+    /// The queue of messages sent to Binance, shared by all connections of the stream.
+    pub outbox: Arc<Outbox>,
+    /// The maximum number of messages sent to Binance per second.
+    pub messages_per_second: u32,
+    /// The rate limiter shared by every connection of a `ConnectionManager`, applied besides the
+    /// limit of this connection.
+    pub global_rate_limit: Option<Arc<Mutex<TokenBucket>>>,
+    /// The minimum notional of forwarded force order events.
+    #[cfg(feature = "market-data")]
+    pub force_order_min_notional: Option<f64>,
+    /// The optional user-supplied filter applied before events are sent.
+    pub event_filter: Option<Arc<EventFilter>>,
+    /// The optional filter suppressing the events received again after a reconnect or on the
+    /// other connection of a redundant pair.
+    pub update_id_filter: Option<Arc<Mutex<UpdateIdFilter>>>,
+    /// The frame filter shared by a redundant pair and the index (`0` or `1`) of this connection
+    /// in the pair, set on both connections of the pair.
+    pub redundant_filter: Option<(Arc<Mutex<RedundantFilter>>, usize)>,
+    /// The routes of a pooled connection of a `ConnectionManager`, set on the pooled connections,
+    /// which route their frames to the attached streams instead of processing them.
+    pub router: Option<Arc<Router>>,
+    /// The fresh listen key the user data connection reconnects with, set on that connection.
+    pub listen_key_renewal: Option<Arc<ListenKeyRenewal>>,
+    /// The account snapshot requested on every connect, set on the user data connection when
+    /// `with_account_snapshot` is configured.
+    pub account_snapshot: Option<Arc<SnapshotRequest>>,
+    /// The connections of the stream the streams of new listings are subscribed to on, set on the
+    /// connections opened by the stream itself.
+    pub shards: Option<Arc<Shards>>,
+    /// The options of the outbound TCP connections.
+    pub transport: Arc<Transport>,
+    /// The optional user-defined filter/transform applied to every event.
+    #[cfg(feature = "scripting")]
+    pub event_script: Option<EventScript>,
+}
+
+/// The account snapshot requested whenever the user data connection connects.
+#[derive(Debug)]
+pub(crate) struct SnapshotRequest {
+    /// The credentials of the account.
+    pub api_auth: ApiAuth,
+    /// The REST base URL the snapshot is requested from.
+    pub base_url: String,
+    /// The last sent snapshot, an identical snapshot of a reconnect is not sent again.
+    pub last_snapshot: Mutex<Option<AccountSnapshot>>,
+}
 
 /// Establishes a WebSocket connection to the provided URL, reads and processes messages,
-/// and sends events to the specified sender.
+/// and sends events to the sender of the context.
 ///
 /// # Arguments
 ///
-/// * `sender` - A `Sender<Event>` to send events to.
 /// * `url` - The URL to connect to.
-/// * `would_block_config` - Configuration for handling WouldBlock errors.
 /// * `subscribe_payload` - An optional JSON payload to subscribe to specific streams.
+/// * `context` - The `ConnectionContext` of the connection.
 ///
 /// # Returns
 ///
 /// This function returns `Ok(())` if the connection and processing were successful, or
 /// a `BinanceConnectError` if an error occurred.
 pub fn client(
-    sender: Sender<Event>,
     url: Url,
-    stop_signal: Arc<AtomicBool>,
-    would_block_config: WouldBlockConfig,
     subscribe_payload: Option<String>,
+    context: &ConnectionContext,
 ) -> Result<(), BinanceConnectError> {
     // Establish a WebSocket connection.
-    let mut socket: WebSocket<MaybeTlsStream<TcpStream>> = socket(url)?;
+    let socket: Socket = connect_socket(url, subscribe_payload, &context.transport)?;
+    run(socket, context)
+}
 
+/// Establishes a WebSocket connection and sends the optional subscribe payload.
+pub(crate) fn connect_socket(
+    url: Url,
+    subscribe_payload: Option<String>,
+    transport: &Transport,
+) -> Result<Socket, BinanceConnectError> {
+    info!(url = %url, "futures_usd connecting");
+    let mut socket: Socket = socket(url, transport)?;
     // If a subscribe payload is provided, send the subscription request.
     if let Some(subscribe_payload) = subscribe_payload {
-        debug!("{:?}", subscribe_payload);
+        debug!(payload = %subscribe_payload, "futures_usd subscribing");
         socket.send(Message::Text(subscribe_payload))?;
     }
+    Ok(socket)
+}
+
+/// Reads and processes messages of an established connection until stopped or an error occurs.
+pub(crate) fn run(
+    mut socket: Socket,
+    context: &ConnectionContext,
+) -> Result<(), BinanceConnectError> {
+    context.status.set_connected(true);
+    context.stats.set_connected(true);
+    context.outbox.reconnected();
+    if let Some(update_id_filter) = &context.update_id_filter {
+        update_id_filter.lock().unwrap().connected();
+    }
+    set_poll_timeout(&socket, context);
+    info!("futures_usd connected");
+    let result: Result<(), BinanceConnectError> = downtime_report(context)
+        .and_then(|()| account_snapshot(context))
+        .and_then(|snapshot_time| {
+            let frames: FrameState = FrameState {
+                account_snapshot_time: snapshot_time,
+                ..FrameState::default()
+            };
+            read_loop(&mut socket, context, frames)
+        });
+    // Stopped, close the connection instead of leaving Binance to time it out.
+    if result.is_ok() {
+        let _ = socket.close(None);
+        let _ = socket.flush();
+    }
+    context.status.set_connected(false);
+    context.stats.set_connected(false);
+    match &result {
+        Ok(()) => info!("futures_usd disconnected"),
+        Err(err) => warn!(error = %err, "futures_usd disconnected"),
+    }
+    result
+}
+
+/// Closes the open connectivity incident of the re-established connection and sends its report
+/// as a `DowntimeReportEvent`, see `FuturesWebSocketConfig::with_downtime_reports`.
+///
+/// # Returns
+///
+/// A `BinanceConnectError` if the report could not be sent.
+///
+fn downtime_report(context: &ConnectionContext) -> Result<(), BinanceConnectError> {
+    if let Some(report) = context
+        .downtime
+        .as_ref()
+        .and_then(|downtime| downtime.reconnected())
+    {
+        info!(report = ?report, "futures_usd downtime report");
+        context.sender.send(Event::DowntimeReportEvent(report))?;
+    }
+    Ok(())
+}
+
+/// Requests the account snapshot of a connected user data connection and sends it as an
+/// `AccountSnapshotEvent`, see `FuturesWebSocketConfig::with_account_snapshot`. A snapshot equal
+/// to the one sent on the previous connection is not sent again.
+///
+/// The events received meanwhile are buffered by the socket until the read loop starts; the
+/// account updates already contained in the snapshot are then discarded by `process_frame`.
+///
+/// # Returns
+///
+/// The time (UNIX millis) of the snapshot, `None` when no snapshot is configured, or a
+/// `BinanceConnectError` if the request failed.
+///
+fn account_snapshot(context: &ConnectionContext) -> Result<Option<i64>, BinanceConnectError> {
+    let snapshot_request: &SnapshotRequest = match &context.account_snapshot {
+        Some(snapshot_request) => snapshot_request,
+        None => return Ok(None),
+    };
+    let account_snapshot: AccountSnapshot =
+        get_account_from(&snapshot_request.api_auth, &snapshot_request.base_url)?;
+    let snapshot_time: i64 = account_snapshot.update_time();
+    // Nothing changed since the snapshot of the previous connection.
+    let mut last_snapshot = snapshot_request.last_snapshot.lock().unwrap();
+    if last_snapshot.as_ref() == Some(&account_snapshot) {
+        debug!(
+            snapshot_time = snapshot_time,
+            "futures_usd account snapshot unchanged"
+        );
+        return Ok(Some(snapshot_time));
+    }
+    *last_snapshot = Some(account_snapshot.clone());
+    let event: Event = Event::AccountSnapshotEvent(account_snapshot);
+    if let Some(state_store) = &context.state_store {
+        state_store.apply(&event);
+    }
+    if let Some(pnl_tracker) = &context.pnl_tracker {
+        pnl_tracker.apply(&event);
+    }
+    context.sender.send(event)?;
+    Ok(Some(snapshot_time))
+}
+
+/// Processes messages until stopped or an error occurs.
+fn read_loop(
+    socket: &mut Socket,
+    context: &ConnectionContext,
+    mut frames: FrameState,
+) -> Result<(), BinanceConnectError> {
+    let ConnectionContext {
+        sender,
+        stop_signal,
+        stats,
+        would_block_config,
+        stale_timeout,
+        client_ping,
+        rotation,
+        outbox,
+        messages_per_second,
+        global_rate_limit,
+        redundant_filter,
+        router,
+        listen_key_renewal,
+        ..
+    } = context;
+
+    let mut token_bucket: TokenBucket = TokenBucket::new(*messages_per_second);
+    let mut last_message: Instant = Instant::now();
+    let mut connected: Instant = Instant::now();
+    let mut replacement: Option<Standby> = None;
+    // The subscribed replacement and the end of the overlap, while the old connection is drained.
+    let mut handover: Option<(Socket, Instant)> = None;
+    let mut duplicates: DuplicateFilter = DuplicateFilter::default();
+    let mut ping_monitor: Option<PingMonitor> = client_ping.clone().map(PingMonitor::new);
+    // Bounded reads return regularly to check the watchdog, send the client pings and notice a
+    // renewed listen key.
+    let bounded_reads: bool =
+        stale_timeout.is_some() || ping_monitor.is_some() || listen_key_renewal.is_some();
 
     // Continuously read and process WebSocket messages.
     while !stop_signal.load(Ordering::Relaxed) {
-        match socket.read() {
-            Ok(message) => match message {
+        if listen_key_renewal
+            .as_ref()
+            .map_or(false, |listen_key_renewal| listen_key_renewal.pending())
+        {
+            return Err(BinanceConnectError::ListenKeyExpired);
+        }
+        if let Some(ping_monitor) = ping_monitor.as_mut() {
+            if let Some(ping) = ping_monitor.poll()? {
+                outbox.ping(ping);
+            }
+        }
+        // Send the queued pings and subscription changes the rate limit allows.
+        outbox.flush(socket, &mut token_bucket, global_rate_limit.as_deref())?;
+        // Once the replacement is subscribed the expiring connection is still read for the
+        // overlap, so the events in flight on it are not lost.
+        if handover.is_none() {
+            if let Some(rotation) = rotation {
+                if let Some(replaced) = rotation.poll(connected, &mut replacement, stop_signal) {
+                    set_read_timeout(socket, Some(OVERLAP_POLL_INTERVAL));
+                    handover = Some((replaced, Instant::now() + rotation.overlap()));
+                }
+            }
+        }
+        // Switch to the replacement after the overlap, then close the expiring connection.
+        let overlap_ended: Option<(Socket, Instant)> = match &handover {
+            Some((_, overlap_end)) if Instant::now() >= *overlap_end => handover.take(),
+            _ => None,
+        };
+        if let Some((replaced, _)) = overlap_ended {
+            let mut expired: Socket = std::mem::replace(socket, replaced);
+            let _ = expired.close(None);
+            let _ = expired.flush();
+            set_poll_timeout(socket, context);
+            // The update ids restart at the handover, the runtime subscriptions are sent again.
+            frames.restart_updates();
+            ping_monitor = client_ping.clone().map(PingMonitor::new);
+            outbox.reconnected();
+            connected = Instant::now();
+            last_message = Instant::now();
+            if let Some(rotation) = rotation {
+                duplicates.suppress_for(rotation.overlap());
+            }
+            info!("futures_usd connection rotated");
+        }
+        let read: Result<Message, tungstenite::Error> = socket.read();
+        if read.is_ok() {
+            last_message = Instant::now();
+        }
+        match read {
+            Ok(message) => match decode_binary(message) {
                 // Handle incoming JSON messages.
                 Message::Text(json_response) => {
                     // Stop signal might have been called
                     if stop_signal.load(Ordering::Relaxed) {
                         return Ok(());
                     };
-
-                    // Deserialize the JSON into an `Event` and send it to the sender.
-                    let event: Event = deserialize(json_response)?;
-                    sender.send(event)?;
+                    // The frames received on both connections of a rotation are forwarded once.
+                    if replacement.is_some() || handover.is_some() {
+                        duplicates.record(&json_response);
+                    } else if duplicates.is_duplicate(&json_response) {
+                        debug!("futures_usd duplicate frame suppressed");
+                        continue;
+                    }
+                    stats.message_received(json_response.len());
+                    // The first copy of a frame received on a redundant pair is forwarded.
+                    if let Some((redundant_filter, connection)) = redundant_filter {
+                        if redundant_filter
+                            .lock()
+                            .unwrap()
+                            .is_duplicate(*connection, &json_response)
+                        {
+                            continue;
+                        }
+                    }
+                    // The frames of a pooled connection are processed by the streams they are
+                    // routed to.
+                    if let Some(router) = router {
+                        router.dispatch(json_response);
+                        continue;
+                    }
+                    process_frame(json_response, context, &mut frames)?;
                 }
-                // Handle incoming Ping messages.
-                Message::Ping(ping) => {
+                // The socket answers the pings of Binance with a pong on its own.
+                Message::Ping(_) => {
                     // Stop signal might have been called
                     if stop_signal.load(Ordering::Relaxed) {
                         return Ok(());
                     };
-                    // Respond to Ping with Pong to keep the connection alive.
-                    socket.send(Message::Pong(ping))?;
-                    debug!("Pong");
+                    debug!("futures_usd ping received");
+                }
+                // Handle the pongs of the client pings.
+                Message::Pong(pong) => {
+                    if let Some(rtt) = ping_monitor
+                        .as_mut()
+                        .and_then(|ping_monitor| ping_monitor.pong_received(&pong))
+                    {
+                        debug!(rtt = ?rtt, "futures_usd ping round trip");
+                        stats.ping_observed(rtt);
+                    }
+                }
+                // Handle incoming Close messages, the reason is used for the reconnect decision.
+                Message::Close(close_frame) => {
+                    let (code, reason) = close_frame
+                        .map(|frame| (u16::from(frame.code), frame.reason.into_owned()))
+                        .unwrap_or((u16::from(CloseCode::Status), String::new()));
+                    info!(code = code, reason = %reason, "futures_usd close frame received");
+                    sender.send(Event::ConnectionClosedEvent(ConnectionClosed {
+                        code,
+                        reason: reason.clone(),
+                    }))?;
+                    return Err(BinanceConnectError::ConnectionClosed { code, reason });
                 }
                 _ => {}
             },
             Err(err) => match err {
+                // The read timeout of the bounded reads expired.
+                tungstenite::Error::Io(ref io_err)
+                    if (bounded_reads || handover.is_some())
+                        && (io_err.kind() == ErrorKind::WouldBlock
+                            || io_err.kind() == ErrorKind::TimedOut) =>
+                {
+                    // Stop signal might have been called
+                    if stop_signal.load(Ordering::Relaxed) {
+                        return Ok(());
+                    };
+                    let silence: Duration = last_message.elapsed();
+                    if stale_timeout.map_or(false, |stale_timeout| silence >= stale_timeout) {
+                        warn!(silence = ?silence, "futures_usd stale connection");
+                        return Err(BinanceConnectError::StaleConnection(silence));
+                    }
+                }
                 tungstenite::Error::Io(ref io_err) if io_err.kind() == ErrorKind::WouldBlock => {
                     // Stop signal might have been called
                     if stop_signal.load(Ordering::Relaxed) {
@@ -80,12 +477,12 @@ pub fn client(
 
                     if would_block_config.error_on_block {
                         // Return a SocketError if configured to do so.
-                        Err(BinanceConnectError::SocketError(err))?;
+                        Err(BinanceConnectError::SocketError(Box::new(err)))?;
                     }
                     // Sleep for the specified time if a WouldBlock error occurs.
                     info!(
-                        "futures_usd client thread slept {:?} because of WouldBlock error",
-                        would_block_config.time_out
+                        time_out = ?would_block_config.time_out,
+                        "futures_usd client thread slept because of WouldBlock error"
                     );
                     std::thread::sleep(would_block_config.time_out);
                 }
@@ -95,7 +492,7 @@ pub fn client(
                         return Ok(());
                     };
                     // Return a SocketError for other types of errors.
-                    Err(BinanceConnectError::SocketError(err))?;
+                    Err(BinanceConnectError::SocketError(Box::new(err)))?;
                 }
             },
         }
@@ -104,8 +501,337 @@ pub fn client(
     Ok(())
 }
 
-/// Establishes a WebSocket connection to the provided URL.
-fn socket(url: Url) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, BinanceConnectError> {
-    let (socket, _) = connect(url)?;
-    Ok(socket)
+/// The per-connection state of the frame processing: sequence numbers and update ids are tracked
+/// per connection.
+#[derive(Debug, Default)]
+pub(crate) struct FrameState {
+    sequence_generator: SequenceGenerator,
+    ordering_guard: OrderingGuard,
+    /// The time (UNIX millis) of the account snapshot requested on connect, the account updates
+    /// up to this time are contained in it.
+    account_snapshot_time: Option<i64>,
+}
+
+impl FrameState {
+    /// Returns whether the event is an account update already contained in the account snapshot.
+    fn in_account_snapshot(&self, event: &Event) -> bool {
+        match (event, self.account_snapshot_time) {
+            #[cfg(feature = "user-data")]
+            (Event::AccountUpdateEvent(account_update), Some(snapshot_time)) => {
+                account_update.transaction_time <= snapshot_time
+            }
+            _ => false,
+        }
+    }
+
+    /// Restarts the update id tracking, called when the frames continue on a new connection.
+    pub(crate) fn restart_updates(&mut self) {
+        self.ordering_guard = OrderingGuard::default();
+    }
+}
+
+/// Processes a text frame received on the connection: applies the filters, caches and trackers of
+/// the context, deserializes it and sends the event.
+pub(crate) fn process_frame(
+    json_response: String,
+    context: &ConnectionContext,
+    frames: &mut FrameState,
+) -> Result<(), BinanceConnectError> {
+    let ConnectionContext {
+        sender,
+        status,
+        stats,
+        skew_monitor,
+        receive_latency,
+        raw_json,
+        funding_tracker,
+        alert_engine,
+        listing_detector,
+        latest_cache,
+        state_store,
+        pnl_tracker,
+        quote_cache,
+        ticker_cache,
+        recorder,
+        conflator,
+        symbol_filter,
+        kline_filter,
+        symbol_aliases,
+        fill_callback,
+        downtime,
+        outbox,
+        #[cfg(feature = "market-data")]
+        force_order_min_notional,
+        event_filter,
+        update_id_filter,
+        shards,
+        #[cfg(feature = "scripting")]
+        event_script,
+        ..
+    } = context;
+
+    let raw: Option<Arc<str>> = match raw_json {
+        true => Some(Arc::from(json_response.as_str())),
+        false => None,
+    };
+
+    let sequence: Sequence = frames.sequence_generator.next_sequence();
+    status.event_received(sequence);
+    if let Some(downtime) = downtime {
+        downtime.event_received(&json_response);
+    }
+    let received: u64 = server_now();
+    let json_response: String = match symbol_filter {
+        Some(symbol_filter) => match symbol_filter.apply(json_response) {
+            Some(filtered) => filtered,
+            None => return Ok(()),
+        },
+        None => json_response,
+    };
+    if let Some(kline_filter) = kline_filter {
+        if kline_filter.drops(&json_response) {
+            return Ok(());
+        }
+    }
+    // The single point the aliases are applied, everything after it sees them.
+    let json_response: String = match symbol_aliases
+        .as_ref()
+        .and_then(|symbol_aliases| symbol_aliases.apply(&json_response))
+    {
+        Some(aliased) => aliased,
+        None => json_response,
+    };
+    // Notify fills before anything else is done with the frame.
+    if let Some(fill_callback) = fill_callback {
+        fill_callback.notify(&json_response);
+    }
+    // Apply the user-defined script, a failing script leaves the event untouched.
+    #[cfg(feature = "scripting")]
+    let json_response: String = match event_script
+        .as_ref()
+        .map(|event_script| event_script.apply(&json_response))
+    {
+        Some(Ok(Some(transformed))) => transformed,
+        Some(Ok(None)) => return Ok(()),
+        Some(Err(err)) => {
+            warn!(error = %err, "futures_usd event script failed");
+            json_response
+        }
+        None => json_response,
+    };
+    if let Some(latest_cache) = latest_cache {
+        latest_cache.update(&json_response);
+    }
+    if let Some(recorder) = recorder {
+        recorder.record(received, &json_response);
+    }
+    // Deserialize the JSON into an `Event` and send it to the sender.
+    let event: Event = match trace_span!("futures_usd_deserialize")
+        .in_scope(|| deserialize_slice(json_response.as_bytes()))
+    {
+        Ok(event) => event,
+        Err(err) => {
+            if let BinanceConnectError::JsonError {
+                event_type, stream, ..
+            } = &err
+            {
+                error!(
+                    event_type = ?event_type,
+                    stream = ?stream,
+                    error = %err,
+                    "futures_usd deserialization failed"
+                );
+            }
+            return Err(err);
+        }
+    };
+    // Match the responses to the requests of this connection.
+    let event: Event = outbox.complete(event);
+    if frames.in_account_snapshot(&event) {
+        debug!("futures_usd account update contained in the snapshot discarded");
+        return Ok(());
+    }
+    if let Some(update_id_filter) = update_id_filter {
+        if update_id_filter.lock().unwrap().is_duplicate(&event) {
+            debug!("futures_usd duplicate event suppressed");
+            return Ok(());
+        }
+    }
+    if let Some(state_store) = state_store {
+        state_store.apply(&event);
+    }
+    if let Some(pnl_tracker) = pnl_tracker {
+        pnl_tracker.apply(&event);
+    }
+    if let Some(quote_cache) = quote_cache {
+        quote_cache.apply(&event);
+    }
+    if let Some(ticker_cache) = ticker_cache {
+        ticker_cache.apply(&event);
+    }
+    if let Some(skew_monitor) = skew_monitor {
+        let alerts: Vec<SkewAlert> = skew_monitor.lock().unwrap().observe(&event, received);
+        for alert in alerts {
+            warn!(alert = ?alert, "futures_usd event time skew");
+            sender.send(Event::SkewAlertEvent(alert))?;
+        }
+    }
+    if *receive_latency {
+        for (_, event_time) in event_times(&event) {
+            stats.lag_observed(received as i64 - event_time);
+        }
+    }
+    if let Some(funding_tracker) = funding_tracker {
+        let (alerts, countdowns): (Vec<FundingAlert>, Vec<FundingCountdown>) = {
+            let mut funding_tracker = funding_tracker.lock().unwrap();
+            (
+                funding_tracker.observe(&event),
+                funding_tracker.drain_countdowns(),
+            )
+        };
+        for alert in alerts {
+            info!(alert = ?alert, "futures_usd funding rate threshold crossed");
+            sender.send(Event::FundingAlertEvent(alert))?;
+        }
+        for countdown in countdowns {
+            info!(countdown = ?countdown, "futures_usd funding countdown");
+            sender.send(Event::FundingCountdownEvent(countdown))?;
+        }
+    }
+    if let Some(alert_engine) = alert_engine {
+        let alerts: Vec<Alert> = alert_engine.lock().unwrap().observe(&event);
+        for alert in alerts {
+            info!(alert = ?alert, "futures_usd alert triggered");
+            sender.send(Event::AlertEvent(alert))?;
+        }
+    }
+    // New listings are subscribed to on a connection of the stream with capacity.
+    if let Some(listing_detector) = listing_detector {
+        let listing: Option<NewListing> = listing_detector.lock().unwrap().observe(&event);
+        if let Some(listing) = listing {
+            info!(listing = ?listing, "futures_usd new listing");
+            if !listing.streams.is_empty() {
+                match shards {
+                    Some(shards) => {
+                        shards.subscribe(listing.streams.clone());
+                    }
+                    None => {
+                        outbox.subscribe(listing.streams.clone());
+                    }
+                }
+            }
+            sender.send(Event::NewListingEvent(listing))?;
+        }
+    }
+    // Drop liquidations below the configured notional.
+    #[cfg(feature = "market-data")]
+    if let (Event::ForceOrderEvent(force_order), Some(min_notional)) =
+        (&event, force_order_min_notional)
+    {
+        if force_order.order.notional() < *min_notional {
+            return Ok(());
+        }
+    }
+    let event: Event = match event_filter {
+        Some(event_filter) => match event_filter.apply(event) {
+            Some(event) => event,
+            None => return Ok(()),
+        },
+        None => event,
+    };
+    if let Err(violation) = frames.ordering_guard.check(&event) {
+        warn!(violation = %violation, "futures_usd ordering violation");
+    }
+    // Conflated events are emitted by the conflator at its interval.
+    let event: Event = match conflator {
+        Some(conflator) => match conflator.offer(event) {
+            Some(event) => event,
+            None => return Ok(()),
+        },
+        None => event,
+    };
+    let event: Event = match raw {
+        Some(raw) => Event::WithRawEvent(WithRaw {
+            event: Box::new(event),
+            raw,
+        }),
+        None => event,
+    };
+    sender.send(event)?;
+    Ok(())
+}
+
+/// Establishes a WebSocket connection to the provided URL over a TCP connection of the
+/// `Transport`, using a TLS connector trusting the added root certificates when there are any.
+fn socket(url: Url, transport: &Transport) -> Result<Socket, BinanceConnectError> {
+    let connector: Option<Connector> = tls::connector()?;
+    let host: &str = url
+        .host_str()
+        .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?;
+    let port: u16 = url
+        .port_or_known_default()
+        .ok_or(tungstenite::Error::Url(UrlError::UnsupportedUrlScheme))?;
+    // Connection failures are socket errors, so they follow the reconnect path.
+    let stream: TcpStream = transport
+        .connect(host, port)
+        .and_then(|stream| stream.set_nodelay(true).map(|_| stream))
+        .map_err(tungstenite::Error::Io)?;
+    match client_tls_with_config(url, stream, Some(transport.websocket_config), connector) {
+        Ok((socket, _)) => Ok(socket),
+        Err(HandshakeError::Failure(err)) => Err(BinanceConnectError::SocketError(Box::new(err))),
+        Err(HandshakeError::Interrupted(_)) => Err(BinanceConnectError::Other(
+            "WebSocket handshake interrupted".to_string(),
+        )),
+    }
+}
+
+/// Decodes a binary frame as UTF-8 text, so it takes the path of the text frames. Frames that are
+/// not UTF-8 are returned unchanged and dropped.
+fn decode_binary(message: Message) -> Message {
+    match message {
+        Message::Binary(payload) => match String::from_utf8(payload) {
+            Ok(text) => Message::Text(text),
+            Err(err) => {
+                warn!(
+                    bytes = err.as_bytes().len(),
+                    "futures_usd binary frame is not UTF-8, dropped"
+                );
+                Message::Binary(err.into_bytes())
+            }
+        },
+        message => message,
+    }
+}
+
+/// Bounds the reads when the watchdog or the client pings are enabled, so a connection that stopped
+/// delivering is noticed and the pings are sent in time.
+fn set_poll_timeout(socket: &Socket, context: &ConnectionContext) {
+    let timeouts = [
+        context
+            .stale_timeout
+            .map(|stale_timeout| stale_timeout.min(STALE_POLL_INTERVAL)),
+        context.client_ping.as_ref().map(ClientPing::poll_interval),
+        context
+            .listen_key_renewal
+            .as_ref()
+            .map(|_| STALE_POLL_INTERVAL),
+    ];
+    if let Some(timeout) = timeouts.iter().flatten().min() {
+        set_read_timeout(socket, Some(*timeout));
+    }
+}
+
+/// Sets the read timeout on the underlying `TcpStream`, so blocking reads return regularly.
+pub(crate) fn set_read_timeout(socket: &Socket, timeout: Option<Duration>) {
+    let stream: Option<&TcpStream> = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => Some(stream),
+        #[cfg(feature = "native-tls")]
+        MaybeTlsStream::NativeTls(stream) => Some(stream.get_ref()),
+        #[cfg(feature = "rustls")]
+        MaybeTlsStream::Rustls(stream) => Some(stream.get_ref()),
+        _ => None,
+    };
+    if let Some(stream) = stream {
+        let _ = stream.set_read_timeout(timeout);
+    }
 }
@@ -0,0 +1,181 @@
+#[cfg(feature = "market-data")]
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "market-data")]
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::{BookTicker, BookTickers, MarkPriceUpdate, MarkPriceUpdates};
+use crate::futures_usd::sink::EventSender;
+
+/// The granularity with which the emit threads check their stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The latest-value stream types that can be conflated, see
+/// `FuturesWebSocketConfig::with_conflation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflatedStream {
+    /// `bookTicker` events, single symbol and all-market.
+    BookTicker,
+    /// `markPriceUpdate` events, single symbol and all-market.
+    MarkPriceUpdate,
+}
+
+/// The latest pending value of a symbol, with a flag indicating whether it was received as part
+/// of an all-market array.
+#[cfg(feature = "market-data")]
+type Pending<T> = BTreeMap<String, (T, bool)>;
+
+/// Keeps only the most recent value per symbol of the conflated stream types and emits the
+/// pending values at the configured interval, so slow consumers get fresh data instead of an
+/// ever-growing backlog.
+#[derive(Debug)]
+pub(crate) struct Conflator {
+    intervals: Vec<(ConflatedStream, Duration)>,
+    #[cfg(feature = "market-data")]
+    book_tickers: Mutex<Pending<BookTicker>>,
+    #[cfg(feature = "market-data")]
+    mark_price_updates: Mutex<Pending<MarkPriceUpdate>>,
+}
+
+impl Conflator {
+    /// Creates a conflator for the stream types and their emit intervals.
+    pub(crate) fn new(intervals: Vec<(ConflatedStream, Duration)>) -> Conflator {
+        Self {
+            intervals,
+            #[cfg(feature = "market-data")]
+            book_tickers: Mutex::new(BTreeMap::new()),
+            #[cfg(feature = "market-data")]
+            mark_price_updates: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns whether the stream type is conflated.
+    #[cfg(feature = "market-data")]
+    fn conflates(&self, stream: ConflatedStream) -> bool {
+        self.intervals
+            .iter()
+            .any(|(conflated, _)| *conflated == stream)
+    }
+
+    /// Stores the event when its type is conflated, returning it otherwise.
+    pub(crate) fn offer(&self, event: Event) -> Option<Event> {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::BookTickerEvent(book_ticker) if self.conflates(ConflatedStream::BookTicker) => {
+                let mut pending = self.book_tickers.lock().unwrap();
+                pending.insert(book_ticker.symbol.clone(), (book_ticker, false));
+                None
+            }
+            #[cfg(feature = "market-data")]
+            Event::BookTickersEvent(book_tickers)
+                if self.conflates(ConflatedStream::BookTicker) =>
+            {
+                let mut pending = self.book_tickers.lock().unwrap();
+                for book_ticker in book_tickers.data {
+                    pending.insert(book_ticker.symbol.clone(), (book_ticker, true));
+                }
+                None
+            }
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdateEvent(mark_price_update)
+                if self.conflates(ConflatedStream::MarkPriceUpdate) =>
+            {
+                let mut pending = self.mark_price_updates.lock().unwrap();
+                pending.insert(mark_price_update.symbol.clone(), (mark_price_update, false));
+                None
+            }
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdatesEvent(mark_price_updates)
+                if self.conflates(ConflatedStream::MarkPriceUpdate) =>
+            {
+                let mut pending = self.mark_price_updates.lock().unwrap();
+                for mark_price_update in mark_price_updates.data {
+                    pending.insert(mark_price_update.symbol.clone(), (mark_price_update, true));
+                }
+                None
+            }
+            event => Some(event),
+        }
+    }
+
+    /// Takes the pending values of the stream type as events: one event per symbol for single
+    /// symbol streams and one array event for the values of all-market streams.
+    #[cfg(feature = "market-data")]
+    fn drain(&self, stream: ConflatedStream) -> Vec<Event> {
+        match stream {
+            ConflatedStream::BookTicker => {
+                drain(&self.book_tickers, Event::BookTickerEvent, |data| {
+                    Event::BookTickersEvent(BookTickers::new(data))
+                })
+            }
+            ConflatedStream::MarkPriceUpdate => drain(
+                &self.mark_price_updates,
+                Event::MarkPriceUpdateEvent,
+                |data| Event::MarkPriceUpdatesEvent(MarkPriceUpdates::new(data)),
+            ),
+        }
+    }
+
+    /// Without the `market-data` feature nothing is conflated.
+    #[cfg(not(feature = "market-data"))]
+    fn drain(&self, _stream: ConflatedStream) -> Vec<Event> {
+        Vec::new()
+    }
+
+    /// Spawns a thread per conflated stream type emitting its pending values at the interval,
+    /// until the stop signal is set or the receiver is dropped.
+    pub(crate) fn start(
+        conflator: &Arc<Conflator>,
+        sender: EventSender,
+        stop_signal: Arc<AtomicBool>,
+    ) {
+        for (stream, interval) in conflator.intervals.clone() {
+            let conflator: Arc<Conflator> = Arc::clone(conflator);
+            let sender: EventSender = sender.clone();
+            let stop_signal: Arc<AtomicBool> = Arc::clone(&stop_signal);
+            thread::spawn(move || {
+                let mut next_emit: Instant = Instant::now() + interval;
+                while !stop_signal.load(Ordering::Relaxed) {
+                    let now: Instant = Instant::now();
+                    if now < next_emit {
+                        thread::sleep((next_emit - now).min(STOP_CHECK_INTERVAL));
+                        continue;
+                    }
+                    next_emit = now + interval;
+                    for event in conflator.drain(stream) {
+                        if sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Takes the pending values, wrapping the single symbol values and the all-market values.
+#[cfg(feature = "market-data")]
+fn drain<T>(
+    pending: &Mutex<Pending<T>>,
+    single: impl Fn(T) -> Event,
+    all_market: impl Fn(Vec<T>) -> Event,
+) -> Vec<Event> {
+    let pending: Pending<T> = std::mem::take(&mut *pending.lock().unwrap());
+    let mut events: Vec<Event> = Vec::new();
+    let mut array: Vec<T> = Vec::new();
+    for (value, from_array) in pending.into_values() {
+        match from_array {
+            true => array.push(value),
+            false => events.push(single(value)),
+        }
+    }
+    if !array.is_empty() {
+        events.push(all_market(array));
+    }
+    events
+}
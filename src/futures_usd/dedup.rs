@@ -0,0 +1,189 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::ordering::update_ids;
+
+/// The time a frame forwarded from one redundant connection waits for its copy on the other.
+const REDUNDANT_FRAME_WINDOW: Duration = Duration::from_secs(10);
+
+/// Suppresses the frames of a replacement connection that were already received on the
+/// connection it replaces.
+///
+/// While the replacement is warmed and the expiring connection is drained the frames of the
+/// expiring connection are recorded. Once the events are read from the replacement, its frames
+/// that match a recorded frame are dropped for the suppression window. Frames are matched by
+/// their payload, every recorded frame suppresses a single frame of the replacement, so repeated
+/// identical frames are not lost.
+#[derive(Debug, Default)]
+pub(crate) struct DuplicateFilter {
+    /// The number of times each frame hash was received on the expiring connection.
+    recorded: HashMap<u64, usize>,
+    /// The end of the suppression window, `None` while recording.
+    until: Option<Instant>,
+}
+
+impl DuplicateFilter {
+    /// Records a frame of the expiring connection.
+    pub(crate) fn record(&mut self, frame: &str) {
+        self.until = None;
+        *self.recorded.entry(hash(frame)).or_insert(0) += 1;
+    }
+
+    /// Starts suppressing the recorded frames on the replacement for the window, after which the
+    /// recorded frames are forgotten.
+    pub(crate) fn suppress_for(&mut self, window: Duration) {
+        self.until = Some(Instant::now() + window);
+    }
+
+    /// Returns true when the frame of the replacement was already received on the expiring
+    /// connection.
+    pub(crate) fn is_duplicate(&mut self, frame: &str) -> bool {
+        match self.until {
+            Some(until) if Instant::now() < until => {}
+            Some(_) => {
+                self.recorded.clear();
+                self.until = None;
+                return false;
+            }
+            None => return false,
+        }
+        match self.recorded.get_mut(&hash(frame)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Matches the frames of the two connections of a redundant pair, so only the first copy of each
+/// frame is forwarded.
+///
+/// A forwarded frame waits for its copy on the other connection for `REDUNDANT_FRAME_WINDOW`,
+/// every forwarded frame suppresses a single copy, so repeated identical frames are not lost.
+#[derive(Debug)]
+pub(crate) struct RedundantFilter {
+    /// The forwarded frame hashes per connection with the times they were received, waiting for
+    /// their copy on the other connection.
+    unmatched: [HashMap<u64, VecDeque<Instant>>; 2],
+    /// The time the expired frames were last removed.
+    swept: Instant,
+}
+
+impl Default for RedundantFilter {
+    fn default() -> Self {
+        Self {
+            unmatched: [HashMap::new(), HashMap::new()],
+            swept: Instant::now(),
+        }
+    }
+}
+
+impl RedundantFilter {
+    /// Returns true when the frame of the connection (`0` or `1`) is a copy of a frame that was
+    /// already forwarded from the other connection.
+    pub(crate) fn is_duplicate(&mut self, connection: usize, frame: &str) -> bool {
+        let now: Instant = Instant::now();
+        if now.duration_since(self.swept) >= REDUNDANT_FRAME_WINDOW {
+            for unmatched in self.unmatched.iter_mut() {
+                unmatched.retain(|_, received| {
+                    received.retain(|at| now.duration_since(*at) < REDUNDANT_FRAME_WINDOW);
+                    !received.is_empty()
+                });
+            }
+            self.swept = now;
+        }
+        let hash: u64 = hash(frame);
+        if let Some(received) = self.unmatched[1 - connection].get_mut(&hash) {
+            while let Some(at) = received.pop_front() {
+                if now.duration_since(at) < REDUNDANT_FRAME_WINDOW {
+                    return true;
+                }
+            }
+        }
+        self.unmatched[connection]
+            .entry(hash)
+            .or_default()
+            .push_back(now);
+        false
+    }
+}
+
+/// Suppresses the events that are received again after a reconnect or on the other connection
+/// of a redundant pair, by stream and update id.
+///
+/// The last forwarded update id of every stream is tracked. An event is a duplicate when none of
+/// its update ids is newer than the forwarded ones, so trades are not counted twice. After a
+/// reconnect only the events of the window after the connection was established are checked, for
+/// a redundant pair every event is. Only book tickers and aggregated trades carry such an id;
+/// every other event, including depth updates (see `update_ids`), is forwarded.
+#[derive(Debug)]
+pub(crate) struct UpdateIdFilter {
+    /// The window after each connect, every event is checked when `None`.
+    window: Option<Duration>,
+    last_ids: HashMap<String, u64>,
+    /// The end of the window after the latest connection was established.
+    until: Option<Instant>,
+}
+
+impl UpdateIdFilter {
+    /// Creates a filter suppressing the duplicates for the window after each (re)connect.
+    pub(crate) fn new(window: Duration) -> UpdateIdFilter {
+        Self {
+            window: Some(window),
+            last_ids: HashMap::new(),
+            until: None,
+        }
+    }
+
+    /// Creates a filter suppressing the duplicates of a redundant pair, checking every event.
+    pub(crate) fn redundant() -> UpdateIdFilter {
+        Self {
+            window: None,
+            last_ids: HashMap::new(),
+            until: None,
+        }
+    }
+
+    /// Opens the window, called whenever a connection is established.
+    pub(crate) fn connected(&mut self) {
+        if let Some(window) = self.window {
+            self.until = Some(Instant::now() + window);
+        }
+    }
+
+    /// Returns true when the event is a duplicate within the window, otherwise records its update
+    /// ids as forwarded.
+    pub(crate) fn is_duplicate(&mut self, event: &Event) -> bool {
+        let ids: Vec<(String, u64)> = update_ids(event);
+        if ids.is_empty() {
+            return false;
+        }
+        let in_window: bool =
+            self.window.is_none() || self.until.map_or(false, |until| Instant::now() < until);
+        let duplicate: bool = in_window
+            && ids.iter().all(|(stream, id)| {
+                self.last_ids
+                    .get(stream)
+                    .map_or(false, |last_id| id <= last_id)
+            });
+        if !duplicate {
+            for (stream, id) in ids {
+                let last_id: &mut u64 = self.last_ids.entry(stream).or_insert(id);
+                *last_id = (*last_id).max(id);
+            }
+        }
+        duplicate
+    }
+}
+
+/// Hashes the payload of a frame.
+fn hash(frame: &str) -> u64 {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+    frame.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,18 +1,40 @@
-use log::info;
+#[cfg(feature = "market-data")]
+use serde::de::DeserializeOwned;
+use serde::de::IgnoredAny;
 use serde::ser::Error;
+use serde::Deserialize;
+use serde_json::value::RawValue;
 use serde_json::Value;
+use tracing::{info, warn};
 
+use crate::error::BinanceConnectError;
 use crate::futures_usd::enums::events::Event;
 use crate::futures_usd::enums::events::Event::*;
+#[cfg(any(
+    feature = "market-data",
+    feature = "user-data",
+    feature = "klines",
+    feature = "depth"
+))]
 use crate::futures_usd::enums::events::EventType::*;
+#[cfg(feature = "market-data")]
 use crate::futures_usd::response::{
-    AssetIndexUpdate, AssetIndexUpdates, BookTicker, BookTickers, EventTypeWrapper,
-    MarkPriceUpdate, MarkPriceUpdates, MiniTicker, MiniTickers, SubscribeResponse, Ticker, Tickers,
+    AssetIndexUpdate, AssetIndexUpdates, BookTicker, BookTickers, MarkPriceUpdate,
+    MarkPriceUpdates, MiniTicker, MiniTickers, Ticker, Tickers,
 };
+use crate::futures_usd::response::{
+    BinanceErrorMessage, ErrorResponse, EventTypeWrapper, PropertyValue, SubscribeResponse,
+    SubscriptionConfirmed,
+};
+
+/// The start of a response in the combined format.
+const COMBINED_PREFIX: &str = "{\"stream\":";
 
 /// Deserialize a JSON response into an Event.
 ///
-/// This function takes a JSON response as a String and attempts to deserialize it into an Event.
+/// This function takes a JSON response as a String and attempts to deserialize it into an Event,
+/// like the streams do. It allows running an own WebSocket transport or reading recorded payloads
+/// while still working with the typed events of this crate.
 ///
 /// # Arguments
 ///
@@ -20,101 +42,220 @@ use crate::futures_usd::response::{
 ///
 /// # Returns
 ///
-/// * A Result containing the deserialized Event or a serde_json::Error if deserialization fails.
+/// * A Result containing the deserialized Event or a `BinanceConnectError::JsonError` carrying the
+///   event type, stream and a snippet of the payload if deserialization fails.
+///
+pub fn deserialize(json_response: String) -> Result<Event, BinanceConnectError> {
+    deserialize_slice(json_response.as_bytes())
+}
+
+/// Deserialize a JSON response held in a byte buffer into an Event.
+///
+/// The payload is parsed in place with `serde_json::from_slice` and does not need to be an owned
+/// `String`, so a transport of its own can read the frames into a reused buffer. The connections
+/// of `FuturesUsdStream` still receive every frame as the owned `String` of tungstenite's
+/// `Message::Text`.
+///
+/// # Arguments
+///
+/// * `json_response` - A JSON response as UTF-8 bytes.
+///
+/// # Returns
 ///
-pub fn deserialize(json_response: String) -> Result<Event, serde_json::Error> {
+/// * A Result containing the deserialized Event or a `BinanceConnectError::JsonError` carrying the
+///   event type, stream and a snippet of the payload if deserialization fails.
+///
+pub fn deserialize_slice(json_response: &[u8]) -> Result<Event, BinanceConnectError> {
+    deserialize_event(json_response).map_err(|err| {
+        BinanceConnectError::json_error(err, &String::from_utf8_lossy(json_response))
+    })
+}
+
+/// A combined format response, the payload is deserialized by its event type.
+#[derive(Deserialize)]
+struct CombinedResponse<'a> {
+    stream: Option<String>,
+    #[serde(borrow)]
+    data: Option<&'a RawValue>,
+}
+
+/// Deserialize a JSON response into an Event, without error context.
+fn deserialize_event(json_response: &[u8]) -> Result<Event, serde_json::Error> {
+    // Unwrap the payload of the combined format
+    if json_response.starts_with(COMBINED_PREFIX.as_bytes()) {
+        if let Some(result) = try_deserialize_combined(json_response) {
+            return result;
+        }
+    }
     // Try to deserialize into EventTypeWrapper
-    if let Some(result) = try_deserialize_event_type_wrapper(&json_response) {
+    if let Some(result) = try_deserialize_event_type_wrapper(json_response) {
+        return result;
+    }
+    // Try to deserialize into an error of Binance, before the lenient SubscribeResponse
+    if let Some(result) = try_deserialize_error_message(json_response) {
         return result;
     }
     // Try to deserialize into SubscribeResponse
-    if let Some(result) = try_deserialize_subscribe_response(&json_response) {
+    if let Some(result) = try_deserialize_subscribe_response(json_response) {
         return result;
     }
     // Try to deserialize an anonymous array into EventTypeWrapper
-    if let Some(result) = try_deserialize_anonymous_array(&json_response) {
+    if let Some(result) = try_deserialize_anonymous_array(json_response) {
         return result;
     }
+    // Emit any other JSON as is, e.g. of a stream added with `with_raw_stream`
+    if serde_json::from_slice::<IgnoredAny>(json_response).is_ok() {
+        return Ok(Raw {
+            stream: None,
+            json: String::from_utf8_lossy(json_response).into_owned(),
+        });
+    }
     // Don't know what to do with response
-    Err(serde_json::Error::custom(format!(
-        "No deserializer for {:?}",
-        json_response
-    )))
+    Err(serde_json::Error::custom("No deserializer for payload"))
 }
 
 /// Try to deserialize a JSON response into an Event based on EventTypeWrapper.
 fn try_deserialize_event_type_wrapper(
-    json_response: &str,
+    json_response: &[u8],
 ) -> Option<Result<Event, serde_json::Error>> {
-    if let Ok(event_type_wrapper) = serde_json::from_str::<EventTypeWrapper>(json_response) {
+    if let Ok(event_type_wrapper) = serde_json::from_slice::<EventTypeWrapper>(json_response) {
         // Match the event_type field inside the EventTypeWrapper
-        return Some(match event_type_wrapper.event_type {
+        return match event_type_wrapper.event_type {
             /* MARKET DATA */
-            BookTickerEventType => Ok(BookTickerEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            AggTradeEventType => Ok(AggTradeEvent(serde_json::from_str(json_response).unwrap())),
-            MarkPriceUpdateEventType => Ok(MarkPriceUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            KlineEventType => Ok(KlineEvent(serde_json::from_str(json_response).unwrap())),
-            ContinuousKlineEventType => Ok(ContinuousKlineEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            MiniTickerEventType => Ok(MiniTickerEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            TickerEventType => Ok(TickerEvent(serde_json::from_str(json_response).unwrap())),
-            ForceOrderEventType => Ok(ForceOrderEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            BookDepthEventType => Ok(BookDepthEvent(serde_json::from_str(json_response).unwrap())),
-            CompositeIndexEventType => Ok(CompositeIndexEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            ContractInfoEventType => Ok(ContractInfoEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            AssetIndexUpdateEventType => Ok(AssetIndexUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
+            #[cfg(feature = "market-data")]
+            BookTickerEventType => Some(serde_json::from_slice(json_response).map(BookTickerEvent)),
+            #[cfg(feature = "market-data")]
+            AggTradeEventType => Some(serde_json::from_slice(json_response).map(AggTradeEvent)),
+            #[cfg(feature = "market-data")]
+            MarkPriceUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(MarkPriceUpdateEvent))
+            }
+            #[cfg(feature = "klines")]
+            KlineEventType => Some(serde_json::from_slice(json_response).map(KlineEvent)),
+            #[cfg(feature = "klines")]
+            ContinuousKlineEventType => {
+                Some(serde_json::from_slice(json_response).map(ContinuousKlineEvent))
+            }
+            #[cfg(feature = "market-data")]
+            MiniTickerEventType => Some(serde_json::from_slice(json_response).map(MiniTickerEvent)),
+            #[cfg(feature = "market-data")]
+            TickerEventType => Some(serde_json::from_slice(json_response).map(TickerEvent)),
+            #[cfg(feature = "market-data")]
+            ForceOrderEventType => Some(serde_json::from_slice(json_response).map(ForceOrderEvent)),
+            #[cfg(feature = "depth")]
+            BookDepthEventType => Some(serde_json::from_slice(json_response).map(BookDepthEvent)),
+            #[cfg(feature = "market-data")]
+            CompositeIndexEventType => {
+                Some(serde_json::from_slice(json_response).map(CompositeIndexEvent))
+            }
+            #[cfg(feature = "market-data")]
+            ContractInfoEventType => {
+                Some(serde_json::from_slice(json_response).map(ContractInfoEvent))
+            }
+            #[cfg(feature = "market-data")]
+            AssetIndexUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(AssetIndexUpdateEvent))
+            }
             /* USER DATA */
-            AccountUpdateEventType => Ok(AccountUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            OrderTradeUpdateEventType => Ok(OrderTradeUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            MarginCallEventType => Ok(MarginCallEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            AccountConfigUpdateEventType => Ok(AccountConfigUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            StrategyUpdateEventType => Ok(StrategyUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            GridUpdateEventType => Ok(GridUpdateEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-            ConditionalOrderTriggerRejectEventType => Ok(ConditionalOrderTriggerRejectEvent(
-                serde_json::from_str(json_response).unwrap(),
-            )),
-        });
+            #[cfg(feature = "user-data")]
+            AccountUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(AccountUpdateEvent))
+            }
+            #[cfg(feature = "user-data")]
+            OrderTradeUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(OrderTradeUpdateEvent))
+            }
+            #[cfg(feature = "user-data")]
+            MarginCallEventType => Some(serde_json::from_slice(json_response).map(MarginCallEvent)),
+            #[cfg(feature = "user-data")]
+            AccountConfigUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(AccountConfigUpdateEvent))
+            }
+            #[cfg(feature = "user-data")]
+            StrategyUpdateEventType => {
+                Some(serde_json::from_slice(json_response).map(StrategyUpdateEvent))
+            }
+            #[cfg(feature = "user-data")]
+            GridUpdateEventType => Some(serde_json::from_slice(json_response).map(GridUpdateEvent)),
+            #[cfg(feature = "user-data")]
+            ConditionalOrderTriggerRejectEventType => {
+                Some(serde_json::from_slice(json_response).map(ConditionalOrderTriggerRejectEvent))
+            }
+            // The family is disabled, the payload is emitted as `Raw`
+            #[allow(unreachable_patterns)]
+            _ => None,
+        };
     }
     None
 }
 
+/// Try to deserialize a JSON response into a BinanceErrorMessage, either of a request (wrapped in
+/// an `error` field) or on its own.
+fn try_deserialize_error_message(json_response: &[u8]) -> Option<Result<Event, serde_json::Error>> {
+    let error_message: BinanceErrorMessage =
+        match serde_json::from_slice::<ErrorResponse>(json_response) {
+            Ok(error_response) => BinanceErrorMessage {
+                id: error_response.id,
+                ..error_response.error
+            },
+            Err(_) => serde_json::from_slice::<BinanceErrorMessage>(json_response).ok()?,
+        };
+    warn!(
+        code = error_message.code,
+        msg = %error_message.msg,
+        id = ?error_message.id,
+        "futures_usd error message received"
+    );
+    Some(Ok(ErrorMessageEvent(error_message)))
+}
+
+/// Try to deserialize the payload of a combined format response, `{"stream":..,"data":..}`.
+fn try_deserialize_combined(json_response: &[u8]) -> Option<Result<Event, serde_json::Error>> {
+    // The payload is borrowed from the response, not parsed into a `Value` and serialized again
+    let combined: CombinedResponse = serde_json::from_slice(json_response).ok()?;
+    let data: &RawValue = combined.data?;
+    Some(
+        deserialize_event(data.get().as_bytes()).map(|event| match event {
+            // The wrapper holds the name of the raw stream
+            Raw { stream: None, json } => Raw {
+                stream: combined.stream,
+                json,
+            },
+            event => event,
+        }),
+    )
+}
+
 /// Try to deserialize a JSON response into a SubscribeResponse.
 fn try_deserialize_subscribe_response(
-    json_response: &String,
+    json_response: &[u8],
 ) -> Option<Result<Event, serde_json::Error>> {
     // Try to deserialize the JSON response into a SubscribeResponse
-    if let Ok(subscribe_response) = serde_json::from_str::<SubscribeResponse>(&json_response) {
-        // Check if the SubscribeResponse contains an 'id' field
-        if let Some(id) = subscribe_response.id {
-            // Log a message indicating a futures_usd subscription request was received with the 'id'
-            info!("futures_usd subscription request received ({})", id);
+    if let Ok(subscribe_response) = serde_json::from_slice::<SubscribeResponse>(json_response) {
+        // Any object matches the lenient SubscribeResponse, a response holds an 'id' or 'result'
+        if subscribe_response.id.is_none() && subscribe_response.result.is_none() {
+            return None;
+        }
+        // A request with an 'id' and without result is confirmed, the streams are filled in by
+        // the connection that sent the request.
+        if let (Some(id), None) = (subscribe_response.id, &subscribe_response.result) {
+            info!(id = id, "futures_usd subscription request confirmed");
+            return Some(Ok(SubscriptionConfirmedEvent(SubscriptionConfirmed {
+                id,
+                method: String::new(),
+                streams: Vec::new(),
+            })));
+        }
+        // A boolean result answers a GET_PROPERTY request, the property is filled in by the
+        // connection that sent the request.
+        if let (Some(id), Some(Value::Bool(value))) =
+            (subscribe_response.id, &subscribe_response.result)
+        {
+            return Some(Ok(PropertyEvent(PropertyValue {
+                id,
+                property: None,
+                value: *value,
+            })));
         }
         // Return a Some variant containing the deserialized SubscribeResponseE event
         return Some(Ok(SubscribeResponseEvent));
@@ -124,62 +265,45 @@ fn try_deserialize_subscribe_response(
 
 /// Try to deserialize anonymous array and convert it into an Event.
 fn try_deserialize_anonymous_array(
-    json_response: &String,
+    json_response: &[u8],
 ) -> Option<Result<Event, serde_json::Error>> {
-    // Deserialize the JSON response into a serde_json::Value and handle errors
-    let value: Value = serde_json::from_str(json_response).map_err(Some).ok()?;
-    // Check if the deserialized value is an array
-    if let Some(arr) = value.as_array() {
-        // Check if the array contains at least one item
-        if let Some(first_item) = arr.get(0) {
-            // Try to deserialize the first item into an EventTypeWrapper
-            if let Ok(event_type_wrapper) =
-                serde_json::from_value::<EventTypeWrapper>(first_item.clone())
-            {
-                match event_type_wrapper.event_type {
-                    MarkPriceUpdateEventType => {
-                        let market_price_updates: Vec<MarkPriceUpdate> = arr
-                            .iter()
-                            .map(|item| serde_json::from_value(item.clone()).unwrap())
-                            .collect();
-                        return Some(Ok(MarkPriceUpdatesEvent(MarkPriceUpdates {
-                            data: market_price_updates,
-                        })));
-                    }
-                    MiniTickerEventType => {
-                        let mini_tickers: Vec<MiniTicker> = arr
-                            .iter()
-                            .map(|item| serde_json::from_value(item.clone()).unwrap())
-                            .collect();
-                        return Some(Ok(MiniTickersEvent(MiniTickers { data: mini_tickers })));
-                    }
-                    TickerEventType => {
-                        let tickers: Vec<Ticker> = arr
-                            .iter()
-                            .map(|item| serde_json::from_value(item.clone()).unwrap())
-                            .collect();
-                        return Some(Ok(TickersEvent(Tickers { data: tickers })));
-                    }
-                    BookTickerEventType => {
-                        let book_tickers: Vec<BookTicker> = arr
-                            .iter()
-                            .map(|item| serde_json::from_value(item.clone()).unwrap())
-                            .collect();
-                        return Some(Ok(BookTickersEvent(BookTickers { data: book_tickers })));
-                    }
-                    AssetIndexUpdateEventType => {
-                        let asset_index_updates: Vec<AssetIndexUpdate> = arr
-                            .iter()
-                            .map(|item| serde_json::from_value(item.clone()).unwrap())
-                            .collect();
-                        return Some(Ok(AssetIndexUpdatesEvent(AssetIndexUpdates {
-                            data: asset_index_updates,
-                        })));
-                    }
-                    _ => {}
-                }
-            }
+    // Split the array into the borrowed items, each is parsed once into its type
+    let arr: Vec<&RawValue> = serde_json::from_slice(json_response).ok()?;
+    // Try to deserialize the first item into an EventTypeWrapper
+    let first_item: &RawValue = arr.first()?;
+    let event_type_wrapper: EventTypeWrapper = serde_json::from_str(first_item.get()).ok()?;
+    match event_type_wrapper.event_type {
+        #[cfg(feature = "market-data")]
+        MarkPriceUpdateEventType => Some(
+            from_items::<MarkPriceUpdate>(&arr)
+                .map(|data| MarkPriceUpdatesEvent(MarkPriceUpdates { data })),
+        ),
+        #[cfg(feature = "market-data")]
+        MiniTickerEventType => {
+            Some(from_items::<MiniTicker>(&arr).map(|data| MiniTickersEvent(MiniTickers { data })))
         }
+        #[cfg(feature = "market-data")]
+        TickerEventType => {
+            Some(from_items::<Ticker>(&arr).map(|data| TickersEvent(Tickers { data })))
+        }
+        #[cfg(feature = "market-data")]
+        BookTickerEventType => {
+            Some(from_items::<BookTicker>(&arr).map(|data| BookTickersEvent(BookTickers { data })))
+        }
+        #[cfg(feature = "market-data")]
+        AssetIndexUpdateEventType => Some(
+            from_items::<AssetIndexUpdate>(&arr)
+                .map(|data| AssetIndexUpdatesEvent(AssetIndexUpdates { data })),
+        ),
+        _ => None,
     }
-    None
+}
+
+/// Deserialize every item of an anonymous array.
+#[cfg(feature = "market-data")]
+fn from_items<T: DeserializeOwned>(items: &[&RawValue]) -> Result<Vec<T>, serde_json::Error> {
+    items
+        .iter()
+        .map(|item| serde_json::from_str(item.get()))
+        .collect()
 }
@@ -0,0 +1,211 @@
+#[cfg(feature = "native")]
+use std::collections::HashMap;
+#[cfg(feature = "native")]
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::futures_usd::reconnect::{DisconnectReason, ReconnectDecision};
+#[cfg(feature = "native")]
+use crate::futures_usd::status::now_millis;
+
+/// The number of reports kept for retrieval via `FuturesUsdStream::downtime_reports`.
+#[cfg(feature = "native")]
+const MAX_REPORTS: usize = 100;
+
+/// An action taken to recover from a connectivity incident. The missed events are not
+/// backfilled, the gaps are reported so the application can request them via REST.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum RecoveryAction {
+    /// The connection was re-established following the reconnect decision.
+    Reconnect(ReconnectDecision),
+    /// The warm standby connection was promoted.
+    StandbyPromoted,
+    /// The latest-value cache was invalidated until fresh data arrived.
+    LatestCacheInvalidated,
+}
+
+/// The gap in the events of a single stream during an incident.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StreamGap {
+    /// The stream, `SYMBOL@eventType` or `!eventType` for all-market streams.
+    pub stream: String,
+    /// The local time (UNIX millis) of the last event before the disconnect.
+    pub gap_start: u64,
+    /// The local time (UNIX millis) at which the connection was re-established.
+    pub gap_end: u64,
+    /// The number of events missed, estimated from the event rate before the incident.
+    pub estimated_missed_events: u64,
+}
+
+/// A structured report of a connectivity incident (one or more reconnects in a row).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DowntimeReport {
+    /// The local time (UNIX millis) of the first disconnect.
+    pub started: u64,
+    /// The local time (UNIX millis) at which the connection was re-established, closing the
+    /// incident.
+    pub ended: u64,
+    /// The reasons of the disconnects, in order.
+    pub reasons: Vec<DisconnectReason>,
+    pub gaps: Vec<StreamGap>,
+    pub actions: Vec<RecoveryAction>,
+}
+
+/// The event statistics of a stream.
+#[cfg(feature = "native")]
+#[derive(Debug)]
+struct StreamState {
+    first_event: u64,
+    last_event: u64,
+    events: u64,
+}
+
+#[cfg(feature = "native")]
+impl StreamState {
+    /// Returns the events per millisecond before the incident.
+    fn rate(&self) -> f64 {
+        match self.last_event.saturating_sub(self.first_event) {
+            0 => 0.0,
+            duration => (self.events - 1) as f64 / duration as f64,
+        }
+    }
+}
+
+/// An incident that has not been closed yet.
+#[cfg(feature = "native")]
+#[derive(Debug)]
+struct Incident {
+    started: u64,
+    reasons: Vec<DisconnectReason>,
+    actions: Vec<RecoveryAction>,
+    /// The time (UNIX millis) of the last event and the event rate per stream before the
+    /// incident.
+    streams: Vec<(String, u64, f64)>,
+}
+
+/// Tracks the event rate per stream of a connection and reports the gaps of incidents.
+#[cfg(feature = "native")]
+#[derive(Debug)]
+pub(crate) struct DowntimeTracker {
+    state: Mutex<TrackerState>,
+    /// The reports of all connections of the stream.
+    reports: Arc<Mutex<Vec<DowntimeReport>>>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Default)]
+struct TrackerState {
+    streams: HashMap<String, StreamState>,
+    incident: Option<Incident>,
+}
+
+#[cfg(feature = "native")]
+impl DowntimeTracker {
+    /// Creates a tracker storing its reports in the shared report list.
+    pub(crate) fn new(reports: Arc<Mutex<Vec<DowntimeReport>>>) -> DowntimeTracker {
+        Self {
+            state: Mutex::new(TrackerState::default()),
+            reports,
+        }
+    }
+
+    /// Records an event of the stream of the payload.
+    pub(crate) fn event_received(&self, json_response: &str) {
+        let stream: String = match stream_key(json_response) {
+            Some(stream) => stream,
+            None => return,
+        };
+        let now: u64 = now_millis();
+        let mut state = self.state.lock().unwrap();
+        let stream_state: &mut StreamState = state.streams.entry(stream).or_insert(StreamState {
+            first_event: now,
+            last_event: now,
+            events: 0,
+        });
+        stream_state.last_event = now;
+        stream_state.events += 1;
+    }
+
+    /// Records the re-established connection, returning the report of the open incident.
+    pub(crate) fn reconnected(&self) -> Option<DowntimeReport> {
+        let incident: Incident = self.state.lock().unwrap().incident.take()?;
+        let report: DowntimeReport = Self::report(incident, now_millis());
+        let mut reports = self.reports.lock().unwrap();
+        if reports.len() == MAX_REPORTS {
+            reports.remove(0);
+        }
+        reports.push(report.clone());
+        Some(report)
+    }
+
+    /// Records a disconnect, opening an incident when none is open.
+    pub(crate) fn disconnected(&self, reason: DisconnectReason) {
+        let now: u64 = now_millis();
+        let mut state = self.state.lock().unwrap();
+        let TrackerState { streams, incident } = &mut *state;
+        let incident: &mut Incident = incident.get_or_insert_with(|| Incident {
+            started: now,
+            reasons: Vec::new(),
+            actions: Vec::new(),
+            streams: streams
+                .iter()
+                .map(|(stream, stream_state)| {
+                    (stream.clone(), stream_state.last_event, stream_state.rate())
+                })
+                .collect(),
+        });
+        incident.reasons.push(reason);
+    }
+
+    /// Records an action taken to recover from the open incident.
+    pub(crate) fn action_taken(&self, action: RecoveryAction) {
+        if let Some(incident) = self.state.lock().unwrap().incident.as_mut() {
+            incident.actions.push(action);
+        }
+    }
+
+    /// Closes the incident, estimating the missed events of every stream from its event rate.
+    fn report(incident: Incident, now: u64) -> DowntimeReport {
+        let mut gaps: Vec<StreamGap> = incident
+            .streams
+            .into_iter()
+            .map(|(stream, gap_start, rate)| StreamGap {
+                stream,
+                gap_start,
+                gap_end: now,
+                estimated_missed_events: (now.saturating_sub(gap_start) as f64 * rate) as u64,
+            })
+            .collect();
+        gaps.sort_by(|a, b| a.stream.cmp(&b.stream));
+        DowntimeReport {
+            started: incident.started,
+            ended: now,
+            reasons: incident.reasons,
+            gaps,
+            actions: incident.actions,
+        }
+    }
+}
+
+/// Returns the stream of the payload, `SYMBOL@eventType` or `!eventType` for arrays, using a
+/// lightweight scan of the first event type and symbol.
+#[cfg(feature = "native")]
+fn stream_key(json_response: &str) -> Option<String> {
+    let event_type: &str = scan_string(json_response, "\"e\":\"")?;
+    match json_response.trim_start().starts_with('[') {
+        true => Some(format!("!{}", event_type)),
+        false => Some(format!(
+            "{}@{}",
+            scan_string(json_response, "\"s\":\"").unwrap_or_default(),
+            event_type
+        )),
+    }
+}
+
+/// Returns the string value following the first occurrence of the pattern.
+#[cfg(feature = "native")]
+fn scan_string<'a>(json: &'a str, pattern: &str) -> Option<&'a str> {
+    let value: &str = &json[json.find(pattern)? + pattern.len()..];
+    Some(&value[..value.find('"')?])
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+use std::str::FromStr;
+
+use url::Url;
+
+use crate::constants;
+use crate::error::BinanceConnectError;
+
+/// The WebSocket endpoint a `FuturesWebSocketConfig` connects to.
+///
+/// Every endpoint belongs to an environment, the REST requests of the stream (listen keys,
+/// account snapshots) go to the REST API of that environment, so the WebSocket and REST
+/// environments can not be mixed up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Endpoint {
+    /// The Binance Futures market, `wss://fstream.binance.com`.
+    #[default]
+    Production,
+    /// The Binance Futures testnet, `wss://stream.binancefuture.com`.
+    Testnet,
+    /// The authenticated Binance Futures streams, `wss://fstream-auth.binance.com`.
+    AuthPortal,
+    /// Any other WebSocket URL of the production environment, e.g. a regional host, a proxy or a
+    /// `MockServer`.
+    Custom(Url),
+}
+
+impl Endpoint {
+    /// Creates a `Custom` endpoint, only accepting WebSocket (`ws`/`wss`) URLs.
+    ///
+    /// # Returns
+    ///
+    /// The `Endpoint` or a `BinanceConnectError` when the URL can not be parsed or is not a
+    /// WebSocket URL.
+    ///
+    pub fn custom(url: &str) -> Result<Endpoint, BinanceConnectError> {
+        Ok(Endpoint::Custom(websocket_url(url)?))
+    }
+
+    /// Returns the WebSocket URL of the endpoint.
+    pub fn ws_url(&self) -> Url {
+        match self {
+            Endpoint::Production => constants::WS_FUTURES_URL.clone(),
+            Endpoint::Testnet => constants::WS_FUTURES_TESTNET_URL.clone(),
+            Endpoint::AuthPortal => constants::WS_FUTURES_AUTH_URL.clone(),
+            Endpoint::Custom(url) => url.clone(),
+        }
+    }
+
+    /// Returns true when the endpoint belongs to the testnet environment.
+    pub fn is_testnet(&self) -> bool {
+        matches!(self, Endpoint::Testnet)
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = BinanceConnectError;
+
+    /// Parses `production`, `testnet`, `auth_portal` or a WebSocket URL.
+    fn from_str(endpoint: &str) -> Result<Self, Self::Err> {
+        match endpoint {
+            "production" => Ok(Endpoint::Production),
+            "testnet" => Ok(Endpoint::Testnet),
+            "auth_portal" => Ok(Endpoint::AuthPortal),
+            url => Endpoint::custom(url),
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ws_url())
+    }
+}
+
+/// Parses the URL, only accepting WebSocket (`ws`/`wss`) URLs.
+pub(crate) fn websocket_url(url: &str) -> Result<Url, BinanceConnectError> {
+    let url: Url = Url::parse(url)?;
+    match url.scheme() {
+        "ws" | "wss" => Ok(url),
+        scheme => Err(BinanceConnectError::Other(format!(
+            "Unsupported WebSocket URL scheme {:?}",
+            scheme
+        ))),
+    }
+}
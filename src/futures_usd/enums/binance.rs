@@ -1,9 +1,14 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
+use crate::error::BinanceConnectError;
+
 /// Holds all the enums used by and with Binance operations
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum StrategyStatus {
     #[serde(rename = "NEW")]
     New,
@@ -13,6 +18,9 @@ pub enum StrategyStatus {
     Cancelled,
     #[serde(rename = "EXPIRED")]
     Expired,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl StrategyStatus {
@@ -22,11 +30,13 @@ impl StrategyStatus {
             StrategyStatus::Working => "WORKING",
             StrategyStatus::Cancelled => "CANCELLED",
             StrategyStatus::Expired => "EXPIRED",
+            StrategyStatus::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum AccountUpdateReason {
     #[serde(rename = "DEPOSIT")]
     Deposit,
@@ -62,6 +72,9 @@ pub enum AccountUpdateReason {
     CoinSwapDeposit,
     #[serde(rename = "COIN_SWAP_WITHDRAW")]
     CoinSwapWithdraw,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl AccountUpdateReason {
@@ -84,15 +97,16 @@ impl AccountUpdateReason {
             AccountUpdateReason::AutoExchange => "AUTO_EXCHANGE",
             AccountUpdateReason::CoinSwapDeposit => "COIN_SWAP_DEPOSIT",
             AccountUpdateReason::CoinSwapWithdraw => "COIN_SWAP_WITHDRAW",
+            AccountUpdateReason::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
 pub enum MarginType {
     #[serde(rename = "isolated")]
     Isolated,
-    #[serde(rename = "crossed")]
+    #[serde(rename = "crossed", alias = "cross")]
     Crossed,
 }
 
@@ -105,9 +119,11 @@ impl MarginType {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum PriceMatch {
     #[serde(rename = "NONE")]
+    #[default]
     None,
     #[serde(rename = "OPPONENT")]
     Opponent,
@@ -125,6 +141,9 @@ pub enum PriceMatch {
     Queue10,
     #[serde(rename = "QUEUE_20")]
     Queue20,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl PriceMatch {
@@ -139,13 +158,16 @@ impl PriceMatch {
             PriceMatch::Queue5 => "QUEUE_5",
             PriceMatch::Queue10 => "QUEUE_10",
             PriceMatch::Queue20 => "QUEUE_20",
+            PriceMatch::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum StpMode {
     #[serde(rename = "NONE")]
+    #[default]
     None,
     #[serde(rename = "EXPIRE_TAKER")]
     ExpireTaker,
@@ -153,6 +175,9 @@ pub enum StpMode {
     ExpireBoth,
     #[serde(rename = "EXPIRE_MAKER")]
     ExpireMaker,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl StpMode {
@@ -162,11 +187,13 @@ impl StpMode {
             StpMode::ExpireTaker => "EXPIRE_TAKER",
             StpMode::ExpireBoth => "EXPIRE_BOTH",
             StpMode::ExpireMaker => "EXPIRE_MAKER",
+            StpMode::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum ContractType {
     #[serde(rename = "PERPETUAL")]
     Perpetual,
@@ -180,6 +207,9 @@ pub enum ContractType {
     NextQuarter,
     #[serde(rename = "PERPETUAL_DELIVERING")]
     PerpetualDelivering,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl ContractType {
@@ -191,11 +221,13 @@ impl ContractType {
             ContractType::CurrentQuarter => "CURRENT_QUARTER",
             ContractType::NextQuarter => "NEXT_QUARTER",
             ContractType::PerpetualDelivering => "PERPETUAL_DELIVERING",
+            ContractType::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum ContractStatus {
     #[serde(rename = "PENDING_TRADING")]
     PendingTrading,
@@ -213,6 +245,9 @@ pub enum ContractStatus {
     Settling,
     #[serde(rename = "CLOSE")]
     Close,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl ContractStatus {
@@ -226,11 +261,13 @@ impl ContractStatus {
             ContractStatus::PreSettle => "PRE_SETTLE",
             ContractStatus::Settling => "SETTLING",
             ContractStatus::Close => "CLOSE",
+            ContractStatus::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum OrderStatus {
     #[serde(rename = "NEW")]
     New,
@@ -244,6 +281,9 @@ pub enum OrderStatus {
     Rejected,
     #[serde(rename = "EXPIRED")]
     Expired,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl OrderStatus {
@@ -255,16 +295,21 @@ impl OrderStatus {
             OrderStatus::Canceled => "CANCELED",
             OrderStatus::Rejected => "REJECTED",
             OrderStatus::Expired => "EXPIRED",
+            OrderStatus::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum WorkingType {
     #[serde(rename = "MARK_PRICE")]
     MarkPrice,
     #[serde(rename = "CONTRACT_PRICE")]
     ContractPrice,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl WorkingType {
@@ -272,17 +317,22 @@ impl WorkingType {
         match self {
             WorkingType::MarkPrice => "MARK_PRICE",
             WorkingType::ContractPrice => "CONTRACT_PRICE",
+            WorkingType::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum TimeInForce {
     GTC,
     IOC,
     FOK,
     GTX,
     GTD,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl TimeInForce {
@@ -293,11 +343,13 @@ impl TimeInForce {
             TimeInForce::FOK => "FOK",
             TimeInForce::GTX => "GTX",
             TimeInForce::GTD => "GTD",
+            TimeInForce::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum ExecutionType {
     #[serde(rename = "NEW")]
     New,
@@ -311,6 +363,9 @@ pub enum ExecutionType {
     Trade,
     #[serde(rename = "AMENDMENT")]
     Amendment,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl ExecutionType {
@@ -322,11 +377,13 @@ impl ExecutionType {
             ExecutionType::Expired => "EXPIRED",
             ExecutionType::Trade => "TRADE",
             ExecutionType::Amendment => "AMENDMENT",
+            ExecutionType::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
+#[non_exhaustive]
 pub enum OrderType {
     #[serde(rename = "LIMIT")]
     Limit,
@@ -342,6 +399,9 @@ pub enum OrderType {
     TakeProfitMarket,
     #[serde(rename = "TRAILING_STOP_MARKET")]
     TrailingStopMarket,
+    /// A value added by Binance after this version.
+    #[serde(other, rename = "UNKNOWN")]
+    Unknown,
 }
 
 impl OrderType {
@@ -354,11 +414,12 @@ impl OrderType {
             OrderType::TakeProfit => "TAKE_PROFIT",
             OrderType::TakeProfitMarket => "TAKE_PROFIT_MARKET",
             OrderType::TrailingStopMarket => "TRAILING_STOP_MARKET",
+            OrderType::Unknown => "UNKNOWN",
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
 pub enum Side {
     #[serde(rename = "BUY")]
     Buy,
@@ -375,7 +436,7 @@ impl Side {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq, Eq, Hash)]
 pub enum PositionSide {
     #[serde(rename = "LONG")]
     Long,
@@ -397,7 +458,23 @@ impl PositionSide {
 
 /* CONFIG */
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+/// A property of a WebSocket connection, see `FuturesUsdStream::set_property`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub enum StreamProperty {
+    /// Wraps every payload as `{"stream":<stream name>,"data":<payload>}`.
+    #[serde(rename = "combined")]
+    Combined,
+}
+
+impl StreamProperty {
+    pub fn to_str(&self) -> &str {
+        match self {
+            StreamProperty::Combined => "combined",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum MarkPriceUpdateSpeed {
     #[serde(rename = "1s")]
     Seconds1,
@@ -414,7 +491,8 @@ impl MarkPriceUpdateSpeed {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+/// The intervals of the kline streams, ordered from short to long.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum KlineInterval {
     #[serde(rename = "1m")]
     Minutes1,
@@ -468,9 +546,70 @@ impl KlineInterval {
             KlineInterval::Months1 => "1M",
         }
     }
+
+    /// Returns every supported interval, from short to long.
+    pub fn iter() -> impl Iterator<Item = KlineInterval> {
+        KLINE_INTERVALS.iter().copied()
+    }
+
+    /// Returns the length of the interval. A month is taken as 30 days, the actual klines follow
+    /// the calendar months.
+    pub fn as_duration(&self) -> Duration {
+        let minutes: u64 = match self {
+            KlineInterval::Minutes1 => 1,
+            KlineInterval::Minutes3 => 3,
+            KlineInterval::Minutes5 => 5,
+            KlineInterval::Minutes15 => 15,
+            KlineInterval::Minutes30 => 30,
+            KlineInterval::Hours1 => 60,
+            KlineInterval::Hours2 => 2 * 60,
+            KlineInterval::Hours4 => 4 * 60,
+            KlineInterval::Hours6 => 6 * 60,
+            KlineInterval::Hours8 => 8 * 60,
+            KlineInterval::Hours12 => 12 * 60,
+            KlineInterval::Days1 => 24 * 60,
+            KlineInterval::Days3 => 3 * 24 * 60,
+            KlineInterval::Weeks1 => 7 * 24 * 60,
+            KlineInterval::Months1 => 30 * 24 * 60,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+
+    /// Returns the interval of exactly the duration.
+    ///
+    /// # Arguments
+    ///
+    /// - `duration`: The length of the interval, e.g. `Duration::from_secs(900)`.
+    ///
+    /// # Returns
+    ///
+    /// The `KlineInterval` or `None` when Binance has no interval of the duration.
+    ///
+    pub fn from_duration(duration: Duration) -> Option<KlineInterval> {
+        KlineInterval::iter().find(|interval| interval.as_duration() == duration)
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+/// The supported kline intervals, from short to long.
+const KLINE_INTERVALS: [KlineInterval; 15] = [
+    KlineInterval::Minutes1,
+    KlineInterval::Minutes3,
+    KlineInterval::Minutes5,
+    KlineInterval::Minutes15,
+    KlineInterval::Minutes30,
+    KlineInterval::Hours1,
+    KlineInterval::Hours2,
+    KlineInterval::Hours4,
+    KlineInterval::Hours6,
+    KlineInterval::Hours8,
+    KlineInterval::Hours12,
+    KlineInterval::Days1,
+    KlineInterval::Days3,
+    KlineInterval::Weeks1,
+    KlineInterval::Months1,
+];
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum KlineContractType {
     #[serde(rename = "PERPETUAL")]
     Perpetual,
@@ -490,7 +629,7 @@ impl KlineContractType {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum PartialBookDepthLevel {
     #[serde(rename = "5")]
     Five,
@@ -508,15 +647,42 @@ impl PartialBookDepthLevel {
             PartialBookDepthLevel::Twenty => "20",
         }
     }
+
+    /// Returns the depth level for the number of levels, Binance only accepts 5, 10 and 20
+    /// levels for partial book depth streams (use the diff. book depth stream for a deeper book).
+    ///
+    /// # Arguments
+    ///
+    /// - `levels`: The number of bids and asks.
+    ///
+    /// # Returns
+    ///
+    /// The `PartialBookDepthLevel` or a `BinanceConnectError::InvalidStreamConfig` if the number
+    /// of levels is not accepted by Binance.
+    ///
+    pub fn from_levels(levels: u16) -> Result<Self, BinanceConnectError> {
+        match levels {
+            5 => Ok(PartialBookDepthLevel::Five),
+            10 => Ok(PartialBookDepthLevel::Ten),
+            20 => Ok(PartialBookDepthLevel::Twenty),
+            _ => Err(BinanceConnectError::InvalidStreamConfig {
+                stream: "partial book depth".to_string(),
+                reason: format!("{} levels is not supported, use 5, 10 or 20", levels),
+            }),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+/// The update speed of the (partial) book depth streams, Binance pushes every 250 milliseconds
+/// when the stream name has no speed suffix.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub enum BookDepthUpdateSpeed {
     /// Updates every 100 milliseconds.
     #[serde(rename = "100ms")]
     Millis100,
-    /// Updates every 250 milliseconds.
+    /// Updates every 250 milliseconds, the default speed of Binance.
     #[serde(rename = "250ms")]
+    #[default]
     Millis250,
     /// Updates every 500 milliseconds.
     #[serde(rename = "500ms")]
@@ -531,4 +697,39 @@ impl BookDepthUpdateSpeed {
             BookDepthUpdateSpeed::Millis500 => "500ms",
         }
     }
+
+    /// Returns the stream name suffix of the speed, empty for the default speed because Binance
+    /// does not accept an explicit `@250ms`.
+    pub fn stream_suffix(&self) -> String {
+        match self {
+            BookDepthUpdateSpeed::Millis250 => String::new(),
+            speed => format!("@{}", speed.to_str()),
+        }
+    }
+
+    /// Returns the update speed for the interval in milliseconds.
+    ///
+    /// # Arguments
+    ///
+    /// - `millis`: The update interval in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// The `BookDepthUpdateSpeed` or a `BinanceConnectError::InvalidStreamConfig` if the interval
+    /// is not accepted by Binance.
+    ///
+    pub fn from_millis(millis: u64) -> Result<Self, BinanceConnectError> {
+        match millis {
+            100 => Ok(BookDepthUpdateSpeed::Millis100),
+            250 => Ok(BookDepthUpdateSpeed::Millis250),
+            500 => Ok(BookDepthUpdateSpeed::Millis500),
+            _ => Err(BinanceConnectError::InvalidStreamConfig {
+                stream: "book depth".to_string(),
+                reason: format!(
+                    "{}ms update speed is not supported, use 100, 250 or 500",
+                    millis
+                ),
+            }),
+        }
+    }
 }
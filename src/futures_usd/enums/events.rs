@@ -1,42 +1,140 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
+use crate::futures_usd::alert::Alert;
+use crate::futures_usd::candle::CandleBoundary;
+use crate::futures_usd::downtime::DowntimeReport;
+use crate::futures_usd::funding::{FundingAlert, FundingCountdown};
+use crate::futures_usd::listing::NewListing;
+use crate::futures_usd::reconnect::{
+    ConnectionClosed, ConnectionFailed, ListenKeyRefreshFailed, ReconnectTrace,
+};
 use crate::futures_usd::response::*;
+use crate::futures_usd::sequence::Sequenced;
+use crate::futures_usd::skew::SkewAlert;
 
 /// Holds all the Events send within the library
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Event {
     /* MARKET_DATA */
+    #[cfg(feature = "market-data")]
     BookTickerEvent(BookTicker),
+    #[cfg(feature = "market-data")]
     BookTickersEvent(BookTickers),
+    #[cfg(feature = "market-data")]
     AggTradeEvent(AggTrade),
+    #[cfg(feature = "market-data")]
     MarkPriceUpdateEvent(MarkPriceUpdate),
+    #[cfg(feature = "market-data")]
     MarkPriceUpdatesEvent(MarkPriceUpdates),
+    #[cfg(feature = "klines")]
     KlineEvent(Kline),
+    #[cfg(feature = "klines")]
     ContinuousKlineEvent(ContinuousKline),
+    #[cfg(feature = "market-data")]
     MiniTickerEvent(MiniTicker),
+    #[cfg(feature = "market-data")]
     MiniTickersEvent(MiniTickers),
+    #[cfg(feature = "market-data")]
     TickerEvent(Ticker),
+    #[cfg(feature = "market-data")]
     TickersEvent(Tickers),
+    #[cfg(feature = "market-data")]
     ForceOrderEvent(ForceOrder),
+    #[cfg(feature = "depth")]
     BookDepthEvent(BookDepth),
+    #[cfg(feature = "market-data")]
     CompositeIndexEvent(CompositeIndex),
+    #[cfg(feature = "market-data")]
     ContractInfoEvent(ContractInfo),
+    #[cfg(feature = "market-data")]
     AssetIndexUpdateEvent(AssetIndexUpdate),
+    #[cfg(feature = "market-data")]
     AssetIndexUpdatesEvent(AssetIndexUpdates),
     /* USER_DATA */
+    #[cfg(feature = "user-data")]
     OrderTradeUpdateEvent(OrderTradeUpdate),
+    #[cfg(feature = "user-data")]
     AccountUpdateEvent(AccountUpdate),
+    #[cfg(feature = "user-data")]
     MarginCallEvent(MarginCall),
+    #[cfg(feature = "user-data")]
     AccountConfigUpdateEvent(AccountConfigUpdate),
+    #[cfg(feature = "user-data")]
     StrategyUpdateEvent(StrategyUpdate),
+    #[cfg(feature = "user-data")]
     GridUpdateEvent(GridUpdate),
+    #[cfg(feature = "user-data")]
     ConditionalOrderTriggerRejectEvent(ConditionalOrderTriggerReject),
+    AccountSnapshotEvent(AccountSnapshot),
+    /* SYSTEM */
     SubscribeResponseEvent,
+    SubscriptionConfirmedEvent(SubscriptionConfirmed),
+    PropertyEvent(PropertyValue),
+    ErrorMessageEvent(BinanceErrorMessage),
+    ConnectionClosedEvent(ConnectionClosed),
+    ReconnectEvent(ReconnectTrace),
+    ConnectionFailedEvent(ConnectionFailed),
+    ListenKeyRefreshFailedEvent(ListenKeyRefreshFailed),
+    SkewAlertEvent(SkewAlert),
+    DowntimeReportEvent(DowntimeReport),
+    FundingAlertEvent(FundingAlert),
+    NewListingEvent(NewListing),
+    AlertEvent(Alert),
+    CandleBoundaryEvent(CandleBoundary),
+    FundingCountdownEvent(FundingCountdown),
+    /* DEBUG */
+    WithRawEvent(WithRaw),
+    /* DELIVERY */
+    SequencedEvent(Sequenced<Box<Event>>),
+    /* FALLBACK */
+    /// A payload without first-class type, e.g. of a stream added with `with_raw_stream`.
+    Raw {
+        /// The stream name, `None` unless received in the combined format.
+        stream: Option<String>,
+        /// The JSON payload.
+        json: String,
+    },
+}
+
+impl Event {
+    /// Splits the event into the decoded event and the JSON it was decoded from, which is only
+    /// attached when the stream is configured `with_raw_json`.
+    ///
+    /// # Returns
+    ///
+    /// The unwrapped event and the raw JSON, `None` for an event without attachment.
+    ///
+    pub fn into_parts(self) -> (Event, Option<Arc<str>>) {
+        match self {
+            Event::WithRawEvent(with_raw) => (*with_raw.event, Some(with_raw.raw)),
+            event => (event, None),
+        }
+    }
+
+    /// Returns the sequence number the event was stamped with, `None` unless the stream is
+    /// configured `with_sequence_wal`.
+    pub fn sequence(&self) -> Option<u64> {
+        match self {
+            Event::SequencedEvent(sequenced) => Some(sequenced.sequence),
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON the event was decoded from, `None` for an event without attachment.
+    pub fn raw(&self) -> Option<&str> {
+        match self {
+            Event::WithRawEvent(with_raw) => Some(&with_raw.raw),
+            Event::SequencedEvent(sequenced) => sequenced.event.raw(),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, EnumString, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
 pub enum EventType {
     /* MARKET_DATA */
     #[serde(rename = "bookTicker")]
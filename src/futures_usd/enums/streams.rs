@@ -6,6 +6,7 @@ use crate::futures_usd::enums::streams::Streams::*;
 
 /// Holds all the streams used in the Futures USD environment of Binance
 
+#[allow(clippy::empty_line_after_doc_comments)]
 const STREAM_BOOK_TICKER: &str = "@bookTicker";
 const STREAM_BOOK_TICKERS: &str = "!bookTicker";
 const STREAM_AGG_TRADE: &str = "@aggTrade";
@@ -26,7 +27,9 @@ const STREAM_CONTRACT_INFO: &str = "!contractInfo";
 const STREAM_ASSET_INDEX_UPDATE: &str = "@assetIndex";
 const STREAM_ASSET_INDEX_UPDATES: &str = "!assetIndex@arr";
 
-#[derive(Debug)]
+/// A stream of Binance, built with the constructors, e.g. `Streams::book_ticker("btcusdt")`, and
+/// added with `FuturesUsdStream::with_streams`.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Streams {
     BookTicker(String),
     BookTickers(String),
@@ -47,6 +50,8 @@ pub enum Streams {
     ContractInfo(String),
     AssetIndexUpdate(String),
     AssetIndexUpdates(String),
+    /// A stream without first-class type, e.g. released by Binance after this version.
+    Raw(String),
 }
 
 impl Streams {
@@ -139,10 +144,7 @@ impl Streams {
         book_depth_level: PartialBookDepthLevel,
         book_depth_update_speed: BookDepthUpdateSpeed,
     ) -> Self {
-        let mut update_speed: String = String::new();
-        if book_depth_update_speed != BookDepthUpdateSpeed::Millis250 {
-            update_speed = format!("@{}", book_depth_update_speed.to_str());
-        }
+        let update_speed: String = book_depth_update_speed.stream_suffix();
         PartialBookDepth(format!(
             "{}{}{}{}",
             symbol.to_lowercase(),
@@ -153,10 +155,7 @@ impl Streams {
     }
 
     pub fn book_depth(symbol: &str, book_depth_update_speed: BookDepthUpdateSpeed) -> Self {
-        let mut update_speed: String = String::new();
-        if book_depth_update_speed != BookDepthUpdateSpeed::Millis250 {
-            update_speed = format!("@{}", book_depth_update_speed.to_str());
-        }
+        let update_speed: String = book_depth_update_speed.stream_suffix();
         BookDepth(format!(
             "{}{}{}",
             symbol.to_lowercase(),
@@ -189,6 +188,10 @@ impl Streams {
         AssetIndexUpdates(STREAM_ASSET_INDEX_UPDATES.to_string())
     }
 
+    pub fn raw(stream: &str) -> Self {
+        Raw(stream.to_string())
+    }
+
     pub fn to_str(&self) -> &str {
         match self {
             BookTicker(stream) => stream.as_str(),
@@ -210,6 +213,7 @@ impl Streams {
             ContractInfo(stream) => stream.as_str(),
             AssetIndexUpdate(stream) => stream.as_str(),
             AssetIndexUpdates(stream) => stream.as_str(),
+            Raw(stream) => stream.as_str(),
         }
     }
 }
@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::futures_usd::enums::events::{Event, EventType};
+
+/// A user-supplied predicate deciding whether an event is forwarded.
+type Predicate = Arc<dyn Fn(&Event) -> bool + Send + Sync>;
+
+/// A filter applied on the connection thread before events are sent over the channel, reducing
+/// the cross-thread traffic of high-volume (all-market) streams.
+///
+/// The declarative conditions only apply to the events having the filtered property, e.g. the
+/// symbols do not filter account updates and the minimum quantity only filters aggregate trades,
+/// force orders and order updates. The items of all-market events are filtered individually.
+/// System events (reconnects, skew alerts, ...) are never filtered by the declarative conditions.
+#[derive(Clone, Default)]
+pub struct EventFilter {
+    symbols: Option<HashSet<String>>,
+    event_types: Option<Vec<EventType>>,
+    min_quantity: Option<f64>,
+    predicate: Option<Predicate>,
+}
+
+impl EventFilter {
+    /// Creates a filter forwarding every event.
+    pub fn new() -> EventFilter {
+        Self::default()
+    }
+
+    /// Only forwards the market data events of the symbols.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbols`: The symbols to forward, e.g. `BTCUSDT`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the symbols set.
+    ///
+    pub fn with_symbols(mut self, symbols: &[&str]) -> Self {
+        self.symbols = Some(symbols.iter().map(|symbol| symbol.to_uppercase()).collect());
+        self
+    }
+
+    /// Only forwards the events of the event types.
+    ///
+    /// # Arguments
+    ///
+    /// - `event_types`: The event types to forward, e.g. `EventType::AggTradeEventType`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the event types set.
+    ///
+    pub fn with_event_types(mut self, event_types: Vec<EventType>) -> Self {
+        self.event_types = Some(event_types);
+        self
+    }
+
+    /// Only forwards aggregate trades, force orders and order updates with at least the quantity.
+    ///
+    /// # Arguments
+    ///
+    /// - `min_quantity`: The minimum (last filled) quantity in the base asset.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the minimum quantity set.
+    ///
+    pub fn with_min_quantity(mut self, min_quantity: f64) -> Self {
+        self.min_quantity = Some(min_quantity);
+        self
+    }
+
+    /// Only forwards the events for which the predicate returns `true`, evaluated after the
+    /// declarative conditions.
+    ///
+    /// # Arguments
+    ///
+    /// - `predicate`: The predicate, it runs on the connection thread and should return quickly.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the predicate set.
+    ///
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(&Event) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Applies the filter, returning `None` when the event (or every item of it) is dropped.
+    #[cfg(feature = "native")]
+    pub(crate) fn apply(&self, event: Event) -> Option<Event> {
+        let event: Event = match event {
+            #[cfg(feature = "market-data")]
+            Event::BookTickersEvent(mut book_tickers) => {
+                book_tickers.data.retain(|item| self.symbol(&item.symbol));
+                non_empty(
+                    book_tickers.data.len(),
+                    Event::BookTickersEvent(book_tickers),
+                )?
+            }
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdatesEvent(mut mark_price_updates) => {
+                mark_price_updates
+                    .data
+                    .retain(|item| self.symbol(&item.symbol));
+                non_empty(
+                    mark_price_updates.data.len(),
+                    Event::MarkPriceUpdatesEvent(mark_price_updates),
+                )?
+            }
+            #[cfg(feature = "market-data")]
+            Event::MiniTickersEvent(mut mini_tickers) => {
+                mini_tickers.data.retain(|item| self.symbol(&item.symbol));
+                non_empty(
+                    mini_tickers.data.len(),
+                    Event::MiniTickersEvent(mini_tickers),
+                )?
+            }
+            #[cfg(feature = "market-data")]
+            Event::TickersEvent(mut tickers) => {
+                tickers.data.retain(|item| self.symbol(&item.symbol));
+                non_empty(tickers.data.len(), Event::TickersEvent(tickers))?
+            }
+            event => event,
+        };
+        let matches: bool = self.event_type(&event)
+            && symbol(&event).map_or(true, |symbol| self.symbol(symbol))
+            && quantity(&event).map_or(true, |quantity| {
+                self.min_quantity
+                    .map_or(true, |min_quantity| quantity >= min_quantity)
+            })
+            && self
+                .predicate
+                .as_ref()
+                .map_or(true, |predicate| predicate(&event));
+        match matches {
+            true => Some(event),
+            false => None,
+        }
+    }
+
+    /// Returns whether the symbol passes the filter.
+    #[cfg(feature = "native")]
+    fn symbol(&self, symbol: &str) -> bool {
+        self.symbols
+            .as_ref()
+            .map_or(true, |symbols| symbols.contains(symbol))
+    }
+
+    /// Returns whether the event type of the event passes the filter.
+    #[cfg(feature = "native")]
+    fn event_type(&self, event: &Event) -> bool {
+        match (&self.event_types, event_type(event)) {
+            (Some(event_types), Some(event_type)) => event_types.contains(event_type),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Debug for EventFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventFilter")
+            .field("symbols", &self.symbols)
+            .field("event_types", &self.event_types)
+            .field("min_quantity", &self.min_quantity)
+            .field("predicate", &self.predicate.is_some())
+            .finish()
+    }
+}
+
+/// Returns the event, or `None` when all its items were dropped.
+#[cfg(all(feature = "native", feature = "market-data"))]
+fn non_empty(len: usize, event: Event) -> Option<Event> {
+    match len {
+        0 => None,
+        _ => Some(event),
+    }
+}
+
+/// Returns the symbol of a single-symbol market data event or order update.
+#[cfg(feature = "native")]
+fn symbol(event: &Event) -> Option<&str> {
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::BookTickerEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdateEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "klines")]
+        Event::KlineEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "klines")]
+        Event::ContinuousKlineEvent(event) => Some(&event.pair),
+        #[cfg(feature = "market-data")]
+        Event::MiniTickerEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::TickerEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::ForceOrderEvent(event) => Some(&event.order.symbol),
+        #[cfg(feature = "depth")]
+        Event::BookDepthEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::CompositeIndexEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "market-data")]
+        Event::ContractInfoEvent(event) => Some(&event.symbol),
+        #[cfg(feature = "user-data")]
+        Event::OrderTradeUpdateEvent(event) => Some(&event.order_data.symbol),
+        _ => None,
+    }
+}
+
+/// Returns the quantity of aggregate trades, force orders and order updates.
+#[cfg(feature = "native")]
+fn quantity(event: &Event) -> Option<f64> {
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(event) => Some(event.quantity),
+        #[cfg(feature = "market-data")]
+        Event::ForceOrderEvent(event) => event.order.original_quantity.parse().ok(),
+        #[cfg(feature = "user-data")]
+        Event::OrderTradeUpdateEvent(event) => Some(event.order_data.order_last_filled_quantity),
+        _ => None,
+    }
+}
+
+/// Returns the event type of a market data or user data event, `None` for system events.
+#[cfg(feature = "native")]
+fn event_type(event: &Event) -> Option<&EventType> {
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::BookTickerEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::BookTickersEvent(event) => event.data.first().map(|item| &item.event_type),
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdatesEvent(event) => event.data.first().map(|item| &item.event_type),
+        #[cfg(feature = "klines")]
+        Event::KlineEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "klines")]
+        Event::ContinuousKlineEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::MiniTickerEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::MiniTickersEvent(event) => event.data.first().map(|item| &item.event_type),
+        #[cfg(feature = "market-data")]
+        Event::TickerEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::TickersEvent(event) => event.data.first().map(|item| &item.event_type),
+        #[cfg(feature = "market-data")]
+        Event::ForceOrderEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "depth")]
+        Event::BookDepthEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::CompositeIndexEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::ContractInfoEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdatesEvent(event) => event.data.first().map(|item| &item.event_type),
+        #[cfg(feature = "user-data")]
+        Event::OrderTradeUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::AccountUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::MarginCallEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::AccountConfigUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::StrategyUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::GridUpdateEvent(event) => Some(&event.event_type),
+        #[cfg(feature = "user-data")]
+        Event::ConditionalOrderTriggerRejectEvent(event) => Some(&event.event_type),
+        _ => None,
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::Duration;
+
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::replay::ReplayStream;
+use crate::futures_usd::stream::FuturesUsdStream;
+
+/// A source of `Event`s, implemented by the live `FuturesUsdStream`, the `ReplayStream` and the
+/// `SyntheticSource`, so strategy code written against it runs live or simulated.
+pub trait EventSource {
+    /// Returns the receiver of the events.
+    fn receiver(&self) -> &Receiver<Event>;
+
+    /// Stops the source.
+    fn stop(&self);
+
+    /// Blocks until the next event, `None` once the source is exhausted or stopped.
+    fn next_event(&self) -> Option<Event> {
+        self.receiver().recv().ok()
+    }
+
+    /// Returns the next event if one is available, `None` when none is pending or the source
+    /// is exhausted.
+    fn try_next_event(&self) -> Option<Event> {
+        match self.receiver().try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Blocks until the next event or the timeout, `None` on timeout or once the source is
+    /// exhausted.
+    fn next_event_timeout(&self, timeout: Duration) -> Option<Event> {
+        match self.receiver().recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl EventSource for FuturesUsdStream {
+    fn receiver(&self) -> &Receiver<Event> {
+        self.consume()
+    }
+
+    fn stop(&self) {
+        FuturesUsdStream::stop(self)
+    }
+}
+
+impl EventSource for ReplayStream {
+    fn receiver(&self) -> &Receiver<Event> {
+        self.consume()
+    }
+
+    fn stop(&self) {
+        ReplayStream::stop(self)
+    }
+}
+
+/// A backtest source emitting programmatically created events, e.g. generated or loaded with
+/// `HistoricalData`.
+#[derive(Debug)]
+pub struct SyntheticSource {
+    receiver: Receiver<Event>,
+}
+
+impl SyntheticSource {
+    /// Creates a source emitting the events in order, after which it is exhausted.
+    pub fn new(events: impl IntoIterator<Item = Event>) -> SyntheticSource {
+        let (sender, source) = Self::channel();
+        for event in events {
+            // The receiver is held by the source, sending can not fail.
+            let _ = sender.send(event);
+        }
+        source
+    }
+
+    /// Creates a source emitting the events sent with the returned sender, it is exhausted once
+    /// the sender is dropped.
+    pub fn channel() -> (Sender<Event>, SyntheticSource) {
+        let (sender, receiver) = channel();
+        (sender, Self { receiver })
+    }
+}
+
+impl EventSource for SyntheticSource {
+    fn receiver(&self) -> &Receiver<Event> {
+        &self.receiver
+    }
+
+    /// A synthetic source is stopped by dropping its sender.
+    fn stop(&self) {}
+}
@@ -0,0 +1,96 @@
+#[cfg(feature = "native")]
+use std::fmt;
+#[cfg(feature = "native")]
+use std::sync::Arc;
+
+use serde::de::value::Error as ValueError;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+
+use crate::futures_usd::enums::binance::OrderStatus;
+
+/// The event type marker of order update frames.
+const ORDER_TRADE_UPDATE: &str = "\"e\":\"ORDER_TRADE_UPDATE\"";
+/// The start of the order object of order update frames.
+const ORDER_OBJECT: &str = "\"o\":{";
+
+/// The fields of an `ORDER_TRADE_UPDATE` frame extracted by the pre-parse scanner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillNotice {
+    pub symbol: String,
+    pub order_id: i64,
+    pub status: OrderStatus,
+}
+
+impl FillNotice {
+    /// Extracts the symbol, order id and status of an `ORDER_TRADE_UPDATE` frame with a
+    /// lightweight scanner, without deserializing the frame.
+    ///
+    /// # Arguments
+    ///
+    /// - `json_response`: The raw frame.
+    ///
+    /// # Returns
+    ///
+    /// The `FillNotice`, or `None` when the frame is not an order update or a field is missing.
+    ///
+    pub fn scan(json_response: &str) -> Option<FillNotice> {
+        if !json_response.contains(ORDER_TRADE_UPDATE) {
+            return None;
+        }
+        let order: &str = &json_response[json_response.find(ORDER_OBJECT)? + ORDER_OBJECT.len()..];
+        let status: &str = string_field(order, "X")?;
+        Some(FillNotice {
+            symbol: string_field(order, "s")?.to_string(),
+            order_id: number_field(order, "i")?.parse().ok()?,
+            status: OrderStatus::deserialize(status.into_deserializer())
+                .map_err(|_: ValueError| ())
+                .ok()?,
+        })
+    }
+}
+
+/// Returns the value of the first `"key":"value"` string field of the JSON fragment.
+fn string_field<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let pattern: String = format!("\"{}\":\"", key);
+    let value: &str = &json[json.find(&pattern)? + pattern.len()..];
+    Some(&value[..value.find('"')?])
+}
+
+/// Returns the value of the first `"key":number` field of the JSON fragment.
+fn number_field<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let pattern: String = format!("\"{}\":", key);
+    let value: &str = &json[json.find(&pattern)? + pattern.len()..];
+    let end: usize = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .unwrap_or(value.len());
+    Some(&value[..end])
+}
+
+/// The urgent callback invoked with the `FillNotice` of every order update before it is
+/// deserialized, see `FuturesWebSocketConfig::with_fill_callback`.
+#[cfg(feature = "native")]
+#[derive(Clone)]
+pub struct FillCallback(Arc<dyn Fn(&FillNotice) + Send + Sync>);
+
+#[cfg(feature = "native")]
+impl FillCallback {
+    /// Wraps the callback, it runs on the connection thread and should return quickly.
+    pub fn new(callback: impl Fn(&FillNotice) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Invokes the callback when the frame is an order update.
+    pub(crate) fn notify(&self, json_response: &str) {
+        if let Some(fill_notice) = FillNotice::scan(json_response) {
+            (self.0)(&fill_notice);
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl fmt::Debug for FillCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FillCallback")
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::MarkPriceUpdate;
+
+/// The funding interval (8 hours) assumed until a symbol's interval has been observed.
+#[cfg(feature = "market-data")]
+const DEFAULT_FUNDING_INTERVAL_MS: i64 = 8 * 60 * 60 * 1000;
+
+/// The number of milliseconds in a year (365 days).
+const YEAR_MS: f64 = 365.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// The funding state of a single symbol.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FundingState {
+    pub symbol: String,
+    /// The current (predicted) funding rate of the next funding.
+    pub funding_rate: f64,
+    /// The time (UNIX millis) of the next funding.
+    pub next_funding_time: i64,
+    /// The funding interval in milliseconds, observed from the advancing next funding time.
+    pub funding_interval_ms: i64,
+    pub mark_price: f64,
+}
+
+impl FundingState {
+    /// Returns the funding rate annualized over the funding interval (simple, not compounded).
+    pub fn annualized_rate(&self) -> f64 {
+        self.funding_rate * YEAR_MS / self.funding_interval_ms as f64
+    }
+
+    /// Returns the time in milliseconds until the next funding, relative to the time (UNIX millis).
+    pub fn until_funding_ms(&self, now: i64) -> i64 {
+        (self.next_funding_time - now).max(0)
+    }
+}
+
+/// Raised when the absolute funding rate of a symbol crosses the alert threshold.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FundingAlert {
+    pub symbol: String,
+    pub funding_rate: f64,
+    pub threshold: f64,
+    /// A flag indicating whether the absolute funding rate rose above (`true`) or fell back
+    /// below (`false`) the threshold.
+    pub above: bool,
+}
+
+/// Raised when the time until the next funding of a symbol falls below a configured countdown.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FundingCountdown {
+    pub symbol: String,
+    /// The time (UNIX millis) of the funding counted down to.
+    pub next_funding_time: i64,
+    /// The configured countdown in milliseconds, e.g. `300000` for 5 minutes before funding.
+    pub countdown_ms: i64,
+    /// The time in milliseconds until the funding at the event time of the mark price update.
+    pub remaining_ms: i64,
+    pub funding_rate: f64,
+}
+
+/// Tracks the funding rate, next funding time and funding interval per symbol, fed by
+/// `MarkPriceUpdate` events, and raises a `FundingAlert` when the absolute funding rate crosses
+/// the optional alert threshold and a `FundingCountdown` when the next funding comes within one
+/// of the optional countdowns.
+#[derive(Debug, Clone, Default)]
+pub struct FundingTracker {
+    alert_threshold: Option<f64>,
+    /// The countdowns in milliseconds, from long to short.
+    countdowns: Vec<i64>,
+    states: HashMap<String, FundingState>,
+    /// The funding time and shortest countdown raised per symbol.
+    #[cfg(feature = "market-data")]
+    counted_down: HashMap<String, (i64, i64)>,
+    /// The countdowns raised since they were last drained.
+    pending_countdowns: Vec<FundingCountdown>,
+}
+
+impl FundingTracker {
+    /// Creates an empty tracker without alert threshold.
+    pub fn new() -> FundingTracker {
+        Self::default()
+    }
+
+    /// Sets the absolute funding rate (e.g. `0.001` for 0.1%) above which a `FundingAlert` is raised.
+    ///
+    /// # Arguments
+    ///
+    /// - `alert_threshold`: The absolute funding rate threshold.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the alert threshold set.
+    ///
+    pub fn with_alert_threshold(mut self, alert_threshold: f64) -> Self {
+        self.alert_threshold = Some(alert_threshold.abs());
+        self
+    }
+
+    /// Sets the times before the funding at which a `FundingCountdown` is raised, e.g. 5 minutes
+    /// and 30 seconds. The countdowns are evaluated at every mark price update, so they are raised
+    /// at the first update within the countdown (every 1 or 3 seconds), also when the tracker
+    /// starts within it. Every countdown is raised once per funding time.
+    ///
+    /// # Arguments
+    ///
+    /// - `countdowns`: The times before the funding.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the countdowns set.
+    ///
+    pub fn with_countdowns(mut self, countdowns: &[Duration]) -> Self {
+        self.countdowns = countdowns
+            .iter()
+            .map(|countdown| countdown.as_millis() as i64)
+            .collect();
+        self.countdowns.sort_unstable_by(|a, b| b.cmp(a));
+        self.countdowns.dedup();
+        self
+    }
+
+    /// Updates the funding states with the mark price events, other events are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// - `event`: The event, a `MarkPriceUpdateEvent` or `MarkPriceUpdatesEvent` is tracked.
+    ///
+    /// # Returns
+    ///
+    /// The alerts raised by the update.
+    ///
+    pub fn observe(&mut self, event: &Event) -> Vec<FundingAlert> {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdateEvent(mark_price_update) => {
+                self.update(mark_price_update).into_iter().collect()
+            }
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdatesEvent(mark_price_updates) => mark_price_updates
+                .data
+                .iter()
+                .filter_map(|mark_price_update| self.update(mark_price_update))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Updates the funding state of the symbol, returning the alert when the threshold is crossed.
+    #[cfg(feature = "market-data")]
+    pub fn update(&mut self, mark_price_update: &MarkPriceUpdate) -> Option<FundingAlert> {
+        let previous: Option<FundingState> = self.states.get(&mark_price_update.symbol).cloned();
+        let funding_interval_ms: i64 = match &previous {
+            Some(previous) if mark_price_update.next_funding_time > previous.next_funding_time => {
+                mark_price_update.next_funding_time - previous.next_funding_time
+            }
+            Some(previous) => previous.funding_interval_ms,
+            None => DEFAULT_FUNDING_INTERVAL_MS,
+        };
+        self.states.insert(
+            mark_price_update.symbol.clone(),
+            FundingState {
+                symbol: mark_price_update.symbol.clone(),
+                funding_rate: mark_price_update.funding_rate,
+                next_funding_time: mark_price_update.next_funding_time,
+                funding_interval_ms,
+                mark_price: mark_price_update.mark_price,
+            },
+        );
+        self.count_down(mark_price_update);
+
+        let threshold: f64 = self.alert_threshold?;
+        let above: bool = mark_price_update.funding_rate.abs() > threshold;
+        let was_above: bool =
+            previous.map_or(false, |previous| previous.funding_rate.abs() > threshold);
+        match above != was_above {
+            true => Some(FundingAlert {
+                symbol: mark_price_update.symbol.clone(),
+                funding_rate: mark_price_update.funding_rate,
+                threshold,
+                above,
+            }),
+            false => None,
+        }
+    }
+
+    /// Raises the countdowns the next funding of the symbol came within.
+    #[cfg(feature = "market-data")]
+    fn count_down(&mut self, mark_price_update: &MarkPriceUpdate) {
+        let remaining_ms: i64 = mark_price_update.next_funding_time - mark_price_update.event_time;
+        if self.countdowns.is_empty() || remaining_ms <= 0 {
+            return;
+        }
+        let shortest: i64 = match self.counted_down.get(&mark_price_update.symbol) {
+            Some((funding_time, shortest))
+                if *funding_time == mark_price_update.next_funding_time =>
+            {
+                *shortest
+            }
+            _ => i64::MAX,
+        };
+        for countdown_ms in &self.countdowns {
+            if remaining_ms <= *countdown_ms && *countdown_ms < shortest {
+                self.pending_countdowns.push(FundingCountdown {
+                    symbol: mark_price_update.symbol.clone(),
+                    next_funding_time: mark_price_update.next_funding_time,
+                    countdown_ms: *countdown_ms,
+                    remaining_ms,
+                    funding_rate: mark_price_update.funding_rate,
+                });
+                self.counted_down.insert(
+                    mark_price_update.symbol.clone(),
+                    (mark_price_update.next_funding_time, *countdown_ms),
+                );
+            }
+        }
+    }
+
+    /// Returns the countdowns raised by the updates since the last call, see `with_countdowns`.
+    pub fn drain_countdowns(&mut self) -> Vec<FundingCountdown> {
+        std::mem::take(&mut self.pending_countdowns)
+    }
+
+    /// Returns the funding state of the symbol, `None` when no mark price update was received.
+    pub fn state(&self, symbol: &str) -> Option<&FundingState> {
+        self.states.get(&symbol.to_uppercase())
+    }
+
+    /// Returns the funding states of all symbols.
+    pub fn states(&self) -> &HashMap<String, FundingState> {
+        &self.states
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tracing::{debug, error, info};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::status::{ConnectorStatus, StatusSnapshot};
+
+/// The time a connection may take to send its request head, so an idle connection (e.g. a TCP
+/// probe of a load balancer) does not block the requests after it.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Starts a tiny HTTP server exposing the `ConnectorStatus` as JSON.
+///
+/// Every request, regardless of method or path, is answered with the current `StatusSnapshot`.
+/// The status code is `200 OK` while connected and `503 Service Unavailable` otherwise, so the
+/// endpoint can be used directly as a liveness/readiness probe.
+///
+/// # Arguments
+///
+/// - `addr`: The local address to bind to, e.g. `0.0.0.0:8080`.
+/// - `status`: The shared `ConnectorStatus` to expose.
+///
+pub fn serve(addr: &str, status: Arc<ConnectorStatus>) -> Result<(), BinanceConnectError> {
+    let listener: TcpListener = TcpListener::bind(addr)
+        .map_err(|err| BinanceConnectError::Other(format!("healthcheck bind error: {}", err)))?;
+    info!(addr = %addr, "healthcheck listening");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(err) = respond(stream, &status) {
+                        debug!(error = %err, "healthcheck response failed");
+                    }
+                }
+                Err(err) => error!(error = %err, "healthcheck connection failed"),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Reads the request head and writes the status response.
+fn respond(mut stream: TcpStream, status: &ConnectorStatus) -> std::io::Result<()> {
+    // Consume the request head; its content is irrelevant.
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader: BufReader<&TcpStream> = BufReader::new(&stream);
+    let mut line: String = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let snapshot: StatusSnapshot = status.snapshot();
+    let body: String = serde_json::to_string(&snapshot).unwrap_or_default();
+    let status_line: &str = if snapshot.connected {
+        "200 OK"
+    } else {
+        "503 Service Unavailable"
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_line,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
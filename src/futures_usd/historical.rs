@@ -0,0 +1,300 @@
+use std::io::{Cursor, Read};
+use std::str::FromStr;
+
+use reqwest::blocking::Response;
+use zip::ZipArchive;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::enums::binance::KlineInterval;
+use crate::futures_usd::enums::events::EventType;
+#[cfg(feature = "klines")]
+use crate::futures_usd::response::KlineData;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::{AggTrade, BookTicker};
+use crate::futures_usd::tls;
+
+/// The base URL of the public historical data.
+const BASE_URL_HISTORICAL_DATA: &str = "https://data.binance.vision";
+
+/// The period covered by a historical data file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoricalPeriod {
+    /// A single day, formatted as `YYYY-MM-DD`, e.g. `2024-01-31`.
+    Daily(String),
+    /// A single month, formatted as `YYYY-MM`, e.g. `2024-01`.
+    Monthly(String),
+}
+
+impl HistoricalPeriod {
+    /// Returns the path segment of the period.
+    fn frequency(&self) -> &str {
+        match self {
+            HistoricalPeriod::Daily(_) => "daily",
+            HistoricalPeriod::Monthly(_) => "monthly",
+        }
+    }
+
+    /// Returns the date of the period as used in the file name.
+    fn date(&self) -> &str {
+        match self {
+            HistoricalPeriod::Daily(date) | HistoricalPeriod::Monthly(date) => date,
+        }
+    }
+}
+
+/// Downloads and parses the public historical USD-M futures data files of data.binance.vision
+/// into the typed structs of the streams, for backfills and research.
+///
+/// The files are zipped CSVs, with or without header line depending on their age. Fields that
+/// the files do not contain (e.g. the trade ids of a kline) are zero.
+#[derive(Debug, Clone)]
+pub struct HistoricalData {
+    base_url: String,
+}
+
+impl Default for HistoricalData {
+    fn default() -> Self {
+        Self {
+            base_url: BASE_URL_HISTORICAL_DATA.to_string(),
+        }
+    }
+}
+
+impl HistoricalData {
+    /// Creates a downloader using data.binance.vision.
+    pub fn new() -> HistoricalData {
+        Self::default()
+    }
+
+    /// Sets the base URL, e.g. of a mirror.
+    ///
+    /// # Arguments
+    ///
+    /// - `base_url`: The base URL, without trailing slash.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the base URL set.
+    ///
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Downloads the aggregate trades of the symbol.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: The symbol, e.g. `BTCUSDT`.
+    /// - `period`: The day or month to download.
+    ///
+    /// # Returns
+    ///
+    /// The `AggTrade`s or a `BinanceConnectError` if the download or parsing failed.
+    ///
+    #[cfg(feature = "market-data")]
+    pub fn agg_trades(
+        &self,
+        symbol: &str,
+        period: &HistoricalPeriod,
+    ) -> Result<Vec<AggTrade>, BinanceConnectError> {
+        let symbol: String = symbol.to_uppercase();
+        let url: String = format!(
+            "{}/data/futures/um/{}/aggTrades/{}/{}-aggTrades-{}.zip",
+            self.base_url,
+            period.frequency(),
+            symbol,
+            symbol,
+            period.date()
+        );
+        parse_agg_trades(&symbol, &download_csv(&url)?)
+    }
+
+    /// Downloads the klines of the symbol and interval.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: The symbol, e.g. `BTCUSDT`.
+    /// - `interval`: The kline interval.
+    /// - `period`: The day or month to download.
+    ///
+    /// # Returns
+    ///
+    /// The closed `KlineData` or a `BinanceConnectError` if the download or parsing failed.
+    ///
+    #[cfg(feature = "klines")]
+    pub fn klines(
+        &self,
+        symbol: &str,
+        interval: &KlineInterval,
+        period: &HistoricalPeriod,
+    ) -> Result<Vec<KlineData>, BinanceConnectError> {
+        let symbol: String = symbol.to_uppercase();
+        let url: String = format!(
+            "{}/data/futures/um/{}/klines/{}/{}/{}-{}-{}.zip",
+            self.base_url,
+            period.frequency(),
+            symbol,
+            interval.to_str(),
+            symbol,
+            interval.to_str(),
+            period.date()
+        );
+        parse_klines(&symbol, interval, &download_csv(&url)?)
+    }
+
+    /// Downloads the best bid and ask updates of the symbol.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: The symbol, e.g. `BTCUSDT`.
+    /// - `period`: The day or month to download.
+    ///
+    /// # Returns
+    ///
+    /// The `BookTicker`s or a `BinanceConnectError` if the download or parsing failed.
+    ///
+    #[cfg(feature = "market-data")]
+    pub fn book_tickers(
+        &self,
+        symbol: &str,
+        period: &HistoricalPeriod,
+    ) -> Result<Vec<BookTicker>, BinanceConnectError> {
+        let symbol: String = symbol.to_uppercase();
+        let url: String = format!(
+            "{}/data/futures/um/{}/bookTicker/{}/{}-bookTicker-{}.zip",
+            self.base_url,
+            period.frequency(),
+            symbol,
+            symbol,
+            period.date()
+        );
+        parse_book_tickers(&symbol, &download_csv(&url)?)
+    }
+}
+
+/// Parses an aggregate trades CSV (`agg_trade_id,price,quantity,first_trade_id,last_trade_id,
+/// transact_time,is_buyer_maker`).
+#[cfg(feature = "market-data")]
+pub fn parse_agg_trades(symbol: &str, csv: &str) -> Result<Vec<AggTrade>, BinanceConnectError> {
+    rows(csv)
+        .map(|row| {
+            let trade_time: u64 = field(&row, 5)?;
+            Ok(AggTrade {
+                event_type: EventType::AggTradeEventType,
+                event_time: trade_time,
+                symbol: symbol.to_string(),
+                agg_trade_id: field(&row, 0)?,
+                price: field(&row, 1)?,
+                quantity: field(&row, 2)?,
+                first_trade_id: field(&row, 3)?,
+                last_trade_id: field(&row, 4)?,
+                trade_time,
+                buyer_is_market_maker: field::<String>(&row, 6)?.eq_ignore_ascii_case("true"),
+            })
+        })
+        .collect()
+}
+
+/// Parses a klines CSV (`open_time,open,high,low,close,volume,close_time,quote_volume,count,
+/// taker_buy_volume,taker_buy_quote_volume,ignore`).
+#[cfg(feature = "klines")]
+pub fn parse_klines(
+    symbol: &str,
+    interval: &KlineInterval,
+    csv: &str,
+) -> Result<Vec<KlineData>, BinanceConnectError> {
+    rows(csv)
+        .map(|row| {
+            Ok(KlineData {
+                kline_start_time: field(&row, 0)?,
+                kline_close_time: field(&row, 6)?,
+                kline_symbol: symbol.to_string(),
+                interval: *interval,
+                first_trade_id: 0,
+                last_trade_id: 0,
+                open_price: field(&row, 1)?,
+                close_price: field(&row, 4)?,
+                high_price: field(&row, 2)?,
+                low_price: field(&row, 3)?,
+                base_asset_volume: field(&row, 5)?,
+                number_of_trades: field(&row, 8)?,
+                is_kline_closed: true,
+                quote_asset_volume: field(&row, 7)?,
+                taker_buy_base_asset_volume: field(&row, 9)?,
+                taker_buy_quote_asset_volume: field(&row, 10)?,
+            })
+        })
+        .collect()
+}
+
+/// Parses a book ticker CSV (`update_id,best_bid_price,best_bid_qty,best_ask_price,best_ask_qty,
+/// transaction_time,event_time`).
+#[cfg(feature = "market-data")]
+pub fn parse_book_tickers(symbol: &str, csv: &str) -> Result<Vec<BookTicker>, BinanceConnectError> {
+    rows(csv)
+        .map(|row| {
+            Ok(BookTicker {
+                event_type: EventType::BookTickerEventType,
+                event_time: field(&row, 6)?,
+                symbol: symbol.to_string(),
+                update_id: field(&row, 0)?,
+                bid_price: field(&row, 1)?,
+                bid_quantity: field(&row, 2)?,
+                ask_price: field(&row, 3)?,
+                ask_quantity: field(&row, 4)?,
+                transaction_time: field(&row, 5)?,
+            })
+        })
+        .collect()
+}
+
+/// Downloads the zip file and returns the content of the CSV it holds.
+fn download_csv(url: &str) -> Result<String, BinanceConnectError> {
+    let response: Response = tls::http_client()?.get(url).send()?;
+    if !response.status().is_success() {
+        return Err(BinanceConnectError::HttpResponseError(format!(
+            "{} {}",
+            response.status(),
+            url
+        )));
+    }
+    let bytes: Vec<u8> = response.bytes()?.to_vec();
+    let mut archive: ZipArchive<Cursor<Vec<u8>>> =
+        ZipArchive::new(Cursor::new(bytes)).map_err(zip_error)?;
+    let mut csv: String = String::new();
+    archive
+        .by_index(0)
+        .map_err(zip_error)?
+        .read_to_string(&mut csv)?;
+    Ok(csv)
+}
+
+/// Returns the data rows of the CSV split into fields, skipping the header and empty lines.
+fn rows(csv: &str) -> impl Iterator<Item = Vec<&str>> {
+    csv.lines()
+        .filter(|line| {
+            line.chars()
+                .next()
+                .map_or(false, |first| first.is_ascii_digit())
+        })
+        .map(|line| line.trim_end().split(',').collect())
+}
+
+/// Parses the field at the index of the row.
+fn field<T: FromStr>(row: &[&str], index: usize) -> Result<T, BinanceConnectError> {
+    row.get(index)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| {
+            BinanceConnectError::Other(format!(
+                "Invalid historical data field {} in row {}",
+                index,
+                row.join(",")
+            ))
+        })
+}
+
+/// Converts a zip error into a `BinanceConnectError`.
+fn zip_error(err: zip::result::ZipError) -> BinanceConnectError {
+    BinanceConnectError::Other(format!("Zip error: {}", err))
+}
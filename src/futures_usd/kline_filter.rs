@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use crate::futures_usd::enums::binance::KlineInterval;
+
+/// The event type marker of kline frames.
+const KLINE: &str = "\"e\":\"kline\"";
+/// The marker of klines that are not closed yet.
+const KLINE_OPEN: &str = "\"x\":false";
+
+/// Drops the updates of klines that are not closed yet for the streams subscribed with
+/// `FuturesUsdStream::with_kline_closed_only`, before the frames are deserialized.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClosedKlineFilter {
+    /// The closed-only streams formatted as `SYMBOL@interval` (uppercase symbol).
+    streams: HashSet<String>,
+}
+
+impl ClosedKlineFilter {
+    /// Only lets the closed klines of the symbol and interval pass.
+    pub(crate) fn allow(&mut self, symbol: &str, kline_interval: &KlineInterval) {
+        self.streams.insert(format!(
+            "{}@{}",
+            symbol.to_uppercase(),
+            kline_interval.to_str()
+        ));
+    }
+
+    /// Returns whether the filter drops nothing.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Returns whether the frame is the update of an open kline of a closed-only stream, using a
+    /// lightweight scan instead of deserializing the frame.
+    pub(crate) fn drops(&self, json_response: &str) -> bool {
+        if !json_response.contains(KLINE) || !json_response.contains(KLINE_OPEN) {
+            return false;
+        }
+        match (
+            scan_string(json_response, "\"s\":\""),
+            scan_string(json_response, "\"i\":\""),
+        ) {
+            (Some(symbol), Some(interval)) => {
+                self.streams.contains(&format!("{}@{}", symbol, interval))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the string value following the first occurrence of the pattern.
+fn scan_string<'a>(json: &'a str, pattern: &str) -> Option<&'a str> {
+    let value: &str = &json[json.find(pattern)? + pattern.len()..];
+    Some(&value[..value.find('"')?])
+}
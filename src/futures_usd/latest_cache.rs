@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::futures_usd::time_sync::server_now;
+
+/// The result of a `LatestCache` lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Staleness<T> {
+    /// The value is younger than the TTL of the cache.
+    Fresh(T),
+    /// The value is older than the TTL of the cache.
+    Expired { value: T, age: Duration },
+    /// The value was received before a reconnect and no fresh value arrived since.
+    Invalidated,
+    /// No value was received for the stream.
+    Missing,
+}
+
+impl<T> Staleness<T> {
+    /// Returns the value only when it is fresh.
+    pub fn fresh(self) -> Option<T> {
+        match self {
+            Staleness::Fresh(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// A cached payload with the times used to determine its age.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    value: Value,
+    /// The event time (UNIX millis) of the payload, or the receive time when it has none.
+    event_time: u64,
+    /// A flag indicating whether the entry was received on the current connection.
+    valid: bool,
+}
+
+/// Holds the latest payload per stream (`SYMBOL@eventType`), e.g. `BTCUSDT@bookTicker`.
+///
+/// Values expire once their event time is older than the TTL, and all values are invalidated on
+/// a reconnect until fresh data arrives, so pre-disconnect prices are never read as current.
+#[derive(Debug)]
+pub struct LatestCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, CacheEntry>>,
+}
+
+impl LatestCache {
+    /// Creates a new, empty `LatestCache` using the TTL.
+    pub fn new(ttl: Duration) -> LatestCache {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the latest value of the stream deserialized into `T`, e.g. a `BookTicker`.
+    ///
+    /// # Arguments
+    ///
+    /// - `stream`: The stream formatted as `SYMBOL@eventType`, e.g. `BTCUSDT@markPriceUpdate`.
+    ///
+    /// # Returns
+    ///
+    /// The `Staleness` of the value, `Missing` when it can not be deserialized into `T`.
+    ///
+    pub fn get<T: DeserializeOwned>(&self, stream: &str) -> Staleness<T> {
+        let entries = self.entries.read().unwrap();
+        let entry: &CacheEntry = match entries.get(stream) {
+            Some(entry) => entry,
+            None => return Staleness::Missing,
+        };
+        if !entry.valid {
+            return Staleness::Invalidated;
+        }
+        let value: T = match serde_json::from_value(entry.value.clone()) {
+            Ok(value) => value,
+            Err(_) => return Staleness::Missing,
+        };
+        let age: Duration = Duration::from_millis(server_now().saturating_sub(entry.event_time));
+        match age > self.ttl {
+            true => Staleness::Expired { value, age },
+            false => Staleness::Fresh(value),
+        }
+    }
+
+    /// Returns the streams that have a cached value.
+    pub fn streams(&self) -> Vec<String> {
+        self.entries.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Invalidates all values until fresh data arrives for them.
+    pub fn invalidate_all(&self) {
+        for entry in self.entries.write().unwrap().values_mut() {
+            entry.valid = false;
+        }
+    }
+
+    /// Stores every market data item of the JSON payload.
+    pub(crate) fn update(&self, json_response: &str) {
+        let value: Value = match serde_json::from_str(json_response) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        let items: Vec<Value> = match value {
+            Value::Array(items) => items,
+            value => vec![value],
+        };
+        let mut entries = self.entries.write().unwrap();
+        for item in items {
+            let stream: String = match (item.get("s"), item.get("e")) {
+                (Some(Value::String(symbol)), Some(Value::String(event_type))) => {
+                    format!("{}@{}", symbol, event_type)
+                }
+                _ => continue,
+            };
+            let event_time: u64 = item
+                .get("E")
+                .and_then(Value::as_u64)
+                .unwrap_or_else(server_now);
+            entries.insert(
+                stream,
+                CacheEntry {
+                    value: item,
+                    event_time,
+                    valid: true,
+                },
+            );
+        }
+    }
+}
@@ -1,64 +1,364 @@
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
 use reqwest::blocking::{Client, Response};
 use reqwest::StatusCode;
 use serde::Deserialize;
+use url::Url;
+use zeroize::Zeroizing;
 
 use crate::constants;
 use crate::error::BinanceConnectError;
+use crate::futures_usd::auth::{AuthProvider, StaticAuthProvider};
+use crate::futures_usd::response::BinanceErrorMessage;
+use crate::futures_usd::signer::{HmacSigner, Signer};
+use crate::futures_usd::tls;
+
+/// The environment variable holding the API key, see `ApiAuth::from_env`.
+pub const ENV_API_KEY: &str = "BINANCE_API_KEY";
+/// The environment variable holding the API secret, see `ApiAuth::from_env`.
+pub const ENV_API_SECRET: &str = "BINANCE_API_SECRET";
+
+/// The default interval of the listen key refreshes, Binance expires a listen key 60 minutes after
+/// it was created or last refreshed.
+pub const DEFAULT_LISTEN_KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(3000);
+
+/// Replaces credentials in `Debug` output.
+const REDACTED: &str = "<redacted>";
 
 /// Represents API authentication credentials.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ApiAuth {
+    /// The API key of `new`, `from_env` and `with_signer`, empty for `with_provider`.
+    #[deprecated(
+        since = "2.0.0",
+        note = "use `api_key()`, which supplies the key of every `AuthProvider`"
+    )]
     pub api_key: String,
-    pub api_secret: String,
+    /// Supplies the API key and signs the `TRADE` and `USER_DATA` requests.
+    provider: Arc<dyn AuthProvider>,
 }
 
 impl ApiAuth {
-    /// Creates a new instance of ApiAuth.
+    /// Creates a new instance of ApiAuth for an HMAC API key.
     pub fn new(api_key: String, api_secret: String) -> ApiAuth {
+        Self::with_signer(api_key, HmacSigner::new(api_secret))
+    }
+
+    /// Creates a new instance of ApiAuth for an HMAC API key read from the `BINANCE_API_KEY` and
+    /// `BINANCE_API_SECRET` environment variables.
+    ///
+    /// # Returns
+    ///
+    /// The `ApiAuth` or a `BinanceConnectError` if one of the variables is not set.
+    ///
+    pub fn from_env() -> Result<ApiAuth, BinanceConnectError> {
+        Ok(Self::new(env_var(ENV_API_KEY)?, env_var(ENV_API_SECRET)?))
+    }
+
+    /// Creates a new instance of ApiAuth using the given `Signer`, e.g. an `RsaSigner` or
+    /// `Ed25519Signer` for RSA and Ed25519 API keys.
+    pub fn with_signer(api_key: String, signer: impl Signer + 'static) -> ApiAuth {
+        let mut api_auth: ApiAuth =
+            Self::with_provider(StaticAuthProvider::new(api_key.clone(), signer));
+        #[allow(deprecated)]
+        {
+            api_auth.api_key = api_key;
+        }
+        api_auth
+    }
+
+    /// Creates a new instance of ApiAuth using the given `AuthProvider`, e.g. one backed by a
+    /// key-management system supplying the API key and signatures on demand.
+    pub fn with_provider(provider: impl AuthProvider + 'static) -> ApiAuth {
+        #[allow(deprecated)]
         Self {
-            api_key,
-            api_secret,
+            api_key: String::new(),
+            provider: Arc::new(provider),
         }
     }
+
+    /// Returns the API key supplied by the `AuthProvider`.
+    pub fn api_key(&self) -> Result<Zeroizing<String>, BinanceConnectError> {
+        self.provider.api_key()
+    }
+
+    /// Signs the payload with the `AuthProvider` of this API key.
+    pub fn sign(&self, payload: &str) -> Result<String, BinanceConnectError> {
+        self.provider.sign(payload)
+    }
+}
+
+impl fmt::Debug for ApiAuth {
+    /// Formats the `ApiAuth` without revealing the API key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiAuth")
+            .field("api_key", &REDACTED)
+            .field("provider", &self.provider)
+            .finish()
+    }
+}
+
+/// Reads the environment variable, returning an error naming the variable when it is not set.
+fn env_var(name: &str) -> Result<String, BinanceConnectError> {
+    env::var(name).map_err(|err| BinanceConnectError::Other(format!("{}: {}", name, err)))
 }
 
 /// Represents the response for creating a listen key.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Clone)]
 pub struct ListenKey {
     #[serde(rename = "listenKey")]
     pub key: String,
 }
 
+impl fmt::Debug for ListenKey {
+    /// Formats the `ListenKey` without revealing the key, which grants access to the user data.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListenKey").field("key", &REDACTED).finish()
+    }
+}
+
+/// Hands a fresh listen key from the refresh task to the user data connection, which reconnects
+/// with it.
+#[derive(Debug, Default)]
+pub(crate) struct ListenKeyRenewal {
+    key: Mutex<Option<String>>,
+}
+
+impl ListenKeyRenewal {
+    /// Hands over the fresh listen key.
+    pub(crate) fn renew(&self, key: String) {
+        *self.key.lock().unwrap() = Some(key);
+    }
+
+    /// Returns true when a fresh listen key awaits the reconnect.
+    pub(crate) fn pending(&self) -> bool {
+        self.key.lock().unwrap().is_some()
+    }
+
+    /// Takes the fresh listen key to reconnect with.
+    pub(crate) fn take(&self) -> Option<String> {
+        self.key.lock().unwrap().take()
+    }
+}
+
+/// Returns the wait before the next listen key refresh, the interval shortened by a random part of
+/// the jitter so refreshes of several streams do not line up but never come later than configured.
+pub(crate) fn refresh_delay(interval: Duration, jitter: Duration) -> Duration {
+    let jitter_ms: u64 = jitter.min(interval).as_millis() as u64;
+    if jitter_ms == 0 {
+        return interval;
+    }
+    let random: u64 = RandomState::new().build_hasher().finish();
+    interval - Duration::from_millis(random % (jitter_ms + 1))
+}
+
+/// Creates and deletes the listen keys of the user data stream.
+///
+/// The default `HttpListenKeyProvider` calls the Binance REST API; implement it to bring an own
+/// HTTP client (custom TLS, proxies, instrumentation) or to return fixed keys in tests.
+pub trait ListenKeyProvider: fmt::Debug + Send + Sync {
+    /// Creates a listen key, or extends the validity of the active one, for the API key.
+    fn create(&self, api_auth: &ApiAuth, test_net: bool) -> Result<ListenKey, BinanceConnectError>;
+
+    /// Deletes the listen key of the API key, closing its user data stream.
+    fn delete(&self, api_auth: &ApiAuth, test_net: bool) -> Result<(), BinanceConnectError>;
+}
+
+/// The default `ListenKeyProvider`, calling the Binance REST API with a blocking `reqwest` client.
+#[derive(Debug, Clone, Default)]
+pub struct HttpListenKeyProvider {
+    /// The HTTP client of the requests, a client of `tls::http_client` is created per request
+    /// when `None`.
+    client: Option<Client>,
+}
+
+impl HttpListenKeyProvider {
+    /// Creates a new `HttpListenKeyProvider` sending its requests with the given client, e.g. one
+    /// configured with a proxy or custom root certificates.
+    pub fn with_client(client: Client) -> HttpListenKeyProvider {
+        Self {
+            client: Some(client),
+        }
+    }
+
+    /// Returns the HTTP client of a request.
+    fn client(&self) -> Result<Client, BinanceConnectError> {
+        match &self.client {
+            Some(client) => Ok(client.clone()),
+            None => tls::http_client(),
+        }
+    }
+}
+
+impl ListenKeyProvider for HttpListenKeyProvider {
+    fn create(&self, api_auth: &ApiAuth, test_net: bool) -> Result<ListenKey, BinanceConnectError> {
+        // Determine the appropriate Binance base URL based on the test_net flag.
+        let endpoint: String = format!("{}{}", base_url(test_net), constants::FUTURES_LISTEN_KEY);
+        // Send a POST request to obtain a listen key.
+        let response: Response = self
+            .client()?
+            .post(endpoint)
+            .header("X-MBX-APIKEY", api_auth.api_key()?.as_str())
+            .send()?;
+
+        // Check if the response status is OK (200).
+        if response.status() == StatusCode::OK {
+            // Deserialize the response JSON into a ListenKey struct.
+            let json_response: String = response.text()?;
+            serde_json::from_str(&json_response)
+                .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+        } else {
+            Err(error_response(response))
+        }
+    }
+
+    fn delete(&self, api_auth: &ApiAuth, test_net: bool) -> Result<(), BinanceConnectError> {
+        let endpoint: String = format!("{}{}", base_url(test_net), constants::FUTURES_LISTEN_KEY);
+        let response: Response = self
+            .client()?
+            .delete(endpoint)
+            .header("X-MBX-APIKEY", api_auth.api_key()?.as_str())
+            .send()?;
+
+        if response.status() == StatusCode::OK {
+            Ok(())
+        } else {
+            Err(error_response(response))
+        }
+    }
+}
+
+/// Creates the listen key of a user data connection with its `ListenKeyProvider`, on the
+/// connection thread or the refresh thread.
+#[derive(Debug, Clone)]
+pub(crate) struct ListenKeyRequest {
+    provider: Arc<dyn ListenKeyProvider>,
+    api_auth: ApiAuth,
+    test_net: bool,
+}
+
+impl ListenKeyRequest {
+    /// Creates the request of the listen keys of the API key.
+    pub(crate) fn new(
+        provider: Arc<dyn ListenKeyProvider>,
+        api_auth: ApiAuth,
+        test_net: bool,
+    ) -> ListenKeyRequest {
+        Self {
+            provider,
+            api_auth,
+            test_net,
+        }
+    }
+
+    /// Creates the listen key, blocking the calling thread.
+    pub(crate) fn create(&self) -> Result<ListenKey, BinanceConnectError> {
+        self.provider.create(&self.api_auth, self.test_net)
+    }
+}
+
+/// The state shared by a `ListenKeyFuture` and the thread sending its request.
+#[derive(Default)]
+struct ListenKeyFutureState {
+    result: Option<Result<ListenKey, BinanceConnectError>>,
+    waker: Option<Waker>,
+}
+
+/// Completes with the listen key created on a dedicated thread, so awaiting it does not block the
+/// executor whichever async runtime polls it.
+struct ListenKeyFuture {
+    state: Arc<Mutex<ListenKeyFutureState>>,
+}
+
+impl ListenKeyFuture {
+    /// Spawns the thread creating the listen key of the request.
+    fn spawn(request: ListenKeyRequest) -> ListenKeyFuture {
+        let state: Arc<Mutex<ListenKeyFutureState>> = Arc::default();
+        let thread_state: Arc<Mutex<ListenKeyFutureState>> = Arc::clone(&state);
+        thread::spawn(move || {
+            let result: Result<ListenKey, BinanceConnectError> = request.create();
+            let mut state = thread_state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        Self { state }
+    }
+}
+
+impl Future for ListenKeyFuture {
+    type Output = Result<ListenKey, BinanceConnectError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Replaces the last path segment of the user data connection URL with the listen key.
+pub(crate) fn set_listen_key(url: &mut Url, key: &str) {
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop().push(key);
+    }
+}
+
 /// Retrieves a new listen key from Binance.
 pub fn get_listen_key(
     api_auth: &ApiAuth,
     test_net: bool,
 ) -> Result<ListenKey, BinanceConnectError> {
-    // Create a new HTTP client.
-    let client: Client = Client::new();
-    // Determine the appropriate Binance base URL based on the test_net flag.
-    let endpoint: String = format!("{}{}", base_url(test_net), constants::FUTURES_LISTEN_KEY);
-    // Send a POST request to obtain a listen key.
-    let response: Response = client
-        .post(endpoint)
-        .header("X-MBX-APIKEY", &api_auth.api_key)
-        .send()?;
-
-    // Check if the response status is OK (200).
-    if response.status() == StatusCode::OK {
-        // Deserialize the response JSON into a ListenKey struct.
-        serde_json::from_str(&response.text().unwrap()).map_err(BinanceConnectError::JsonError)
-    } else {
-        // Handle non-OK HTTP status codes by returning an error.
-        Err(BinanceConnectError::HttpResponseError(format!(
+    HttpListenKeyProvider::default().create(api_auth, test_net)
+}
+
+/// Retrieves a new listen key from Binance without blocking, for use in async contexts.
+pub async fn get_listen_key_async(
+    api_auth: &ApiAuth,
+    test_net: bool,
+) -> Result<ListenKey, BinanceConnectError> {
+    ListenKeyFuture::spawn(ListenKeyRequest::new(
+        Arc::new(HttpListenKeyProvider::default()),
+        api_auth.clone(),
+        test_net,
+    ))
+    .await
+}
+
+/// Deletes the listen key of the API key at Binance, closing its user data stream.
+pub fn delete_listen_key(api_auth: &ApiAuth, test_net: bool) -> Result<(), BinanceConnectError> {
+    HttpListenKeyProvider::default().delete(api_auth, test_net)
+}
+
+/// Handles non-OK HTTP status codes by returning the error of Binance, if any.
+fn error_response(response: Response) -> BinanceConnectError {
+    let status: StatusCode = response.status();
+    let body: String = response.text().unwrap_or_default();
+    match serde_json::from_str::<BinanceErrorMessage>(&body) {
+        Ok(error_message) => error_message.into(),
+        Err(_) => BinanceConnectError::HttpResponseError(format!(
             "Not-OK status code received {:?}",
-            response.status()
-        )))
+            status
+        )),
     }
 }
 
 /// Returns the appropriate Binance base URL based on the test_net flag.
-fn base_url(test_net: bool) -> &'static str {
+pub(crate) fn base_url(test_net: bool) -> &'static str {
     if test_net {
         constants::BASE_URL_FUTURES_TESTNET
     } else {
@@ -0,0 +1,99 @@
+#[cfg(feature = "market-data")]
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "market-data")]
+use crate::futures_usd::enums::binance::ContractStatus;
+use crate::futures_usd::enums::binance::ContractType;
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::enums::streams::Streams;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::ContractInfo;
+
+/// Creates a stream of a newly listed symbol, e.g. `Streams::agg_trade`.
+pub type ListingStream = fn(&str) -> Streams;
+
+/// Raised when a contract starts trading that never traded before.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NewListing {
+    pub symbol: String,
+    pub pair: String,
+    pub contract_type: ContractType,
+    /// The time (UNIX millis) the contract was onboarded.
+    pub onboard_time: i64,
+    /// The streams of the symbol that were subscribed to.
+    pub streams: Vec<String>,
+}
+
+/// Detects new listings from the contract info events: a contract whose status changes to
+/// `TRADING` that never traded before, starting from the contracts trading when the detector was
+/// created. A contract trading again after e.g. settling is not a new listing.
+#[derive(Debug, Clone, Default)]
+pub struct ListingDetector {
+    /// The symbols of the contracts that traded since the detector was created (or before).
+    #[cfg(feature = "market-data")]
+    listed: HashSet<String>,
+    #[cfg(feature = "market-data")]
+    streams: Vec<ListingStream>,
+}
+
+impl ListingDetector {
+    /// Creates a detector.
+    ///
+    /// # Arguments
+    ///
+    /// - `trading`: The symbols of the contracts currently trading, e.g. from the `ExchangeInfo`.
+    /// - `streams`: The streams subscribed to for every new listing.
+    ///
+    pub fn new(trading: Vec<String>, streams: Vec<ListingStream>) -> ListingDetector {
+        #[cfg(not(feature = "market-data"))]
+        let _ = (trading, streams);
+        Self {
+            #[cfg(feature = "market-data")]
+            listed: trading.into_iter().collect(),
+            #[cfg(feature = "market-data")]
+            streams,
+        }
+    }
+
+    /// Updates the listed contracts with the contract info events, other events are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// - `event`: The event, a `ContractInfoEvent` is tracked.
+    ///
+    /// # Returns
+    ///
+    /// The `NewListing` when the contract started trading.
+    ///
+    pub fn observe(&mut self, event: &Event) -> Option<NewListing> {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::ContractInfoEvent(contract_info) => self.update(contract_info),
+            _ => None,
+        }
+    }
+
+    /// Updates the status of the contract, returning the listing when it started trading for the
+    /// first time.
+    #[cfg(feature = "market-data")]
+    pub fn update(&mut self, contract_info: &ContractInfo) -> Option<NewListing> {
+        if contract_info.contract_status != ContractStatus::Trading
+            || !self.listed.insert(contract_info.symbol.clone())
+        {
+            return None;
+        }
+        Some(NewListing {
+            symbol: contract_info.symbol.clone(),
+            pair: contract_info.pair.clone(),
+            contract_type: contract_info.contract_type.clone(),
+            onboard_time: contract_info.onboard_date_time,
+            streams: self
+                .streams
+                .iter()
+                .map(|stream| stream(&contract_info.symbol).to_str().to_string())
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,183 @@
+use crate::futures_usd::enums::events::Event;
+#[cfg(any(
+    feature = "market-data",
+    feature = "user-data",
+    feature = "klines",
+    feature = "depth"
+))]
+use crate::futures_usd::response::*;
+
+/// The fields shared by the market data and user data responses, so generic code (e.g. logging or
+/// latency measurements) does not need to match every `Event` variant.
+pub trait MarketEvent {
+    /// Returns the event time (UNIX millis).
+    fn event_time(&self) -> i64;
+
+    /// Returns the symbol the event is about, `None` when it covers several or no symbol.
+    fn symbol(&self) -> Option<&str>;
+}
+
+/// Implements `MarketEvent` for a response, reading the symbol with the closure.
+#[cfg(any(
+    feature = "market-data",
+    feature = "user-data",
+    feature = "klines",
+    feature = "depth"
+))]
+macro_rules! market_event {
+    ($target:ty, |$item:ident| $symbol:expr) => {
+        impl MarketEvent for $target {
+            fn event_time(&self) -> i64 {
+                self.event_time as i64
+            }
+
+            fn symbol(&self) -> Option<&str> {
+                let $item = self;
+                $symbol
+            }
+        }
+    };
+}
+
+/// Implements `MarketEvent` for an array response, the event time is the latest of its items.
+#[cfg(feature = "market-data")]
+macro_rules! market_events {
+    ($target:ty) => {
+        impl MarketEvent for $target {
+            fn event_time(&self) -> i64 {
+                self.data
+                    .iter()
+                    .map(MarketEvent::event_time)
+                    .max()
+                    .unwrap_or(0)
+            }
+
+            fn symbol(&self) -> Option<&str> {
+                None
+            }
+        }
+    };
+}
+
+/* MARKET_DATA */
+#[cfg(feature = "market-data")]
+market_event!(BookTicker, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_events!(BookTickers);
+#[cfg(feature = "market-data")]
+market_event!(AggTrade, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_event!(MarkPriceUpdate, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_events!(MarkPriceUpdates);
+#[cfg(feature = "klines")]
+market_event!(Kline, |item| Some(&item.symbol));
+#[cfg(feature = "klines")]
+market_event!(ContinuousKline, |item| Some(&item.pair));
+#[cfg(feature = "market-data")]
+market_event!(MiniTicker, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_events!(MiniTickers);
+#[cfg(feature = "market-data")]
+market_event!(Ticker, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_events!(Tickers);
+#[cfg(feature = "market-data")]
+market_event!(ForceOrder, |item| Some(&item.order.symbol));
+#[cfg(feature = "depth")]
+market_event!(BookDepth, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_event!(CompositeIndex, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_event!(ContractInfo, |item| Some(&item.symbol));
+#[cfg(feature = "market-data")]
+market_event!(AssetIndexUpdate, |item| Some(&item.asset_index_symbol));
+#[cfg(feature = "market-data")]
+market_events!(AssetIndexUpdates);
+/* USER_DATA */
+#[cfg(feature = "user-data")]
+market_event!(OrderTradeUpdate, |item| Some(&item.order_data.symbol));
+#[cfg(feature = "user-data")]
+market_event!(AccountUpdate, |_item| None);
+#[cfg(feature = "user-data")]
+market_event!(MarginCall, |_item| None);
+#[cfg(feature = "user-data")]
+market_event!(AccountConfigUpdate, |item| item
+    .account_config
+    .as_ref()
+    .map(|account_config| account_config.symbol.as_str()));
+#[cfg(feature = "user-data")]
+market_event!(StrategyUpdate, |item| Some(&item.strategy.symbol));
+#[cfg(feature = "user-data")]
+market_event!(GridUpdate, |item| Some(&item.grid.symbol));
+#[cfg(feature = "user-data")]
+market_event!(ConditionalOrderTriggerReject, |item| Some(
+    &item.order_reject.symbol
+));
+
+impl MarketEvent for Event {
+    /// Returns the event time of a market data or user data event, `0` for the other events.
+    fn event_time(&self) -> i64 {
+        market_event(self).map_or(0, MarketEvent::event_time)
+    }
+
+    fn symbol(&self) -> Option<&str> {
+        market_event(self).and_then(MarketEvent::symbol)
+    }
+}
+
+/// Returns the response of a market data or user data event, `None` for the other events.
+fn market_event(event: &Event) -> Option<&dyn MarketEvent> {
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::BookTickerEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::BookTickersEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdateEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdatesEvent(event) => Some(event),
+        #[cfg(feature = "klines")]
+        Event::KlineEvent(event) => Some(event),
+        #[cfg(feature = "klines")]
+        Event::ContinuousKlineEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::MiniTickerEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::MiniTickersEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::TickerEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::TickersEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::ForceOrderEvent(event) => Some(event),
+        #[cfg(feature = "depth")]
+        Event::BookDepthEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::CompositeIndexEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::ContractInfoEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdateEvent(event) => Some(event),
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdatesEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::OrderTradeUpdateEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::AccountUpdateEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::MarginCallEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::AccountConfigUpdateEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::StrategyUpdateEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::GridUpdateEvent(event) => Some(event),
+        #[cfg(feature = "user-data")]
+        Event::ConditionalOrderTriggerRejectEvent(event) => Some(event),
+        Event::WithRawEvent(with_raw) => market_event(&with_raw.event),
+        _ => None,
+    }
+}
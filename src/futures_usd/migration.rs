@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::deserializer::deserialize_slice;
+
+/// A fix-up applied to a raw frame that no longer deserializes, e.g. renaming or defaulting a field.
+pub type FrameMigration = fn(&mut Value);
+
+/// A frame of a capture that does not deserialize with the current response structs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Incompatibility {
+    /// The line number (starting at 1) of the frame in the capture file.
+    pub line: u64,
+    /// The deserialization error, including the event type and a payload snippet.
+    pub error: String,
+}
+
+/// The result of checking or migrating a capture file.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    /// The number of frames read.
+    pub frames: u64,
+    /// The number of frames that deserialized without any migration.
+    pub compatible: u64,
+    /// The number of frames that deserialized after applying the migrations.
+    pub migrated: u64,
+    /// The frames that did not deserialize, even after applying the migrations.
+    pub incompatible: Vec<Incompatibility>,
+}
+
+impl MigrationReport {
+    /// Returns whether every frame of the capture deserializes (after migration).
+    pub fn is_compatible(&self) -> bool {
+        self.incompatible.is_empty()
+    }
+}
+
+/// Re-runs every frame of a capture file (one raw JSON frame per line) through the current
+/// deserializer and reports the frames that are no longer compatible.
+///
+/// # Arguments
+///
+/// - `input`: The path of the capture file.
+///
+/// # Returns
+///
+/// The `MigrationReport` or a `BinanceConnectError` if the file could not be read.
+///
+pub fn check_capture(input: &Path) -> Result<MigrationReport, BinanceConnectError> {
+    process(input, None, &[])
+}
+
+/// Converts a capture file, applying the migrations (in order) to every frame that does not
+/// deserialize until it does. Frames that stay incompatible are written unchanged and reported.
+///
+/// # Arguments
+///
+/// - `input`: The path of the capture file.
+/// - `output`: The path the converted capture is written to.
+/// - `migrations`: The fix-ups to apply to incompatible frames.
+///
+/// # Returns
+///
+/// The `MigrationReport` or a `BinanceConnectError` if a file could not be read or written.
+///
+pub fn migrate_capture(
+    input: &Path,
+    output: &Path,
+    migrations: &[FrameMigration],
+) -> Result<MigrationReport, BinanceConnectError> {
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(output)?);
+    let report: MigrationReport = process(input, Some(&mut writer), migrations)?;
+    writer.flush()?;
+    Ok(report)
+}
+
+/// Checks (and migrates) every frame, writing the (migrated) frames to the writer when given.
+fn process(
+    input: &Path,
+    mut writer: Option<&mut BufWriter<File>>,
+    migrations: &[FrameMigration],
+) -> Result<MigrationReport, BinanceConnectError> {
+    let mut report: MigrationReport = MigrationReport::default();
+    let reader: BufReader<File> = BufReader::new(File::open(input)?);
+    for (index, line) in reader.lines().enumerate() {
+        let frame: String = line?;
+        if frame.trim().is_empty() {
+            continue;
+        }
+        report.frames += 1;
+        let converted: String = match deserialize_slice(frame.as_bytes()) {
+            Ok(_) => {
+                report.compatible += 1;
+                frame
+            }
+            Err(err) => match migrate(&frame, migrations) {
+                Some(migrated) => {
+                    report.migrated += 1;
+                    migrated
+                }
+                None => {
+                    report.incompatible.push(Incompatibility {
+                        line: index as u64 + 1,
+                        error: err.to_string(),
+                    });
+                    frame
+                }
+            },
+        };
+        if let Some(writer) = writer.as_mut() {
+            writeln!(writer, "{}", converted)?;
+        }
+    }
+    Ok(report)
+}
+
+/// Applies the migrations one by one, returning the frame as soon as it deserializes.
+fn migrate(frame: &str, migrations: &[FrameMigration]) -> Option<String> {
+    let mut value: Value = serde_json::from_str(frame).ok()?;
+    for migration in migrations {
+        migration(&mut value);
+        let migrated: String = value.to_string();
+        if deserialize_slice(migrated.as_bytes()).is_ok() {
+            return Some(migrated);
+        }
+    }
+    None
+}
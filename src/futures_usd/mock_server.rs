@@ -0,0 +1,470 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::{accept_hdr, Message, WebSocket};
+
+use crate::error::BinanceConnectError;
+
+/// A canned `bookTicker` payload of `BTCUSDT`.
+pub const BOOK_TICKER: &str = r#"{"e":"bookTicker","u":400900217,"E":1568014460893,"T":1568014460891,"s":"BTCUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+/// A canned `aggTrade` payload of `BTCUSDT`.
+pub const AGG_TRADE: &str = r#"{"e":"aggTrade","E":123456789,"s":"BTCUSDT","a":5933014,"p":"0.001","q":"100","f":100,"l":105,"T":123456785,"m":true}"#;
+/// A canned `ORDER_TRADE_UPDATE` payload of a new `BTCUSDT` limit order.
+pub const ORDER_TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}}"#;
+/// A canned `markPriceUpdate` payload of `BTCUSDT`.
+pub const MARK_PRICE_UPDATE: &str = r#"{"e":"markPriceUpdate","E":1562305380000,"s":"BTCUSDT","p":"11794.15000000","i":"11784.62659091","P":"11784.25641265","r":"0.00038167","T":1562306400000}"#;
+
+/// The read timeout of a connection, bounding the pacing accuracy of the frames.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// The maximum size of the request head of a plain HTTP request.
+const MAX_REQUEST_HEAD: usize = 8192;
+
+/// The canned frames and behavior of the `MockServer`.
+#[derive(Debug, Clone)]
+struct MockBehavior {
+    frames: Vec<Message>,
+    interval: Duration,
+    close_after_frames: bool,
+    /// The request paths of the connections closed after their last frame.
+    close_paths: Vec<String>,
+    confirm_requests: bool,
+    live: Option<Instant>,
+    /// The bodies of the plain HTTP GET requests by request path.
+    http_responses: Vec<(String, String)>,
+}
+
+/// A local WebSocket server serving canned Binance payloads, so the streams, reconnect logic and
+/// deserialization can be exercised in CI without the (test)net.
+///
+/// Every connection receives all frames in order at the interval. When `with_close_after_frames`
+/// is set, the connection is closed (code 1000) after the last frame, so the stream reconnects
+/// and the next connection receives the frames again. With `with_live_frames` the frames are
+/// paced from the start of the server instead, like a live Binance stream.
+///
+/// Plain HTTP GET requests (e.g. the REST requests of the time sync) are answered with the
+/// bodies of `with_http_response`.
+#[derive(Debug)]
+pub struct MockServer {
+    behavior: MockBehavior,
+    addr: Option<SocketAddr>,
+    connections: Arc<AtomicUsize>,
+    closes: Arc<AtomicUsize>,
+    paths: Arc<Mutex<Vec<String>>>,
+    /// The messages of the clients with the request path of their connection.
+    messages: Arc<Mutex<Vec<(String, Message)>>>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl Default for MockServer {
+    fn default() -> Self {
+        Self {
+            behavior: MockBehavior {
+                frames: Vec::new(),
+                interval: Duration::ZERO,
+                close_after_frames: false,
+                close_paths: Vec::new(),
+                confirm_requests: false,
+                live: None,
+                http_responses: Vec::new(),
+            },
+            addr: None,
+            connections: Arc::new(AtomicUsize::new(0)),
+            closes: Arc::new(AtomicUsize::new(0)),
+            paths: Arc::new(Mutex::new(Vec::new())),
+            messages: Arc::new(Mutex::new(Vec::new())),
+            stop_signal: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl MockServer {
+    /// Creates a server without frames.
+    pub fn new() -> MockServer {
+        Self::default()
+    }
+
+    /// Appends a text frame, e.g. `BOOK_TICKER`.
+    ///
+    /// # Arguments
+    ///
+    /// - `frame`: The JSON payload.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the frame appended.
+    ///
+    pub fn with_frame(mut self, frame: &str) -> Self {
+        self.behavior.frames.push(Message::Text(frame.to_string()));
+        self
+    }
+
+    /// Appends a binary frame holding the UTF-8 bytes of the payload.
+    ///
+    /// # Arguments
+    ///
+    /// - `frame`: The JSON payload.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the frame appended.
+    ///
+    pub fn with_binary_frame(mut self, frame: &str) -> Self {
+        self.behavior
+            .frames
+            .push(Message::Binary(frame.as_bytes().to_vec()));
+        self
+    }
+
+    /// Appends a ping frame, the pongs of the clients are returned by `pongs`.
+    ///
+    /// # Arguments
+    ///
+    /// - `payload`: The payload of the ping.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the ping appended.
+    ///
+    pub fn with_ping(mut self, payload: &str) -> Self {
+        self.behavior
+            .frames
+            .push(Message::Ping(payload.as_bytes().to_vec()));
+        self
+    }
+
+    /// Sets the interval between the frames, by default they are sent at once.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The interval between two frames.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the interval set.
+    ///
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.behavior.interval = interval;
+        self
+    }
+
+    /// Closes every connection after its last frame, triggering a reconnect of the stream.
+    pub fn with_close_after_frames(mut self) -> Self {
+        self.behavior.close_after_frames = true;
+        self
+    }
+
+    /// Closes the connections to the request path after their last frame, e.g.
+    /// `/ws/btcusdt@aggTrade`, so a single connection of a stream can be failed.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The request path of the connections to close.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct closing the connections to the path.
+    ///
+    pub fn with_close_after_frames_on(mut self, path: &str) -> Self {
+        self.behavior.close_paths.push(path.to_string());
+        self
+    }
+
+    /// Answers every request of the clients (e.g. `SUBSCRIBE`) with a confirmation like Binance,
+    /// `{"result":null,"id":<id>}`.
+    pub fn with_confirmed_requests(mut self) -> Self {
+        self.behavior.confirm_requests = true;
+        self
+    }
+
+    /// Paces the frames from the start of the server instead of the start of each connection, so
+    /// concurrent connections receive the same frames at the same time and a connection only
+    /// receives the frames due after it connected, like a live Binance stream.
+    pub fn with_live_frames(mut self) -> Self {
+        self.behavior.live = Some(Instant::now());
+        self
+    }
+
+    /// Answers the plain HTTP GET requests of the path with the JSON body, other paths are
+    /// answered with `404 Not Found`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The request path without query string, e.g. `/fapi/v1/time`.
+    /// - `body`: The JSON body of the response.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct answering the path.
+    ///
+    pub fn with_http_response(mut self, path: &str, body: &str) -> Self {
+        self.behavior
+            .http_responses
+            .push((path.to_string(), body.to_string()));
+        self
+    }
+
+    /// Binds the server to a free local port and starts accepting connections.
+    pub fn start(mut self) -> Result<Self, BinanceConnectError> {
+        if self.behavior.live.is_some() {
+            self.behavior.live = Some(Instant::now());
+        }
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        self.addr = Some(listener.local_addr()?);
+        let behavior: MockBehavior = self.behavior.clone();
+        let connections: Arc<AtomicUsize> = Arc::clone(&self.connections);
+        let closes: Arc<AtomicUsize> = Arc::clone(&self.closes);
+        let paths: Arc<Mutex<Vec<String>>> = Arc::clone(&self.paths);
+        let messages: Arc<Mutex<Vec<(String, Message)>>> = Arc::clone(&self.messages);
+        let stop_signal: Arc<AtomicBool> = Arc::clone(&self.stop_signal);
+        thread::spawn(move || {
+            while !stop_signal.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        connections.fetch_add(1, Ordering::Relaxed);
+                        let behavior: MockBehavior = behavior.clone();
+                        let closes: Arc<AtomicUsize> = Arc::clone(&closes);
+                        let paths: Arc<Mutex<Vec<String>>> = Arc::clone(&paths);
+                        let messages: Arc<Mutex<Vec<(String, Message)>>> = Arc::clone(&messages);
+                        let stop_signal: Arc<AtomicBool> = Arc::clone(&stop_signal);
+                        thread::spawn(move || {
+                            serve(stream, &behavior, &closes, &paths, &messages, &stop_signal)
+                        });
+                    }
+                    Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                    Err(_) => break,
+                }
+            }
+        });
+        Ok(self)
+    }
+
+    /// Returns the URL to configure the stream with, e.g. `ws://127.0.0.1:40123/`.
+    pub fn url(&self) -> String {
+        match self.addr {
+            Some(addr) => format!("ws://{}/", addr),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the REST base URL to send the plain HTTP requests to, e.g. `http://127.0.0.1:40123`.
+    pub fn http_url(&self) -> String {
+        match self.addr {
+            Some(addr) => format!("http://{}", addr),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the number of accepted connections.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of connections closed by the client with a Close frame.
+    pub fn closes(&self) -> usize {
+        self.closes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the request paths of the connections, e.g. `/ws/btcusdt@bookTicker`.
+    pub fn paths(&self) -> Vec<String> {
+        self.paths.lock().unwrap().clone()
+    }
+
+    /// Returns the text messages sent by the clients, e.g. the subscription requests.
+    pub fn messages(&self) -> Vec<String> {
+        self.messages_where(|_| true)
+    }
+
+    /// Returns the text messages sent by the clients connected to the request path.
+    pub fn messages_on(&self, path: &str) -> Vec<String> {
+        self.messages_where(|connection| connection == path)
+    }
+
+    /// Returns the request paths of the connections with the payloads of the pongs they sent.
+    pub fn pongs(&self) -> Vec<(String, String)> {
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(path, message)| match message {
+                Message::Pong(payload) => {
+                    Some((path.clone(), String::from_utf8_lossy(payload).into_owned()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the text messages of the connections whose request path matches.
+    fn messages_where(&self, connection: impl Fn(&str) -> bool) -> Vec<String> {
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(path, message)| match message {
+                Message::Text(text) if connection(path) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Stops accepting connections and closes the open connections.
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Returns the request path (without query string) of a plain HTTP request, `None` for a
+/// WebSocket upgrade. The request head is consumed only for plain HTTP requests.
+fn http_request(stream: &TcpStream) -> Option<String> {
+    let mut buffer: Vec<u8> = vec![0; MAX_REQUEST_HEAD];
+    let started: Instant = Instant::now();
+    loop {
+        let read: usize = stream.peek(&mut buffer).ok()?;
+        let head: String = String::from_utf8_lossy(&buffer[..read]).into_owned();
+        if let Some(end) = head.find("\r\n\r\n") {
+            if head.to_ascii_lowercase().contains("upgrade: websocket") {
+                return None;
+            }
+            let mut stream: &TcpStream = stream;
+            stream.read_exact(&mut buffer[..end + 4]).ok()?;
+            let target: &str = head.split_whitespace().nth(1)?;
+            return target.split('?').next().map(str::to_string);
+        }
+        if read == 0 || read == buffer.len() || started.elapsed() > Duration::from_secs(1) {
+            return None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Answers a plain HTTP request with the body of the path and closes the connection.
+fn respond(mut stream: TcpStream, path: &str, http_responses: &[(String, String)]) {
+    let response: String = match http_responses.iter().find(|(known, _)| known == path) {
+        Some((_, body)) => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+/// Serves the frames to a connection and records the messages of the client.
+// The error response of the handshake callback is defined by tungstenite.
+#[allow(clippy::result_large_err)]
+fn serve(
+    stream: TcpStream,
+    behavior: &MockBehavior,
+    closes: &AtomicUsize,
+    paths: &Mutex<Vec<String>>,
+    messages: &Mutex<Vec<(String, Message)>>,
+    stop_signal: &AtomicBool,
+) {
+    if stream.set_nonblocking(false).is_err() || stream.set_nodelay(true).is_err() {
+        return;
+    }
+    if !behavior.http_responses.is_empty() {
+        if let Some(path) = http_request(&stream) {
+            paths.lock().unwrap().push(path.clone());
+            respond(stream, &path, &behavior.http_responses);
+            return;
+        }
+    }
+    let mut path: String = String::new();
+    let mut socket: WebSocket<TcpStream> =
+        match accept_hdr(stream, |request: &Request, response: Response| {
+            path = request.uri().to_string();
+            paths.lock().unwrap().push(path.clone());
+            Ok(response)
+        }) {
+            Ok(socket) => socket,
+            Err(_) => return,
+        };
+    if socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .is_err()
+    {
+        return;
+    }
+    let started: Instant = behavior.live.unwrap_or_else(Instant::now);
+    // A live connection skips the frames that were due before it connected.
+    let mut sent: usize = match behavior.live {
+        Some(_) if !behavior.interval.is_zero() => behavior
+            .frames
+            .len()
+            .min((started.elapsed().as_nanos() / behavior.interval.as_nanos()) as usize + 1),
+        _ => 0,
+    };
+    while !stop_signal.load(Ordering::Relaxed) {
+        if sent < behavior.frames.len() && started.elapsed() >= behavior.interval * sent as u32 {
+            if socket.send(behavior.frames[sent].clone()).is_err() {
+                return;
+            }
+            sent += 1;
+            continue;
+        }
+        if sent == behavior.frames.len()
+            && (behavior.close_after_frames || behavior.close_paths.contains(&path))
+        {
+            let _ = socket.close(Some(CloseFrame {
+                code: CloseCode::Normal,
+                reason: "mock server closed".into(),
+            }));
+            let _ = socket.flush();
+            return;
+        }
+        match socket.read() {
+            Ok(Message::Text(message)) => {
+                let id: Option<u64> = serde_json::from_str::<Value>(&message)
+                    .ok()
+                    .and_then(|request| request.get("id").and_then(Value::as_u64));
+                messages
+                    .lock()
+                    .unwrap()
+                    .push((path.clone(), Message::Text(message)));
+                if let (true, Some(id)) = (behavior.confirm_requests, id) {
+                    let confirmation: String = format!("{{\"result\":null,\"id\":{}}}", id);
+                    if socket.send(Message::Text(confirmation)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Ok(Message::Pong(payload)) => {
+                messages
+                    .lock()
+                    .unwrap()
+                    .push((path.clone(), Message::Pong(payload)));
+            }
+            Ok(Message::Close(_)) => {
+                closes.fetch_add(1, Ordering::Relaxed);
+                let _ = socket.flush();
+                return;
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err))
+                if err.kind() == ErrorKind::WouldBlock || err.kind() == ErrorKind::TimedOut => {}
+            Err(_) => return,
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::listen_key::ApiAuth;
+use crate::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+/// The granularity with which the forwarding threads check the stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An event received on the user data stream of one of the accounts of a `MultiAccountStream`.
+#[derive(Debug)]
+pub struct AccountEvent {
+    /// The identifier under which the account was registered.
+    pub account: String,
+    pub event: Event,
+}
+
+/// Aggregates the user data streams of multiple accounts (e.g. sub-accounts) into one receiver.
+///
+/// Every account gets its own listen key and connection, the events of all accounts are funneled
+/// into a single receiver and tagged with the identifier of the account.
+#[derive(Debug)]
+pub struct MultiAccountStream {
+    config: FuturesWebSocketConfig,
+    accounts: Vec<(String, ApiAuth)>,
+    sender: Sender<AccountEvent>,
+    receiver: Receiver<AccountEvent>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl MultiAccountStream {
+    /// Creates a new instance using the configuration for the connections of all accounts.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The WebSocket configuration, the `ApiAuth` of every account replaces its `api_auth`.
+    ///
+    pub fn with_config(config: FuturesWebSocketConfig) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            config,
+            accounts: Vec::new(),
+            sender,
+            receiver,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Registers an account.
+    ///
+    /// # Arguments
+    ///
+    /// - `account`: The identifier the events of the account are tagged with.
+    /// - `api_auth`: The API credentials of the account.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the account added.
+    ///
+    pub fn with_account(mut self, account: &str, api_auth: ApiAuth) -> Self {
+        self.accounts.push((account.to_string(), api_auth));
+        self
+    }
+
+    /// Starts a connection for every registered account and forwards their events.
+    pub fn start(self) -> Self {
+        for (account, api_auth) in &self.accounts {
+            let stream: FuturesUsdStream =
+                FuturesUsdStream::with_config(self.config.clone().with_api_auth(api_auth.clone()))
+                    .start();
+            info!(account = %account, "futures_usd user data stream started");
+            Self::forward(
+                account.clone(),
+                stream,
+                self.sender.clone(),
+                Arc::clone(&self.stop_signal),
+            );
+        }
+        self
+    }
+
+    /// Returns the receiver of the tagged events of all accounts.
+    pub fn consume(&self) -> &Receiver<AccountEvent> {
+        &self.receiver
+    }
+
+    /// Stops the connections of all accounts.
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+
+    /// Spawns a thread forwarding the events of the account's stream, tagged with the account.
+    fn forward(
+        account: String,
+        stream: FuturesUsdStream,
+        sender: Sender<AccountEvent>,
+        stop_signal: Arc<AtomicBool>,
+    ) {
+        thread::spawn(move || {
+            while !stop_signal.load(Ordering::Relaxed) {
+                match stream.consume().recv_timeout(STOP_CHECK_INTERVAL) {
+                    Ok(event) => {
+                        let account_event: AccountEvent = AccountEvent {
+                            account: account.clone(),
+                            event,
+                        };
+                        if sender.send(account_event).is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            stream.stop();
+        });
+    }
+}
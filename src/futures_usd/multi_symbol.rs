@@ -0,0 +1,158 @@
+#[cfg(feature = "native")]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "native")]
+use serde_json::Value;
+
+use crate::futures_usd::enums::binance::MarkPriceUpdateSpeed;
+#[cfg(feature = "native")]
+use crate::futures_usd::enums::streams::Streams;
+
+/// The default number of symbols from which a multi-symbol subscription uses the all-market stream.
+pub const DEFAULT_ALL_MARKET_THRESHOLD: usize = 50;
+
+/// The stream types that can be subscribed to for multiple symbols at once, see
+/// `FuturesUsdStream::with_multi_symbol`.
+///
+/// Binance does not accept multiple symbols in one stream name. The types with an all-market
+/// stream (`!bookTicker`, `!miniTicker@arr`, ...) subscribe to it once the number of symbols
+/// reaches the threshold and drop the other symbols client-side; below the threshold, and for
+/// types without an all-market stream, one stream per symbol is subscribed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultiSymbolStream {
+    BookTicker,
+    AggTrade,
+    MarkPriceUpdate(MarkPriceUpdateSpeed),
+    MiniTicker,
+    Ticker,
+    ForceOrder,
+}
+
+#[cfg(feature = "native")]
+impl MultiSymbolStream {
+    /// Returns the stream of a single symbol.
+    fn per_symbol(&self, symbol: &str) -> Streams {
+        match self {
+            MultiSymbolStream::BookTicker => Streams::book_ticker(symbol),
+            MultiSymbolStream::AggTrade => Streams::agg_trade(symbol),
+            MultiSymbolStream::MarkPriceUpdate(update_speed) => {
+                Streams::mark_price_update(symbol, update_speed.clone())
+            }
+            MultiSymbolStream::MiniTicker => Streams::mini_ticker(symbol),
+            MultiSymbolStream::Ticker => Streams::ticker(symbol),
+            MultiSymbolStream::ForceOrder => Streams::force_order(symbol),
+        }
+    }
+
+    /// Returns the all-market stream and the event type of its events, `None` when Binance has
+    /// no all-market stream for the type.
+    fn all_market(&self) -> Option<(Streams, &'static str)> {
+        match self {
+            MultiSymbolStream::BookTicker => Some((Streams::book_tickers(), "bookTicker")),
+            MultiSymbolStream::AggTrade => None,
+            MultiSymbolStream::MarkPriceUpdate(update_speed) => Some((
+                Streams::mark_price_updates(update_speed.clone()),
+                "markPriceUpdate",
+            )),
+            MultiSymbolStream::MiniTicker => Some((Streams::mini_tickers(), "24hrMiniTicker")),
+            MultiSymbolStream::Ticker => Some((Streams::tickers(), "24hrTicker")),
+            MultiSymbolStream::ForceOrder => Some((Streams::force_orders(), "forceOrder")),
+        }
+    }
+
+    /// Returns the streams to subscribe to for the symbols, registering the symbols on the filter
+    /// when the all-market stream is used.
+    pub(crate) fn expand(
+        &self,
+        symbols: &[&str],
+        all_market_threshold: usize,
+        symbol_filter: &mut SymbolFilter,
+    ) -> Vec<Streams> {
+        match self.all_market() {
+            Some((stream, event_type)) if symbols.len() >= all_market_threshold => {
+                symbol_filter.allow(event_type, symbols);
+                vec![stream]
+            }
+            _ => symbols
+                .iter()
+                .map(|symbol| self.per_symbol(symbol))
+                .collect(),
+        }
+    }
+}
+
+/// Drops the events of all-market streams for symbols that were not requested.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SymbolFilter {
+    /// The requested symbols (uppercase) per event type.
+    allowed: HashMap<&'static str, HashSet<String>>,
+}
+
+#[cfg(feature = "native")]
+impl SymbolFilter {
+    /// Allows the events of the event type for the symbols.
+    fn allow(&mut self, event_type: &'static str, symbols: &[&str]) {
+        self.allowed
+            .entry(event_type)
+            .or_default()
+            .extend(symbols.iter().map(|symbol| symbol.to_uppercase()));
+    }
+
+    /// Returns whether the filter drops nothing.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.allowed.is_empty()
+    }
+
+    /// Filters the JSON payload, returning `None` when all its items were dropped.
+    pub(crate) fn apply(&self, json_response: String) -> Option<String> {
+        // Only payloads of a filtered event type are parsed.
+        if !self
+            .allowed
+            .keys()
+            .any(|event_type| json_response.contains(&format!("\"e\":\"{}\"", event_type)))
+        {
+            return Some(json_response);
+        }
+        let value: Value = match serde_json::from_str(&json_response) {
+            Ok(value) => value,
+            Err(_) => return Some(json_response),
+        };
+        match value {
+            Value::Array(items) => {
+                let count: usize = items.len();
+                let kept: Vec<Value> = items
+                    .into_iter()
+                    .filter(|item| self.is_allowed(item))
+                    .collect();
+                match kept.len() {
+                    0 => None,
+                    len if len == count => Some(json_response),
+                    _ => Some(Value::Array(kept).to_string()),
+                }
+            }
+            item => match self.is_allowed(&item) {
+                true => Some(json_response),
+                false => None,
+            },
+        }
+    }
+
+    /// Returns whether the item is not filtered or its symbol was requested.
+    fn is_allowed(&self, item: &Value) -> bool {
+        let symbols: &HashSet<String> = match item
+            .get("e")
+            .and_then(Value::as_str)
+            .and_then(|event_type| self.allowed.get(event_type))
+        {
+            Some(symbols) => symbols,
+            None => return true,
+        };
+        // Force orders hold the symbol in the order object.
+        let symbol: Option<&str> = item
+            .get("s")
+            .or_else(|| item.get("o").and_then(|order| order.get("s")))
+            .and_then(Value::as_str);
+        symbol.map_or(true, |symbol| symbols.contains(symbol))
+    }
+}
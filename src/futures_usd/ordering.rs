@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::futures_usd::enums::events::Event;
+
+// Holds the ordering guarantees of the library
+//
+// 1. Events of a single stream are delivered in network order: every connection is read by exactly
+//    one thread which forwards events over a FIFO channel, no buffering or reordering takes place.
+// 2. User data events are never reordered relative to each other, they share the connection (and
+//    with that the guarantee above) of the listen key.
+// 3. Every connection numbers its events with a `Sequence` that is strictly monotonic for the
+//    lifetime of that connection, a new connection starts over at `Sequence::FIRST`.
+//
+// The `OrderingGuard` verifies these invariants against the exchange supplied update ids at runtime,
+// a violation is logged as a warning.
+
+/// A strictly monotonic sequence number assigned to every event received on a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sequence(u64);
+
+impl Sequence {
+    /// The sequence number of the first event received on a connection.
+    pub const FIRST: Sequence = Sequence(1);
+
+    /// Returns the sequence number as `u64`.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Issues the `Sequence` numbers of a single connection.
+///
+/// The generator can only move forward; a new generator has to be created for every connection.
+#[derive(Debug, Default)]
+pub struct SequenceGenerator {
+    last: u64,
+}
+
+impl SequenceGenerator {
+    /// Returns the next `Sequence`, which is always greater than all previously issued ones.
+    pub fn next_sequence(&mut self) -> Sequence {
+        self.last = self
+            .last
+            .checked_add(1)
+            .expect("sequence numbers are exhausted");
+        Sequence(self.last)
+    }
+
+    /// Returns the last issued `Sequence`, `None` when no sequence was issued yet.
+    pub fn last(&self) -> Option<Sequence> {
+        match self.last {
+            0 => None,
+            last => Some(Sequence(last)),
+        }
+    }
+}
+
+/// Describes an event that arrived with an update id lower than a previously seen one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderingViolation {
+    pub stream: String,
+    pub previous_id: u64,
+    pub received_id: u64,
+}
+
+impl fmt::Display for OrderingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} received update id {} after {}",
+            self.stream, self.received_id, self.previous_id
+        )
+    }
+}
+
+/// Verifies that the exchange supplied update ids never go backwards per stream.
+///
+/// Only events that carry an id that Binance guarantees to increase are checked: book tickers
+/// (`update_id`) and aggregated trades (`agg_trade_id`), see `update_ids`.
+#[derive(Debug, Default)]
+pub struct OrderingGuard {
+    last_ids: HashMap<String, u64>,
+}
+
+impl OrderingGuard {
+    /// Records the update id(s) of the event and returns an `OrderingViolation` when an id is lower
+    /// than the last id seen for the same stream.
+    pub fn check(&mut self, event: &Event) -> Result<(), OrderingViolation> {
+        for (stream, id) in update_ids(event) {
+            self.record(stream, id)?;
+        }
+        Ok(())
+    }
+
+    /// Stores the id for the stream when it does not go backwards.
+    fn record(&mut self, stream: String, id: u64) -> Result<(), OrderingViolation> {
+        match self.last_ids.get(&stream) {
+            Some(&previous_id) if id < previous_id => Err(OrderingViolation {
+                stream,
+                previous_id,
+                received_id: id,
+            }),
+            _ => {
+                self.last_ids.insert(stream, id);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the stream and update id pairs of the events carrying an id that Binance guarantees to
+/// increase, empty for every other event.
+///
+/// Book depth updates are excluded: the diff. and partial depth streams (at every update speed)
+/// of a symbol decode into the same event while they are pushed independently, so a lower id is
+/// neither out of order nor a duplicate.
+pub(crate) fn update_ids(event: &Event) -> Vec<(String, u64)> {
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::BookTickerEvent(book_ticker) => vec![(
+            format!("{}@bookTicker", book_ticker.symbol),
+            book_ticker.update_id,
+        )],
+        #[cfg(feature = "market-data")]
+        Event::BookTickersEvent(book_tickers) => book_tickers
+            .data
+            .iter()
+            .map(|book_ticker| {
+                (
+                    format!("{}@bookTicker", book_ticker.symbol),
+                    book_ticker.update_id,
+                )
+            })
+            .collect(),
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(agg_trade) => vec![(
+            format!("{}@aggTrade", agg_trade.symbol),
+            agg_trade.agg_trade_id,
+        )],
+        _ => Vec::new(),
+    }
+}
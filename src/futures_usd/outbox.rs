@@ -0,0 +1,329 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use tracing::{debug, warn};
+use tungstenite::Message;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::client::Socket;
+use crate::futures_usd::enums::binance::StreamProperty;
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::response::{PropertyValue, SubscriptionConfirmed};
+
+/// The number of messages per second Binance accepts from a client per connection.
+pub const DEFAULT_MESSAGES_PER_SECOND: u32 = 10;
+
+/// The id of the next request, unique over all connections.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A token bucket limiting the number of messages sent per second.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket refilling `messages_per_second` tokens per second.
+    pub(crate) fn new(messages_per_second: u32) -> TokenBucket {
+        let capacity: f64 = messages_per_second.max(1) as f64;
+        Self {
+            capacity,
+            // The subscription sent when connecting used the first token.
+            tokens: capacity - 1.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills the bucket, returns `false` when it is empty.
+    fn has_token(&mut self) -> bool {
+        let now: Instant = Instant::now();
+        let refill: f64 = now.duration_since(self.last_refill).as_secs_f64() * self.capacity;
+        self.tokens = (self.tokens + refill).min(self.capacity);
+        self.last_refill = now;
+        self.tokens >= 1.0
+    }
+
+    /// Takes a token, returns `false` when the bucket is empty.
+    fn try_take(&mut self) -> bool {
+        if !self.has_token() {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// A request sent to Binance awaiting its response.
+#[derive(Debug, Clone)]
+enum PendingRequest {
+    Subscription {
+        method: String,
+        streams: Vec<String>,
+    },
+    /// A `SET_PROPERTY` (with value) or `GET_PROPERTY` request.
+    Property {
+        property: StreamProperty,
+        value: Option<bool>,
+    },
+}
+
+/// The runtime subscription changes relative to the subscription sent when connecting, and the
+/// properties set at runtime.
+#[derive(Debug, Default)]
+struct SubscriptionChanges {
+    subscribed: Vec<String>,
+    unsubscribed: Vec<String>,
+    properties: Vec<(StreamProperty, bool)>,
+}
+
+/// Queues the messages sent to Binance, so runtime subscription changes and client pings are sent
+/// at the rate Binance accepts instead of getting the connection closed or the IP banned. Every
+/// connection has its own outbox, the pings of Binance are answered by the socket itself.
+///
+/// The runtime subscription changes and properties are remembered and sent again after a reconnect. Every
+/// request gets a unique id, the requests awaiting their response are kept by id so the
+/// confirmations can be matched to the streams they cover.
+#[derive(Debug, Default)]
+pub(crate) struct Outbox {
+    queue: Mutex<VecDeque<Message>>,
+    changes: Mutex<SubscriptionChanges>,
+    /// The id and streams of the subscription sent when connecting.
+    connect_request: Mutex<Option<(u64, Vec<String>)>>,
+    pending: Mutex<HashMap<u64, PendingRequest>>,
+}
+
+impl Outbox {
+    /// Returns the subscription payload sent when connecting, `None` without streams. The request
+    /// is expected to be answered after every (re)connect.
+    pub(crate) fn connect_payload(&self, streams: Vec<String>) -> Option<String> {
+        if streams.is_empty() {
+            return None;
+        }
+        let id: u64 = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let payload: String = request_payload("SUBSCRIBE", &stream_params(&streams), id);
+        *self.connect_request.lock().unwrap() = Some((id, streams));
+        Some(payload)
+    }
+
+    /// Queues a subscription to the streams, returning the id of the request.
+    pub(crate) fn subscribe(&self, streams: Vec<String>) -> u64 {
+        let mut changes = self.changes.lock().unwrap();
+        changes
+            .unsubscribed
+            .retain(|stream| !streams.contains(stream));
+        for stream in &streams {
+            if !changes.subscribed.contains(stream) {
+                changes.subscribed.push(stream.clone());
+            }
+        }
+        self.push_request("SUBSCRIBE", &streams)
+    }
+
+    /// Queues an unsubscription from the streams, returning the id of the request.
+    pub(crate) fn unsubscribe(&self, streams: Vec<String>) -> u64 {
+        let mut changes = self.changes.lock().unwrap();
+        changes
+            .subscribed
+            .retain(|stream| !streams.contains(stream));
+        for stream in &streams {
+            if !changes.unsubscribed.contains(stream) {
+                changes.unsubscribed.push(stream.clone());
+            }
+        }
+        self.push_request("UNSUBSCRIBE", &streams)
+    }
+
+    /// Queues a client ping ahead of the other messages, so its round trip time is not skewed by
+    /// the rate limiter.
+    pub(crate) fn ping(&self, payload: Vec<u8>) {
+        self.queue
+            .lock()
+            .unwrap()
+            .push_front(Message::Ping(payload));
+    }
+
+    /// Replaces the queued messages of the previous socket of the connection by the runtime
+    /// subscription changes, called when the connection is (re)established or rotated.
+    pub(crate) fn reconnected(&self) {
+        self.queue.lock().unwrap().clear();
+        // The requests of the previous connection are never answered.
+        let mut pending = self.pending.lock().unwrap();
+        pending.clear();
+        if let Some((id, streams)) = self.connect_request.lock().unwrap().clone() {
+            pending.insert(
+                id,
+                PendingRequest::Subscription {
+                    method: "SUBSCRIBE".to_string(),
+                    streams,
+                },
+            );
+        }
+        drop(pending);
+        let changes = self.changes.lock().unwrap();
+        if !changes.subscribed.is_empty() {
+            self.push_request("SUBSCRIBE", &changes.subscribed);
+        }
+        if !changes.unsubscribed.is_empty() {
+            self.push_request("UNSUBSCRIBE", &changes.unsubscribed);
+        }
+        for (property, value) in &changes.properties {
+            self.push_property(property.clone(), *value);
+        }
+    }
+
+    /// Sends the queued messages as long as the token bucket of the connection and the optional
+    /// global one allow.
+    pub(crate) fn flush(
+        &self,
+        socket: &mut Socket,
+        token_bucket: &mut TokenBucket,
+        global_rate_limit: Option<&Mutex<TokenBucket>>,
+    ) -> Result<(), BinanceConnectError> {
+        let mut queue = self.queue.lock().unwrap();
+        while !queue.is_empty()
+            && token_bucket.has_token()
+            && global_rate_limit.map_or(true, |global| global.lock().unwrap().try_take())
+        {
+            token_bucket.try_take();
+            if let Some(message) = queue.pop_front() {
+                debug!(message = ?message, "futures_usd sending");
+                socket.send(message)?;
+            }
+        }
+        if !queue.is_empty() {
+            debug!(
+                queued = queue.len(),
+                "futures_usd outgoing messages queued by the rate limiter"
+            );
+        }
+        Ok(())
+    }
+
+    /// Queues a `SET_PROPERTY` request, returning its id. The property is set again after a
+    /// reconnect.
+    pub(crate) fn set_property(&self, property: StreamProperty, value: bool) -> u64 {
+        let mut changes = self.changes.lock().unwrap();
+        changes.properties.retain(|(set, _)| *set != property);
+        changes.properties.push((property.clone(), value));
+        self.push_property(property, value)
+    }
+
+    /// Queues a `SET_PROPERTY` request without remembering it, returning its id.
+    fn push_property(&self, property: StreamProperty, value: bool) -> u64 {
+        let params: String = format!("\"{}\",{}", property.to_str(), value);
+        self.push(
+            "SET_PROPERTY",
+            &params,
+            PendingRequest::Property {
+                property,
+                value: Some(value),
+            },
+        )
+    }
+
+    /// Queues a `GET_PROPERTY` request, returning its id.
+    pub(crate) fn get_property(&self, property: StreamProperty) -> u64 {
+        let params: String = format!("\"{}\"", property.to_str());
+        self.push(
+            "GET_PROPERTY",
+            &params,
+            PendingRequest::Property {
+                property,
+                value: None,
+            },
+        )
+    }
+
+    /// Completes the request a response event answers, filling in what the request covered:
+    /// the method and streams of a confirmed subscription or the property of a property request.
+    /// Responses of unknown requests (e.g. replayed) and other events are returned unchanged.
+    pub(crate) fn complete(&self, event: Event) -> Event {
+        match event {
+            Event::SubscriptionConfirmedEvent(confirmed) => {
+                match self.pending.lock().unwrap().remove(&confirmed.id) {
+                    Some(PendingRequest::Subscription { method, streams }) => {
+                        Event::SubscriptionConfirmedEvent(SubscriptionConfirmed {
+                            id: confirmed.id,
+                            method,
+                            streams,
+                        })
+                    }
+                    Some(PendingRequest::Property {
+                        property,
+                        value: Some(value),
+                    }) => Event::PropertyEvent(PropertyValue {
+                        id: confirmed.id,
+                        property: Some(property),
+                        value,
+                    }),
+                    _ => Event::SubscriptionConfirmedEvent(confirmed),
+                }
+            }
+            Event::PropertyEvent(property_value) => {
+                match self.pending.lock().unwrap().remove(&property_value.id) {
+                    Some(PendingRequest::Property { property, .. }) => {
+                        Event::PropertyEvent(PropertyValue {
+                            property: Some(property),
+                            ..property_value
+                        })
+                    }
+                    _ => Event::PropertyEvent(property_value),
+                }
+            }
+            Event::ErrorMessageEvent(error_message) => {
+                if let Some(request) = error_message
+                    .id
+                    .and_then(|id| self.pending.lock().unwrap().remove(&id))
+                {
+                    warn!(request = ?request, "futures_usd request failed");
+                }
+                Event::ErrorMessageEvent(error_message)
+            }
+            event => event,
+        }
+    }
+
+    /// Queues a `SUBSCRIBE`/`UNSUBSCRIBE` request for the streams, returning its id.
+    fn push_request(&self, method: &str, streams: &[String]) -> u64 {
+        self.push(
+            method,
+            &stream_params(streams),
+            PendingRequest::Subscription {
+                method: method.to_string(),
+                streams: streams.to_vec(),
+            },
+        )
+    }
+
+    /// Queues a request with the formatted params, returning its id.
+    fn push(&self, method: &str, params: &str, request: PendingRequest) -> u64 {
+        let id: u64 = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(id, request);
+        self.queue
+            .lock()
+            .unwrap()
+            .push_back(Message::Text(request_payload(method, params, id)));
+        id
+    }
+}
+
+/// Formats the streams as the params of a request.
+fn stream_params(streams: &[String]) -> String {
+    streams
+        .iter()
+        .map(|stream| format!("\"{}\"", stream))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Formats a request with the formatted params.
+fn request_payload(method: &str, params: &str, id: u64) -> String {
+    format!(
+        "{{\"method\": \"{}\",\"params\":[{}],\"id\": {}}}",
+        method, params, id
+    )
+}
@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use crate::error::BinanceConnectError;
+
+/// Configures the pings the client sends on its own, besides answering the pings of Binance.
+#[derive(Debug, Clone)]
+pub struct ClientPing {
+    /// The interval between two pings.
+    pub interval: Duration,
+    /// The maximum time to wait for the pong, the connection is reconnected when it expires.
+    pub pong_timeout: Duration,
+}
+
+impl Default for ClientPing {
+    /// Creates a new `ClientPing` instance with an interval of 30 seconds and a pong timeout of 10 seconds.
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            pong_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ClientPing {
+    /// Returns the read timeout that lets a connection send the pings and notice a missing pong
+    /// in time.
+    pub(crate) fn poll_interval(&self) -> Duration {
+        self.interval.min(self.pong_timeout)
+    }
+}
+
+/// Sends the client pings of a single connection and matches the pongs to them.
+#[derive(Debug)]
+pub(crate) struct PingMonitor {
+    config: ClientPing,
+    next_ping: Instant,
+    /// The payload and send time of the ping awaiting its pong.
+    pending: Option<(Vec<u8>, Instant)>,
+    counter: u64,
+}
+
+impl PingMonitor {
+    /// Creates the monitor of a new connection, the first ping is sent after the interval.
+    pub(crate) fn new(config: ClientPing) -> PingMonitor {
+        Self {
+            next_ping: Instant::now() + config.interval,
+            config,
+            pending: None,
+            counter: 0,
+        }
+    }
+
+    /// Returns the payload of the ping to send when one is due.
+    ///
+    /// # Returns
+    ///
+    /// The ping payload, `None` when no ping is due, or a `BinanceConnectError::PongTimeout`
+    /// when the pong of the previous ping did not arrive within the timeout.
+    ///
+    pub(crate) fn poll(&mut self) -> Result<Option<Vec<u8>>, BinanceConnectError> {
+        let now: Instant = Instant::now();
+        if let Some((_, sent)) = &self.pending {
+            let waited: Duration = now.duration_since(*sent);
+            if waited >= self.config.pong_timeout {
+                return Err(BinanceConnectError::PongTimeout(waited));
+            }
+            return Ok(None);
+        }
+        if now < self.next_ping {
+            return Ok(None);
+        }
+        self.counter += 1;
+        let payload: Vec<u8> = self.counter.to_be_bytes().to_vec();
+        self.pending = Some((payload.clone(), now));
+        self.next_ping = now + self.config.interval;
+        Ok(Some(payload))
+    }
+
+    /// Matches a pong to the pending ping, pongs of other (e.g. unsolicited) pings are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The round trip time of the ping, `None` when the pong does not belong to it.
+    ///
+    pub(crate) fn pong_received(&mut self, payload: &[u8]) -> Option<Duration> {
+        match &self.pending {
+            Some((pending, sent)) if pending.as_slice() == payload => {
+                let rtt: Duration = sent.elapsed();
+                self.pending = None;
+                Some(rtt)
+            }
+            _ => None,
+        }
+    }
+}
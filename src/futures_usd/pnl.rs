@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::futures_usd::enums::binance::PositionSide;
+#[cfg(feature = "user-data")]
+use crate::futures_usd::enums::binance::{ExecutionType, Side};
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::response::AccountSnapshot;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::MarkPriceUpdate;
+#[cfg(feature = "user-data")]
+use crate::futures_usd::response::{AccountUpdate, OrderData};
+
+/// The PnL of a symbol and position side.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Pnl {
+    pub symbol: String,
+    pub position_side: PositionSide,
+    /// The signed position amount, negative for short positions.
+    pub position_amount: f64,
+    pub entry_price: f64,
+    /// The latest mark price, `None` when no mark price update was received.
+    pub mark_price: Option<f64>,
+    /// The accumulated realized profit of the fills, excluding the commission.
+    pub realized_pnl: f64,
+    /// The accumulated commission of the fills per commission asset (e.g. `USDT` or `BNB`).
+    pub commissions: BTreeMap<String, f64>,
+    /// The unrealized PnL at the latest mark price, `0.0` without a mark price.
+    pub unrealized_pnl: f64,
+}
+
+/// The tracked position and realized PnL of a symbol and position side.
+#[derive(Debug, Clone, Default)]
+struct PositionPnl {
+    position_amount: f64,
+    entry_price: f64,
+    realized_pnl: f64,
+    commissions: BTreeMap<String, f64>,
+}
+
+impl PositionPnl {
+    /// Applies a fill of the signed quantity at the price to the position amount and entry price.
+    #[cfg(feature = "user-data")]
+    fn fill(&mut self, quantity: f64, price: f64) {
+        let amount: f64 = self.position_amount + quantity;
+        if self.position_amount == 0.0 || self.position_amount.signum() == quantity.signum() {
+            // Opening or increasing, the entry price is the weighted average.
+            self.entry_price = (self.entry_price * self.position_amount.abs()
+                + price * quantity.abs())
+                / amount.abs();
+        } else if amount != 0.0 && amount.signum() != self.position_amount.signum() {
+            // Flipping, the remainder is opened at the fill price.
+            self.entry_price = price;
+        } else if amount == 0.0 {
+            self.entry_price = 0.0;
+        }
+        self.position_amount = amount;
+    }
+}
+
+/// The positions and mark prices maintained by the `PnlTracker`.
+#[derive(Debug, Default)]
+struct PnlState {
+    positions: HashMap<(String, PositionSide), PositionPnl>,
+    mark_prices: HashMap<String, f64>,
+}
+
+/// Accumulates the realized PnL from the order fills (`OrderData::realized_profit`) and computes
+/// the unrealized PnL from the latest mark prices, per symbol and position side.
+///
+/// The positions follow the fills and are corrected by the `AccountUpdate` and `AccountSnapshot`
+/// events. Subscribe to the mark price stream of the symbols (or `with_mark_price_updates()`) for the
+/// unrealized PnL.
+#[derive(Debug, Default)]
+pub struct PnlTracker {
+    state: RwLock<PnlState>,
+}
+
+impl PnlTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> PnlTracker {
+        Self::default()
+    }
+
+    /// Applies the order update, account and mark price events, other events are ignored.
+    pub fn apply(&self, event: &Event) {
+        match event {
+            #[cfg(feature = "user-data")]
+            Event::OrderTradeUpdateEvent(order_trade_update) => {
+                self.apply_fill(&order_trade_update.order_data)
+            }
+            #[cfg(feature = "user-data")]
+            Event::AccountUpdateEvent(account_update) => self.apply_account_update(account_update),
+            Event::AccountSnapshotEvent(account_snapshot) => self.apply_snapshot(account_snapshot),
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdateEvent(mark_price_update) => {
+                self.apply_mark_prices(std::slice::from_ref(mark_price_update))
+            }
+            #[cfg(feature = "market-data")]
+            Event::MarkPriceUpdatesEvent(mark_price_updates) => {
+                self.apply_mark_prices(&mark_price_updates.data)
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the PnL of the symbol and position side (`Both` in one-way mode).
+    pub fn pnl(&self, symbol: &str, position_side: PositionSide) -> Option<Pnl> {
+        let state = self.state.read().unwrap();
+        let key: (String, PositionSide) = (symbol.to_uppercase(), position_side);
+        state
+            .positions
+            .get(&key)
+            .map(|position| to_pnl(&key, position, &state.mark_prices))
+    }
+
+    /// Returns the PnL of every tracked position side, of all symbols when `symbol` is `None`.
+    pub fn pnls(&self, symbol: Option<&str>) -> Vec<Pnl> {
+        let symbol: Option<String> = symbol.map(str::to_uppercase);
+        let state = self.state.read().unwrap();
+        state
+            .positions
+            .iter()
+            .filter(|(key, _)| symbol.as_ref().map_or(true, |symbol| key.0 == *symbol))
+            .map(|(key, position)| to_pnl(key, position, &state.mark_prices))
+            .collect()
+    }
+
+    /// Returns the total realized PnL, of all symbols when `symbol` is `None`.
+    pub fn realized_pnl(&self, symbol: Option<&str>) -> f64 {
+        self.pnls(symbol).iter().map(|pnl| pnl.realized_pnl).sum()
+    }
+
+    /// Returns the total unrealized PnL, of all symbols when `symbol` is `None`.
+    pub fn unrealized_pnl(&self, symbol: Option<&str>) -> f64 {
+        self.pnls(symbol).iter().map(|pnl| pnl.unrealized_pnl).sum()
+    }
+
+    /// Accumulates the realized profit and commission of a fill and updates the position.
+    #[cfg(feature = "user-data")]
+    fn apply_fill(&self, order_data: &OrderData) {
+        if order_data.execution_type != ExecutionType::Trade {
+            return;
+        }
+        let quantity: f64 = match order_data.side {
+            Side::Buy => order_data.order_last_filled_quantity,
+            Side::Sell => -order_data.order_last_filled_quantity,
+        };
+        let mut state = self.state.write().unwrap();
+        let position: &mut PositionPnl = state
+            .positions
+            .entry((order_data.symbol.clone(), order_data.position_side.clone()))
+            .or_default();
+        position.realized_pnl += order_data.realized_profit;
+        *position
+            .commissions
+            .entry(order_data.commission_asset.clone())
+            .or_default() += order_data.commission;
+        position.fill(quantity, order_data.last_filled_price);
+    }
+
+    /// Corrects the position amounts and entry prices by the account update.
+    #[cfg(feature = "user-data")]
+    fn apply_account_update(&self, account_update: &AccountUpdate) {
+        let mut state = self.state.write().unwrap();
+        for position in &account_update.update_data.positions {
+            let tracked: &mut PositionPnl = state
+                .positions
+                .entry((position.symbol.clone(), position.position_side.clone()))
+                .or_default();
+            tracked.position_amount = position.position_amount;
+            tracked.entry_price = position.entry_price;
+        }
+    }
+
+    /// Replaces the position amounts and entry prices by the snapshot, keeping the realized PnL.
+    fn apply_snapshot(&self, account_snapshot: &AccountSnapshot) {
+        let mut state = self.state.write().unwrap();
+        for position in state.positions.values_mut() {
+            position.position_amount = 0.0;
+            position.entry_price = 0.0;
+        }
+        for position in &account_snapshot.positions {
+            let tracked: &mut PositionPnl = state
+                .positions
+                .entry((position.symbol.clone(), position.position_side.clone()))
+                .or_default();
+            tracked.position_amount = position.position_amount;
+            tracked.entry_price = position.entry_price;
+        }
+    }
+
+    /// Stores the latest mark prices.
+    #[cfg(feature = "market-data")]
+    fn apply_mark_prices(&self, mark_price_updates: &[MarkPriceUpdate]) {
+        let mut state = self.state.write().unwrap();
+        for mark_price_update in mark_price_updates {
+            state.mark_prices.insert(
+                mark_price_update.symbol.clone(),
+                mark_price_update.mark_price,
+            );
+        }
+    }
+}
+
+/// Returns the PnL of the tracked position at the latest mark price.
+fn to_pnl(
+    key: &(String, PositionSide),
+    position: &PositionPnl,
+    mark_prices: &HashMap<String, f64>,
+) -> Pnl {
+    let mark_price: Option<f64> = mark_prices.get(&key.0).copied();
+    Pnl {
+        symbol: key.0.clone(),
+        position_side: key.1.clone(),
+        position_amount: position.position_amount,
+        entry_price: position.entry_price,
+        mark_price,
+        realized_pnl: position.realized_pnl,
+        commissions: position.commissions.clone(),
+        unrealized_pnl: mark_price.map_or(0.0, |mark_price| {
+            position.position_amount * (mark_price - position.entry_price)
+        }),
+    }
+}
@@ -0,0 +1,320 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::rate_limit;
+use crate::futures_usd::response::{ExchangeInfo, FundingRate, OpenInterest};
+use crate::futures_usd::rest::{get_funding_rate_history, get_open_interest};
+use crate::futures_usd::shared::shared;
+
+/// The granularity with which the scheduler thread checks its stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// The tick at which the slowdown follows the weight headroom, whether or not polls are due.
+const SLOWDOWN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The request weight of the `/fapi/v1/openInterest` endpoint.
+const OPEN_INTEREST_WEIGHT: u32 = 1;
+/// The request weight of the `/fapi/v1/fundingRate` endpoint.
+const FUNDING_RATE_WEIGHT: u32 = 1;
+/// The request weight of the `/fapi/v1/exchangeInfo` endpoint.
+const EXCHANGE_INFO_WEIGHT: u32 = 1;
+
+/// The REST endpoint polled by a scheduled poll.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Poll {
+    OpenInterest { symbol: String },
+    FundingRateHistory { symbol: String, limit: u16 },
+    ExchangeInfo,
+}
+
+impl Poll {
+    /// Returns the request weight of the poll.
+    fn weight(&self) -> u32 {
+        match self {
+            Poll::OpenInterest { .. } => OPEN_INTEREST_WEIGHT,
+            Poll::FundingRateHistory { .. } => FUNDING_RATE_WEIGHT,
+            Poll::ExchangeInfo => EXCHANGE_INFO_WEIGHT,
+        }
+    }
+
+    /// Executes the request of the poll.
+    fn execute(&self, test_net: bool) -> Result<PollEvent, BinanceConnectError> {
+        match self {
+            Poll::OpenInterest { symbol } => {
+                get_open_interest(test_net, symbol).map(PollEvent::OpenInterest)
+            }
+            Poll::FundingRateHistory { symbol, limit } => {
+                get_funding_rate_history(test_net, symbol, *limit).map(PollEvent::FundingRates)
+            }
+            // The refreshed exchange info replaces the one shared by the process.
+            Poll::ExchangeInfo => shared()
+                .refresh_exchange_info(test_net)
+                .map(PollEvent::ExchangeInfo),
+        }
+    }
+}
+
+/// The result of a scheduled poll.
+#[derive(Debug)]
+pub enum PollEvent {
+    OpenInterest(OpenInterest),
+    FundingRates(Vec<FundingRate>),
+    ExchangeInfo(Arc<ExchangeInfo>),
+    /// The poll failed, it is retried at its next scheduled time.
+    Failed {
+        poll: Poll,
+        error: String,
+    },
+}
+
+/// A poll with its base interval and the time it is due next.
+#[derive(Debug)]
+struct ScheduledPoll {
+    poll: Poll,
+    interval: Duration,
+    next_due: Instant,
+}
+
+/// Schedules the REST pollers (open interest, funding rate history, exchangeInfo refresh) on a
+/// single thread that shares the request weight budget of `rate_limit` with all other requests.
+///
+/// The polls of multiple symbols are staggered over their interval instead of firing at once.
+/// When the weight headroom drops below the low headroom threshold, the polling frequency is
+/// halved (down to `1 / max_slowdown`), and it recovers once the headroom is back above twice the
+/// threshold. The headroom is checked every second, independent of the polls that are due. Polls
+/// never use the last weight of a minute, they wait for the weight to reset.
+#[derive(Debug)]
+pub struct PollerScheduler {
+    test_net: bool,
+    polls: Vec<ScheduledPoll>,
+    /// The fraction of the weight limit below which the polling frequency is degraded.
+    low_headroom: f64,
+    /// The maximum factor by which the poll intervals are stretched.
+    max_slowdown: u32,
+    /// The factor by which the poll intervals are currently stretched.
+    slowdown: Arc<AtomicU32>,
+    /// The sender of the poll results, moved to the scheduler thread on start so the receiver
+    /// disconnects once the thread has finished.
+    sender: Option<Sender<PollEvent>>,
+    receiver: Receiver<PollEvent>,
+    stop_signal: Arc<AtomicBool>,
+    /// The scheduler thread, joined on stop.
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PollerScheduler {
+    /// Creates a new scheduler without polls.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be polled.
+    ///
+    pub fn new(test_net: bool) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            test_net,
+            polls: Vec::new(),
+            low_headroom: 0.25,
+            max_slowdown: 8,
+            slowdown: Arc::new(AtomicU32::new(1)),
+            sender: Some(sender),
+            receiver,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            thread: Mutex::new(None),
+        }
+    }
+
+    /// Polls the open interest of the symbols, staggered over the interval.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbols`: The trading symbols to poll.
+    /// - `interval`: The interval with which every symbol is polled.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the polls added.
+    ///
+    pub fn with_open_interest(self, symbols: &[&str], interval: Duration) -> Self {
+        let polls: Vec<Poll> = symbols
+            .iter()
+            .map(|symbol| Poll::OpenInterest {
+                symbol: symbol.to_uppercase(),
+            })
+            .collect();
+        self.with_staggered(polls, interval)
+    }
+
+    /// Polls the funding rate history of the symbols, staggered over the interval.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbols`: The trading symbols to poll.
+    /// - `limit`: The number of most recent funding rates retrieved per poll.
+    /// - `interval`: The interval with which every symbol is polled.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the polls added.
+    ///
+    pub fn with_funding_rate_history(
+        self,
+        symbols: &[&str],
+        limit: u16,
+        interval: Duration,
+    ) -> Self {
+        let polls: Vec<Poll> = symbols
+            .iter()
+            .map(|symbol| Poll::FundingRateHistory {
+                symbol: symbol.to_uppercase(),
+                limit,
+            })
+            .collect();
+        self.with_staggered(polls, interval)
+    }
+
+    /// Refreshes the exchangeInfo with the interval.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The interval with which the exchangeInfo is refreshed.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the poll added.
+    ///
+    pub fn with_exchange_info_refresh(self, interval: Duration) -> Self {
+        self.with_staggered(vec![Poll::ExchangeInfo], interval)
+    }
+
+    /// Sets the fraction (0.0 to 1.0) of the weight limit below which the polling frequency is
+    /// degraded, defaults to 0.25.
+    pub fn with_low_headroom(mut self, low_headroom: f64) -> Self {
+        self.low_headroom = low_headroom.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the maximum factor by which the poll intervals are stretched when the weight
+    /// headroom is low, defaults to 8.
+    pub fn with_max_slowdown(mut self, max_slowdown: u32) -> Self {
+        self.max_slowdown = max_slowdown.max(1);
+        self
+    }
+
+    /// Starts the scheduler thread, the thread finishes when stopped, when the receiver is dropped
+    /// or right away without polls.
+    pub fn start(mut self) -> Self {
+        let sender: Sender<PollEvent> = match self.sender.take() {
+            Some(sender) => sender,
+            None => return self,
+        };
+        let mut polls: Vec<ScheduledPoll> = std::mem::take(&mut self.polls);
+        let test_net: bool = self.test_net;
+        let low_headroom: f64 = self.low_headroom;
+        let max_slowdown: u32 = self.max_slowdown;
+        let slowdown: Arc<AtomicU32> = Arc::clone(&self.slowdown);
+        let stop_signal: Arc<AtomicBool> = Arc::clone(&self.stop_signal);
+        let thread: JoinHandle<()> = thread::spawn(move || {
+            let mut factor: u32 = adjust_slowdown(&slowdown, low_headroom, max_slowdown);
+            let mut next_adjustment: Instant = Instant::now() + SLOWDOWN_INTERVAL;
+            while !stop_signal.load(Ordering::Relaxed) {
+                let now: Instant = Instant::now();
+                if now >= next_adjustment {
+                    factor = adjust_slowdown(&slowdown, low_headroom, max_slowdown);
+                    next_adjustment = now + SLOWDOWN_INTERVAL;
+                }
+                let next: &mut ScheduledPoll = match polls.iter_mut().min_by_key(|p| p.next_due) {
+                    Some(next) => next,
+                    None => break,
+                };
+                if next.next_due > now {
+                    thread::sleep((next.next_due - now).min(STOP_CHECK_INTERVAL));
+                    continue;
+                }
+                if rate_limit::remaining_weight() <= next.poll.weight() {
+                    debug!(poll = ?next.poll, "futures_usd weight budget used up, poll delayed");
+                    next.next_due = now + rate_limit::until_reset();
+                    continue;
+                }
+                let event: PollEvent = match next.poll.execute(test_net) {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!(poll = ?next.poll, error = %err, "futures_usd poll failed");
+                        PollEvent::Failed {
+                            poll: next.poll.clone(),
+                            error: err.to_string(),
+                        }
+                    }
+                };
+                next.next_due = now + next.interval * factor;
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        *self.thread.lock().unwrap() = Some(thread);
+        self
+    }
+
+    /// Returns the receiver of the poll results, disconnected once the scheduler thread has
+    /// finished and the results are drained.
+    pub fn consume(&self) -> &Receiver<PollEvent> {
+        &self.receiver
+    }
+
+    /// Returns the factor by which the poll intervals are currently stretched, 1 at full speed.
+    pub fn slowdown(&self) -> u32 {
+        self.slowdown.load(Ordering::Relaxed)
+    }
+
+    /// Stops the scheduler thread and waits for it to finish, including a poll in flight.
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.lock().unwrap().take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Adds the polls, spreading their first execution evenly over the interval.
+    fn with_staggered(mut self, polls: Vec<Poll>, interval: Duration) -> Self {
+        let now: Instant = Instant::now();
+        let count: u32 = polls.len() as u32;
+        for (index, poll) in polls.into_iter().enumerate() {
+            self.polls.push(ScheduledPoll {
+                poll,
+                interval,
+                next_due: now + interval * index as u32 / count,
+            });
+        }
+        self
+    }
+}
+
+/// Doubles the slowdown when the weight headroom is below the threshold and halves it once the
+/// headroom is back above twice the threshold, returning the new slowdown.
+fn adjust_slowdown(slowdown: &AtomicU32, low_headroom: f64, max_slowdown: u32) -> u32 {
+    let headroom: f64 = rate_limit::headroom();
+    let current: u32 = slowdown.load(Ordering::Relaxed);
+    let adjusted: u32 = if headroom < low_headroom {
+        (current * 2).min(max_slowdown)
+    } else if headroom >= low_headroom * 2.0 {
+        (current / 2).max(1)
+    } else {
+        current
+    };
+    if adjusted != current {
+        debug!(
+            headroom = headroom,
+            slowdown = adjusted,
+            previous_slowdown = current,
+            "futures_usd poll slowdown adjusted"
+        );
+        slowdown.store(adjusted, Ordering::Relaxed);
+    }
+    adjusted
+}
@@ -0,0 +1,400 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use tracing::{debug, info, warn};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::client::{process_frame, ConnectionContext, FrameState};
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::outbox::{Outbox, TokenBucket};
+use crate::futures_usd::sink::{EventSender, EventSink};
+use crate::futures_usd::stats::{ConnectionStats, ConnectionStatsSnapshot};
+use crate::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+/// The granularity with which the processing threads of the attached streams check the stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A frame or connection event routed to an attached stream.
+#[derive(Debug)]
+enum Routed {
+    Frame(String),
+    Event(Box<Event>),
+}
+
+/// A payload of a combined stream, wrapped with its stream name.
+#[derive(Deserialize)]
+struct Combined<'a> {
+    stream: &'a str,
+    #[serde(borrow)]
+    data: &'a RawValue,
+}
+
+/// A pooled connection and the stream names it carries.
+#[derive(Debug)]
+struct PooledConnection {
+    streams: HashSet<String>,
+    /// The subscription changes of the connection.
+    outbox: Arc<Outbox>,
+}
+
+/// The attached streams and the pooled connections carrying their stream names.
+#[derive(Debug, Default)]
+struct Routes {
+    /// The frame channel of every attached stream.
+    attached: HashMap<u64, Sender<Routed>>,
+    /// The attached streams subscribed to every stream name.
+    subscribers: HashMap<String, Vec<u64>>,
+    /// The pooled connections, in connection order.
+    connections: Vec<PooledConnection>,
+}
+
+/// Routes the frames of the pooled connections to the attached streams subscribed to them.
+#[derive(Debug, Default)]
+pub(crate) struct Router {
+    routes: Mutex<Routes>,
+}
+
+impl Router {
+    /// Sends the payload of a combined stream frame to the streams subscribed to its stream.
+    pub(crate) fn dispatch(&self, frame: String) {
+        let combined: Combined = match serde_json::from_str(&frame) {
+            Ok(combined) => combined,
+            // The responses to the subscription requests are not routed.
+            Err(_) => {
+                debug!(frame = %frame, "futures_usd pooled frame not routed");
+                return;
+            }
+        };
+        let routes = self.routes.lock().unwrap();
+        for id in routes
+            .subscribers
+            .get(combined.stream)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(frames) = routes.attached.get(id) {
+                let _ = frames.send(Routed::Frame(combined.data.get().to_string()));
+            }
+        }
+    }
+
+    /// Sends an event of the pooled connection to the streams attached to any of its stream names,
+    /// once per stream.
+    fn notify(&self, connection: usize, event: &Event) {
+        let routes = self.routes.lock().unwrap();
+        let mut notified: HashSet<u64> = HashSet::new();
+        let streams = routes
+            .connections
+            .get(connection)
+            .map(|connection| &connection.streams);
+        for stream in streams.into_iter().flatten() {
+            for id in routes.subscribers.get(stream).into_iter().flatten() {
+                if let (true, Some(frames)) = (notified.insert(*id), routes.attached.get(id)) {
+                    let _ = frames.send(Routed::Event(Box::new(event.clone())));
+                }
+            }
+        }
+    }
+}
+
+/// Forwards the connection events (reconnects, closes and failures) of a pooled connection to the
+/// streams attached to it.
+struct ConnectionEvents {
+    router: Arc<Router>,
+    connection: usize,
+}
+
+impl EventSink for ConnectionEvents {
+    fn publish(&mut self, event: &Event) {
+        if let Event::ReconnectEvent(_)
+        | Event::ConnectionClosedEvent(_)
+        | Event::ConnectionFailedEvent(_) = event
+        {
+            self.router.notify(self.connection, event);
+        }
+    }
+}
+
+/// Shares connections and an outgoing-message rate limit between the `FuturesUsdStream`s of a
+/// process, e.g. the streams of several strategies.
+///
+/// A stream attached with `FuturesUsdStream::with_connection_manager` does not open connections
+/// for its public streams. Its stream names are subscribed on the pooled connections of the
+/// manager instead, so a stream name requested by several attached streams is received once. The
+/// pooled connections receive the combined stream, at most `max_streams_per_connection` stream
+/// names each, and are opened with the configuration of the manager: endpoint, transport,
+/// reconnect policy and rotation. Every attached stream processes the frames routed to it on its
+/// own thread with its own configuration (filters, aliases, caches, sinks) and receives the
+/// reconnect, close and failure events of the pooled connections carrying its streams.
+///
+/// Every connection of the attached streams, pooled or not (e.g. a user data connection), sends
+/// at most the global number of messages per second besides its own limit, so a process with many
+/// streams stays within the message rate of its IP address.
+///
+/// Cloning a `ConnectionManager` shares its connections. The pooled connections are closed when
+/// the manager and its clones are dropped or on `stop`.
+#[derive(Debug, Clone)]
+pub struct ConnectionManager {
+    inner: Arc<Pool>,
+}
+
+#[derive(Debug)]
+struct Pool {
+    config: FuturesWebSocketConfig,
+    router: Arc<Router>,
+    rate_limit: Arc<Mutex<TokenBucket>>,
+    /// The statistics of the pooled connections, in connection order.
+    stats: Mutex<Vec<Arc<ConnectionStats>>>,
+    /// The sender of the events of the pooled connections, without receiver: their connection
+    /// events are forwarded to the attached streams by the sinks of the connections.
+    sender: EventSender,
+    stop_signal: Arc<AtomicBool>,
+    next_id: AtomicU64,
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+}
+
+impl ConnectionManager {
+    /// Creates a manager without connections, they are opened once streams are attached.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The configuration of the pooled connections.
+    /// - `messages_per_second`: The maximum number of messages sent per second by all connections
+    ///   of the attached streams together.
+    ///
+    /// # Returns
+    ///
+    /// A new `ConnectionManager`.
+    ///
+    pub fn new(config: FuturesWebSocketConfig, messages_per_second: u32) -> ConnectionManager {
+        Self {
+            inner: Arc::new(Pool {
+                config,
+                router: Arc::new(Router::default()),
+                rate_limit: Arc::new(Mutex::new(TokenBucket::new(messages_per_second))),
+                stats: Mutex::new(Vec::new()),
+                sender: EventSender::sinks_only(),
+                stop_signal: Arc::new(AtomicBool::new(false)),
+                next_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Returns the statistics of every pooled connection, in the order they were opened.
+    pub fn stats(&self) -> Vec<ConnectionStatsSnapshot> {
+        self.inner
+            .stats
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|stats| stats.snapshot())
+            .collect()
+    }
+
+    /// Closes the pooled connections.
+    pub fn stop(&self) {
+        self.inner.stop_signal.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the rate limiter shared by the connections of the attached streams.
+    pub(crate) fn rate_limit(&self) -> Arc<Mutex<TokenBucket>> {
+        Arc::clone(&self.inner.rate_limit)
+    }
+
+    /// Attaches a stream, subscribing to its stream names on the pooled connections and processing
+    /// the routed frames with its context on a new thread.
+    ///
+    /// # Returns
+    ///
+    /// The id of the attached stream.
+    ///
+    pub(crate) fn attach(&self, streams: Vec<String>, context: ConnectionContext) -> u64 {
+        let id: u64 = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (frames, receiver) = channel();
+        self.inner
+            .router
+            .routes
+            .lock()
+            .unwrap()
+            .attached
+            .insert(id, frames);
+        self.subscribe(id, streams);
+        context.status.set_connected(true);
+        context.stats.set_connected(true);
+        thread::spawn(move || process(receiver, context));
+        id
+    }
+
+    /// Subscribes the attached stream to the stream names, those not yet received are subscribed
+    /// on a pooled connection with room left or on a new one.
+    ///
+    /// # Returns
+    ///
+    /// The id of the last subscription request, `0` when every stream was already received.
+    ///
+    pub(crate) fn subscribe(&self, id: u64, streams: Vec<String>) -> u64 {
+        let max_streams: usize = self.inner.config.max_streams();
+        let mut routes = self.inner.router.routes.lock().unwrap();
+        let mut missing: Vec<String> = Vec::new();
+        for stream in streams {
+            let subscribers: &mut Vec<u64> = routes.subscribers.entry(stream.clone()).or_default();
+            if subscribers.is_empty() {
+                missing.push(stream);
+            }
+            if !subscribers.contains(&id) {
+                subscribers.push(id);
+            }
+        }
+        let mut request_id: u64 = 0;
+        for connection in routes.connections.iter_mut() {
+            let room: usize = max_streams.saturating_sub(connection.streams.len());
+            if room == 0 || missing.is_empty() {
+                continue;
+            }
+            let batch: Vec<String> = missing.drain(..missing.len().min(room)).collect();
+            connection.streams.extend(batch.iter().cloned());
+            request_id = connection.outbox.subscribe(batch);
+        }
+        while !missing.is_empty() {
+            let batch: Vec<String> = missing.drain(..missing.len().min(max_streams)).collect();
+            let outbox: Arc<Outbox> = self.open(routes.connections.len(), &batch[0]);
+            if batch.len() > 1 {
+                request_id = outbox.subscribe(batch[1..].to_vec());
+            }
+            routes.connections.push(PooledConnection {
+                streams: batch.into_iter().collect(),
+                outbox,
+            });
+        }
+        request_id
+    }
+
+    /// Unsubscribes the attached stream from the stream names, those no attached stream is
+    /// subscribed to anymore are unsubscribed on their pooled connection.
+    ///
+    /// # Returns
+    ///
+    /// The id of the last unsubscription request, `0` when the streams are still received.
+    ///
+    pub(crate) fn unsubscribe(&self, id: u64, streams: Vec<String>) -> u64 {
+        let mut routes = self.inner.router.routes.lock().unwrap();
+        let mut unused: Vec<String> = Vec::new();
+        for stream in streams {
+            if let Some(subscribers) = routes.subscribers.get_mut(&stream) {
+                subscribers.retain(|subscriber| *subscriber != id);
+                if subscribers.is_empty() {
+                    routes.subscribers.remove(&stream);
+                    unused.push(stream);
+                }
+            }
+        }
+        let mut request_id: u64 = 0;
+        for connection in routes.connections.iter_mut() {
+            let batch: Vec<String> = unused
+                .iter()
+                .filter(|stream| connection.streams.remove(*stream))
+                .cloned()
+                .collect();
+            if !batch.is_empty() {
+                request_id = connection.outbox.unsubscribe(batch);
+            }
+        }
+        request_id
+    }
+
+    /// Detaches a stream, unsubscribing from the stream names only it was subscribed to.
+    pub(crate) fn detach(&self, id: u64) {
+        let streams: Vec<String> = {
+            let mut routes = self.inner.router.routes.lock().unwrap();
+            routes.attached.remove(&id);
+            routes
+                .subscribers
+                .iter()
+                .filter(|(_, subscribers)| subscribers.contains(&id))
+                .map(|(stream, _)| stream.clone())
+                .collect()
+        };
+        self.unsubscribe(id, streams);
+    }
+
+    /// Opens a pooled connection to the combined stream of the stream name.
+    ///
+    /// # Returns
+    ///
+    /// The `Outbox` of the subscription changes of the connection.
+    ///
+    fn open(&self, connection: usize, stream: &str) -> Arc<Outbox> {
+        let stats: Arc<ConnectionStats> = Arc::new(ConnectionStats::new(connection));
+        self.inner.stats.lock().unwrap().push(Arc::clone(&stats));
+        let outbox: Arc<Outbox> = Arc::new(Outbox::default());
+        let mut sender: EventSender = self.inner.sender.clone();
+        sender.add_sink(Arc::new(Mutex::new(ConnectionEvents {
+            router: Arc::clone(&self.inner.router),
+            connection,
+        })));
+        info!(
+            connection = connection,
+            stream = stream,
+            "futures_usd opening pooled connection"
+        );
+        FuturesUsdStream::open_pooled(self.inner.config.clone(), stream, |context| {
+            context.sender = sender;
+            context.stop_signal = Arc::clone(&self.inner.stop_signal);
+            context.stats = stats;
+            context.outbox = Arc::clone(&outbox);
+            context.global_rate_limit = Some(self.rate_limit());
+            context.router = Some(Arc::clone(&self.inner.router));
+        });
+        outbox
+    }
+}
+
+/// Processes the frames routed to an attached stream until it is stopped or detached.
+fn process(frames: Receiver<Routed>, context: ConnectionContext) {
+    let mut state: FrameState = FrameState::default();
+    while !context.stop_signal.load(Ordering::Relaxed) {
+        let result: Result<(), BinanceConnectError> = match frames.recv_timeout(STOP_CHECK_INTERVAL)
+        {
+            Ok(Routed::Frame(frame)) => {
+                context.stats.message_received(frame.len());
+                process_frame(frame, &context, &mut state)
+            }
+            Ok(Routed::Event(event)) => {
+                // Values received before the disconnect must not be read as current.
+                if let Event::ReconnectEvent(_) = &*event {
+                    state.restart_updates();
+                    context.status.reconnected();
+                    context.stats.reconnected();
+                    if let Some(latest_cache) = &context.latest_cache {
+                        latest_cache.invalidate_all();
+                    }
+                }
+                context
+                    .sender
+                    .send(*event)
+                    .map_err(BinanceConnectError::from)
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(()),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        match result {
+            Ok(()) => {}
+            // The stream was dropped.
+            Err(BinanceConnectError::MpscSendError(_)) => break,
+            Err(err) => warn!(error = %err, "futures_usd pooled frame dropped"),
+        }
+    }
+    context.status.set_connected(false);
+    context.stats.set_connected(false);
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::BookTicker;
+
+/// The best bid and ask of a symbol.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Quote {
+    pub bid_price: f64,
+    pub bid_quantity: f64,
+    pub ask_price: f64,
+    pub ask_quantity: f64,
+    pub update_id: u64,
+    /// The transaction time (UNIX millis) of the update.
+    pub transaction_time: u64,
+}
+
+/// Maintains the best bid and ask per symbol from the `bookTicker` events, so other threads can
+/// query them without consuming the event stream.
+///
+/// Updates with an update id older than the cached quote are ignored.
+#[derive(Debug, Default)]
+pub struct QuoteCache {
+    quotes: RwLock<HashMap<String, Quote>>,
+}
+
+impl QuoteCache {
+    /// Creates an empty cache.
+    pub fn new() -> QuoteCache {
+        Self::default()
+    }
+
+    /// Applies the book ticker events, other events are ignored.
+    pub fn apply(&self, event: &Event) {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::BookTickerEvent(book_ticker) => self.update(std::slice::from_ref(book_ticker)),
+            #[cfg(feature = "market-data")]
+            Event::BookTickersEvent(book_tickers) => self.update(&book_tickers.data),
+            _ => {}
+        }
+    }
+
+    /// Returns the best bid and ask of the symbol, `None` when no book ticker was received.
+    pub fn quote(&self, symbol: &str) -> Option<Quote> {
+        self.quotes
+            .read()
+            .unwrap()
+            .get(&symbol.to_uppercase())
+            .cloned()
+    }
+
+    /// Returns the best bid price of the symbol.
+    pub fn best_bid(&self, symbol: &str) -> Option<f64> {
+        self.quote(symbol).map(|quote| quote.bid_price)
+    }
+
+    /// Returns the best ask price of the symbol.
+    pub fn best_ask(&self, symbol: &str) -> Option<f64> {
+        self.quote(symbol).map(|quote| quote.ask_price)
+    }
+
+    /// Returns the mid price (the average of the best bid and ask) of the symbol.
+    pub fn mid(&self, symbol: &str) -> Option<f64> {
+        self.quote(symbol)
+            .map(|quote| (quote.bid_price + quote.ask_price) / 2.0)
+    }
+
+    /// Returns the symbols with a cached quote.
+    pub fn symbols(&self) -> Vec<String> {
+        self.quotes.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Stores the book tickers unless an update with a newer update id is cached.
+    #[cfg(feature = "market-data")]
+    fn update(&self, book_tickers: &[BookTicker]) {
+        let mut quotes = self.quotes.write().unwrap();
+        for book_ticker in book_tickers {
+            if quotes
+                .get(&book_ticker.symbol)
+                .map_or(false, |quote| quote.update_id > book_ticker.update_id)
+            {
+                continue;
+            }
+            quotes.insert(
+                book_ticker.symbol.clone(),
+                Quote {
+                    bid_price: book_ticker.bid_price,
+                    bid_quantity: book_ticker.bid_quantity,
+                    ask_price: book_ticker.ask_price,
+                    ask_quantity: book_ticker.ask_quantity,
+                    update_id: book_ticker.update_id,
+                    transaction_time: book_ticker.transaction_time,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+use crate::futures_usd::time_sync::server_now;
+
+/// The header in which Binance reports the request weight used in the current minute.
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+/// The default request weight limit per minute of the USD-M Futures REST API.
+pub const DEFAULT_WEIGHT_LIMIT: u32 = 2400;
+
+/// The request weight used in the current minute, as last reported by Binance.
+static USED_WEIGHT: AtomicU32 = AtomicU32::new(0);
+/// The minute (server time in UNIX minutes) to which `USED_WEIGHT` belongs.
+static USED_WEIGHT_MINUTE: AtomicU64 = AtomicU64::new(0);
+/// The request weight limit per minute shared by all REST requests of the process.
+static WEIGHT_LIMIT: AtomicU32 = AtomicU32::new(DEFAULT_WEIGHT_LIMIT);
+
+/// Sets the request weight limit per minute the budget is computed against, e.g. the
+/// `REQUEST_WEIGHT` limit of the `ExchangeInfo` rate limits or a lower share of it when other
+/// processes use the same IP.
+pub fn set_weight_limit(limit: u32) {
+    WEIGHT_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Returns the request weight limit per minute.
+pub fn weight_limit() -> u32 {
+    WEIGHT_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Returns the request weight used in the current minute, 0 when no request was made this minute.
+pub fn used_weight() -> u32 {
+    match USED_WEIGHT_MINUTE.load(Ordering::Relaxed) == current_minute() {
+        true => USED_WEIGHT.load(Ordering::Relaxed),
+        false => 0,
+    }
+}
+
+/// Returns the request weight that can still be used in the current minute.
+pub fn remaining_weight() -> u32 {
+    weight_limit().saturating_sub(used_weight())
+}
+
+/// Returns the fraction (0.0 to 1.0) of the request weight limit still available this minute.
+pub fn headroom() -> f64 {
+    match weight_limit() {
+        0 => 0.0,
+        limit => remaining_weight() as f64 / limit as f64,
+    }
+}
+
+/// Records the used request weight reported in the headers of a REST response.
+pub(crate) fn record(headers: &HeaderMap) {
+    let used_weight: u32 = match headers
+        .get(USED_WEIGHT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+    {
+        Some(used_weight) => used_weight,
+        None => return,
+    };
+    USED_WEIGHT.store(used_weight, Ordering::Relaxed);
+    USED_WEIGHT_MINUTE.store(current_minute(), Ordering::Relaxed);
+}
+
+/// Marks the request weight of the current minute as used up, after Binance rejected a request
+/// because of too many requests (429) or an IP ban (418).
+pub(crate) fn record_exhausted() {
+    USED_WEIGHT.store(weight_limit(), Ordering::Relaxed);
+    USED_WEIGHT_MINUTE.store(current_minute(), Ordering::Relaxed);
+}
+
+/// Returns the time until the used request weight resets at the start of the next minute.
+pub fn until_reset() -> Duration {
+    Duration::from_millis(60_000 - server_now() % 60_000)
+}
+
+/// Returns the current server time in UNIX minutes, the window of the used weight.
+fn current_minute() -> u64 {
+    server_now() / 60_000
+}
@@ -0,0 +1,57 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryIter};
+use std::time::Duration;
+
+use crate::futures_usd::enums::events::Event;
+
+/// A thin wrapper around the event receiver for polling consumers, which must not block forever
+/// when the feed quiets down.
+///
+/// Obtained through `FuturesUsdStream::events` or `ReplayStream::events`.
+#[derive(Debug, Clone, Copy)]
+pub struct EventReceiver<'a> {
+    receiver: &'a Receiver<Event>,
+}
+
+impl<'a> EventReceiver<'a> {
+    /// Wraps the event receiver.
+    ///
+    /// # Arguments
+    ///
+    /// - `receiver`: The receiver of the events.
+    ///
+    pub fn new(receiver: &'a Receiver<Event>) -> Self {
+        Self { receiver }
+    }
+
+    /// Blocks until the next event or the timeout.
+    ///
+    /// # Arguments
+    ///
+    /// - `timeout`: The maximum time to wait for an event.
+    ///
+    /// # Returns
+    ///
+    /// The next event, `None` on timeout or once the stream is stopped.
+    ///
+    pub fn next_event(&self, timeout: Duration) -> Option<Event> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Returns the events already received without blocking.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over the pending events, ending when none is pending.
+    ///
+    pub fn try_events(&self) -> TryIter<'a, Event> {
+        self.receiver.try_iter()
+    }
+
+    /// Returns the wrapped receiver, e.g. for blocking iteration.
+    pub fn receiver(&self) -> &'a Receiver<Event> {
+        self.receiver
+    }
+}
@@ -0,0 +1,322 @@
+use std::time::Duration;
+#[cfg(feature = "native")]
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
+use url::Url;
+
+use crate::error::BinanceConnectError;
+#[cfg(feature = "native")]
+use crate::futures_usd::status::now_millis;
+
+/// The close code (RFC 6455) of a normal closure.
+const CLOSE_CODE_NORMAL: u16 = 1000;
+/// The close code of an endpoint going away, e.g. a server going down.
+const CLOSE_CODE_AWAY: u16 = 1001;
+/// The close code of a message violating the policy of the endpoint, e.g. a rate limit.
+const CLOSE_CODE_POLICY: u16 = 1008;
+
+/// Holds the typed reasons for which a connection to Binance can end
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum DisconnectReason {
+    /// Binance announced a restart or maintenance of the server.
+    ServerMaintenance(String),
+    /// The connection was refused or closed because of too many requests or connections.
+    RateLimited(String),
+    /// The connection was closed by Binance with the given close code and reason.
+    Closed { code: u16, reason: String },
+    /// A network or protocol error occurred on the socket.
+    SocketError(String),
+    /// No message was received within the stale timeout, e.g. of a half-open TCP connection.
+    Stale(Duration),
+    /// The pong of a client ping did not arrive within the pong timeout.
+    PongTimeout(Duration),
+    /// A payload could not be deserialized.
+    InvalidPayload(String),
+    /// The listen key could not be refreshed, the user data stream reconnects with a fresh one.
+    ListenKeyExpired,
+}
+
+/// The classes of `DisconnectReason` that trigger a reconnect, the connection ends (panics)
+/// on the others. See `FuturesWebSocketConfig::with_reconnect_triggers`. An expired listen key
+/// always triggers a reconnect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectTriggers {
+    /// Network and protocol errors of the socket (`SocketError`).
+    pub socket_errors: bool,
+    /// Payloads that can not be deserialized (`InvalidPayload`).
+    pub json_errors: bool,
+    /// Connections closed or refused by Binance (`Closed`, `ServerMaintenance` and `RateLimited`).
+    pub server_close: bool,
+    /// Connections that stopped delivering (`Stale` and `PongTimeout`).
+    pub stale_connection: bool,
+}
+
+/// The close frame Binance ended a connection with, emitted as `ConnectionClosedEvent`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ConnectionClosed {
+    /// The close code, `1005` (no status) when the frame did not contain one.
+    pub code: u16,
+    pub reason: String,
+}
+
+/// The action the reconnect policy takes for a `DisconnectReason`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum ReconnectDecision {
+    /// Reconnect to the same endpoint right away.
+    Immediate,
+    /// Reconnect to the same endpoint after waiting for the given duration.
+    Backoff(Duration),
+    /// Reconnect right away to the next configured endpoint.
+    RotateEndpoint,
+}
+
+/// Describes a reconnect decision for post-mortems, emitted as `ReconnectEvent`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ReconnectTrace {
+    /// The local time (UNIX millis) at which the decision was taken.
+    pub time: u64,
+    pub reason: DisconnectReason,
+    pub decision: ReconnectDecision,
+    /// The URL of the connection that ended.
+    pub from_url: String,
+    /// The URL that will be connected to next.
+    pub to_url: String,
+}
+
+/// The connection ended without reconnecting, emitted as `ConnectionFailedEvent` before the
+/// connection thread exits: the reconnect limits were exceeded, reconnecting is disabled or the
+/// reason is not one of the `ReconnectTriggers`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ConnectionFailed {
+    /// The reconnect attempts since the connection was last established.
+    pub attempts: u32,
+    /// The milliseconds spent reconnecting since the connection was lost.
+    pub elapsed_ms: u64,
+    /// The reason the last attempt ended.
+    pub reason: DisconnectReason,
+    /// The URL of the last attempt.
+    pub url: String,
+}
+
+/// The listen key could not be refreshed within the retries, emitted as
+/// `ListenKeyRefreshFailedEvent` before the user data stream reconnects with a fresh listen key.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ListenKeyRefreshFailed {
+    /// The refresh attempts made.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: String,
+}
+
+/// Counts the reconnect attempts of a connection against the limits set with
+/// `with_max_reconnect_attempts` and `with_max_reconnect_time`.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone)]
+pub(crate) struct ReconnectAttempts {
+    max_attempts: Option<u32>,
+    max_time: Option<Duration>,
+    attempts: u32,
+    /// The moment the connection was lost, or the first attempt was made.
+    since: Instant,
+}
+
+impl DisconnectReason {
+    /// Classifies a connection ending error, returns `None` for errors that are not connection related.
+    pub fn from_error(err: &BinanceConnectError) -> Option<DisconnectReason> {
+        match err {
+            BinanceConnectError::ConnectionClosed { code, reason } => {
+                Some(Self::from_close(*code, reason))
+            }
+            #[cfg(feature = "native")]
+            BinanceConnectError::SocketError(err) => match err.as_ref() {
+                tungstenite::Error::Http(response)
+                    if response.status().as_u16() == 429 || response.status().as_u16() == 418 =>
+                {
+                    Some(DisconnectReason::RateLimited(response.status().to_string()))
+                }
+                err => Some(DisconnectReason::SocketError(err.to_string())),
+            },
+            BinanceConnectError::StaleConnection(silence) => {
+                Some(DisconnectReason::Stale(*silence))
+            }
+            BinanceConnectError::PongTimeout(waited) => {
+                Some(DisconnectReason::PongTimeout(*waited))
+            }
+            BinanceConnectError::JsonError { .. } => {
+                Some(DisconnectReason::InvalidPayload(err.to_string()))
+            }
+            BinanceConnectError::ListenKeyExpired => Some(DisconnectReason::ListenKeyExpired),
+            _ => None,
+        }
+    }
+
+    /// Classifies a close frame sent by Binance.
+    fn from_close(code: u16, reason: &str) -> DisconnectReason {
+        let lowercase_reason: String = reason.to_lowercase();
+        if ["restart", "maintenance", "upgrade"]
+            .iter()
+            .any(|needle| lowercase_reason.contains(needle))
+        {
+            DisconnectReason::ServerMaintenance(reason.to_string())
+        } else if code == CLOSE_CODE_POLICY || lowercase_reason.contains("too many") {
+            DisconnectReason::RateLimited(reason.to_string())
+        } else {
+            DisconnectReason::Closed {
+                code,
+                reason: reason.to_string(),
+            }
+        }
+    }
+
+    /// Returns the `ReconnectDecision` of the reconnect policy for this reason.
+    pub fn decision(&self) -> ReconnectDecision {
+        match self {
+            DisconnectReason::ServerMaintenance(_) => ReconnectDecision::RotateEndpoint,
+            DisconnectReason::RateLimited(_) => ReconnectDecision::Backoff(Duration::from_secs(10)),
+            DisconnectReason::Closed { code, .. }
+                if *code == CLOSE_CODE_NORMAL || *code == CLOSE_CODE_AWAY =>
+            {
+                ReconnectDecision::Immediate
+            }
+            DisconnectReason::Stale(_)
+            | DisconnectReason::PongTimeout(_)
+            | DisconnectReason::InvalidPayload(_)
+            | DisconnectReason::ListenKeyExpired => ReconnectDecision::Immediate,
+            DisconnectReason::Closed { .. } | DisconnectReason::SocketError(_) => {
+                ReconnectDecision::Backoff(Duration::from_millis(100))
+            }
+        }
+    }
+}
+
+impl Default for ReconnectTriggers {
+    /// Reconnects on every class but the JSON errors, which usually repeat on the next connection.
+    fn default() -> Self {
+        Self {
+            socket_errors: true,
+            json_errors: false,
+            server_close: true,
+            stale_connection: true,
+        }
+    }
+}
+
+impl ReconnectTriggers {
+    /// Reconnects on every class, including the JSON errors.
+    pub fn all() -> ReconnectTriggers {
+        Self {
+            json_errors: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether the reason triggers a reconnect.
+    pub fn triggers(&self, reason: &DisconnectReason) -> bool {
+        match reason {
+            DisconnectReason::SocketError(_) => self.socket_errors,
+            DisconnectReason::InvalidPayload(_) => self.json_errors,
+            DisconnectReason::ServerMaintenance(_)
+            | DisconnectReason::RateLimited(_)
+            | DisconnectReason::Closed { .. } => self.server_close,
+            DisconnectReason::Stale(_) | DisconnectReason::PongTimeout(_) => self.stale_connection,
+            DisconnectReason::ListenKeyExpired => true,
+        }
+    }
+}
+
+impl ReconnectTrace {
+    /// Creates a new trace of a reconnect decision, taken now.
+    #[cfg(feature = "native")]
+    pub(crate) fn new(
+        reason: DisconnectReason,
+        decision: ReconnectDecision,
+        from_url: &Url,
+        to_url: &Url,
+    ) -> ReconnectTrace {
+        ReconnectTrace {
+            time: now_millis(),
+            reason,
+            decision,
+            from_url: from_url.to_string(),
+            to_url: to_url.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl ReconnectAttempts {
+    /// Creates the count of a connection that is about to be attempted for the first time.
+    pub(crate) fn new(max_attempts: Option<u32>, max_time: Option<Duration>) -> ReconnectAttempts {
+        Self {
+            max_attempts,
+            max_time,
+            attempts: 0,
+            since: Instant::now(),
+        }
+    }
+
+    /// Restarts the count, the connection was established before it was lost.
+    pub(crate) fn established(&mut self) {
+        self.attempts = 0;
+        self.since = Instant::now();
+    }
+
+    /// Counts a reconnect attempt, returning the `ConnectionFailed` when it exceeds a limit.
+    pub(crate) fn attempt(
+        &mut self,
+        reason: &DisconnectReason,
+        url: &Url,
+    ) -> Option<ConnectionFailed> {
+        self.attempts += 1;
+        let elapsed: Duration = self.since.elapsed();
+        let exceeded: bool = self
+            .max_attempts
+            .map_or(false, |max_attempts| self.attempts > max_attempts)
+            || self.max_time.map_or(false, |max_time| elapsed >= max_time);
+        match exceeded {
+            true => {
+                self.attempts -= 1;
+                Some(self.failed(reason, url))
+            }
+            false => None,
+        }
+    }
+
+    /// Returns the `ConnectionFailed` of the connection ending for the reason, without another
+    /// attempt.
+    pub(crate) fn failed(&self, reason: &DisconnectReason, url: &Url) -> ConnectionFailed {
+        ConnectionFailed {
+            attempts: self.attempts,
+            elapsed_ms: self.since.elapsed().as_millis() as u64,
+            reason: reason.clone(),
+            url: url.to_string(),
+        }
+    }
+}
+
+/// Returns the URL with its endpoint (scheme, host and port) replaced by the endpoint that follows
+/// the current one in `endpoints`, the URL is returned unchanged when there is nothing to rotate to.
+#[cfg(feature = "native")]
+pub(crate) fn rotate_endpoint(url: &Url, endpoints: &[Url]) -> Url {
+    if endpoints.len() < 2 {
+        return url.clone();
+    }
+    let current: usize = endpoints
+        .iter()
+        .position(|endpoint| endpoint.host_str() == url.host_str())
+        .unwrap_or(endpoints.len() - 1);
+    with_endpoint(url, &endpoints[(current + 1) % endpoints.len()])
+}
+
+/// Returns the URL with the scheme, host and port of the endpoint, keeping the path (streams or
+/// listen key) and query.
+#[cfg(feature = "native")]
+pub(crate) fn with_endpoint(url: &Url, endpoint: &Url) -> Url {
+    let mut moved: Url = url.clone();
+    let _ = moved.set_scheme(endpoint.scheme());
+    let _ = moved.set_host(endpoint.host_str());
+    let _ = moved.set_port(endpoint.port());
+    moved
+}
@@ -0,0 +1,175 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use crate::error::BinanceConnectError;
+
+/// The interval at which the buffered lines are flushed to the file.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The configuration of a `Recorder`.
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    path: PathBuf,
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+}
+
+impl RecorderConfig {
+    /// Creates a configuration recording to the file, without rotation.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the active JSONL file, e.g. `events.jsonl`.
+    ///
+    pub fn new(path: impl AsRef<Path>) -> RecorderConfig {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            max_bytes: None,
+            max_age: None,
+        }
+    }
+
+    /// Rotates the file once it holds at least the number of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_bytes`: The size of the active file after which it is rotated.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the maximum size set.
+    ///
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Rotates the file once it was opened at least the duration ago.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_age`: The age of the active file after which it is rotated.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the maximum age set.
+    ///
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+}
+
+/// The active file of a `Recorder`.
+#[derive(Debug)]
+struct ActiveFile {
+    writer: BufWriter<File>,
+    bytes: u64,
+    opened: Instant,
+    flushed: Instant,
+}
+
+/// Records the received event payloads with their receive time to a newline-delimited JSON file,
+/// one `{"received":<UNIX millis>,"event":<payload>}` object per line.
+///
+/// The payload is the event as it is deserialized into an `Event`, after the symbol aliases and
+/// script were applied. A rotated file is renamed to `<path>.<UNIX millis>` and a new active file
+/// is opened at the path. Recording errors are logged and never interrupt the stream.
+#[derive(Debug)]
+pub struct Recorder {
+    config: RecorderConfig,
+    file: Mutex<Option<ActiveFile>>,
+}
+
+impl Recorder {
+    /// Creates a recorder, the file is opened (appending) on the first recorded event.
+    pub fn new(config: RecorderConfig) -> Recorder {
+        Self {
+            config,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Records the payload, logging a failure.
+    ///
+    /// # Arguments
+    ///
+    /// - `received`: The receive time (UNIX millis).
+    /// - `payload`: The JSON payload of the event.
+    ///
+    pub fn record(&self, received: u64, payload: &str) {
+        if let Err(err) = self.try_record(received, payload) {
+            warn!(error = %err, "futures_usd recorder");
+        }
+    }
+
+    /// Flushes the buffered lines to the file.
+    pub fn flush(&self) -> Result<(), BinanceConnectError> {
+        if let Some(active) = self.file.lock().unwrap().as_mut() {
+            active.writer.flush()?;
+            active.flushed = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Records the payload, rotating and (re)opening the file when needed.
+    fn try_record(&self, received: u64, payload: &str) -> Result<(), BinanceConnectError> {
+        let mut file = self.file.lock().unwrap();
+        if file
+            .as_ref()
+            .map_or(false, |active| self.rotation_due(active))
+        {
+            if let Some(mut active) = file.take() {
+                active.writer.flush()?;
+            }
+            let rotated: PathBuf =
+                PathBuf::from(format!("{}.{}", self.config.path.display(), received));
+            fs::rename(&self.config.path, rotated)?;
+        }
+        if file.is_none() {
+            let opened: File = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.config.path)?;
+            *file = Some(ActiveFile {
+                bytes: opened.metadata()?.len(),
+                writer: BufWriter::new(opened),
+                opened: Instant::now(),
+                flushed: Instant::now(),
+            });
+        }
+        let active: &mut ActiveFile = file.as_mut().unwrap();
+        let line: String = format!("{{\"received\":{},\"event\":{}}}\n", received, payload);
+        active.writer.write_all(line.as_bytes())?;
+        active.bytes += line.len() as u64;
+        if active.flushed.elapsed() >= FLUSH_INTERVAL {
+            active.writer.flush()?;
+            active.flushed = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Returns whether the active file reached the maximum size or age.
+    fn rotation_due(&self, active: &ActiveFile) -> bool {
+        self.config
+            .max_bytes
+            .map_or(false, |max_bytes| active.bytes >= max_bytes)
+            || self
+                .config
+                .max_age
+                .map_or(false, |max_age| active.opened.elapsed() >= max_age)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            warn!(error = %err, "futures_usd recorder");
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::error::BinanceConnectError;
+#[cfg(feature = "binary")]
+use crate::futures_usd::binary::read_frame;
+use crate::futures_usd::deserializer::deserialize_slice;
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::receiver::EventReceiver;
+
+/// The prefix of a line written by the `Recorder`.
+const RECEIVED_PREFIX: &str = "{\"received\":";
+/// The separator between the receive time and the payload of a recorded line.
+const EVENT_SEPARATOR: &str = ",\"event\":";
+
+/// The pace at which a `ReplayStream` emits the recorded events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaySpeed {
+    /// The original pace, using the recorded receive times.
+    Original,
+    /// The original pace multiplied by the factor, e.g. `10.0` replays ten times faster.
+    Accelerated(f64),
+    /// Every event is emitted as soon as it is read.
+    AsFastAsPossible,
+}
+
+/// Replays files recorded by the `Recorder` through a `Receiver<Event>`, emitting the same
+/// `Event` values as the `FuturesUsdStream`, so strategies can be backtested against the
+/// production interface.
+///
+/// The receiver disconnects once all files are replayed. Malformed lines are logged and skipped.
+#[derive(Debug)]
+pub struct ReplayStream {
+    files: Vec<PathBuf>,
+    speed: ReplaySpeed,
+    /// A flag indicating whether the files hold binary frames instead of recorded JSON lines.
+    binary: bool,
+    /// The sender, moved to the replay thread on start so the receiver disconnects at the end.
+    sender: Option<Sender<Event>>,
+    receiver: Receiver<Event>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl ReplayStream {
+    /// Creates a replay of the recorded file at the original pace.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the recorded JSONL file.
+    ///
+    pub fn new(path: impl AsRef<Path>) -> ReplayStream {
+        let (sender, receiver) = channel();
+        Self {
+            files: vec![path.as_ref().to_path_buf()],
+            speed: ReplaySpeed::Original,
+            binary: false,
+            sender: Some(sender),
+            receiver,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Appends a recorded file replayed after the previous files, e.g. the next rotated file.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the recorded JSONL file.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the file appended.
+    ///
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.files.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the pace at which the events are emitted.
+    ///
+    /// # Arguments
+    ///
+    /// - `speed`: The `ReplaySpeed`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the speed set.
+    ///
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Replays files of binary frames, e.g. written by the `BinaryFileSink`, instead of files
+    /// recorded by the `Recorder`.
+    #[cfg(feature = "binary")]
+    pub fn with_binary_frames(mut self) -> Self {
+        self.binary = true;
+        self
+    }
+
+    /// Starts replaying on a separate thread.
+    pub fn start(mut self) -> Self {
+        if let Some(sender) = self.sender.take() {
+            let files: Vec<PathBuf> = self.files.clone();
+            let speed: ReplaySpeed = self.speed.clone();
+            let binary: bool = self.binary;
+            let stop_signal: Arc<AtomicBool> = Arc::clone(&self.stop_signal);
+            thread::spawn(move || {
+                if let Err(err) = replay(&files, &speed, binary, &sender, &stop_signal) {
+                    warn!(error = %err, "futures_usd replay");
+                }
+            });
+        }
+        self
+    }
+
+    /// Stops the replay.
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the event receiver.
+    pub fn consume(&self) -> &Receiver<Event> {
+        &self.receiver
+    }
+
+    /// Returns the event receiver wrapped for polling with timeouts.
+    pub fn events(&self) -> EventReceiver<'_> {
+        EventReceiver::new(&self.receiver)
+    }
+}
+
+/// Paces the replayed events by their receive times.
+struct Pace<'a> {
+    speed: &'a ReplaySpeed,
+    started: Instant,
+    first_received: Option<u64>,
+}
+
+impl Pace<'_> {
+    /// Sleeps until the event received at the time is due.
+    fn wait(&mut self, received: u64) {
+        let offset: u64 = received.saturating_sub(*self.first_received.get_or_insert(received));
+        if let Some(due) = due(self.started, offset, self.speed) {
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            }
+        }
+    }
+}
+
+/// Replays the files, pacing the events by their receive times.
+fn replay(
+    files: &[PathBuf],
+    speed: &ReplaySpeed,
+    binary: bool,
+    sender: &Sender<Event>,
+    stop_signal: &AtomicBool,
+) -> Result<(), BinanceConnectError> {
+    let mut pace: Pace = Pace {
+        speed,
+        started: Instant::now(),
+        first_received: None,
+    };
+    for path in files {
+        info!(path = %path.display(), "futures_usd replaying");
+        if binary {
+            #[cfg(feature = "binary")]
+            replay_frames(path, &mut pace, sender, stop_signal)?;
+        } else {
+            replay_lines(path, &mut pace, sender, stop_signal)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replays the JSON lines of a file recorded by the `Recorder`.
+fn replay_lines(
+    path: &Path,
+    pace: &mut Pace,
+    sender: &Sender<Event>,
+    stop_signal: &AtomicBool,
+) -> Result<(), BinanceConnectError> {
+    for line in BufReader::new(File::open(path)?).lines() {
+        if stop_signal.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let line: String = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (received, payload) = match parse_line(&line) {
+            Some(parsed) => parsed,
+            None => {
+                warn!(line = %line, "futures_usd replay skipped malformed line");
+                continue;
+            }
+        };
+        pace.wait(received);
+        match deserialize_slice(payload.as_bytes()) {
+            Ok(event) => sender.send(event)?,
+            Err(err) => warn!(error = %err, "futures_usd replay skipped event"),
+        }
+    }
+    Ok(())
+}
+
+/// Replays the binary frames of a file, e.g. written by the `BinaryFileSink`.
+#[cfg(feature = "binary")]
+fn replay_frames(
+    path: &Path,
+    pace: &mut Pace,
+    sender: &Sender<Event>,
+    stop_signal: &AtomicBool,
+) -> Result<(), BinanceConnectError> {
+    let mut reader: BufReader<File> = BufReader::new(File::open(path)?);
+    while let Some((received, event)) = read_frame(&mut reader)? {
+        if stop_signal.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        pace.wait(received);
+        sender.send(event)?;
+    }
+    Ok(())
+}
+
+/// Returns the receive time and payload of a recorded line.
+fn parse_line(line: &str) -> Option<(u64, &str)> {
+    let rest: &str = line.strip_prefix(RECEIVED_PREFIX)?;
+    let (received, payload) = rest.split_once(EVENT_SEPARATOR)?;
+    Some((received.parse().ok()?, payload.strip_suffix('}')?))
+}
+
+/// Returns the instant the event at the offset (millis) since the first event is due.
+fn due(started: Instant, offset: u64, speed: &ReplaySpeed) -> Option<Instant> {
+    let offset: Duration = Duration::from_millis(offset);
+    match speed {
+        ReplaySpeed::Original => Some(started + offset),
+        ReplaySpeed::Accelerated(factor) if *factor > 0.0 => {
+            Some(started + offset.div_f64(*factor))
+        }
+        ReplaySpeed::Accelerated(_) | ReplaySpeed::AsFastAsPossible => None,
+    }
+}
@@ -1,11 +1,16 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Deserializer, Serialize};
 
+#[cfg(feature = "user-data")]
+use crate::futures_usd::enums::binance::{AccountUpdateReason, ExecutionType, StrategyStatus};
 use crate::futures_usd::enums::binance::{
-    AccountUpdateReason, ContractStatus, ContractType, ExecutionType, KlineContractType,
-    KlineInterval, MarginType, OrderStatus, OrderType, PositionSide, PriceMatch, Side, StpMode,
-    StrategyStatus, TimeInForce, WorkingType,
+    ContractStatus, ContractType, MarginType, OrderStatus, OrderType, PositionSide, PriceMatch,
+    Side, StpMode, StreamProperty, TimeInForce, WorkingType,
 };
-use crate::futures_usd::enums::events::EventType;
+#[cfg(feature = "klines")]
+use crate::futures_usd::enums::binance::{KlineContractType, KlineInterval};
+use crate::futures_usd::enums::events::{Event, EventType};
 
 /// Holds all the possible responses from Binance
 
@@ -26,10 +31,15 @@ use crate::futures_usd::enums::events::EventType;
 ///
 /// - `Result<f64, D::Error>`: A result containing the parsed `f64` or a deserialization error.
 ///
+#[allow(clippy::empty_line_after_doc_comments)]
 fn deserialize_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
     D: Deserializer<'de>,
 {
+    // Binary formats (see `binary::encode`) hold the value as serialized, not as a string.
+    if !deserializer.is_human_readable() {
+        return f64::deserialize(deserializer);
+    }
     let s: String = Deserialize::deserialize(deserializer)?;
     match s.parse::<f64>() {
         Ok(f) => Ok(f),
@@ -37,34 +47,118 @@ where
     }
 }
 
+/// Deserialize an integer represented as a string.
+fn deserialize_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // See `deserialize_f64`.
+    if !deserializer.is_human_readable() {
+        return i64::deserialize(deserializer);
+    }
+    let s: String = Deserialize::deserialize(deserializer)?;
+    match s.parse::<i64>() {
+        Ok(i) => Ok(i),
+        Err(_) => Err(serde::de::Error::custom("Failed to parse i64")),
+    }
+}
+
+/// Deserialize a boolean represented as a string.
+fn deserialize_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // See `deserialize_f64`.
+    if !deserializer.is_human_readable() {
+        return bool::deserialize(deserializer);
+    }
+    let s: String = Deserialize::deserialize(deserializer)?;
+    match s.parse::<bool>() {
+        Ok(b) => Ok(b),
+        Err(_) => Err(serde::de::Error::custom("Failed to parse bool")),
+    }
+}
+
 /* GENERIC */
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct EventTypeWrapper {
     #[serde(rename = "e")]
     pub event_type: EventType,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SubscribeResponse {
     pub result: Option<serde_json::Value>,
     pub id: Option<u64>,
 }
 
+/// The confirmation of a `SUBSCRIBE`/`UNSUBSCRIBE` request, emitted as `SubscriptionConfirmedEvent`
+/// so callers can verify every stream they asked for is live.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SubscriptionConfirmed {
+    /// The id of the request, as returned by `FuturesUsdStream::subscribe`/`unsubscribe`.
+    pub id: u64,
+    /// The method of the request, empty when the request is unknown (e.g. replayed).
+    pub method: String,
+    /// The streams covered by the request, empty when the request is unknown.
+    pub streams: Vec<String>,
+}
+
+/// The value of a connection property, emitted as `PropertyEvent` in response to
+/// `FuturesUsdStream::get_property` and (once confirmed) `set_property`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PropertyValue {
+    /// The id of the request.
+    pub id: u64,
+    /// The property, `None` when the request is unknown (e.g. replayed).
+    pub property: Option<StreamProperty>,
+    pub value: bool,
+}
+
+/// An error reported by Binance, e.g. `{"code":-1121,"msg":"Invalid symbol."}`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BinanceErrorMessage {
+    pub code: i64,
+    pub msg: String,
+    /// The id of the request that failed, `None` when the error does not belong to a request.
+    #[serde(default)]
+    pub id: Option<u64>,
+}
+
+/// The error response to a request, e.g. `{"error":{"code":2,"msg":"Invalid request"},"id":1}`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ErrorResponse {
+    pub error: BinanceErrorMessage,
+    pub id: Option<u64>,
+}
+
+/// A decoded event with the JSON it was decoded from, emitted as `WithRawEvent` when the stream
+/// is configured `with_raw_json`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WithRaw {
+    pub event: Box<Event>,
+    /// The JSON as received, shared without copying, e.g. with an audit log.
+    pub raw: Arc<str>,
+}
+
 /* MARKET */
 
-#[derive(Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct BookTickers {
     pub data: Vec<BookTicker>,
 }
 
+#[cfg(feature = "market-data")]
 impl BookTickers {
     pub fn new(book_tickers: Vec<BookTicker>) -> BookTickers {
         Self { data: book_tickers }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct BookTicker {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -86,7 +180,8 @@ pub struct BookTicker {
     pub transaction_time: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AggTrade {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -110,11 +205,13 @@ pub struct AggTrade {
     pub buyer_is_market_maker: bool,
 }
 
-#[derive(Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MarkPriceUpdates {
     pub data: Vec<MarkPriceUpdate>,
 }
 
+#[cfg(feature = "market-data")]
 impl MarkPriceUpdates {
     pub fn new(mark_price_updates: Vec<MarkPriceUpdate>) -> MarkPriceUpdates {
         Self {
@@ -123,7 +220,8 @@ impl MarkPriceUpdates {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MarkPriceUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -143,7 +241,8 @@ pub struct MarkPriceUpdate {
     pub next_funding_time: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "klines")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Kline {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -155,7 +254,8 @@ pub struct Kline {
     pub kline_data: KlineData,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "klines")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ContinuousKline {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -169,7 +269,8 @@ pub struct ContinuousKline {
     pub kline_data: KlineData,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "klines")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct KlineData {
     #[serde(rename = "t")]
     pub kline_start_time: i64,
@@ -205,18 +306,21 @@ pub struct KlineData {
     pub taker_buy_quote_asset_volume: f64,
 }
 
-#[derive(Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MiniTickers {
     pub data: Vec<MiniTicker>,
 }
 
+#[cfg(feature = "market-data")]
 impl MiniTickers {
     pub fn new(mini_tickers: Vec<MiniTicker>) -> MiniTickers {
         Self { data: mini_tickers }
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MiniTicker {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -238,18 +342,21 @@ pub struct MiniTicker {
     pub total_traded_quote_asset_volume: f64,
 }
 
-#[derive(Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Tickers {
     pub data: Vec<Ticker>,
 }
 
+#[cfg(feature = "market-data")]
 impl Tickers {
     pub fn new(tickers: Vec<Ticker>) -> Tickers {
         Self { data: tickers }
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Ticker {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -289,7 +396,8 @@ pub struct Ticker {
     pub total_number_of_trades: u64,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ForceOrder {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -299,7 +407,8 @@ pub struct ForceOrder {
     pub order: ForceOrderData,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ForceOrderData {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -325,7 +434,16 @@ pub struct ForceOrderData {
     pub order_trade_time: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "market-data")]
+impl ForceOrderData {
+    /// Returns the notional (price × original quantity) of the force order.
+    pub fn notional(&self) -> f64 {
+        self.price * self.original_quantity.parse::<f64>().unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "depth")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct BookDepth {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -347,7 +465,8 @@ pub struct BookDepth {
     pub asks: Vec<AskUpdate>,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "depth")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct BidUpdate {
     #[serde(rename = "0", deserialize_with = "deserialize_f64")]
     pub price_level: f64,
@@ -355,7 +474,8 @@ pub struct BidUpdate {
     pub quantity: f64,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "depth")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AskUpdate {
     #[serde(rename = "0", deserialize_with = "deserialize_f64")]
     pub price_level: f64,
@@ -363,7 +483,8 @@ pub struct AskUpdate {
     pub quantity: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CompositeIndex {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -379,7 +500,8 @@ pub struct CompositeIndex {
     pub composition: Vec<Composition>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Composition {
     #[serde(rename = "b")]
     pub base_asset: String,
@@ -393,7 +515,7 @@ pub struct Composition {
     pub index_price: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ContractInfo {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -415,7 +537,7 @@ pub struct ContractInfo {
     pub brackets: Vec<ContractInfoBracket>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ContractInfoBracket {
     #[serde(rename = "bs")]
     pub notional_bracket: i32,
@@ -433,11 +555,13 @@ pub struct ContractInfoBracket {
     pub max_leverage: i32,
 }
 
-#[derive(Debug)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AssetIndexUpdates {
     pub data: Vec<AssetIndexUpdate>,
 }
 
+#[cfg(feature = "market-data")]
 impl AssetIndexUpdates {
     pub fn new(asset_index_updates: Vec<AssetIndexUpdate>) -> AssetIndexUpdates {
         Self {
@@ -446,7 +570,8 @@ impl AssetIndexUpdates {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "market-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AssetIndexUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -476,7 +601,8 @@ pub struct AssetIndexUpdate {
 
 /* USER DATA */
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct OrderTradeUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -488,7 +614,8 @@ pub struct OrderTradeUpdate {
     pub order_data: OrderData,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct OrderData {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -548,23 +675,24 @@ pub struct OrderData {
     pub activation_price: f64,
     #[serde(rename = "cr", default, deserialize_with = "deserialize_f64")]
     pub callback_rate: f64,
-    #[serde(rename = "pP")]
+    #[serde(rename = "pP", default)]
     pub is_price_protection_enabled: bool,
-    #[serde(rename = "si")]
+    #[serde(rename = "si", default)]
     pub ignore1: i64,
-    #[serde(rename = "ss")]
+    #[serde(rename = "ss", default)]
     pub ignore2: i64,
-    #[serde(rename = "rp", deserialize_with = "deserialize_f64")]
+    #[serde(rename = "rp", default, deserialize_with = "deserialize_f64")]
     pub realized_profit: f64,
-    #[serde(rename = "V")]
+    #[serde(rename = "V", default)]
     pub stp_mode: StpMode,
-    #[serde(rename = "pm")]
+    #[serde(rename = "pm", default)]
     pub price_match_mode: PriceMatch,
-    #[serde(rename = "gtd")]
+    #[serde(rename = "gtd", default)]
     pub gtd_order_auto_cancel_time: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AccountUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -576,7 +704,8 @@ pub struct AccountUpdate {
     pub update_data: UpdateData,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct UpdateData {
     #[serde(rename = "m")]
     pub event_reason_type: AccountUpdateReason,
@@ -586,7 +715,8 @@ pub struct UpdateData {
     pub positions: Vec<Position>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Balance {
     #[serde(rename = "a")]
     pub asset: String,
@@ -598,7 +728,8 @@ pub struct Balance {
     pub balance_change: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Position {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -620,7 +751,8 @@ pub struct Position {
     pub position_side: PositionSide,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MarginCall {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -632,7 +764,8 @@ pub struct MarginCall {
     pub positions: Vec<MarginCallPosition>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MarginCallPosition {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -652,7 +785,8 @@ pub struct MarginCallPosition {
     pub maintenance_margin_required: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AccountConfigUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -666,7 +800,8 @@ pub struct AccountConfigUpdate {
     pub account_info: Option<AccountInfo>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AccountConfig {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -674,13 +809,15 @@ pub struct AccountConfig {
     pub leverage: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AccountInfo {
     #[serde(rename = "j")]
     pub multi_assets_mode: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct StrategyUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -692,7 +829,8 @@ pub struct StrategyUpdate {
     pub strategy: Strategy,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Strategy {
     #[serde(rename = "si")]
     pub strategy_id: i64,
@@ -708,7 +846,8 @@ pub struct Strategy {
     pub op_code: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct GridUpdate {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -720,7 +859,8 @@ pub struct GridUpdate {
     pub grid: Grid,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Grid {
     #[serde(rename = "si")]
     pub strategy_id: i64,
@@ -744,7 +884,8 @@ pub struct Grid {
     pub update_time: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ConditionalOrderTriggerReject {
     #[serde(rename = "e")]
     pub event_type: EventType,
@@ -756,7 +897,8 @@ pub struct ConditionalOrderTriggerReject {
     pub order_reject: OrderReject,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "user-data")]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct OrderReject {
     #[serde(rename = "s")]
     pub symbol: String,
@@ -765,3 +907,452 @@ pub struct OrderReject {
     #[serde(rename = "r")]
     pub reject_reason: String,
 }
+
+/* REST */
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PositionRisk {
+    pub symbol: String,
+    #[serde(rename = "positionAmt", deserialize_with = "deserialize_f64")]
+    pub position_amount: f64,
+    #[serde(rename = "entryPrice", deserialize_with = "deserialize_f64")]
+    pub entry_price: f64,
+    #[serde(rename = "breakEvenPrice", deserialize_with = "deserialize_f64")]
+    pub breakeven_price: f64,
+    #[serde(rename = "markPrice", deserialize_with = "deserialize_f64")]
+    pub mark_price: f64,
+    #[serde(rename = "unRealizedProfit", deserialize_with = "deserialize_f64")]
+    pub unrealized_pnl: f64,
+    #[serde(rename = "liquidationPrice", deserialize_with = "deserialize_f64")]
+    pub liquidation_price: f64,
+    #[serde(rename = "leverage", deserialize_with = "deserialize_i64")]
+    pub leverage: i64,
+    #[serde(rename = "maxNotionalValue", deserialize_with = "deserialize_f64")]
+    pub max_notional_value: f64,
+    #[serde(rename = "marginType")]
+    pub margin_type: MarginType,
+    #[serde(rename = "isolatedMargin", deserialize_with = "deserialize_f64")]
+    pub isolated_margin: f64,
+    #[serde(rename = "isAutoAddMargin", deserialize_with = "deserialize_bool")]
+    pub is_auto_add_margin: bool,
+    #[serde(rename = "positionSide")]
+    pub position_side: PositionSide,
+    #[serde(rename = "notional", deserialize_with = "deserialize_f64")]
+    pub notional: f64,
+    #[serde(rename = "isolatedWallet", deserialize_with = "deserialize_f64")]
+    pub isolated_wallet: f64,
+    #[serde(rename = "updateTime")]
+    pub update_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OpenOrder {
+    pub symbol: String,
+    #[serde(rename = "orderId")]
+    pub order_id: i64,
+    #[serde(rename = "clientOrderId")]
+    pub client_order_id: String,
+    pub side: Side,
+    #[serde(rename = "positionSide")]
+    pub position_side: PositionSide,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[serde(rename = "origType")]
+    pub original_order_type: OrderType,
+    #[serde(rename = "status")]
+    pub order_status: OrderStatus,
+    #[serde(rename = "timeInForce")]
+    pub time_in_force: TimeInForce,
+    #[serde(rename = "price", deserialize_with = "deserialize_f64")]
+    pub price: f64,
+    #[serde(rename = "avgPrice", deserialize_with = "deserialize_f64")]
+    pub average_price: f64,
+    #[serde(rename = "stopPrice", deserialize_with = "deserialize_f64")]
+    pub stop_price: f64,
+    #[serde(rename = "origQty", deserialize_with = "deserialize_f64")]
+    pub original_quantity: f64,
+    #[serde(rename = "executedQty", deserialize_with = "deserialize_f64")]
+    pub executed_quantity: f64,
+    #[serde(rename = "cumQuote", deserialize_with = "deserialize_f64")]
+    pub cumulative_quote: f64,
+    #[serde(
+        rename = "activatePrice",
+        default,
+        deserialize_with = "deserialize_f64"
+    )]
+    pub activation_price: f64,
+    #[serde(rename = "priceRate", default, deserialize_with = "deserialize_f64")]
+    pub callback_rate: f64,
+    #[serde(rename = "reduceOnly")]
+    pub is_reduce_only: bool,
+    #[serde(rename = "closePosition")]
+    pub is_close_all: bool,
+    #[serde(rename = "priceProtect")]
+    pub is_price_protection_enabled: bool,
+    #[serde(rename = "workingType")]
+    pub stop_price_working_type: WorkingType,
+    #[serde(rename = "priceMatch")]
+    pub price_match_mode: PriceMatch,
+    #[serde(rename = "selfTradePreventionMode")]
+    pub stp_mode: StpMode,
+    #[serde(rename = "goodTillDate")]
+    pub gtd_order_auto_cancel_time: i64,
+    pub time: i64,
+    #[serde(rename = "updateTime")]
+    pub update_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AccountSnapshot {
+    #[serde(rename = "totalInitialMargin", deserialize_with = "deserialize_f64")]
+    pub total_initial_margin: f64,
+    #[serde(rename = "totalMaintMargin", deserialize_with = "deserialize_f64")]
+    pub total_maintenance_margin: f64,
+    #[serde(rename = "totalWalletBalance", deserialize_with = "deserialize_f64")]
+    pub total_wallet_balance: f64,
+    #[serde(rename = "totalUnrealizedProfit", deserialize_with = "deserialize_f64")]
+    pub total_unrealized_pnl: f64,
+    #[serde(rename = "totalMarginBalance", deserialize_with = "deserialize_f64")]
+    pub total_margin_balance: f64,
+    #[serde(
+        rename = "totalPositionInitialMargin",
+        deserialize_with = "deserialize_f64"
+    )]
+    pub total_position_initial_margin: f64,
+    #[serde(
+        rename = "totalOpenOrderInitialMargin",
+        deserialize_with = "deserialize_f64"
+    )]
+    pub total_open_order_initial_margin: f64,
+    #[serde(
+        rename = "totalCrossWalletBalance",
+        deserialize_with = "deserialize_f64"
+    )]
+    pub total_cross_wallet_balance: f64,
+    #[serde(rename = "totalCrossUnPnl", deserialize_with = "deserialize_f64")]
+    pub total_cross_unrealized_pnl: f64,
+    #[serde(rename = "availableBalance", deserialize_with = "deserialize_f64")]
+    pub available_balance: f64,
+    #[serde(rename = "maxWithdrawAmount", deserialize_with = "deserialize_f64")]
+    pub max_withdraw_amount: f64,
+    pub assets: Vec<AccountSnapshotAsset>,
+    pub positions: Vec<AccountSnapshotPosition>,
+}
+
+impl AccountSnapshot {
+    /// Returns the time (UNIX millis) of the latest update of the assets and positions contained
+    /// in the snapshot, `0` when there are none.
+    pub fn update_time(&self) -> i64 {
+        self.assets
+            .iter()
+            .map(|asset| asset.update_time)
+            .chain(self.positions.iter().map(|position| position.update_time))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AccountSnapshotAsset {
+    pub asset: String,
+    #[serde(rename = "walletBalance", deserialize_with = "deserialize_f64")]
+    pub wallet_balance: f64,
+    #[serde(rename = "unrealizedProfit", deserialize_with = "deserialize_f64")]
+    pub unrealized_pnl: f64,
+    #[serde(rename = "marginBalance", deserialize_with = "deserialize_f64")]
+    pub margin_balance: f64,
+    #[serde(rename = "maintMargin", deserialize_with = "deserialize_f64")]
+    pub maintenance_margin: f64,
+    #[serde(rename = "initialMargin", deserialize_with = "deserialize_f64")]
+    pub initial_margin: f64,
+    #[serde(rename = "positionInitialMargin", deserialize_with = "deserialize_f64")]
+    pub position_initial_margin: f64,
+    #[serde(
+        rename = "openOrderInitialMargin",
+        deserialize_with = "deserialize_f64"
+    )]
+    pub open_order_initial_margin: f64,
+    #[serde(rename = "crossWalletBalance", deserialize_with = "deserialize_f64")]
+    pub cross_wallet_balance: f64,
+    #[serde(rename = "crossUnPnl", deserialize_with = "deserialize_f64")]
+    pub cross_unrealized_pnl: f64,
+    #[serde(rename = "availableBalance", deserialize_with = "deserialize_f64")]
+    pub available_balance: f64,
+    #[serde(rename = "maxWithdrawAmount", deserialize_with = "deserialize_f64")]
+    pub max_withdraw_amount: f64,
+    #[serde(rename = "marginAvailable", default)]
+    pub is_margin_available: bool,
+    #[serde(rename = "updateTime")]
+    pub update_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AccountSnapshotPosition {
+    pub symbol: String,
+    #[serde(rename = "positionSide")]
+    pub position_side: PositionSide,
+    #[serde(rename = "positionAmt", deserialize_with = "deserialize_f64")]
+    pub position_amount: f64,
+    #[serde(rename = "entryPrice", deserialize_with = "deserialize_f64")]
+    pub entry_price: f64,
+    #[serde(
+        rename = "breakEvenPrice",
+        default,
+        deserialize_with = "deserialize_f64"
+    )]
+    pub breakeven_price: f64,
+    #[serde(rename = "unrealizedProfit", deserialize_with = "deserialize_f64")]
+    pub unrealized_pnl: f64,
+    #[serde(rename = "initialMargin", deserialize_with = "deserialize_f64")]
+    pub initial_margin: f64,
+    #[serde(rename = "maintMargin", deserialize_with = "deserialize_f64")]
+    pub maintenance_margin: f64,
+    #[serde(rename = "positionInitialMargin", deserialize_with = "deserialize_f64")]
+    pub position_initial_margin: f64,
+    #[serde(
+        rename = "openOrderInitialMargin",
+        deserialize_with = "deserialize_f64"
+    )]
+    pub open_order_initial_margin: f64,
+    #[serde(deserialize_with = "deserialize_i64")]
+    pub leverage: i64,
+    pub isolated: bool,
+    #[serde(rename = "maxNotional", deserialize_with = "deserialize_f64")]
+    pub max_notional: f64,
+    #[serde(rename = "updateTime")]
+    pub update_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ServerTime {
+    #[serde(rename = "serverTime")]
+    pub server_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OpenInterest {
+    pub symbol: String,
+    #[serde(rename = "openInterest", deserialize_with = "deserialize_f64")]
+    pub open_interest: f64,
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FundingRate {
+    pub symbol: String,
+    #[serde(rename = "fundingRate", deserialize_with = "deserialize_f64")]
+    pub funding_rate: f64,
+    #[serde(rename = "fundingTime")]
+    pub funding_time: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LeverageChange {
+    pub symbol: String,
+    pub leverage: i64,
+    #[serde(rename = "maxNotionalValue", deserialize_with = "deserialize_f64")]
+    pub max_notional_value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MarginTypeChange {
+    pub code: i64,
+    pub msg: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExchangeInfo {
+    pub timezone: String,
+    #[serde(rename = "serverTime")]
+    pub server_time: i64,
+    #[serde(rename = "rateLimits")]
+    pub rate_limits: Vec<RateLimit>,
+    pub symbols: Vec<SymbolInfo>,
+}
+
+impl ExchangeInfo {
+    /// Returns the `SymbolInfo` of the given symbol.
+    pub fn symbol(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols
+            .iter()
+            .find(|symbol_info| symbol_info.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Rounds the price to the nearest valid tick of the symbol's `PRICE_FILTER`.
+    ///
+    /// Returns `None` when the symbol is unknown, the price is returned as is when the symbol
+    /// has no `PRICE_FILTER`.
+    pub fn round_price(&self, symbol: &str, price: f64) -> Option<f64> {
+        let symbol_info: &SymbolInfo = self.symbol(symbol)?;
+        Some(match symbol_info.price_filter() {
+            Some((_, _, tick_size)) => round_to_step(price, tick_size, f64::round),
+            None => price,
+        })
+    }
+
+    /// Rounds the quantity down to a valid step of the symbol's `LOT_SIZE` filter.
+    ///
+    /// Returns `None` when the symbol is unknown, the quantity is returned as is when the symbol
+    /// has no `LOT_SIZE` filter.
+    pub fn round_qty(&self, symbol: &str, quantity: f64) -> Option<f64> {
+        let symbol_info: &SymbolInfo = self.symbol(symbol)?;
+        Some(match symbol_info.lot_size() {
+            Some((_, _, step_size)) => round_to_step(quantity, step_size, f64::floor),
+            None => quantity,
+        })
+    }
+}
+
+/// Rounds the value to a multiple of step using the rounding function, removing float noise.
+pub(crate) fn round_to_step(value: f64, step: f64, rounding: fn(f64) -> f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    // A value within float noise of a multiple (e.g. 0.29999999 for 0.3) is that multiple, in
+    // either rounding direction.
+    let steps: f64 = value / step;
+    let steps: f64 = match (steps - steps.round()).abs() < 1e-9 {
+        true => steps.round(),
+        false => rounding(steps),
+    };
+    // The decimals of the step as Binance sends it, e.g. 2 for a tick size of 0.25.
+    let decimals: usize = step
+        .to_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    format!("{:.*}", decimals, steps * step)
+        .parse()
+        .unwrap_or(steps * step)
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RateLimit {
+    #[serde(rename = "rateLimitType")]
+    pub rate_limit_type: String,
+    pub interval: String,
+    #[serde(rename = "intervalNum")]
+    pub interval_num: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub pair: String,
+    #[serde(rename = "contractType")]
+    pub contract_type: ContractType,
+    #[serde(rename = "deliveryDate")]
+    pub delivery_date: i64,
+    #[serde(rename = "onboardDate")]
+    pub onboard_date: i64,
+    pub status: ContractStatus,
+    #[serde(rename = "baseAsset")]
+    pub base_asset: String,
+    #[serde(rename = "quoteAsset")]
+    pub quote_asset: String,
+    #[serde(rename = "marginAsset")]
+    pub margin_asset: String,
+    #[serde(rename = "pricePrecision")]
+    pub price_precision: i64,
+    #[serde(rename = "quantityPrecision")]
+    pub quantity_precision: i64,
+    pub filters: Vec<SymbolFilter>,
+}
+
+impl SymbolInfo {
+    /// Returns the (min price, max price, tick size) of the `PRICE_FILTER`.
+    pub fn price_filter(&self) -> Option<(f64, f64, f64)> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::PriceFilter {
+                min_price,
+                max_price,
+                tick_size,
+            } => Some((*min_price, *max_price, *tick_size)),
+            _ => None,
+        })
+    }
+
+    /// Returns the (min quantity, max quantity, step size) of the `LOT_SIZE` filter.
+    pub fn lot_size(&self) -> Option<(f64, f64, f64)> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::LotSize {
+                min_qty,
+                max_qty,
+                step_size,
+            } => Some((*min_qty, *max_qty, *step_size)),
+            _ => None,
+        })
+    }
+
+    /// Returns the (min quantity, max quantity, step size) of the `MARKET_LOT_SIZE` filter.
+    pub fn market_lot_size(&self) -> Option<(f64, f64, f64)> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::MarketLotSize {
+                min_qty,
+                max_qty,
+                step_size,
+            } => Some((*min_qty, *max_qty, *step_size)),
+            _ => None,
+        })
+    }
+
+    /// Returns the notional of the `MIN_NOTIONAL` filter.
+    pub fn min_notional(&self) -> Option<f64> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::MinNotional { notional } => Some(*notional),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "filterType")]
+pub enum SymbolFilter {
+    #[serde(rename = "PRICE_FILTER")]
+    PriceFilter {
+        #[serde(rename = "minPrice", deserialize_with = "deserialize_f64")]
+        min_price: f64,
+        #[serde(rename = "maxPrice", deserialize_with = "deserialize_f64")]
+        max_price: f64,
+        #[serde(rename = "tickSize", deserialize_with = "deserialize_f64")]
+        tick_size: f64,
+    },
+    #[serde(rename = "LOT_SIZE")]
+    LotSize {
+        #[serde(rename = "minQty", deserialize_with = "deserialize_f64")]
+        min_qty: f64,
+        #[serde(rename = "maxQty", deserialize_with = "deserialize_f64")]
+        max_qty: f64,
+        #[serde(rename = "stepSize", deserialize_with = "deserialize_f64")]
+        step_size: f64,
+    },
+    #[serde(rename = "MARKET_LOT_SIZE")]
+    MarketLotSize {
+        #[serde(rename = "minQty", deserialize_with = "deserialize_f64")]
+        min_qty: f64,
+        #[serde(rename = "maxQty", deserialize_with = "deserialize_f64")]
+        max_qty: f64,
+        #[serde(rename = "stepSize", deserialize_with = "deserialize_f64")]
+        step_size: f64,
+    },
+    #[serde(rename = "MAX_NUM_ORDERS")]
+    MaxNumOrders { limit: i64 },
+    #[serde(rename = "MAX_NUM_ALGO_ORDERS")]
+    MaxNumAlgoOrders { limit: i64 },
+    #[serde(rename = "MIN_NOTIONAL")]
+    MinNotional {
+        #[serde(deserialize_with = "deserialize_f64")]
+        notional: f64,
+    },
+    #[serde(rename = "PERCENT_PRICE")]
+    PercentPrice {
+        #[serde(rename = "multiplierUp", deserialize_with = "deserialize_f64")]
+        multiplier_up: f64,
+        #[serde(rename = "multiplierDown", deserialize_with = "deserialize_f64")]
+        multiplier_down: f64,
+        #[serde(rename = "multiplierDecimal", deserialize_with = "deserialize_f64")]
+        multiplier_decimal: f64,
+    },
+    #[serde(other)]
+    Unknown,
+}
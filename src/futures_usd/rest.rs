@@ -0,0 +1,355 @@
+use reqwest::blocking::Response;
+use reqwest::{Method, StatusCode};
+use url::form_urlencoded;
+
+use crate::constants;
+use crate::error::BinanceConnectError;
+use crate::futures_usd::enums::binance::MarginType;
+use crate::futures_usd::listen_key::{base_url, ApiAuth};
+use crate::futures_usd::rate_limit;
+use crate::futures_usd::response::{
+    AccountSnapshot, BinanceErrorMessage, ExchangeInfo, FundingRate, LeverageChange,
+    MarginTypeChange, OpenInterest, OpenOrder, PositionRisk, ServerTime,
+};
+use crate::futures_usd::time_sync::server_now;
+use crate::futures_usd::tls;
+
+/// Retrieves the current exchange trading rules and symbol information from Binance.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// The `ExchangeInfo` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_exchange_info(test_net: bool) -> Result<ExchangeInfo, BinanceConnectError> {
+    let json_response: String =
+        public_request(test_net, constants::FUTURES_EXCHANGE_INFO, Vec::new())?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the current server time from Binance.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// The `ServerTime` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_server_time(test_net: bool) -> Result<ServerTime, BinanceConnectError> {
+    get_server_time_from(base_url(test_net))
+}
+
+/// Retrieves the current server time from the base URL, e.g. of a mock server.
+pub(crate) fn get_server_time_from(base_url: &str) -> Result<ServerTime, BinanceConnectError> {
+    let json_response: String = public_request_to(base_url, constants::FUTURES_TIME, Vec::new())?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the present open interest of a symbol from Binance.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: The trading symbol for which the open interest should be retrieved.
+///
+/// # Returns
+///
+/// The `OpenInterest` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_open_interest(
+    test_net: bool,
+    symbol: &str,
+) -> Result<OpenInterest, BinanceConnectError> {
+    let json_response: String = public_request(
+        test_net,
+        constants::FUTURES_OPEN_INTEREST,
+        symbol_params(Some(symbol)),
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the funding rate history of a symbol from Binance.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: The trading symbol for which the funding rates should be retrieved.
+/// - `limit`: The number of (most recent) funding rates to retrieve, at most 1000.
+///
+/// # Returns
+///
+/// A `Vec<FundingRate>` (oldest first) or a `BinanceConnectError` if the request failed.
+///
+pub fn get_funding_rate_history(
+    test_net: bool,
+    symbol: &str,
+    limit: u16,
+) -> Result<Vec<FundingRate>, BinanceConnectError> {
+    let mut params: Vec<(&str, String)> = symbol_params(Some(symbol));
+    params.push(("limit", limit.to_string()));
+    let json_response: String = public_request(test_net, constants::FUTURES_FUNDING_RATE, params)?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the current position information from Binance.
+///
+/// # Arguments
+///
+/// - `api_auth`: The API credentials used to sign the request.
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: An optional trading symbol; when `None` the positions of all symbols are returned.
+///
+/// # Returns
+///
+/// A `Vec<PositionRisk>` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_position_risk(
+    api_auth: &ApiAuth,
+    test_net: bool,
+    symbol: Option<&str>,
+) -> Result<Vec<PositionRisk>, BinanceConnectError> {
+    let json_response: String = signed_request(
+        api_auth,
+        test_net,
+        Method::GET,
+        constants::FUTURES_POSITION_RISK,
+        symbol_params(symbol),
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves all open orders from Binance.
+///
+/// # Arguments
+///
+/// - `api_auth`: The API credentials used to sign the request.
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: An optional trading symbol; when `None` the open orders of all symbols are returned.
+///
+/// # Returns
+///
+/// A `Vec<OpenOrder>` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_open_orders(
+    api_auth: &ApiAuth,
+    test_net: bool,
+    symbol: Option<&str>,
+) -> Result<Vec<OpenOrder>, BinanceConnectError> {
+    let json_response: String = signed_request(
+        api_auth,
+        test_net,
+        Method::GET,
+        constants::FUTURES_OPEN_ORDERS,
+        symbol_params(symbol),
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the current account information (balances and positions) from Binance.
+///
+/// # Arguments
+///
+/// - `api_auth`: The API credentials used to sign the request.
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// An `AccountSnapshot` or a `BinanceConnectError` if the request failed.
+///
+pub fn get_account(
+    api_auth: &ApiAuth,
+    test_net: bool,
+) -> Result<AccountSnapshot, BinanceConnectError> {
+    get_account_from(api_auth, base_url(test_net))
+}
+
+/// Retrieves the balances and positions of the account from the base URL, e.g. of a proxy.
+pub(crate) fn get_account_from(
+    api_auth: &ApiAuth,
+    base_url: &str,
+) -> Result<AccountSnapshot, BinanceConnectError> {
+    let json_response: String = signed_request_to(
+        api_auth,
+        base_url,
+        Method::GET,
+        constants::FUTURES_ACCOUNT,
+        Vec::new(),
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Changes the initial leverage of a symbol.
+///
+/// # Arguments
+///
+/// - `api_auth`: The API credentials used to sign the request.
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: The trading symbol for which the leverage should be changed.
+/// - `leverage`: The target initial leverage (1 to 125).
+///
+/// # Returns
+///
+/// A `LeverageChange` or a `BinanceConnectError` if the request failed.
+///
+pub fn change_leverage(
+    api_auth: &ApiAuth,
+    test_net: bool,
+    symbol: &str,
+    leverage: u8,
+) -> Result<LeverageChange, BinanceConnectError> {
+    let json_response: String = signed_request(
+        api_auth,
+        test_net,
+        Method::POST,
+        constants::FUTURES_LEVERAGE,
+        vec![
+            ("symbol", symbol.to_uppercase()),
+            ("leverage", leverage.to_string()),
+        ],
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Changes the margin type (isolated or crossed) of a symbol.
+///
+/// # Arguments
+///
+/// - `api_auth`: The API credentials used to sign the request.
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `symbol`: The trading symbol for which the margin type should be changed.
+/// - `margin_type`: The target `MarginType`.
+///
+/// # Returns
+///
+/// A `MarginTypeChange` or a `BinanceConnectError` if the request failed.
+///
+pub fn change_margin_type(
+    api_auth: &ApiAuth,
+    test_net: bool,
+    symbol: &str,
+    margin_type: MarginType,
+) -> Result<MarginTypeChange, BinanceConnectError> {
+    let json_response: String = signed_request(
+        api_auth,
+        test_net,
+        Method::POST,
+        constants::FUTURES_MARGIN_TYPE,
+        vec![
+            ("symbol", symbol.to_uppercase()),
+            ("marginType", margin_type.to_str().to_uppercase()),
+        ],
+    )?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Builds the query parameters for endpoints that accept an optional symbol.
+fn symbol_params(symbol: Option<&str>) -> Vec<(&'static str, String)> {
+    match symbol {
+        Some(symbol) => vec![("symbol", symbol.to_uppercase())],
+        None => Vec::new(),
+    }
+}
+
+/// Sends a public (`NONE` security type) GET request to Binance and returns the response body.
+pub(crate) fn public_request(
+    test_net: bool,
+    endpoint: &str,
+    params: Vec<(&str, String)>,
+) -> Result<String, BinanceConnectError> {
+    public_request_to(base_url(test_net), endpoint, params)
+}
+
+/// Sends a public GET request to the base URL and returns the response body.
+fn public_request_to(
+    base_url: &str,
+    endpoint: &str,
+    params: Vec<(&str, String)>,
+) -> Result<String, BinanceConnectError> {
+    let response: Response = tls::http_client()?
+        .get(format!("{}{}", base_url, endpoint))
+        .query(&params)
+        .send()?;
+    response_text(response)
+}
+
+/// Sends a signed (`USER_DATA`/`TRADE`) request to Binance and returns the response body.
+///
+/// The `timestamp` parameter is appended to the given parameters, after which the query string
+/// is signed with the `Signer` of the API key and sent together with the `X-MBX-APIKEY` header.
+pub(crate) fn signed_request(
+    api_auth: &ApiAuth,
+    test_net: bool,
+    method: Method,
+    endpoint: &str,
+    params: Vec<(&str, String)>,
+) -> Result<String, BinanceConnectError> {
+    signed_request_to(api_auth, base_url(test_net), method, endpoint, params)
+}
+
+/// Sends a signed request to the base URL and returns the response body.
+fn signed_request_to(
+    api_auth: &ApiAuth,
+    base_url: &str,
+    method: Method,
+    endpoint: &str,
+    mut params: Vec<(&str, String)>,
+) -> Result<String, BinanceConnectError> {
+    params.push(("timestamp", timestamp().to_string()));
+    let query: String = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<String>>()
+        .join("&");
+    // RSA and Ed25519 signatures are base64 encoded and need to be URL encoded.
+    let signature: String =
+        form_urlencoded::byte_serialize(api_auth.sign(&query)?.as_bytes()).collect();
+    let url: String = format!("{}{}?{}&signature={}", base_url, endpoint, query, signature);
+
+    let response: Response = tls::http_client()?
+        .request(method, url)
+        .header("X-MBX-APIKEY", api_auth.api_key()?.as_str())
+        .send()?;
+    response_text(response)
+}
+
+/// Returns the body of an OK (200) response or a `HttpResponseError` otherwise.
+fn response_text(response: Response) -> Result<String, BinanceConnectError> {
+    rate_limit::record(response.headers());
+    // Check if the response status is OK (200).
+    let status: StatusCode = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+        rate_limit::record_exhausted();
+    }
+    if status == StatusCode::OK {
+        Ok(response.text()?)
+    } else {
+        // Handle non-OK HTTP status codes by returning the error of Binance, if any.
+        let body: String = response.text().unwrap_or_default();
+        Err(match serde_json::from_str::<BinanceErrorMessage>(&body) {
+            Ok(error_message) => error_message.into(),
+            Err(_) => BinanceConnectError::HttpResponseError(format!(
+                "Not-OK status code received {:?}: {}",
+                status, body
+            )),
+        })
+    }
+}
+
+/// Returns the current Binance server time in milliseconds, see `time_sync::server_now`.
+fn timestamp() -> u64 {
+    server_now()
+}
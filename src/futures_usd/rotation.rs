@@ -0,0 +1,123 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::futures_usd::client::Socket;
+use crate::futures_usd::standby::Standby;
+use crate::futures_usd::transport::Transport;
+
+/// The default maximum age of a connection, Binance drops connections after 24 hours.
+pub const DEFAULT_MAX_CONNECTION_AGE: Duration = Duration::from_secs(23 * 60 * 60 + 45 * 60);
+/// The default time the expiring connection is still read after its replacement is subscribed.
+pub const DEFAULT_MIGRATION_OVERLAP: Duration = Duration::from_secs(1);
+
+/// The time of the latest `FuturesUsdStream::migrate` call, shared by the connections of a stream.
+#[derive(Debug, Default)]
+pub(crate) struct MigrationRequest(Mutex<Option<Instant>>);
+
+impl MigrationRequest {
+    /// Requests the migration of every connection established before now.
+    pub(crate) fn request(&self) {
+        *self.0.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns true when a migration was requested after the connection was established.
+    fn requested_since(&self, connected: Instant) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .map_or(false, |requested| requested > connected)
+    }
+}
+
+/// Replaces a connection "make-before-break", before Binance drops it at the 24 hour limit or
+/// when a migration is requested.
+///
+/// Once the connection is due a replacement is connected and subscribed in the background while
+/// the events are still read from the old connection. Once the replacement is subscribed the old
+/// connection is read for the overlap window, only then the events are read from the replacement
+/// and the old connection is closed. The frames the replacement received during the overlap are
+/// suppressed by the `DuplicateFilter`, so consumers see neither a gap nor a duplicate.
+#[derive(Debug, Clone)]
+pub(crate) struct Rotation {
+    url: Url,
+    subscribe_payload: Option<String>,
+    max_age: Option<Duration>,
+    overlap: Duration,
+    migration: Arc<MigrationRequest>,
+    transport: Arc<Transport>,
+}
+
+impl Rotation {
+    /// Creates the rotation of a connection to the URL.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The WebSocket URL the replacement connects to.
+    /// - `subscribe_payload`: An optional subscription payload the replacement sends.
+    /// - `max_age`: The age at which the connection is replaced, only on request when `None`.
+    /// - `overlap`: The time the old connection is still read once the replacement is subscribed.
+    /// - `migration`: The migration requests of the stream.
+    /// - `transport`: The options of the outbound TCP connection of the replacement.
+    ///
+    pub(crate) fn new(
+        url: Url,
+        subscribe_payload: Option<String>,
+        max_age: Option<Duration>,
+        overlap: Duration,
+        migration: Arc<MigrationRequest>,
+        transport: Arc<Transport>,
+    ) -> Rotation {
+        Self {
+            url,
+            subscribe_payload,
+            max_age,
+            overlap,
+            migration,
+            transport,
+        }
+    }
+
+    /// Returns the time the old connection is still read once the replacement is subscribed.
+    pub(crate) fn overlap(&self) -> Duration {
+        self.overlap
+    }
+
+    /// Starts warming the replacement once the connection reached the maximum age or a migration
+    /// was requested and returns its socket once it is subscribed, `None` until then.
+    ///
+    /// # Arguments
+    ///
+    /// - `connected`: The time the current connection was established.
+    /// - `replacement`: The replacement being warmed, kept by the caller between the calls.
+    /// - `stop_signal`: A flag that stops the replacement when set.
+    ///
+    pub(crate) fn poll(
+        &self,
+        connected: Instant,
+        replacement: &mut Option<Standby>,
+        stop_signal: &Arc<AtomicBool>,
+    ) -> Option<Socket> {
+        let due: bool = self
+            .max_age
+            .map_or(false, |max_age| connected.elapsed() >= max_age)
+            || self.migration.requested_since(connected);
+        if !due && replacement.is_none() {
+            return None;
+        }
+        let socket: Socket = replacement
+            .get_or_insert_with(|| {
+                Standby::warm(
+                    self.url.clone(),
+                    self.subscribe_payload.clone(),
+                    Arc::clone(stop_signal),
+                    Arc::clone(&self.transport),
+                )
+            })
+            .promote(Duration::ZERO)?;
+        *replacement = None;
+        Some(socket)
+    }
+}
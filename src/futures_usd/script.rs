@@ -0,0 +1,163 @@
+use evalexpr::{
+    build_operator_tree, ContextWithMutableVariables, HashMapContext, IterateVariablesContext,
+    Node, Value as ExprValue,
+};
+use serde_json::{Map, Number, Value};
+
+use crate::error::BinanceConnectError;
+
+/// A user-defined filter/transform, written as an `evalexpr` expression, applied to every event
+/// before it is deserialized.
+///
+/// The top-level fields of the event payload are available as variables under their Binance
+/// names, e.g. `s` (symbol), `p` (price) and `q` (quantity). Numeric strings are exposed as floats
+/// so they can be used in calculations. The expression:
+///
+/// - drops the event when it evaluates to `false`, e.g. `s == "BTCUSDT" && p * q > 10000.0`.
+/// - transforms the event by assigning to fields, e.g. `s = "BTC"; true`.
+///
+/// Items of array payloads (all market streams) are filtered and transformed individually.
+#[derive(Debug, Clone)]
+pub struct EventScript {
+    expression: String,
+    node: Node,
+}
+
+impl EventScript {
+    /// Compiles the expression into a new `EventScript`.
+    ///
+    /// # Arguments
+    ///
+    /// - `expression`: The `evalexpr` expression to apply to every event.
+    ///
+    /// # Returns
+    ///
+    /// The `EventScript` or a `BinanceConnectError` when the expression does not compile.
+    ///
+    pub fn new(expression: &str) -> Result<EventScript, BinanceConnectError> {
+        let node: Node = build_operator_tree(expression).map_err(|err| {
+            BinanceConnectError::Other(format!("Invalid event script {:?}: {}", expression, err))
+        })?;
+        Ok(Self {
+            expression: expression.to_string(),
+            node,
+        })
+    }
+
+    /// Returns the expression of the script.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Applies the script to the JSON payload.
+    ///
+    /// # Returns
+    ///
+    /// The (transformed) payload, `None` when the event is dropped, or a `BinanceConnectError`
+    /// when the script could not be evaluated.
+    ///
+    pub(crate) fn apply(&self, json_response: &str) -> Result<Option<String>, BinanceConnectError> {
+        let mut value: Value = serde_json::from_str(json_response)
+            .map_err(|err| BinanceConnectError::json_error(err, json_response))?;
+        // Combined mode wraps the event as `{"stream": .., "data": ..}`
+        let data: &mut Value = match value.get("data").is_some() {
+            true => &mut value["data"],
+            false => &mut value,
+        };
+        let keep: bool = match data {
+            Value::Array(items) => {
+                let mut kept: Vec<Value> = Vec::with_capacity(items.len());
+                for mut item in items.drain(..) {
+                    if self.apply_object(&mut item)? {
+                        kept.push(item);
+                    }
+                }
+                *items = kept;
+                !items.is_empty()
+            }
+            _ => self.apply_object(data)?,
+        };
+        Ok(match keep {
+            true => Some(value.to_string()),
+            false => None,
+        })
+    }
+
+    /// Evaluates the script against a single event object, writing assigned fields back.
+    fn apply_object(&self, value: &mut Value) -> Result<bool, BinanceConnectError> {
+        let object: &mut Map<String, Value> = match value.as_object_mut() {
+            Some(object) => object,
+            None => return Ok(true),
+        };
+        let mut context: HashMapContext = HashMapContext::new();
+        for (key, field) in object.iter() {
+            if let Some(variable) = to_variable(field) {
+                context
+                    .set_value(key.clone(), variable)
+                    .map_err(|err| self.error(err))?;
+            }
+        }
+        let result: ExprValue = self
+            .node
+            .eval_with_context_mut(&mut context)
+            .map_err(|err| self.error(err))?;
+        for (key, variable) in context.iter_variables() {
+            // Only write back assigned fields, keeping the original representation of the others.
+            if object.get(&key).and_then(to_variable).as_ref() == Some(&variable) {
+                continue;
+            }
+            let field: Value = match object.get(&key) {
+                // Keep numeric strings strings, as the deserializers expect them to be.
+                Some(Value::String(_)) => Value::String(variable_to_string(&variable)),
+                _ => from_variable(&variable),
+            };
+            object.insert(key, field);
+        }
+        Ok(!matches!(result, ExprValue::Boolean(false)))
+    }
+
+    /// Creates a `BinanceConnectError` for an evaluation error of the script.
+    fn error(&self, err: evalexpr::EvalexprError) -> BinanceConnectError {
+        BinanceConnectError::Other(format!(
+            "Event script {:?} failed: {}",
+            self.expression, err
+        ))
+    }
+}
+
+/// Converts a JSON field into a script variable, nested objects and arrays are not exposed.
+fn to_variable(field: &Value) -> Option<ExprValue> {
+    match field {
+        Value::String(string) => Some(match string.parse::<f64>() {
+            Ok(float) => ExprValue::Float(float),
+            Err(_) => ExprValue::String(string.clone()),
+        }),
+        Value::Number(number) => Some(match number.as_i64() {
+            Some(int) => ExprValue::Int(int),
+            None => ExprValue::Float(number.as_f64().unwrap_or_default()),
+        }),
+        Value::Bool(boolean) => Some(ExprValue::Boolean(*boolean)),
+        _ => None,
+    }
+}
+
+/// Converts a script variable into a JSON field.
+fn from_variable(variable: &ExprValue) -> Value {
+    match variable {
+        ExprValue::String(string) => Value::String(string.clone()),
+        ExprValue::Float(float) => Number::from_f64(*float)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ExprValue::Int(int) => Value::Number(Number::from(*int)),
+        ExprValue::Boolean(boolean) => Value::Bool(*boolean),
+        _ => Value::Null,
+    }
+}
+
+/// Converts a script variable into a JSON string field.
+fn variable_to_string(variable: &ExprValue) -> String {
+    match variable {
+        ExprValue::String(string) => string.clone(),
+        variable => variable.to_string(),
+    }
+}
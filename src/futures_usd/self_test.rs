@@ -0,0 +1,202 @@
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tungstenite::Message;
+use url::Url;
+
+use crate::constants;
+use crate::error::BinanceConnectError;
+use crate::futures_usd::client::{connect_socket, set_read_timeout, Socket};
+use crate::futures_usd::deserializer::deserialize;
+use crate::futures_usd::enums::binance::{
+    BookDepthUpdateSpeed, KlineContractType, KlineInterval, MarkPriceUpdateSpeed,
+    PartialBookDepthLevel,
+};
+use crate::futures_usd::enums::streams::Streams;
+use crate::futures_usd::transport::Transport;
+
+/// The read timeout used so the deadline of the self-test is checked on quiet streams.
+const SELF_TEST_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The outcome of the self-test for a single stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestResult {
+    /// The stream name as subscribed, e.g. `btcusdt@aggTrade`.
+    pub stream: String,
+    /// The event type (`e` field) the stream is expected to deliver.
+    pub event_type: String,
+    /// A flag indicating whether Binance acknowledged the subscription.
+    pub acked: bool,
+    /// The number of events of the stream that deserialized successfully.
+    pub events: u64,
+    /// The subscription or deserialization error, if any occurred.
+    pub error: Option<String>,
+}
+
+impl SelfTestResult {
+    /// Returns whether the stream was acknowledged or delivered an event, without any error.
+    pub fn passed(&self) -> bool {
+        (self.acked || self.events > 0) && self.error.is_none()
+    }
+}
+
+/// The structured report of `FuturesUsdStream::self_test`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub results: Vec<SelfTestResult>,
+    /// The time the self-test took in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl SelfTestReport {
+    /// Returns whether every stream passed the self-test.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(SelfTestResult::passed)
+    }
+
+    /// Returns the results of the streams that did not pass the self-test.
+    pub fn failures(&self) -> Vec<&SelfTestResult> {
+        self.results
+            .iter()
+            .filter(|result| !result.passed())
+            .collect()
+    }
+}
+
+/// Returns one instance of every supported public stream with the event type it delivers.
+fn streams() -> Vec<(Streams, &'static str)> {
+    vec![
+        (Streams::book_ticker("btcusdt"), "bookTicker"),
+        (Streams::book_tickers(), "bookTicker"),
+        (Streams::agg_trade("btcusdt"), "aggTrade"),
+        (
+            Streams::mark_price_update("btcusdt", MarkPriceUpdateSpeed::Seconds1),
+            "markPriceUpdate",
+        ),
+        (
+            Streams::mark_price_updates(MarkPriceUpdateSpeed::Seconds1),
+            "markPriceUpdate",
+        ),
+        (Streams::kline("btcusdt", KlineInterval::Minutes1), "kline"),
+        (
+            Streams::continuous_kline(
+                "btcusdt",
+                KlineContractType::Perpetual,
+                KlineInterval::Minutes1,
+            ),
+            "continuous_kline",
+        ),
+        (Streams::mini_ticker("btcusdt"), "24hrMiniTicker"),
+        (Streams::mini_tickers(), "24hrMiniTicker"),
+        (Streams::ticker("btcusdt"), "24hrTicker"),
+        (Streams::tickers(), "24hrTicker"),
+        (Streams::force_order("btcusdt"), "forceOrder"),
+        (Streams::force_orders(), "forceOrder"),
+        (
+            Streams::partial_book_depth(
+                "btcusdt",
+                PartialBookDepthLevel::Five,
+                BookDepthUpdateSpeed::Millis100,
+            ),
+            "depthUpdate",
+        ),
+        (
+            Streams::book_depth("btcusdt", BookDepthUpdateSpeed::Millis100),
+            "depthUpdate",
+        ),
+        (Streams::composite_index("defiusdt"), "compositeIndex"),
+        (Streams::contract_info(), "contractInfo"),
+        (Streams::asset_index_update("btcusd"), "assetIndexUpdate"),
+        (Streams::asset_index_updates(), "assetIndexUpdate"),
+    ]
+}
+
+/// Connects to the combined stream of the testnet, subscribes to one instance of every supported
+/// stream (each with its own request id) and collects the acknowledgements and events, matched by
+/// stream name, until `timeout` elapsed or every stream has been acknowledged and delivered an
+/// event.
+pub(crate) fn run(timeout: Duration) -> Result<SelfTestReport, BinanceConnectError> {
+    let started: Instant = Instant::now();
+    let mut results: Vec<SelfTestResult> = streams()
+        .into_iter()
+        .map(|(stream, event_type)| SelfTestResult {
+            stream: stream.to_str().to_string(),
+            event_type: event_type.to_string(),
+            acked: false,
+            events: 0,
+            error: None,
+        })
+        .collect();
+
+    let url: Url = Url::parse(&format!("{}/stream", constants::WS_URL_FUTURES_TESTNET))?;
+    let mut socket: Socket = connect_socket(url, None, &Transport::default())?;
+    set_read_timeout(&socket, Some(SELF_TEST_READ_TIMEOUT));
+    for (index, result) in results.iter().enumerate() {
+        socket.send(Message::Text(format!(
+            "{{\"method\": \"SUBSCRIBE\",\"params\":[\"{}\"],\"id\": {}}}",
+            result.stream,
+            index + 1
+        )))?;
+    }
+
+    while started.elapsed() < timeout && !complete(&results) {
+        match socket.read() {
+            Ok(Message::Text(json_response)) => record(&mut results, json_response),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(ref io_err))
+                if io_err.kind() == ErrorKind::WouldBlock
+                    || io_err.kind() == ErrorKind::TimedOut => {}
+            Err(err) => return Err(BinanceConnectError::SocketError(Box::new(err))),
+        }
+    }
+    let _ = socket.close(None);
+
+    Ok(SelfTestReport {
+        results,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Returns whether every stream has been acknowledged and delivered an event.
+fn complete(results: &[SelfTestResult]) -> bool {
+    results
+        .iter()
+        .all(|result| result.acked && result.events > 0)
+}
+
+/// Records a subscription response or an event in the results.
+fn record(results: &mut [SelfTestResult], json_response: String) {
+    let value: Value = match serde_json::from_str(&json_response) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    // Subscription responses carry the id of the request, which is the index of the stream + 1.
+    if let Some(id) = value.get("id").and_then(Value::as_u64) {
+        if let Some(result) = results.get_mut((id as usize).wrapping_sub(1)) {
+            match value.get("error") {
+                Some(error) => result.error = Some(error.to_string()),
+                None => result.acked = true,
+            }
+        }
+        return;
+    }
+    // The events of the combined stream carry the name of the stream they belong to, streams
+    // sharing an event type (e.g. `btcusdt@bookTicker` and `!bookTicker`) are told apart by it.
+    let stream: &str = match value.get("stream").and_then(Value::as_str) {
+        Some(stream) => stream,
+        None => return,
+    };
+    let result: &mut SelfTestResult = match results
+        .iter_mut()
+        .find(|result| result.stream.eq_ignore_ascii_case(stream))
+    {
+        Some(result) => result,
+        None => return,
+    };
+    match deserialize(json_response) {
+        Ok(_) => result.events += 1,
+        Err(err) => result.error = Some(err.to_string()),
+    }
+}
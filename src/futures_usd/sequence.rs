@@ -0,0 +1,153 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BinanceConnectError;
+
+/// The number of sequence numbers reserved per WAL record, see `SequenceWal::with_block_size`.
+const DEFAULT_BLOCK_SIZE: u64 = 1024;
+
+/// The number of records after which the WAL is compacted into a single record.
+const COMPACT_AFTER_RECORDS: u64 = 1024;
+
+/// An event stamped with an application-level sequence number, emitted as `SequencedEvent` when
+/// the stream is configured `with_sequence_wal`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Sequenced<T> {
+    /// The sequence number, strictly increasing across connector restarts.
+    pub sequence: u64,
+    pub event: T,
+}
+
+/// A persistent, monotonic sequence backed by a small write-ahead log.
+///
+/// Sequence numbers are handed out from blocks whose upper bound is written (and synced) to the
+/// WAL before the first number of the block is used. After a restart the sequence continues at
+/// the last reserved bound, so a number is never handed out twice, even after a crash, at the
+/// cost of a gap of at most one block. Downstream exactly-once sinks (e.g. idempotent Kafka
+/// producers or a database unique key) can use the numbers to deduplicate.
+#[derive(Debug)]
+pub struct SequenceWal {
+    path: PathBuf,
+    file: File,
+    /// The first sequence number handed out since the WAL was opened.
+    session_start: u64,
+    /// The next sequence number to hand out.
+    next: u64,
+    /// The (exclusive) upper bound of the sequence numbers reserved in the WAL.
+    reserved: u64,
+    block_size: u64,
+    /// The number of records in the WAL since it was last compacted.
+    records: u64,
+}
+
+impl SequenceWal {
+    /// Opens (or creates) the WAL and recovers the sequence from it.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the WAL file.
+    ///
+    /// # Returns
+    ///
+    /// The `SequenceWal` or a `BinanceConnectError` if the file could not be read or created.
+    ///
+    pub fn open(path: &Path) -> Result<SequenceWal, BinanceConnectError> {
+        let mut reserved: u64 = 0;
+        let mut records: u64 = 0;
+        if path.exists() {
+            for line in BufReader::new(File::open(path)?).lines() {
+                // A torn last record (crash during the write) does not parse and is ignored,
+                // its block was never handed out.
+                if let Ok(bound) = line?.trim().parse::<u64>() {
+                    reserved = reserved.max(bound);
+                    records += 1;
+                }
+            }
+        }
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            session_start: reserved,
+            next: reserved,
+            reserved,
+            block_size: DEFAULT_BLOCK_SIZE,
+            records,
+        })
+    }
+
+    /// Sets the number of sequence numbers reserved per WAL write, defaults to 1024. Larger
+    /// blocks mean fewer disk syncs but larger gaps after a restart.
+    pub fn with_block_size(mut self, block_size: u64) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Returns the next sequence number, reserving a new block in the WAL when needed.
+    ///
+    /// # Returns
+    ///
+    /// The sequence number or a `BinanceConnectError` if the reservation could not be written.
+    ///
+    pub fn next_sequence(&mut self) -> Result<u64, BinanceConnectError> {
+        if self.next >= self.reserved {
+            self.reserve(self.next + self.block_size)?;
+        }
+        let sequence: u64 = self.next;
+        self.next += 1;
+        Ok(sequence)
+    }
+
+    /// Stamps the event with the next sequence number.
+    ///
+    /// # Arguments
+    ///
+    /// - `event`: The event to stamp, e.g. an `Event` or `AccountEvent`.
+    ///
+    /// # Returns
+    ///
+    /// The `Sequenced` event or a `BinanceConnectError` if the reservation could not be written.
+    ///
+    pub fn stamp<T>(&mut self, event: T) -> Result<Sequenced<T>, BinanceConnectError> {
+        Ok(Sequenced {
+            sequence: self.next_sequence()?,
+            event,
+        })
+    }
+
+    /// Returns the last handed out sequence number, `None` when none was handed out since open.
+    pub fn last(&self) -> Option<u64> {
+        match self.next > self.session_start {
+            true => Some(self.next - 1),
+            false => None,
+        }
+    }
+
+    /// Durably writes the new reservation bound, compacting the WAL when it grew too large.
+    fn reserve(&mut self, bound: u64) -> Result<(), BinanceConnectError> {
+        if self.records >= COMPACT_AFTER_RECORDS {
+            self.compact(bound)?;
+        } else {
+            writeln!(self.file, "{}", bound)?;
+            self.file.sync_data()?;
+            self.records += 1;
+        }
+        self.reserved = bound;
+        Ok(())
+    }
+
+    /// Replaces the WAL with a single record holding the bound.
+    fn compact(&mut self, bound: u64) -> Result<(), BinanceConnectError> {
+        let compacted: PathBuf = self.path.with_extension("compact");
+        let mut file: File = File::create(&compacted)?;
+        writeln!(file, "{}", bound)?;
+        file.sync_all()?;
+        fs::rename(&compacted, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.records = 1;
+        Ok(())
+    }
+}
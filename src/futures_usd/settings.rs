@@ -0,0 +1,198 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::listen_key::ApiAuth;
+use crate::futures_usd::reconnect::ReconnectTriggers;
+use crate::futures_usd::stream::FuturesWebSocketConfig;
+
+/// The prefix of the environment variables read by `ConfigSettings::from_env`, followed by the
+/// upper-cased setting name, e.g. `BINANCE_MAX_RECONNECT_ATTEMPTS`.
+pub const ENV_PREFIX: &str = "BINANCE_";
+
+/// The settings of a `FuturesWebSocketConfig` that deployments can change without recompiling,
+/// loaded from the environment (`FuturesWebSocketConfig::from_env`) or a TOML file
+/// (`FuturesWebSocketConfig::from_toml`). Unset settings keep their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigSettings {
+    /// The `Endpoint`: `production`, `testnet`, `auth_portal` or a WebSocket URL.
+    pub endpoint: Option<String>,
+    /// Alternative WebSocket endpoints the reconnect policy can rotate to, comma-separated in the
+    /// environment.
+    pub fallback_urls: Vec<String>,
+    /// Whether the connections reconnect on errors.
+    pub reconnect: Option<bool>,
+    /// Whether socket errors trigger a reconnect.
+    pub reconnect_on_socket_errors: Option<bool>,
+    /// Whether JSON errors trigger a reconnect.
+    pub reconnect_on_json_errors: Option<bool>,
+    /// Whether server closes trigger a reconnect.
+    pub reconnect_on_server_close: Option<bool>,
+    /// Whether stale connections trigger a reconnect.
+    pub reconnect_on_stale_connection: Option<bool>,
+    /// The reconnect attempts after which a connection gives up.
+    pub max_reconnect_attempts: Option<u32>,
+    /// The seconds spent reconnecting after which a connection gives up.
+    pub max_reconnect_time_secs: Option<u64>,
+    /// The seconds without any message after which a connection is treated as dead.
+    pub stale_timeout_secs: Option<u64>,
+    /// The maximum number of streams per connection.
+    pub max_streams_per_connection: Option<usize>,
+    /// The maximum number of messages sent per second and connection.
+    pub messages_per_second: Option<u32>,
+    /// The API key, used together with the API secret.
+    pub api_key: Option<String>,
+    /// The HMAC API secret, used together with the API key.
+    pub api_secret: Option<String>,
+}
+
+impl ConfigSettings {
+    /// Reads the settings from the `BINANCE_`-prefixed environment variables, e.g. `BINANCE_ENDPOINT`
+    /// or `BINANCE_API_KEY`.
+    ///
+    /// # Returns
+    ///
+    /// The `ConfigSettings` or a `BinanceConnectError` when a variable can not be parsed.
+    ///
+    pub fn from_env() -> Result<ConfigSettings, BinanceConnectError> {
+        Ok(Self {
+            endpoint: env_setting("endpoint")?,
+            fallback_urls: env_setting::<String>("fallback_urls")?
+                .map(|urls| {
+                    urls.split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            reconnect: env_setting("reconnect")?,
+            reconnect_on_socket_errors: env_setting("reconnect_on_socket_errors")?,
+            reconnect_on_json_errors: env_setting("reconnect_on_json_errors")?,
+            reconnect_on_server_close: env_setting("reconnect_on_server_close")?,
+            reconnect_on_stale_connection: env_setting("reconnect_on_stale_connection")?,
+            max_reconnect_attempts: env_setting("max_reconnect_attempts")?,
+            max_reconnect_time_secs: env_setting("max_reconnect_time_secs")?,
+            stale_timeout_secs: env_setting("stale_timeout_secs")?,
+            max_streams_per_connection: env_setting("max_streams_per_connection")?,
+            messages_per_second: env_setting("messages_per_second")?,
+            api_key: env_setting("api_key")?,
+            api_secret: env_setting("api_secret")?,
+        })
+    }
+
+    /// Parses the settings from a TOML document using the setting names as keys.
+    ///
+    /// # Returns
+    ///
+    /// The `ConfigSettings` or a `BinanceConnectError` when the document can not be parsed.
+    ///
+    #[cfg(feature = "config-file")]
+    pub fn from_toml_str(toml: &str) -> Result<ConfigSettings, BinanceConnectError> {
+        toml::from_str(toml).map_err(|err| BinanceConnectError::InvalidConfig {
+            key: "toml".to_string(),
+            reason: err.to_string(),
+        })
+    }
+
+    /// Applies the settings that are set to the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The configuration the settings are applied to.
+    ///
+    /// # Returns
+    ///
+    /// The modified configuration or a `BinanceConnectError` when an endpoint or URL is invalid or
+    /// only one of the API key and secret is set.
+    ///
+    pub fn apply(
+        self,
+        mut config: FuturesWebSocketConfig,
+    ) -> Result<FuturesWebSocketConfig, BinanceConnectError> {
+        if let Some(endpoint) = &self.endpoint {
+            config = config.with_endpoint(endpoint.parse()?);
+        }
+        for fallback_url in &self.fallback_urls {
+            config = config.with_fallback_url(fallback_url)?;
+        }
+        if self.reconnect == Some(false) {
+            config = config.do_not_reconnect();
+        }
+        let defaults: ReconnectTriggers = ReconnectTriggers::default();
+        let reconnect_triggers: ReconnectTriggers = ReconnectTriggers {
+            socket_errors: self
+                .reconnect_on_socket_errors
+                .unwrap_or(defaults.socket_errors),
+            json_errors: self
+                .reconnect_on_json_errors
+                .unwrap_or(defaults.json_errors),
+            server_close: self
+                .reconnect_on_server_close
+                .unwrap_or(defaults.server_close),
+            stale_connection: self
+                .reconnect_on_stale_connection
+                .unwrap_or(defaults.stale_connection),
+        };
+        if reconnect_triggers != defaults {
+            config = config.with_reconnect_triggers(reconnect_triggers);
+        }
+        if let Some(max_attempts) = self.max_reconnect_attempts {
+            config = config.with_max_reconnect_attempts(max_attempts);
+        }
+        if let Some(max_time_secs) = self.max_reconnect_time_secs {
+            config = config.with_max_reconnect_time(Duration::from_secs(max_time_secs));
+        }
+        if let Some(stale_timeout_secs) = self.stale_timeout_secs {
+            config = config.with_stale_timeout(Duration::from_secs(stale_timeout_secs));
+        }
+        if let Some(max_streams) = self.max_streams_per_connection {
+            config = config.with_max_streams_per_connection(max_streams);
+        }
+        if let Some(messages_per_second) = self.messages_per_second {
+            config = config.with_outgoing_rate_limit(messages_per_second);
+        }
+        match (self.api_key, self.api_secret) {
+            (Some(api_key), Some(api_secret)) => {
+                config = config.with_api_auth(ApiAuth::new(api_key, api_secret));
+            }
+            (None, None) => {}
+            (api_key, _) => {
+                return Err(BinanceConnectError::InvalidConfig {
+                    key: match api_key {
+                        Some(_) => "api_secret",
+                        None => "api_key",
+                    }
+                    .to_string(),
+                    reason: "the API key and secret must be set together".to_string(),
+                })
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Reads and parses the environment variable of the setting, `None` when it is not set.
+fn env_setting<T: FromStr>(name: &str) -> Result<Option<T>, BinanceConnectError>
+where
+    T::Err: std::fmt::Display,
+{
+    let key: String = format!("{}{}", ENV_PREFIX, name.to_uppercase());
+    match env::var(&key) {
+        Ok(value) => value.trim().parse().map(Some).map_err(|err: T::Err| {
+            BinanceConnectError::InvalidConfig {
+                key,
+                reason: err.to_string(),
+            }
+        }),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(err) => Err(BinanceConnectError::InvalidConfig {
+            key,
+            reason: err.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::rate_limit;
+use crate::futures_usd::response::ExchangeInfo;
+use crate::futures_usd::rest::get_exchange_info;
+use crate::futures_usd::time_sync::{self, TimeSyncHandle};
+
+/// The default time a cached `ExchangeInfo` is used before it is requested again.
+pub const DEFAULT_EXCHANGE_INFO_TTL: Duration = Duration::from_secs(3600);
+
+/// A cached `ExchangeInfo` with the time it was requested.
+type CachedExchangeInfo = Option<(Arc<ExchangeInfo>, Instant)>;
+
+/// The process-wide context shared by all streams, connections and pollers: a single cached
+/// `ExchangeInfo` per environment, a single clock synchronization thread and the rate-limit
+/// budget, instead of per-connection copies and redundant REST calls.
+///
+/// The clock offset (`time_sync`) and the request weight budget (`rate_limit`) are process-wide
+/// already, the shared context exposes them next to the cached `ExchangeInfo`.
+#[derive(Debug)]
+pub struct SharedContext {
+    exchange_info_ttl: RwLock<Duration>,
+    /// The cached `ExchangeInfo` of the live and testnet environment.
+    exchange_info: [Mutex<CachedExchangeInfo>; 2],
+    /// Held while the `ExchangeInfo` of the environment is requested, so concurrent callers wait
+    /// for a single request while the cache stays readable.
+    exchange_info_request: [Mutex<()>; 2],
+    /// The running clock synchronization, stopped once the last handle is dropped.
+    time_sync: Mutex<Option<Weak<TimeSyncHandle>>>,
+}
+
+/// The process-wide `SharedContext`.
+static SHARED: SharedContext = SharedContext {
+    exchange_info_ttl: RwLock::new(DEFAULT_EXCHANGE_INFO_TTL),
+    exchange_info: [Mutex::new(None), Mutex::new(None)],
+    exchange_info_request: [Mutex::new(()), Mutex::new(())],
+    time_sync: Mutex::new(None),
+};
+
+/// Returns the process-wide `SharedContext`.
+pub fn shared() -> &'static SharedContext {
+    &SHARED
+}
+
+impl SharedContext {
+    /// Sets the time a cached `ExchangeInfo` is used before it is requested again.
+    pub fn set_exchange_info_ttl(&self, ttl: Duration) {
+        *self.exchange_info_ttl.write().unwrap() = ttl;
+    }
+
+    /// Returns the cached `ExchangeInfo`, requesting it when it is missing or older than the TTL.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    ///
+    /// # Returns
+    ///
+    /// The shared `ExchangeInfo` or a `BinanceConnectError` if the request failed.
+    ///
+    pub fn exchange_info(&self, test_net: bool) -> Result<Arc<ExchangeInfo>, BinanceConnectError> {
+        if let Some(exchange_info) = self.cached_exchange_info(test_net) {
+            return Ok(exchange_info);
+        }
+        let _request = self.exchange_info_request[test_net as usize]
+            .lock()
+            .unwrap();
+        // Requested by a concurrent caller while waiting.
+        if let Some(exchange_info) = self.cached_exchange_info(test_net) {
+            return Ok(exchange_info);
+        }
+        self.request_exchange_info(test_net)
+    }
+
+    /// Requests the `ExchangeInfo` regardless of its age and replaces the cached one.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    ///
+    /// # Returns
+    ///
+    /// The fresh `ExchangeInfo` or a `BinanceConnectError` if the request failed.
+    ///
+    pub fn refresh_exchange_info(
+        &self,
+        test_net: bool,
+    ) -> Result<Arc<ExchangeInfo>, BinanceConnectError> {
+        let _request = self.exchange_info_request[test_net as usize]
+            .lock()
+            .unwrap();
+        self.request_exchange_info(test_net)
+    }
+
+    /// Returns the cached `ExchangeInfo` of the environment unless it is older than the TTL.
+    fn cached_exchange_info(&self, test_net: bool) -> Option<Arc<ExchangeInfo>> {
+        let ttl: Duration = *self.exchange_info_ttl.read().unwrap();
+        match self.exchange_info[test_net as usize]
+            .lock()
+            .unwrap()
+            .as_ref()
+        {
+            Some((exchange_info, fetched)) if fetched.elapsed() < ttl => {
+                Some(Arc::clone(exchange_info))
+            }
+            _ => None,
+        }
+    }
+
+    /// Requests the `ExchangeInfo` without holding the cache lock and caches it.
+    fn request_exchange_info(
+        &self,
+        test_net: bool,
+    ) -> Result<Arc<ExchangeInfo>, BinanceConnectError> {
+        let exchange_info: Arc<ExchangeInfo> = Arc::new(get_exchange_info(test_net)?);
+        *self.exchange_info[test_net as usize].lock().unwrap() =
+            Some((Arc::clone(&exchange_info), Instant::now()));
+        Ok(exchange_info)
+    }
+
+    /// Replaces the cached `ExchangeInfo` of the environment, e.g. with one loaded from a file when
+    /// the REST API is not reachable. It is used until it is older than the TTL.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    /// - `exchange_info`: The `ExchangeInfo` to cache.
+    ///
+    pub fn set_exchange_info(&self, test_net: bool, exchange_info: ExchangeInfo) {
+        *self.exchange_info[test_net as usize].lock().unwrap() =
+            Some((Arc::new(exchange_info), Instant::now()));
+    }
+
+    /// Returns a handle to the process-wide clock synchronization, starting it when it is not
+    /// running. The synchronization stops once every handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    /// - `interval`: The time between two synchronizations, used when it is started.
+    ///
+    /// # Returns
+    ///
+    /// The shared `TimeSyncHandle` or a `BinanceConnectError` if the initial synchronization failed.
+    ///
+    pub fn time_sync(
+        &self,
+        test_net: bool,
+        interval: Duration,
+    ) -> Result<Arc<TimeSyncHandle>, BinanceConnectError> {
+        let mut time_sync = self.time_sync.lock().unwrap();
+        if let Some(handle) = time_sync.as_ref().and_then(Weak::upgrade) {
+            return Ok(handle);
+        }
+        let handle: Arc<TimeSyncHandle> = Arc::new(time_sync::start(test_net, interval)?);
+        *time_sync = Some(Arc::downgrade(&handle));
+        Ok(handle)
+    }
+
+    /// Returns the estimated clock offset (server time - local time) in milliseconds.
+    pub fn clock_offset_ms(&self) -> i64 {
+        time_sync::clock_offset_ms()
+    }
+
+    /// Returns the request weight that can still be used in the current minute.
+    pub fn remaining_weight(&self) -> u32 {
+        rate_limit::remaining_weight()
+    }
+}
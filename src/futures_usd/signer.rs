@@ -0,0 +1,123 @@
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer as _};
+use rsa::RsaPrivateKey;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::error::BinanceConnectError;
+
+/// Signs the payload (query string or ws-api parameters) of `TRADE` and `USER_DATA` requests.
+///
+/// Binance supports HMAC-SHA256, RSA and Ed25519 API keys, each having its own implementation.
+pub trait Signer: fmt::Debug + Send + Sync {
+    /// Returns the signature of the payload as expected in the `signature` parameter.
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError>;
+}
+
+/// Signs requests with the secret of an HMAC API key, producing a hex encoded HMAC-SHA256 signature.
+///
+/// The secret is zeroized when the signer is dropped.
+#[derive(Clone)]
+pub struct HmacSigner {
+    api_secret: Zeroizing<String>,
+}
+
+impl HmacSigner {
+    /// Creates a new `HmacSigner` using the API secret.
+    pub fn new(api_secret: String) -> HmacSigner {
+        Self {
+            api_secret: Zeroizing::new(api_secret),
+        }
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
+            .expect("HMAC can take a key of any size");
+        mac.update(payload.as_bytes());
+        Ok(hex::encode(mac.finalize().into_bytes()))
+    }
+}
+
+impl fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSigner").finish_non_exhaustive()
+    }
+}
+
+/// Signs requests with the private key of an RSA API key, producing a base64 encoded
+/// RSASSA-PKCS1-v1_5 (SHA256) signature.
+///
+/// The private key is zeroized when the signer is dropped.
+#[derive(Clone)]
+pub struct RsaSigner {
+    signing_key: SigningKey<Sha256>,
+}
+
+impl RsaSigner {
+    /// Creates a new `RsaSigner` from a PKCS#8 PEM encoded private key.
+    pub fn from_pem(private_key_pem: &str) -> Result<RsaSigner, BinanceConnectError> {
+        let private_key: RsaPrivateKey =
+            RsaPrivateKey::from_pkcs8_pem(private_key_pem).map_err(|err| {
+                BinanceConnectError::Other(format!("Invalid RSA private key: {}", err))
+            })?;
+        Ok(Self {
+            signing_key: SigningKey::new(private_key),
+        })
+    }
+}
+
+impl Signer for RsaSigner {
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError> {
+        let signature = self
+            .signing_key
+            .try_sign(payload.as_bytes())
+            .map_err(|err| BinanceConnectError::Other(format!("RSA signing failed: {}", err)))?;
+        Ok(STANDARD.encode(signature.to_bytes()))
+    }
+}
+
+impl fmt::Debug for RsaSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaSigner").finish_non_exhaustive()
+    }
+}
+
+/// Signs requests with the private key of an Ed25519 API key, producing a base64 encoded signature.
+///
+/// The private key is zeroized when the signer is dropped.
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Signer {
+    /// Creates a new `Ed25519Signer` from a PKCS#8 PEM encoded private key.
+    pub fn from_pem(private_key_pem: &str) -> Result<Ed25519Signer, BinanceConnectError> {
+        let signing_key =
+            ed25519_dalek::SigningKey::from_pkcs8_pem(private_key_pem).map_err(|err| {
+                BinanceConnectError::Other(format!("Invalid Ed25519 private key: {}", err))
+            })?;
+        Ok(Self { signing_key })
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn sign(&self, payload: &str) -> Result<String, BinanceConnectError> {
+        let signature = ed25519_dalek::Signer::sign(&self.signing_key, payload.as_bytes());
+        Ok(STANDARD.encode(signature.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ed25519Signer").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{SendError, Sender};
+use std::sync::{Arc, Mutex};
+
+use tracing::{error, warn};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::sequence::{SequenceWal, Sequenced};
+
+/// A destination the events are fanned out to besides the receiver of the stream, e.g. an adapter
+/// publishing to Kafka or Redis.
+///
+/// The sinks are invoked on the connection threads before the event is sent to the receiver, a
+/// slow sink delays the stream and should hand the events off to its own thread.
+pub trait EventSink: Send {
+    /// Publishes the event.
+    fn publish(&mut self, event: &Event);
+}
+
+/// Forwards a clone of every event to a channel, e.g. of a worker thread. A disconnected
+/// receiver is ignored.
+impl EventSink for Sender<Event> {
+    fn publish(&mut self, event: &Event) {
+        let _ = self.send(event.clone());
+    }
+}
+
+/// Writes every event as a line of JSON to a file.
+#[derive(Debug)]
+pub struct FileSink {
+    writer: LineWriter<File>,
+}
+
+impl FileSink {
+    /// Creates a sink appending to the file, which is created when it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the file, e.g. `events.jsonl`.
+    ///
+    /// # Returns
+    ///
+    /// The `FileSink` or a `BinanceConnectError` if the file can not be opened.
+    ///
+    pub fn create(path: impl AsRef<Path>) -> Result<FileSink, BinanceConnectError> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: LineWriter::new(file),
+        })
+    }
+}
+
+impl EventSink for FileSink {
+    fn publish(&mut self, event: &Event) {
+        let written = serde_json::to_string(event)
+            .map_err(BinanceConnectError::from)
+            .and_then(|line| Ok(writeln!(self.writer, "{}", line)?));
+        if let Err(err) = written {
+            warn!(error = %err, "futures_usd file sink write failed");
+        }
+    }
+}
+
+/// Discards every event, e.g. to disable a sink by configuration.
+#[derive(Debug, Clone, Default)]
+pub struct NoopSink;
+
+impl EventSink for NoopSink {
+    fn publish(&mut self, _event: &Event) {}
+}
+
+/// A sink shared by the connections of a stream.
+pub(crate) type SharedSink = Arc<Mutex<dyn EventSink>>;
+
+/// Sends the events to the receiver of the stream after publishing them to the sinks.
+#[derive(Clone)]
+pub(crate) struct EventSender {
+    /// The channel to the receiver, `None` when the events are only published to the sinks.
+    sender: Option<Sender<Event>>,
+    sinks: Vec<SharedSink>,
+    /// The optional sequence the events are stamped with, shared by the connections of a stream.
+    sequence_wal: Option<Arc<Mutex<SequenceWal>>>,
+}
+
+impl EventSender {
+    /// Creates a sender without sinks.
+    pub(crate) fn new(sender: Sender<Event>) -> EventSender {
+        Self {
+            sender: Some(sender),
+            sinks: Vec::new(),
+            sequence_wal: None,
+        }
+    }
+
+    /// Creates a sender without receiver, publishing the events to its sinks only, e.g. of a
+    /// pooled connection whose events are routed to the attached streams.
+    pub(crate) fn sinks_only() -> EventSender {
+        Self {
+            sender: None,
+            sinks: Vec::new(),
+            sequence_wal: None,
+        }
+    }
+
+    /// Adds a sink the events are published to.
+    pub(crate) fn add_sink(&mut self, sink: SharedSink) {
+        self.sinks.push(sink);
+    }
+
+    /// Stamps the events with the sequence before they are published.
+    pub(crate) fn set_sequence_wal(&mut self, sequence_wal: SequenceWal) {
+        self.sequence_wal = Some(Arc::new(Mutex::new(sequence_wal)));
+    }
+
+    /// Publishes the event to the sinks and sends it to the receiver, if any.
+    ///
+    /// Returns the unsent event like `Sender::send`, boxed by the `From` conversion into a
+    /// `BinanceConnectError`.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn send(&self, event: Event) -> Result<(), SendError<Event>> {
+        // The sequence stays locked until the event is sent, so the events arrive in sequence
+        // order from every connection.
+        let mut sequence_wal = self
+            .sequence_wal
+            .as_ref()
+            .map(|sequence_wal| sequence_wal.lock().unwrap());
+        let event: Event = match sequence_wal.as_mut().map(|wal| wal.next_sequence()) {
+            Some(Ok(sequence)) => Event::SequencedEvent(Sequenced {
+                sequence,
+                event: Box::new(event),
+            }),
+            Some(Err(err)) => {
+                error!(error = %err, "futures_usd sequence reservation failed");
+                event
+            }
+            None => event,
+        };
+        for sink in &self.sinks {
+            if let Ok(mut sink) = sink.lock() {
+                sink.publish(&event);
+            }
+        }
+        match &self.sender {
+            Some(sender) => sender.send(event),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for EventSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSender")
+            .field("sender", &self.sender)
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::futures_usd::enums::events::Event;
+
+/// The upper bounds (in milliseconds) of the skew histogram buckets, the last bucket is unbounded.
+pub const SKEW_HISTOGRAM_BOUNDS_MS: [i64; 12] =
+    [1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// The smoothing factor of the exponentially weighted skew mean and jitter.
+#[cfg(feature = "native")]
+const SMOOTHING: f64 = 0.1;
+
+/// Represents the thresholds above which the `SkewMonitor` raises a `SkewAlert`.
+#[derive(Debug, Clone)]
+pub struct SkewThresholds {
+    /// The maximum accepted (smoothed) difference between the local receive time and the event time.
+    pub max_skew: Duration,
+    /// The maximum accepted (smoothed) deviation of the skew from its mean.
+    pub max_jitter: Duration,
+}
+
+impl Default for SkewThresholds {
+    /// Creates a new `SkewThresholds` instance with a max skew of 1 second and a max jitter of 250 milliseconds.
+    fn default() -> Self {
+        Self {
+            max_skew: Duration::from_millis(1000),
+            max_jitter: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Raised when the skew or jitter of a stream exceeds the `SkewThresholds`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SkewAlert {
+    /// The stream, formatted as `symbol@eventType`.
+    pub stream: String,
+    pub skew_ms: f64,
+    pub jitter_ms: f64,
+}
+
+/// A histogram of the observed skew, see `SKEW_HISTOGRAM_BOUNDS_MS` for the bucket bounds.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SkewHistogram {
+    pub counts: [u64; SKEW_HISTOGRAM_BOUNDS_MS.len() + 1],
+}
+
+#[cfg(feature = "native")]
+impl SkewHistogram {
+    /// Adds the skew to the bucket it falls into.
+    fn record(&mut self, skew_ms: i64) {
+        let bucket: usize = SKEW_HISTOGRAM_BOUNDS_MS
+            .iter()
+            .position(|bound| skew_ms <= *bound)
+            .unwrap_or(SKEW_HISTOGRAM_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+    }
+}
+
+/// The skew statistics of a single stream.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SkewStats {
+    pub samples: u64,
+    pub last_skew_ms: i64,
+    /// The exponentially weighted mean of the skew.
+    pub mean_skew_ms: f64,
+    /// The exponentially weighted mean deviation of the skew from its mean.
+    pub jitter_ms: f64,
+    pub histogram: SkewHistogram,
+    /// A flag indicating whether the stream currently exceeds the thresholds.
+    alerting: bool,
+}
+
+/// Tracks the difference between the event time and the local receive time per stream.
+#[derive(Debug)]
+pub struct SkewMonitor {
+    #[cfg(feature = "native")]
+    thresholds: SkewThresholds,
+    stats: HashMap<String, SkewStats>,
+}
+
+impl SkewMonitor {
+    /// Creates a new `SkewMonitor` using the given thresholds.
+    pub fn new(thresholds: SkewThresholds) -> SkewMonitor {
+        #[cfg(not(feature = "native"))]
+        let _ = thresholds;
+        Self {
+            #[cfg(feature = "native")]
+            thresholds,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Returns the skew statistics per stream.
+    pub fn stats(&self) -> &HashMap<String, SkewStats> {
+        &self.stats
+    }
+
+    /// Records the skew of the event(s) and returns an alert for every stream that started
+    /// exceeding the thresholds; a stream alerts again only after it went back below them.
+    ///
+    /// # Arguments
+    ///
+    /// - `event`: The received event, events without an event time are ignored.
+    /// - `received`: The (clock offset corrected) receive time in UNIX millis.
+    ///
+    /// # Returns
+    ///
+    /// The `SkewAlert`s raised by the event.
+    ///
+    #[cfg(feature = "native")]
+    pub fn observe(&mut self, event: &Event, received: u64) -> Vec<SkewAlert> {
+        let max_skew: f64 = self.thresholds.max_skew.as_millis() as f64;
+        let max_jitter: f64 = self.thresholds.max_jitter.as_millis() as f64;
+        let mut alerts: Vec<SkewAlert> = Vec::new();
+        for (stream, event_time) in event_times(event) {
+            let skew_ms: i64 = received as i64 - event_time;
+            let stats: &mut SkewStats = self.stats.entry(stream.clone()).or_default();
+            if stats.samples == 0 {
+                stats.mean_skew_ms = skew_ms as f64;
+            }
+            stats.samples += 1;
+            stats.last_skew_ms = skew_ms;
+            stats.jitter_ms +=
+                SMOOTHING * ((skew_ms as f64 - stats.mean_skew_ms).abs() - stats.jitter_ms);
+            stats.mean_skew_ms += SMOOTHING * (skew_ms as f64 - stats.mean_skew_ms);
+            stats.histogram.record(skew_ms);
+
+            let exceeded: bool = stats.mean_skew_ms > max_skew || stats.jitter_ms > max_jitter;
+            if exceeded && !stats.alerting {
+                alerts.push(SkewAlert {
+                    stream,
+                    skew_ms: stats.mean_skew_ms,
+                    jitter_ms: stats.jitter_ms,
+                });
+            }
+            stats.alerting = exceeded;
+        }
+        alerts
+    }
+}
+
+/// Returns the stream (`symbol@eventType`) and event time of every market data item in the event.
+#[cfg(feature = "native")]
+pub(crate) fn event_times(event: &Event) -> Vec<(String, i64)> {
+    #[cfg(any(feature = "market-data", feature = "klines", feature = "depth"))]
+    let stream = |symbol: &str, event_type: &str| format!("{}@{}", symbol, event_type);
+    match event {
+        #[cfg(feature = "market-data")]
+        Event::BookTickerEvent(item) => {
+            vec![(stream(&item.symbol, "bookTicker"), item.event_time as i64)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::BookTickersEvent(items) => items
+            .data
+            .iter()
+            .map(|item| (stream(&item.symbol, "bookTicker"), item.event_time as i64))
+            .collect(),
+        #[cfg(feature = "market-data")]
+        Event::AggTradeEvent(item) => {
+            vec![(stream(&item.symbol, "aggTrade"), item.event_time as i64)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdateEvent(item) => {
+            vec![(stream(&item.symbol, "markPriceUpdate"), item.event_time)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::MarkPriceUpdatesEvent(items) => items
+            .data
+            .iter()
+            .map(|item| (stream(&item.symbol, "markPriceUpdate"), item.event_time))
+            .collect(),
+        #[cfg(feature = "klines")]
+        Event::KlineEvent(item) => vec![(stream(&item.symbol, "kline"), item.event_time)],
+        #[cfg(feature = "klines")]
+        Event::ContinuousKlineEvent(item) => {
+            vec![(stream(&item.pair, "continuous_kline"), item.event_time)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::MiniTickerEvent(item) => vec![(
+            stream(&item.symbol, "24hrMiniTicker"),
+            item.event_time as i64,
+        )],
+        #[cfg(feature = "market-data")]
+        Event::MiniTickersEvent(items) => items
+            .data
+            .iter()
+            .map(|item| {
+                (
+                    stream(&item.symbol, "24hrMiniTicker"),
+                    item.event_time as i64,
+                )
+            })
+            .collect(),
+        #[cfg(feature = "market-data")]
+        Event::TickerEvent(item) => {
+            vec![(stream(&item.symbol, "24hrTicker"), item.event_time as i64)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::TickersEvent(items) => items
+            .data
+            .iter()
+            .map(|item| (stream(&item.symbol, "24hrTicker"), item.event_time as i64))
+            .collect(),
+        #[cfg(feature = "market-data")]
+        Event::ForceOrderEvent(item) => {
+            vec![(stream(&item.order.symbol, "forceOrder"), item.event_time)]
+        }
+        #[cfg(feature = "depth")]
+        Event::BookDepthEvent(item) => vec![(stream(&item.symbol, "depthUpdate"), item.event_time)],
+        #[cfg(feature = "market-data")]
+        Event::CompositeIndexEvent(item) => {
+            vec![(stream(&item.symbol, "compositeIndex"), item.event_time)]
+        }
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdateEvent(item) => {
+            vec![(
+                stream(&item.asset_index_symbol, "assetIndexUpdate"),
+                item.event_time,
+            )]
+        }
+        #[cfg(feature = "market-data")]
+        Event::AssetIndexUpdatesEvent(items) => items
+            .data
+            .iter()
+            .map(|item| {
+                (
+                    stream(&item.asset_index_symbol, "assetIndexUpdate"),
+                    item.event_time,
+                )
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,146 @@
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tracing::{debug, info};
+use url::Url;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::client::{connect_socket, set_read_timeout, Socket};
+use crate::futures_usd::transport::Transport;
+
+/// The read timeout used by the standby so it notices a promotion request on quiet streams.
+const STANDBY_READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// An idle, already handshaked and subscribed connection that can replace a failed primary.
+///
+/// The standby reads (and discards) its messages and answers pings so it stays alive; once
+/// promoted the socket is handed over and events are read from it as from any other connection.
+#[derive(Debug)]
+pub(crate) struct Standby {
+    /// A flag requesting the standby thread to hand over its socket.
+    promote: Arc<AtomicBool>,
+    /// A flag requesting the standby thread to close its socket and exit.
+    cancel: Arc<AtomicBool>,
+    /// Receives the socket once the standby thread handed it over.
+    receiver: Receiver<Socket>,
+}
+
+impl Standby {
+    /// Spawns a thread which connects, subscribes and keeps the standby connection warm.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The WebSocket URL to connect to.
+    /// - `subscribe_payload`: An optional subscription payload to send upon connection.
+    /// - `stop_signal`: A flag that stops the standby thread when set.
+    /// - `transport`: The options of the outbound TCP connection.
+    ///
+    pub(crate) fn warm(
+        url: Url,
+        subscribe_payload: Option<String>,
+        stop_signal: Arc<AtomicBool>,
+        transport: Arc<Transport>,
+    ) -> Standby {
+        let (sender, receiver) = channel();
+        let promote: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let cancel: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let (thread_promote, thread_cancel) = (Arc::clone(&promote), Arc::clone(&cancel));
+        thread::spawn(move || {
+            keep_warm(
+                url,
+                subscribe_payload,
+                sender,
+                thread_promote,
+                thread_cancel,
+                stop_signal,
+                &transport,
+            );
+        });
+        Standby {
+            promote,
+            cancel,
+            receiver,
+        }
+    }
+
+    /// Requests the standby socket, waiting at most `timeout` for it to become available.
+    pub(crate) fn promote(&self, timeout: Duration) -> Option<Socket> {
+        self.promote.store(true, Ordering::Relaxed);
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for Standby {
+    /// Signals the standby thread to close its (not promoted) connection.
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Keeps (re)connecting the standby socket until it is promoted, cancelled or stopped.
+fn keep_warm(
+    url: Url,
+    subscribe_payload: Option<String>,
+    sender: Sender<Socket>,
+    promote: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+    stop_signal: Arc<AtomicBool>,
+    transport: &Transport,
+) {
+    let finished = || cancel.load(Ordering::Relaxed) || stop_signal.load(Ordering::Relaxed);
+    while !finished() {
+        let mut socket: Socket =
+            match connect_socket(url.clone(), subscribe_payload.clone(), transport) {
+                Ok(socket) => socket,
+                Err(err) => {
+                    info!(error = %err, "futures_usd standby could not connect");
+                    thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+            };
+        set_read_timeout(&socket, Some(STANDBY_READ_TIMEOUT));
+        debug!("futures_usd standby connection warm");
+        match idle(&mut socket, &promote, &finished) {
+            Ok(true) => {
+                info!("futures_usd standby connection promoted");
+                set_read_timeout(&socket, None);
+                let _ = sender.send(socket);
+                return;
+            }
+            Ok(false) => {
+                let _ = socket.close(None);
+                return;
+            }
+            Err(err) => {
+                info!(error = %err, "futures_usd standby connection dropped");
+            }
+        }
+    }
+}
+
+/// Reads and discards messages until promoted (`Ok(true)`) or finished (`Ok(false)`).
+fn idle(
+    socket: &mut Socket,
+    promote: &AtomicBool,
+    finished: &dyn Fn() -> bool,
+) -> Result<bool, BinanceConnectError> {
+    loop {
+        if finished() {
+            return Ok(false);
+        }
+        if promote.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        match socket.read() {
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(ref io_err))
+                if io_err.kind() == ErrorKind::WouldBlock
+                    || io_err.kind() == ErrorKind::TimedOut => {}
+            Err(err) => return Err(BinanceConnectError::SocketError(Box::new(err))),
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BinanceConnectError;
+
+use crate::futures_usd::enums::binance::{
+    MarginType, OrderStatus, OrderType, PositionSide, Side, TimeInForce,
+};
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::response::AccountSnapshot;
+#[cfg(feature = "user-data")]
+use crate::futures_usd::response::{
+    AccountConfigUpdate, AccountUpdate, OrderData, OrderTradeUpdate,
+};
+
+/// The balance of an asset.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BalanceState {
+    pub asset: String,
+    pub wallet_balance: f64,
+    pub cross_wallet_balance: f64,
+    /// The time (UNIX millis) of the transaction that last changed the balance.
+    pub update_time: i64,
+}
+
+/// The position of a symbol and position side.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PositionState {
+    pub symbol: String,
+    pub position_side: PositionSide,
+    pub position_amount: f64,
+    pub entry_price: f64,
+    pub breakeven_price: f64,
+    /// The unrealized PnL as last reported by Binance.
+    pub unrealized_pnl: f64,
+    pub margin_type: MarginType,
+    pub isolated_wallet: f64,
+    /// The time (UNIX millis) of the transaction that last changed the position.
+    pub update_time: i64,
+}
+
+/// An open (new or partially filled) order.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OrderState {
+    pub symbol: String,
+    pub order_id: i64,
+    pub client_order_id: String,
+    pub side: Side,
+    pub position_side: PositionSide,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub order_status: OrderStatus,
+    pub original_quantity: f64,
+    pub original_price: f64,
+    pub stop_price: f64,
+    pub filled_quantity: f64,
+    pub average_price: f64,
+    pub is_reduce_only: bool,
+    /// The time (UNIX millis) of the last order update.
+    pub update_time: i64,
+}
+
+impl OrderState {
+    /// Creates the state of the order of an order update.
+    #[cfg(feature = "user-data")]
+    fn from_order_data(order_data: &OrderData) -> OrderState {
+        Self {
+            symbol: order_data.symbol.clone(),
+            order_id: order_data.order_id,
+            client_order_id: order_data.client_order_id.clone(),
+            side: order_data.side.clone(),
+            position_side: order_data.position_side.clone(),
+            order_type: order_data.order_type.clone(),
+            time_in_force: order_data.time_in_force.clone(),
+            order_status: order_data.order_status.clone(),
+            original_quantity: order_data.original_quantity,
+            original_price: order_data.original_price,
+            stop_price: order_data.stop_price,
+            filled_quantity: order_data.order_filled_accumulated_quantity,
+            average_price: order_data.average_price,
+            is_reduce_only: order_data.is_reduce_only,
+            update_time: order_data.order_trade_time,
+        }
+    }
+}
+
+/// The account state maintained by the `StateStore`.
+#[derive(Debug, Default)]
+struct AccountState {
+    balances: HashMap<String, BalanceState>,
+    positions: HashMap<(String, PositionSide), PositionState>,
+    leverage: HashMap<String, i64>,
+    open_orders: HashMap<i64, OrderState>,
+    multi_assets_mode: Option<bool>,
+}
+
+/// The account state as written by `StateStore::save`.
+#[derive(Debug, Deserialize, Serialize)]
+struct SavedState {
+    balances: Vec<BalanceState>,
+    positions: Vec<PositionState>,
+    leverage: HashMap<String, i64>,
+    open_orders: Vec<OrderState>,
+    multi_assets_mode: Option<bool>,
+}
+
+/// Maintains the current balances, positions, leverage and open orders of an account from the
+/// user data events (`AccountSnapshot`, `AccountUpdate`, `OrderTradeUpdate` and
+/// `AccountConfigUpdate`) and exposes them via a thread-safe read API.
+///
+/// Start the stream `with_account_snapshot()` to initialize the store, otherwise only the values
+/// changed since the stream started are known.
+#[derive(Debug, Default)]
+pub struct StateStore {
+    state: RwLock<AccountState>,
+}
+
+impl StateStore {
+    /// Creates an empty store.
+    pub fn new() -> StateStore {
+        Self::default()
+    }
+
+    /// Loads the store saved with `save`, e.g. to have the state of the account available before
+    /// the account snapshot of a restarted stream arrived.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the saved state.
+    ///
+    /// # Returns
+    ///
+    /// The `StateStore` or a `BinanceConnectError` if the file could not be read or is corrupt.
+    ///
+    pub fn load(path: &Path) -> Result<StateStore, BinanceConnectError> {
+        let saved: SavedState = serde_json::from_slice(&fs::read(path)?)?;
+        let state: AccountState = AccountState {
+            balances: saved
+                .balances
+                .into_iter()
+                .map(|balance| (balance.asset.clone(), balance))
+                .collect(),
+            positions: saved
+                .positions
+                .into_iter()
+                .map(|position| {
+                    let key = (position.symbol.clone(), position.position_side.clone());
+                    (key, position)
+                })
+                .collect(),
+            leverage: saved.leverage,
+            open_orders: saved
+                .open_orders
+                .into_iter()
+                .map(|order| (order.order_id, order))
+                .collect(),
+            multi_assets_mode: saved.multi_assets_mode,
+        };
+        Ok(Self {
+            state: RwLock::new(state),
+        })
+    }
+
+    /// Saves the state as JSON. The file is replaced atomically, so a crash during the write
+    /// leaves the previously saved state.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path to save the state to.
+    ///
+    /// # Returns
+    ///
+    /// A `BinanceConnectError` if the file could not be written.
+    ///
+    pub fn save(&self, path: &Path) -> Result<(), BinanceConnectError> {
+        let json: Vec<u8> = {
+            let state = self.state.read().unwrap();
+            serde_json::to_vec(&SavedState {
+                balances: state.balances.values().cloned().collect(),
+                positions: state.positions.values().cloned().collect(),
+                leverage: state.leverage.clone(),
+                open_orders: state.open_orders.values().cloned().collect(),
+                multi_assets_mode: state.multi_assets_mode,
+            })?
+        };
+        let saving: PathBuf = path.with_extension("saving");
+        fs::write(&saving, json)?;
+        fs::rename(&saving, path)?;
+        Ok(())
+    }
+
+    /// Applies the user data event, other events are ignored.
+    pub fn apply(&self, event: &Event) {
+        match event {
+            Event::AccountSnapshotEvent(account_snapshot) => self.apply_snapshot(account_snapshot),
+            #[cfg(feature = "user-data")]
+            Event::AccountUpdateEvent(account_update) => self.apply_account_update(account_update),
+            #[cfg(feature = "user-data")]
+            Event::OrderTradeUpdateEvent(order_trade_update) => {
+                self.apply_order_update(order_trade_update)
+            }
+            #[cfg(feature = "user-data")]
+            Event::AccountConfigUpdateEvent(account_config_update) => {
+                self.apply_config_update(account_config_update)
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the balance of the asset, e.g. `USDT`.
+    pub fn balance(&self, asset: &str) -> Option<BalanceState> {
+        self.state.read().unwrap().balances.get(asset).cloned()
+    }
+
+    /// Returns the balances of all assets.
+    pub fn balances(&self) -> Vec<BalanceState> {
+        self.state
+            .read()
+            .unwrap()
+            .balances
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the position of the symbol and position side (`Both` in one-way mode).
+    pub fn position(&self, symbol: &str, position_side: PositionSide) -> Option<PositionState> {
+        self.state
+            .read()
+            .unwrap()
+            .positions
+            .get(&(symbol.to_uppercase(), position_side))
+            .cloned()
+    }
+
+    /// Returns the open (non-zero) positions.
+    pub fn positions(&self) -> Vec<PositionState> {
+        self.state
+            .read()
+            .unwrap()
+            .positions
+            .values()
+            .filter(|position| position.position_amount != 0.0)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the leverage of the symbol.
+    pub fn leverage(&self, symbol: &str) -> Option<i64> {
+        self.state
+            .read()
+            .unwrap()
+            .leverage
+            .get(&symbol.to_uppercase())
+            .copied()
+    }
+
+    /// Returns the open order with the order id.
+    pub fn open_order(&self, order_id: i64) -> Option<OrderState> {
+        self.state
+            .read()
+            .unwrap()
+            .open_orders
+            .get(&order_id)
+            .cloned()
+    }
+
+    /// Returns the open orders, of all symbols when `symbol` is `None`.
+    pub fn open_orders(&self, symbol: Option<&str>) -> Vec<OrderState> {
+        let symbol: Option<String> = symbol.map(str::to_uppercase);
+        self.state
+            .read()
+            .unwrap()
+            .open_orders
+            .values()
+            .filter(|order| {
+                symbol
+                    .as_ref()
+                    .map_or(true, |symbol| order.symbol == *symbol)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether the multi-assets mode is enabled, `None` when it is unknown.
+    pub fn multi_assets_mode(&self) -> Option<bool> {
+        self.state.read().unwrap().multi_assets_mode
+    }
+
+    /// Replaces the balances, positions and leverage by the snapshot.
+    fn apply_snapshot(&self, account_snapshot: &AccountSnapshot) {
+        let mut state = self.state.write().unwrap();
+        state.balances = account_snapshot
+            .assets
+            .iter()
+            .map(|asset| {
+                let balance: BalanceState = BalanceState {
+                    asset: asset.asset.clone(),
+                    wallet_balance: asset.wallet_balance,
+                    cross_wallet_balance: asset.cross_wallet_balance,
+                    update_time: asset.update_time,
+                };
+                (asset.asset.clone(), balance)
+            })
+            .collect();
+        state.positions.clear();
+        for position in &account_snapshot.positions {
+            state
+                .leverage
+                .insert(position.symbol.clone(), position.leverage);
+            state.positions.insert(
+                (position.symbol.clone(), position.position_side.clone()),
+                PositionState {
+                    symbol: position.symbol.clone(),
+                    position_side: position.position_side.clone(),
+                    position_amount: position.position_amount,
+                    entry_price: position.entry_price,
+                    breakeven_price: position.breakeven_price,
+                    unrealized_pnl: position.unrealized_pnl,
+                    margin_type: match position.isolated {
+                        true => MarginType::Isolated,
+                        false => MarginType::Crossed,
+                    },
+                    isolated_wallet: 0.0,
+                    update_time: position.update_time,
+                },
+            );
+        }
+    }
+
+    /// Updates the changed balances and positions.
+    #[cfg(feature = "user-data")]
+    fn apply_account_update(&self, account_update: &AccountUpdate) {
+        let mut state = self.state.write().unwrap();
+        for balance in &account_update.update_data.balances {
+            state.balances.insert(
+                balance.asset.clone(),
+                BalanceState {
+                    asset: balance.asset.clone(),
+                    wallet_balance: balance.wallet_balance,
+                    cross_wallet_balance: balance.cross_wallet_balance,
+                    update_time: account_update.transaction_time,
+                },
+            );
+        }
+        for position in &account_update.update_data.positions {
+            state.positions.insert(
+                (position.symbol.clone(), position.position_side.clone()),
+                PositionState {
+                    symbol: position.symbol.clone(),
+                    position_side: position.position_side.clone(),
+                    position_amount: position.position_amount,
+                    entry_price: position.entry_price,
+                    breakeven_price: position.breakeven_price,
+                    unrealized_pnl: position.unrealized_pnl,
+                    margin_type: position.margin_type.clone(),
+                    isolated_wallet: position.isolated_wallet,
+                    update_time: account_update.transaction_time,
+                },
+            );
+        }
+    }
+
+    /// Tracks new and partially filled orders, removing orders that are no longer open.
+    #[cfg(feature = "user-data")]
+    fn apply_order_update(&self, order_trade_update: &OrderTradeUpdate) {
+        let order_data: &OrderData = &order_trade_update.order_data;
+        let mut state = self.state.write().unwrap();
+        match order_data.order_status {
+            OrderStatus::New | OrderStatus::PartiallyFilled => {
+                state
+                    .open_orders
+                    .insert(order_data.order_id, OrderState::from_order_data(order_data));
+            }
+            _ => {
+                state.open_orders.remove(&order_data.order_id);
+            }
+        }
+    }
+
+    /// Updates the leverage and multi-assets mode.
+    #[cfg(feature = "user-data")]
+    fn apply_config_update(&self, account_config_update: &AccountConfigUpdate) {
+        let mut state = self.state.write().unwrap();
+        if let Some(account_config) = &account_config_update.account_config {
+            state
+                .leverage
+                .insert(account_config.symbol.clone(), account_config.leverage);
+        }
+        if let Some(multi_assets_mode) = account_config_update
+            .account_info
+            .as_ref()
+            .and_then(|account_info| account_info.multi_assets_mode)
+        {
+            state.multi_assets_mode = Some(multi_assets_mode);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::futures_usd::status::now_millis;
+
+/// The smoothing factor of the exponential moving average of the receive lag.
+#[cfg(feature = "native")]
+const SMOOTHING: f64 = 0.1;
+
+/// The messages counted in the current and the rate of the last complete one second window.
+#[derive(Debug, Default)]
+struct RateWindow {
+    /// The start (UNIX millis) of the current window.
+    start: u64,
+    messages: u64,
+    /// The messages per second of the last complete window.
+    rate: f64,
+    /// The receive lag of the current window, `None` without samples.
+    lag: Option<ReceiveLag>,
+    /// The maximum receive lag of the last complete window.
+    max_lag_ms: Option<i64>,
+}
+
+/// The lag between the receive time and the event time of the received events.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiveLag {
+    /// The lag of the last event in milliseconds.
+    pub last_ms: i64,
+    /// The exponential moving average of the lag in milliseconds.
+    pub mean_ms: f64,
+    /// The maximum lag of the last complete second, or of the current one before it completed.
+    pub max_ms: i64,
+}
+
+/// Holds the traffic statistics of a single connection, updated by the connection thread.
+#[derive(Debug, Default)]
+pub struct ConnectionStats {
+    index: usize,
+    url: Mutex<String>,
+    /// The time (UNIX millis) the current connection was established, `0` when disconnected.
+    connected_since: AtomicU64,
+    messages: AtomicU64,
+    bytes: AtomicU64,
+    /// The time (UNIX millis) the last message was received, `0` when none arrived yet.
+    last_message_time: AtomicU64,
+    reconnects: AtomicU64,
+    /// The number of times the connection was established.
+    #[cfg(feature = "native")]
+    established: AtomicU64,
+    rate: Mutex<RateWindow>,
+    /// The round trip time of the last answered client ping.
+    ping_rtt: Mutex<Option<Duration>>,
+}
+
+/// A point-in-time copy of the `ConnectionStats`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionStatsSnapshot {
+    /// The index of the connection, streams are spread over connections in order.
+    pub index: usize,
+    pub url: String,
+    pub connected: bool,
+    /// The messages received per second, measured over the last complete second.
+    pub messages_per_second: f64,
+    pub messages_received: u64,
+    /// The payload bytes of the received messages.
+    pub bytes_received: u64,
+    /// The time (UNIX millis) the last message was received, `None` when none arrived yet.
+    pub last_message_time: Option<u64>,
+    /// The receive lag (receive time minus event time, on the synced clock), `None` when the
+    /// measurement is not enabled (see `FuturesWebSocketConfig::with_receive_latency`) or no
+    /// market data event arrived yet.
+    pub receive_lag: Option<ReceiveLag>,
+    /// The round trip time of the last answered client ping in milliseconds, `None` when client
+    /// pings are not enabled (see `FuturesWebSocketConfig::with_client_ping`) or none was answered yet.
+    pub ping_rtt_ms: Option<f64>,
+    pub reconnects: u64,
+    /// The milliseconds since the current connection was established, `0` when disconnected.
+    pub uptime_ms: u64,
+}
+
+impl ConnectionStats {
+    /// Creates the statistics of the connection with the index.
+    #[cfg(feature = "native")]
+    pub(crate) fn new(index: usize) -> ConnectionStats {
+        Self {
+            index,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the URL the connection (re)connects to.
+    #[cfg(feature = "native")]
+    pub(crate) fn set_url(&self, url: &str) {
+        *self.url.lock().unwrap() = url.to_string();
+    }
+
+    /// Marks the connection as (dis)connected.
+    #[cfg(feature = "native")]
+    pub(crate) fn set_connected(&self, connected: bool) {
+        let connected_since: u64 = match connected {
+            true => {
+                self.established.fetch_add(1, Ordering::Relaxed);
+                now_millis()
+            }
+            false => 0,
+        };
+        self.connected_since
+            .store(connected_since, Ordering::Relaxed);
+    }
+
+    /// Records a received message of the number of bytes.
+    #[cfg(feature = "native")]
+    pub(crate) fn message_received(&self, bytes: usize) {
+        let now: u64 = now_millis();
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.last_message_time.store(now, Ordering::Relaxed);
+        let mut rate = self.rate.lock().unwrap();
+        rate.roll(now);
+        rate.messages += 1;
+    }
+
+    /// Records the lag between the receive time and the event time of an event.
+    #[cfg(feature = "native")]
+    pub(crate) fn lag_observed(&self, lag_ms: i64) {
+        let mut rate = self.rate.lock().unwrap();
+        rate.roll(now_millis());
+        rate.lag = Some(match rate.lag.take() {
+            Some(lag) => ReceiveLag {
+                last_ms: lag_ms,
+                mean_ms: lag.mean_ms + SMOOTHING * (lag_ms as f64 - lag.mean_ms),
+                max_ms: lag.max_ms.max(lag_ms),
+            },
+            None => ReceiveLag {
+                last_ms: lag_ms,
+                mean_ms: lag_ms as f64,
+                max_ms: lag_ms,
+            },
+        });
+    }
+
+    /// Records the round trip time of an answered client ping.
+    #[cfg(feature = "native")]
+    pub(crate) fn ping_observed(&self, rtt: Duration) {
+        *self.ping_rtt.lock().unwrap() = Some(rtt);
+    }
+
+    /// Returns the number of times the connection was established.
+    #[cfg(feature = "native")]
+    pub(crate) fn established(&self) -> u64 {
+        self.established.load(Ordering::Relaxed)
+    }
+
+    /// Increments the reconnect counter.
+    #[cfg(feature = "native")]
+    pub(crate) fn reconnected(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a `ConnectionStatsSnapshot` of the current statistics.
+    pub fn snapshot(&self) -> ConnectionStatsSnapshot {
+        let now: u64 = now_millis();
+        let connected_since: u64 = self.connected_since.load(Ordering::Relaxed);
+        let mut rate = self.rate.lock().unwrap();
+        rate.roll(now);
+        ConnectionStatsSnapshot {
+            index: self.index,
+            url: self.url.lock().unwrap().clone(),
+            connected: connected_since > 0,
+            messages_per_second: rate.rate,
+            messages_received: self.messages.load(Ordering::Relaxed),
+            bytes_received: self.bytes.load(Ordering::Relaxed),
+            last_message_time: match self.last_message_time.load(Ordering::Relaxed) {
+                0 => None,
+                time => Some(time),
+            },
+            receive_lag: rate.lag.clone().map(|lag| ReceiveLag {
+                max_ms: rate.max_lag_ms.unwrap_or(lag.max_ms),
+                ..lag
+            }),
+            ping_rtt_ms: self
+                .ping_rtt
+                .lock()
+                .unwrap()
+                .map(|rtt| rtt.as_secs_f64() * 1000.0),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            uptime_ms: match connected_since {
+                0 => 0,
+                since => now.saturating_sub(since),
+            },
+        }
+    }
+}
+
+impl RateWindow {
+    /// Completes the current window when a second has passed, a gap of more than a second
+    /// results in a zero rate.
+    fn roll(&mut self, now: u64) {
+        let elapsed: u64 = now.saturating_sub(self.start);
+        if elapsed < 1000 {
+            return;
+        }
+        self.rate = match elapsed < 2000 {
+            true => self.messages as f64,
+            false => 0.0,
+        };
+        self.start = now - elapsed % 1000;
+        self.messages = 0;
+        // The last lag and mean carry over, the maximum restarts with the next sample.
+        self.max_lag_ms = self.lag.as_ref().map(|lag| lag.max_ms);
+        if let Some(lag) = self.lag.as_mut() {
+            lag.max_ms = lag.last_ms;
+        }
+    }
+}
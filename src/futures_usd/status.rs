@@ -0,0 +1,133 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::futures_usd::ordering::Sequence;
+
+/// Holds the live status of a connector, shared between the connection thread and its observers.
+///
+/// A connector with several connections holds the status of every connection, its snapshot
+/// aggregates them.
+#[derive(Debug, Default)]
+pub struct ConnectorStatus {
+    /// A flag indicating whether the WebSocket connection is currently established.
+    connected: AtomicBool,
+    /// The local time (UNIX millis) at which the last event was received, `0` when none arrived yet.
+    last_event_time: AtomicU64,
+    /// The number of times the connection has been re-established.
+    reconnects: AtomicU64,
+    /// The `Sequence` of the last event received on the current connection, `0` when none.
+    last_sequence: AtomicU64,
+    /// The statuses of the connections, registered by `connection`.
+    connections: Mutex<Vec<Arc<ConnectorStatus>>>,
+}
+
+/// A point-in-time copy of the `ConnectorStatus`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusSnapshot {
+    pub connected: bool,
+    /// Milliseconds since the last event was received, `None` when no event was received yet.
+    pub last_event_age_ms: Option<u64>,
+    pub reconnects: u64,
+    /// The `Sequence` of the last event received on the current connection.
+    pub last_sequence: u64,
+}
+
+impl ConnectorStatus {
+    /// Marks the connection as (dis)connected.
+    #[cfg(feature = "native")]
+    pub(crate) fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+        if connected {
+            self.last_sequence.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Records that the event with the given `Sequence` was received now.
+    #[cfg(feature = "native")]
+    pub(crate) fn event_received(&self, sequence: Sequence) {
+        self.last_event_time.store(now_millis(), Ordering::Relaxed);
+        self.last_sequence
+            .store(sequence.value(), Ordering::Relaxed);
+    }
+
+    /// Increments the reconnect counter.
+    #[cfg(feature = "native")]
+    pub(crate) fn reconnected(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Registers a connection of the connector.
+    ///
+    /// # Returns
+    ///
+    /// The status of the connection, aggregated by the snapshot of the connector.
+    ///
+    #[cfg(feature = "native")]
+    pub(crate) fn connection(&self) -> Arc<ConnectorStatus> {
+        let connection: Arc<ConnectorStatus> = Arc::new(ConnectorStatus::default());
+        self.connections
+            .lock()
+            .unwrap()
+            .push(Arc::clone(&connection));
+        connection
+    }
+
+    /// Returns a `StatusSnapshot` of the current status.
+    ///
+    /// The status of a connector with registered connections is aggregated: it is connected while
+    /// every connection is, the last event is the last one of any connection (with its sequence)
+    /// and the reconnects are summed.
+    ///
+    pub fn snapshot(&self) -> StatusSnapshot {
+        let connections = self.connections.lock().unwrap();
+        if connections.is_empty() {
+            return self.own_snapshot();
+        }
+        let mut last_event_time: u64 = 0;
+        let mut snapshot: StatusSnapshot = StatusSnapshot {
+            connected: true,
+            last_event_age_ms: None,
+            reconnects: 0,
+            last_sequence: 0,
+        };
+        for connection in connections.iter() {
+            snapshot.connected &= connection.connected.load(Ordering::Relaxed);
+            snapshot.reconnects += connection.reconnects.load(Ordering::Relaxed);
+            let event_time: u64 = connection.last_event_time.load(Ordering::Relaxed);
+            if event_time > last_event_time {
+                last_event_time = event_time;
+                snapshot.last_sequence = connection.last_sequence.load(Ordering::Relaxed);
+            }
+        }
+        if last_event_time > 0 {
+            snapshot.last_event_age_ms = Some(now_millis().saturating_sub(last_event_time));
+        }
+        snapshot
+    }
+
+    /// Returns a `StatusSnapshot` of the status of a single connection.
+    fn own_snapshot(&self) -> StatusSnapshot {
+        let last_event_time: u64 = self.last_event_time.load(Ordering::Relaxed);
+        StatusSnapshot {
+            connected: self.connected.load(Ordering::Relaxed),
+            last_event_age_ms: match last_event_time {
+                0 => None,
+                time => Some(now_millis().saturating_sub(time)),
+            },
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_sequence: self.last_sequence.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Returns the current UNIX time in milliseconds.
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
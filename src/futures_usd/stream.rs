@@ -1,24 +1,78 @@
-use std::sync::atomic::AtomicBool;
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use async_std::task;
-use async_std::task::sleep;
-use log::{error, info};
+use tracing::{error, info, info_span, warn};
 use url::Url;
 
-use crate::constants;
 use crate::error::BinanceConnectError;
-use crate::futures_usd::client::client;
+use crate::futures_usd::alert::{AlertCondition, AlertEngine};
+use crate::futures_usd::alias::SymbolAliases;
+use crate::futures_usd::candle::CandleBoundary;
+use crate::futures_usd::client::{client, run, ConnectionContext, SnapshotRequest, Socket};
+use crate::futures_usd::conflation::{ConflatedStream, Conflator};
+use crate::futures_usd::dedup::{RedundantFilter, UpdateIdFilter};
+use crate::futures_usd::downtime::{DowntimeReport, DowntimeTracker, RecoveryAction};
+use crate::futures_usd::endpoint::{websocket_url, Endpoint};
 use crate::futures_usd::enums::binance::{
-    BookDepthUpdateSpeed, KlineContractType, KlineInterval, MarkPriceUpdateSpeed,
-    PartialBookDepthLevel,
+    BookDepthUpdateSpeed, ContractStatus, ContractType, KlineContractType, KlineInterval,
+    MarkPriceUpdateSpeed, PartialBookDepthLevel, StreamProperty,
 };
 use crate::futures_usd::enums::events::Event;
 use crate::futures_usd::enums::streams::*;
+use crate::futures_usd::event_filter::EventFilter;
+use crate::futures_usd::fill_notice::{FillCallback, FillNotice};
+use crate::futures_usd::funding::{FundingState, FundingTracker};
+use crate::futures_usd::kline_filter::ClosedKlineFilter;
+use crate::futures_usd::latest_cache::LatestCache;
 use crate::futures_usd::listen_key::*;
+use crate::futures_usd::listing::{ListingDetector, ListingStream};
+use crate::futures_usd::multi_symbol::{
+    MultiSymbolStream, SymbolFilter, DEFAULT_ALL_MARKET_THRESHOLD,
+};
+use crate::futures_usd::outbox::{Outbox, DEFAULT_MESSAGES_PER_SECOND};
+use crate::futures_usd::ping::ClientPing;
+use crate::futures_usd::pnl::PnlTracker;
+use crate::futures_usd::pool::ConnectionManager;
+use crate::futures_usd::quote_cache::QuoteCache;
+use crate::futures_usd::receiver::EventReceiver;
+use crate::futures_usd::reconnect::{
+    rotate_endpoint, with_endpoint, ConnectionFailed, DisconnectReason, ListenKeyRefreshFailed,
+    ReconnectAttempts, ReconnectDecision, ReconnectTrace, ReconnectTriggers,
+};
+use crate::futures_usd::recorder::{Recorder, RecorderConfig};
+use crate::futures_usd::response::{self, ExchangeInfo};
+use crate::futures_usd::rotation::{
+    MigrationRequest, Rotation, DEFAULT_MAX_CONNECTION_AGE, DEFAULT_MIGRATION_OVERLAP,
+};
+#[cfg(feature = "scripting")]
+use crate::futures_usd::script::EventScript;
+use crate::futures_usd::self_test::{self, SelfTestReport};
+use crate::futures_usd::sequence::SequenceWal;
+use crate::futures_usd::settings::ConfigSettings;
+use crate::futures_usd::shared::shared;
+use crate::futures_usd::sink::{EventSender, EventSink};
+use crate::futures_usd::skew::{SkewMonitor, SkewStats, SkewThresholds};
+use crate::futures_usd::standby::Standby;
+use crate::futures_usd::state_store::StateStore;
+use crate::futures_usd::stats::{ConnectionStats, ConnectionStatsSnapshot};
+use crate::futures_usd::status::{ConnectorStatus, StatusSnapshot};
+use crate::futures_usd::ticker_cache::TickerCache;
+use crate::futures_usd::time_sync::{server_now, TimeSyncHandle};
+use crate::futures_usd::transport::{IpPreference, Transport};
+
+/// The maximum number of streams Binance accepts per connection.
+const MAX_STREAMS_PER_CONNECTION: usize = 200;
+/// The attempts of a listen key refresh before the user data stream is renewed.
+const LISTEN_KEY_REFRESH_ATTEMPTS: u32 = 5;
+/// The wait before the first retry of a failed listen key refresh, doubled on every retry.
+const LISTEN_KEY_REFRESH_BACKOFF: Duration = Duration::from_secs(1);
+/// The granularity with which the listen key refresh thread checks its stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Represents a configuration struct for handling "would block" situations in the WebSocket.
 #[derive(Debug, Clone)]
@@ -45,21 +99,135 @@ impl Default for WouldBlockConfig {
     }
 }
 
+/// Validates that the symbol can be used in a stream name.
+fn validate_symbol(symbol: &str, stream: &str) -> Result<(), BinanceConnectError> {
+    if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(BinanceConnectError::InvalidStreamConfig {
+            stream: stream.to_string(),
+            reason: format!("invalid symbol '{}'", symbol),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the `DisconnectReason` of the error ending a connection, an error that is not connection
+/// related (e.g. of a REST request) is reported as `SocketError`.
+fn failure_reason(err: &BinanceConnectError) -> DisconnectReason {
+    DisconnectReason::from_error(err)
+        .unwrap_or_else(|| DisconnectReason::SocketError(err.to_string()))
+}
+
+/// Sleeps for the duration in steps of `STOP_CHECK_INTERVAL`, returning false as soon as the stop
+/// signal is set.
+fn wait(duration: Duration, stop_signal: &AtomicBool) -> bool {
+    let until: Instant = Instant::now() + duration;
+    while !stop_signal.load(Ordering::Relaxed) {
+        let now: Instant = Instant::now();
+        if now >= until {
+            return true;
+        }
+        thread::sleep((until - now).min(STOP_CHECK_INTERVAL));
+    }
+    false
+}
+
 /// Represents a configuration struct for the Binance Futures WebSocket client.
 #[derive(Debug, Clone)]
 pub struct FuturesWebSocketConfig {
     /// Optional API authentication credentials.
     api_auth: Option<ApiAuth>,
-    /// The main WebSocket URL for the Binance Futures market.
-    url: Url,
-    /// The WebSocket URL for the Binance Futures testnet.
-    url_testnet: Url,
-    /// A flag indicating whether to use the Binance Futures testnet.
-    testnet: bool,
+    /// Creates and deletes the listen keys of the user data stream.
+    listen_key_provider: Arc<dyn ListenKeyProvider>,
+    /// The WebSocket endpoint, which also selects the environment of the REST requests.
+    endpoint: Endpoint,
+    /// The URL of the deprecated `with_url`, replacing that of `Endpoint::Production`.
+    url: Option<Url>,
+    /// The URL of the deprecated `with_url_testnet`, replacing that of `Endpoint::Testnet`.
+    url_testnet: Option<Url>,
+    /// The local address and interface the outbound TCP connections are bound to.
+    transport: Transport,
     /// Configuration for handling "would block" situations in the WebSocket.
     would_block_config: WouldBlockConfig,
     /// A flag indicating whether the WebSocket client should attempt to reconnect on errors.
     reconnect: bool,
+    /// The classes of disconnect reasons that trigger a reconnect.
+    reconnect_triggers: ReconnectTriggers,
+    /// The reconnect attempts after which a connection gives up, unlimited when `None`.
+    max_reconnect_attempts: Option<u32>,
+    /// The time spent reconnecting after which a connection gives up, unlimited when `None`.
+    max_reconnect_time: Option<Duration>,
+    /// A flag indicating whether an account snapshot is emitted whenever the user data stream connects.
+    account_snapshot: bool,
+    /// The REST base URL of the account snapshot, the Binance URL of the endpoint when `None`.
+    rest_base_url: Option<String>,
+    /// The interval at which the listen key is refreshed.
+    listen_key_refresh_interval: Duration,
+    /// The maximum random amount by which a listen key refresh is brought forward.
+    listen_key_refresh_jitter: Duration,
+    /// A flag indicating whether a subscribed standby connection should be kept ready for failover.
+    warm_standby: bool,
+    /// A flag indicating whether every public connection runs with a redundant twin connection.
+    redundant_connections: bool,
+    /// The endpoint of the redundant twin connections, the endpoint of the stream when `None`.
+    redundant_endpoint: Option<Endpoint>,
+    /// Alternative WebSocket endpoints the reconnect policy can rotate to.
+    fallback_urls: Vec<Url>,
+    /// The duration without any message after which a connection is treated as dead.
+    stale_timeout: Option<Duration>,
+    /// The interval of the shared clock synchronization, not synchronized when `None`.
+    time_sync: Option<Duration>,
+    /// The client pings sent on every connection, disabled when `None`.
+    client_ping: Option<ClientPing>,
+    /// The age at which a connection is replaced by a new one, connections are kept when `None`.
+    max_connection_age: Option<Duration>,
+    /// The time a replaced connection is still read once its replacement is subscribed.
+    migration_overlap: Duration,
+    /// The window after a reconnect in which events with an already forwarded update id are
+    /// suppressed, disabled when `None`.
+    duplicate_window: Option<Duration>,
+    /// The thresholds of the event time skew monitor, the monitor is disabled when `None`.
+    skew_thresholds: Option<SkewThresholds>,
+    /// A flag indicating whether the receive lag of the events is measured in the connection stats.
+    receive_latency: bool,
+    /// A flag indicating whether the events carry the JSON they were decoded from.
+    raw_json: bool,
+    /// The funding tracker fed by the mark price events, disabled when `None`.
+    funding_tracker: Option<FundingTracker>,
+    /// The alert engine evaluating the registered conditions, disabled when `None`.
+    alert_engine: Option<AlertEngine>,
+    /// The TTL of the latest-value cache, the cache is disabled when `None`.
+    latest_cache_ttl: Option<Duration>,
+    /// A flag indicating whether the account state is maintained from the user data events.
+    state_store: bool,
+    /// A flag indicating whether the realized and unrealized PnL is tracked.
+    pnl_tracker: bool,
+    /// A flag indicating whether the best bid and ask are cached from the book ticker events.
+    quote_cache: bool,
+    /// A flag indicating whether the most recent tickers are cached from the ticker events.
+    ticker_cache: bool,
+    /// The optional configuration of the JSONL event recorder.
+    recorder: Option<RecorderConfig>,
+    /// The aliases applied to the subscribed streams and the symbols of emitted events.
+    symbol_aliases: Option<Arc<SymbolAliases>>,
+    /// The urgent callback invoked with the pre-parsed fields of every order update.
+    fill_callback: Option<FillCallback>,
+    /// The maximum number of messages (client pings and subscription changes) sent per second.
+    messages_per_second: u32,
+    /// The number of symbols from which a multi-symbol subscription uses the all-market stream.
+    all_market_threshold: usize,
+    /// The maximum number of streams per connection, more streams are spread over connections.
+    max_streams_per_connection: usize,
+    /// A flag indicating whether downtime reports are generated after connectivity incidents.
+    downtime_reports: bool,
+    /// The conflated stream types and their emit intervals.
+    conflation: Vec<(ConflatedStream, Duration)>,
+    /// The intervals at whose boundaries a `CandleBoundaryEvent` is emitted.
+    candle_boundaries: Vec<KlineInterval>,
+    /// The filter applied to the events before they are sent over the channel.
+    event_filter: Option<EventFilter>,
+    /// The user-defined filter/transform applied to every event.
+    #[cfg(feature = "scripting")]
+    event_script: Option<EventScript>,
 }
 
 impl Default for FuturesWebSocketConfig {
@@ -67,39 +235,294 @@ impl Default for FuturesWebSocketConfig {
     ///
     /// The default configuration sets the following:
     /// - No API authentication (`api_auth` is `None`).
-    /// - Main WebSocket URL to the Binance Futures market.
-    /// - Testnet WebSocket URL to the Binance Futures testnet.
-    /// - Testnet flag is set to `false`.
+    /// - Listen keys are managed by the `HttpListenKeyProvider`.
+    /// - `Endpoint::Production`, the Binance Futures market.
+    /// - Connections are not bound to a local address or interface.
+    /// - `IpPreference::System`, every resolved address is connected to.
+    /// - The WebSocket limits of tungstenite: 64 MiB messages, 16 MiB frames and a 128 KiB write
+    ///   buffer.
     /// - Default `WouldBlockConfig`.
     /// - Reconnect flag is set to `true`.
+    /// - Default `ReconnectTriggers`, every class but JSON errors.
+    /// - No reconnect attempt or time limit.
+    /// - Account snapshot flag is set to `false`.
+    /// - The account snapshot is requested from the Binance REST API of the endpoint.
+    /// - The listen key is refreshed every `DEFAULT_LISTEN_KEY_REFRESH_INTERVAL`, without jitter.
+    /// - Warm standby flag is set to `false`.
+    /// - No redundant connections.
+    /// - No fallback endpoints.
+    /// - No stale connection watchdog.
+    /// - No client pings.
+    /// - Connections are replaced after `DEFAULT_MAX_CONNECTION_AGE`, before Binance drops them.
+    /// - Replaced connections are read for `DEFAULT_MIGRATION_OVERLAP` after the handover.
+    /// - No duplicate suppression after reconnects.
+    /// - No event time skew monitor.
+    /// - No receive lag measurement.
+    /// - No raw JSON attachment.
+    /// - No funding tracker.
+    /// - No latest-value cache.
+    /// - No account state store.
+    /// - No PnL tracking.
+    /// - No quote cache.
+    /// - No ticker cache.
+    /// - No event recording.
+    /// - No conflation.
+    /// - No event filter.
+    /// - No event script.
     fn default() -> Self {
+        Self::from_endpoint(Endpoint::Production)
+    }
+}
+
+impl FuturesWebSocketConfig {
+    /// Creates a new `FuturesWebSocketConfig` with default values for the given endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The main WebSocket URL.
+    /// - `url_testnet`: The testnet WebSocket URL.
+    ///
+    /// # Returns
+    ///
+    /// The `FuturesWebSocketConfig` or a `BinanceConnectError` when one of the URLs can not be
+    /// parsed or is not a WebSocket (`ws`/`wss`) URL.
+    ///
+    #[deprecated(since = "2.0.0", note = "use `from_endpoint(Endpoint::custom(url)?)`")]
+    pub fn new(url: &str, url_testnet: &str) -> Result<Self, BinanceConnectError> {
+        #[allow(deprecated)]
+        Ok(Self::new_unchecked(
+            websocket_url(url)?,
+            websocket_url(url_testnet)?,
+        ))
+    }
+
+    /// Creates a new `FuturesWebSocketConfig` with default values for the given, already parsed,
+    /// endpoints without validating them.
+    #[deprecated(since = "2.0.0", note = "use `from_endpoint(Endpoint::Custom(url))`")]
+    pub fn new_unchecked(url: Url, url_testnet: Url) -> Self {
+        Self {
+            url: Some(url),
+            url_testnet: Some(url_testnet),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new `FuturesWebSocketConfig` with default values for the given endpoint, e.g.
+    /// `Endpoint::Testnet` or `Endpoint::custom(url)?`.
+    pub fn from_endpoint(endpoint: Endpoint) -> Self {
         Self {
             api_auth: None,
-            url: Url::parse(constants::WS_URL_FUTURES).unwrap(),
-            url_testnet: Url::parse(constants::WS_URL_FUTURES_TESTNET).unwrap(),
-            testnet: false,
+            listen_key_provider: Arc::new(HttpListenKeyProvider::default()),
+            endpoint,
+            url: None,
+            url_testnet: None,
+            transport: Transport::default(),
             would_block_config: WouldBlockConfig::default(),
             reconnect: true,
+            reconnect_triggers: ReconnectTriggers::default(),
+            max_reconnect_attempts: None,
+            max_reconnect_time: None,
+            account_snapshot: false,
+            rest_base_url: None,
+            listen_key_refresh_interval: DEFAULT_LISTEN_KEY_REFRESH_INTERVAL,
+            listen_key_refresh_jitter: Duration::ZERO,
+            warm_standby: false,
+            redundant_connections: false,
+            redundant_endpoint: None,
+            fallback_urls: Vec::new(),
+            stale_timeout: None,
+            time_sync: None,
+            client_ping: None,
+            max_connection_age: Some(DEFAULT_MAX_CONNECTION_AGE),
+            migration_overlap: DEFAULT_MIGRATION_OVERLAP,
+            duplicate_window: None,
+            skew_thresholds: None,
+            receive_latency: false,
+            raw_json: false,
+            funding_tracker: None,
+            alert_engine: None,
+            latest_cache_ttl: None,
+            state_store: false,
+            pnl_tracker: false,
+            quote_cache: false,
+            ticker_cache: false,
+            recorder: None,
+            symbol_aliases: None,
+            fill_callback: None,
+            messages_per_second: DEFAULT_MESSAGES_PER_SECOND,
+            all_market_threshold: DEFAULT_ALL_MARKET_THRESHOLD,
+            max_streams_per_connection: MAX_STREAMS_PER_CONNECTION,
+            downtime_reports: false,
+            conflation: Vec::new(),
+            candle_boundaries: Vec::new(),
+            event_filter: None,
+            #[cfg(feature = "scripting")]
+            event_script: None,
         }
     }
-}
 
-impl FuturesWebSocketConfig {
+    /// Creates a new `FuturesWebSocketConfig` from the `BINANCE_`-prefixed environment variables
+    /// (endpoint, reconnect policy, connection limits and API credentials), unset
+    /// variables keep the defaults. See `ConfigSettings` for the settings.
+    ///
+    /// # Returns
+    ///
+    /// The `FuturesWebSocketConfig` or a `BinanceConnectError` when a variable is invalid.
+    ///
+    pub fn from_env() -> Result<Self, BinanceConnectError> {
+        ConfigSettings::from_env()?.apply(Self::default())
+    }
+
+    /// Creates a new `FuturesWebSocketConfig` from a TOML file using the `ConfigSettings` names as
+    /// keys, unset keys keep the defaults.
+    ///
+    /// # Arguments
+    ///
+    /// - `path`: The path of the TOML file.
+    ///
+    /// # Returns
+    ///
+    /// The `FuturesWebSocketConfig` or a `BinanceConnectError` when the file can not be read or a
+    /// setting is invalid.
+    ///
+    #[cfg(feature = "config-file")]
+    pub fn from_toml(path: impl AsRef<std::path::Path>) -> Result<Self, BinanceConnectError> {
+        ConfigSettings::from_toml_str(&std::fs::read_to_string(path)?)?.apply(Self::default())
+    }
+
     /// Sets the `would_block_config` for the WebSocket configuration.
     pub fn with_would_block_config(mut self, would_block_config: WouldBlockConfig) -> Self {
         self.would_block_config = would_block_config;
         self
     }
 
+    /// Sets the WebSocket endpoint, which also selects the environment of the REST requests.
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = endpoint;
+        self.url = None;
+        self.url_testnet = None;
+        self
+    }
+
     /// Sets the main WebSocket URL for the WebSocket configuration.
+    #[deprecated(since = "2.0.0", note = "use `with_endpoint(Endpoint::custom(url)?)`")]
     pub fn with_url(mut self, url: &str) -> Result<Self, url::ParseError> {
-        self.url = Url::parse(url)?;
+        self.url = Some(Url::parse(url)?);
         Ok(self)
     }
 
     /// Sets the testnet WebSocket URL for the WebSocket configuration.
+    #[deprecated(since = "2.0.0", note = "use `with_endpoint(Endpoint::custom(url)?)`")]
     pub fn with_url_testnet(mut self, url: &str) -> Result<Self, url::ParseError> {
-        self.url_testnet = Url::parse(url)?;
+        self.url_testnet = Some(Url::parse(url)?);
+        Ok(self)
+    }
+
+    /// Binds the outbound TCP connections to the local address, so multi-homed servers send the
+    /// exchange traffic from the IP that is allow-listed at Binance. Only addresses of the same
+    /// family (IPv4 or IPv6) as the local address are connected to.
+    ///
+    /// # Arguments
+    ///
+    /// - `local_address`: The local IP address, e.g. `"10.0.0.5".parse()?`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the local address set.
+    ///
+    pub fn with_local_address(mut self, local_address: IpAddr) -> Self {
+        self.transport.local_address = Some(local_address);
+        self
+    }
+
+    /// Binds the outbound TCP connections to the network interface (`SO_BINDTODEVICE`), pinning
+    /// the exchange traffic to a NIC. Only supported on Linux and usually requires
+    /// `CAP_NET_RAW`; elsewhere the connections fail, use `with_local_address` instead.
+    ///
+    /// # Arguments
+    ///
+    /// - `interface`: The name of the network interface, e.g. `"eth1"`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the interface set.
+    ///
+    pub fn with_interface(mut self, interface: &str) -> Self {
+        self.transport.interface = Some(interface.to_string());
+        self
+    }
+
+    /// Sets the IP versions of the resolved exchange addresses that are connected to. The host is
+    /// resolved to all its addresses; when connecting to one fails the next is tried, and
+    /// reconnects start past the addresses that failed.
+    ///
+    /// # Arguments
+    ///
+    /// - `ip_preference`: The `IpPreference`, e.g. `IpPreference::Ipv4First`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the IP preference set.
+    ///
+    pub fn with_ip_preference(mut self, ip_preference: IpPreference) -> Self {
+        self.transport.ip_preference = ip_preference;
+        self
+    }
+
+    /// Sets the maximum size of a received WebSocket message, a larger message fails the
+    /// connection with a socket error. Raise it when the all-market arrays (e.g. `!ticker@arr`)
+    /// exceed it, lower it to bound the memory of a connection.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_message_size`: The maximum size in bytes, `None` for no limit.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the maximum message size set.
+    ///
+    pub fn with_max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.transport.websocket_config.max_message_size = max_message_size;
+        self
+    }
+
+    /// Sets the maximum payload size of a received WebSocket frame, a larger frame fails the
+    /// connection with a socket error.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_frame_size`: The maximum size in bytes, `None` for no limit.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the maximum frame size set.
+    ///
+    pub fn with_max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.transport.websocket_config.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Sets the size the write buffer reaches before outgoing messages (subscriptions, pings) are
+    /// written to the socket, `0` writes every message immediately. Messages are flushed after
+    /// sending either way.
+    ///
+    /// # Arguments
+    ///
+    /// - `write_buffer_size`: The size in bytes.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the write buffer size set.
+    ///
+    pub fn with_write_buffer_size(mut self, write_buffer_size: usize) -> Self {
+        self.transport.websocket_config.write_buffer_size = write_buffer_size;
+        self
+    }
+
+    /// Adds an alternative WebSocket endpoint the reconnect policy can rotate to, e.g. when Binance
+    /// announces a server restart.
+    pub fn with_fallback_url(mut self, url: &str) -> Result<Self, url::ParseError> {
+        self.fallback_urls.push(Url::parse(url)?);
         Ok(self)
     }
 
@@ -109,280 +532,1841 @@ impl FuturesWebSocketConfig {
         self
     }
 
-    /// Configures the WebSocket client to use the Binance Futures testnet.
+    /// Sets the `ListenKeyProvider` creating, refreshing and deleting the listen keys of the user
+    /// data stream, e.g. an `HttpListenKeyProvider::with_client` using a proxy or a mock in tests.
+    pub fn with_listen_key_provider(
+        mut self,
+        listen_key_provider: impl ListenKeyProvider + 'static,
+    ) -> Self {
+        self.listen_key_provider = Arc::new(listen_key_provider);
+        self
+    }
+
+    /// Configures the WebSocket client to use the Binance Futures testnet, short for
+    /// `with_endpoint(Endpoint::Testnet)`.
     pub fn use_testnet(mut self) -> Self {
-        self.testnet = true;
+        self.endpoint = Endpoint::Testnet;
         self
     }
 
-    /// Disables automatic reconnection on WebSocket errors.
+    /// Disables automatic reconnection on WebSocket errors, a `ConnectionFailedEvent` is emitted
+    /// when the connection ends.
     pub fn do_not_reconnect(mut self) -> Self {
         self.reconnect = false;
         self
     }
 
-    /// Retrieves the appropriate WebSocket URL based on the testnet flag.
-    fn get_url(&self) -> Url {
-        if self.testnet {
-            self.url_testnet.clone()
-        } else {
-            self.url.clone()
-        }
+    /// Sets the classes of disconnect reasons (socket errors, JSON errors, server closes and stale
+    /// connections) that trigger a reconnect. On the others a `ConnectionFailedEvent` is emitted
+    /// and the connection thread exits.
+    ///
+    /// # Arguments
+    ///
+    /// - `reconnect_triggers`: The classes that trigger a reconnect, e.g. `ReconnectTriggers::all()`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the triggers set.
+    ///
+    pub fn with_reconnect_triggers(mut self, reconnect_triggers: ReconnectTriggers) -> Self {
+        self.reconnect_triggers = reconnect_triggers;
+        self
     }
-}
 
-#[derive(Debug)]
-pub struct FuturesUsdStream {
-    config: FuturesWebSocketConfig,
-    sender: Sender<Event>,
-    receiver: Receiver<Event>,
-    listen_key: ListenKey,
-    streams_public: Vec<Streams>,
-    authenticated: bool,
-    stop_signal: Arc<AtomicBool>,
-}
+    /// Limits the consecutive reconnect attempts of a connection. When the limit is exceeded a
+    /// `ConnectionFailedEvent` is emitted and the connection thread exits, leaving the recovery
+    /// to the application. The count restarts once a connection is established.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_attempts`: The reconnect attempts after which the connection gives up.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the limit set.
+    ///
+    pub fn with_max_reconnect_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_reconnect_attempts = Some(max_attempts);
+        self
+    }
 
-impl Default for FuturesUsdStream {
-    fn default() -> Self {
-        let (sender, receiver) = channel();
-        Self {
-            sender,
-            receiver,
-            config: FuturesWebSocketConfig::default(),
-            listen_key: ListenKey { key: String::new() },
-            streams_public: Vec::new(),
-            authenticated: false,
-            stop_signal: Arc::new(AtomicBool::new(false)),
-        }
+    /// Limits the time a connection keeps reconnecting, measured from the moment it was lost.
+    /// When the limit is exceeded a `ConnectionFailedEvent` is emitted and the connection thread
+    /// exits, leaving the recovery to the application.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_time`: The time after which the connection gives up, e.g. `Duration::from_secs(300)`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the limit set.
+    ///
+    pub fn with_max_reconnect_time(mut self, max_time: Duration) -> Self {
+        self.max_reconnect_time = Some(max_time);
+        self
     }
-}
 
-impl FuturesUsdStream {
-    /// Creates a new instance of the WebSocket client with the specified configuration.
+    /// Emits an `AccountSnapshotEvent` (balances and positions) whenever the user data stream
+    /// connects.
+    ///
+    /// Only applies to authenticated connections; it provides the initial state to which
+    /// subsequent `AccountUpdateEvent` deltas can be applied. The snapshot is requested once the
+    /// connection is established, the events received meanwhile are buffered and the account
+    /// updates already contained in the snapshot are discarded, so no update is lost or applied
+    /// twice. A snapshot equal to the one of the previous connection is not emitted again. A
+    /// failed request reconnects the user data stream.
+    pub fn with_account_snapshot(mut self) -> Self {
+        self.account_snapshot = true;
+        self
+    }
+
+    /// Sets the REST base URL the account snapshot is requested from, e.g. of a proxy or the
+    /// `MockServer`. The listen key requests are made by the `ListenKeyProvider`.
     ///
     /// # Arguments
     ///
-    /// - `config`: The WebSocket configuration to use for the client.
+    /// - `base_url`: The base URL, without trailing slash, e.g. `https://fapi.binance.com`.
     ///
     /// # Returns
     ///
-    /// A new instance of the WebSocket client configured with the given settings.
+    /// A modified instance of the struct with the REST base URL set.
     ///
-    pub fn with_config(config: FuturesWebSocketConfig) -> Self {
-        let (sender, receiver) = channel();
-        Self {
-            config,
-            sender,
-            receiver,
-            listen_key: ListenKey { key: String::new() },
-            streams_public: Vec::new(),
-            authenticated: false,
-            stop_signal: Arc::new(AtomicBool::new(false)),
-        }
+    pub fn with_rest_base_url(mut self, base_url: &str) -> Self {
+        self.rest_base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
     }
 
-    /// Starts the WebSocket connection and background thread for event handling.
+    /// Sets the interval at which the listen key is refreshed. Binance expires a listen key 60
+    /// minutes after its last refresh, so the interval must stay below that; a shorter interval
+    /// leaves more room for the retries of a failed refresh. Defaults to
+    /// `DEFAULT_LISTEN_KEY_REFRESH_INTERVAL`.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The interval between the listen key refreshes, e.g. `Duration::from_secs(1800)`.
     ///
     /// # Returns
     ///
-    /// A modified instance of the struct with the WebSocket connection started.
+    /// A modified instance of the struct with the refresh interval set.
     ///
-    pub fn start(mut self) -> Self {
-        self.listen_key();
-        let url: Url = self.url();
-        Self::ws_conn_thread(
-            url,
-            self.sender.clone(),
-            self.config.clone(),
-            self.subscribe_payload(),
-            Arc::clone(&self.stop_signal),
-        );
+    pub fn with_listen_key_refresh_interval(mut self, interval: Duration) -> Self {
+        self.listen_key_refresh_interval = interval;
+        self
+    }
+
+    /// Brings every listen key refresh forward by a random amount up to the jitter, so the
+    /// refreshes of many streams (or processes) sharing an API key do not hit Binance at once.
+    ///
+    /// # Arguments
+    ///
+    /// - `jitter`: The maximum amount a refresh is brought forward, capped at the refresh interval.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the refresh jitter set.
+    ///
+    pub fn with_listen_key_refresh_jitter(mut self, jitter: Duration) -> Self {
+        self.listen_key_refresh_jitter = jitter;
+        self
+    }
+
+    /// Enables the stale connection watchdog: when no message (including pings) arrives for the
+    /// timeout, the connection is treated as dead and follows the reconnect path. Without it a
+    /// half-open TCP connection blocks the reads forever. Binance pings every 3 minutes, so the
+    /// timeout should exceed that on quiet streams.
+    ///
+    /// # Arguments
+    ///
+    /// - `stale_timeout`: The duration without any message after which the connection is dropped.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the watchdog enabled.
+    ///
+    pub fn with_stale_timeout(mut self, stale_timeout: Duration) -> Self {
+        self.stale_timeout = Some(stale_timeout);
+        self
+    }
+
+    /// Synchronizes the clock with the Binance server time while the stream runs, so the receive
+    /// latencies, candle boundaries and staleness checks use the server time. The synchronization
+    /// is shared by every stream of the process, see `SharedContext::time_sync`, and stops once
+    /// the last stream using it is dropped. A failed initial synchronization is logged and the
+    /// local clock is used.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The time between two synchronizations, used by the first stream to start it.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the clock synchronization enabled.
+    ///
+    pub fn with_time_sync(mut self, interval: Duration) -> Self {
+        self.time_sync = Some(interval);
+        self
+    }
+
+    /// Sends pings at the interval of the `ClientPing` on every connection, besides answering the
+    /// pings of Binance. When a pong does not arrive within the timeout the connection is treated
+    /// as dead and reconnected. The round trip times are reported as `ping_rtt_ms` of the
+    /// `ConnectionStatsSnapshot`s returned by `FuturesUsdStream::stats`.
+    pub fn with_client_ping(mut self, client_ping: ClientPing) -> Self {
+        self.client_ping = Some(client_ping);
+        self
+    }
+
+    /// Sets the age at which a connection is replaced by a new one. The replacement is connected
+    /// and subscribed before the old connection is closed, so the 24 hour limit of Binance does
+    /// not cause a gap. Defaults to `DEFAULT_MAX_CONNECTION_AGE`.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_connection_age`: The age at which a connection is replaced.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the maximum connection age set.
+    ///
+    pub fn with_max_connection_age(mut self, max_connection_age: Duration) -> Self {
+        self.max_connection_age = Some(max_connection_age);
+        self
+    }
+
+    /// Disables the replacement of connections before the 24 hour limit, Binance then drops the
+    /// connection and the stream reconnects (when reconnecting is enabled).
+    pub fn do_not_rotate(mut self) -> Self {
+        self.max_connection_age = None;
+        self
+    }
+
+    /// Sets the time a replaced connection is still read once its replacement is subscribed, see
+    /// `FuturesUsdStream::migrate`. Frames received on both connections are forwarded once, a
+    /// longer overlap covers a larger latency difference between the connections. Defaults to
+    /// `DEFAULT_MIGRATION_OVERLAP`.
+    ///
+    /// # Arguments
+    ///
+    /// - `overlap`: The time both connections are read.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the migration overlap set.
+    ///
+    pub fn with_migration_overlap(mut self, overlap: Duration) -> Self {
+        self.migration_overlap = overlap;
         self
     }
 
-    // Stops the Websocket thread and drops the sender
-    pub fn stop(&self) {
-        self.stop_signal
-            .store(true, std::sync::atomic::Ordering::Relaxed);
-        let sender_clone = self.sender.clone();
-        drop(sender_clone);
+    /// Suppresses the events received again after a reconnect or a standby promotion: for the
+    /// window after a connection is established, book tickers and aggregated trades whose update
+    /// id is not newer than the last forwarded one of their stream are dropped, so consumers don't
+    /// double-count trades. Depth updates are forwarded, their ids are shared by the diff. and
+    /// partial depth streams, so consumers sync their book with the update ids themselves.
+    ///
+    /// # Arguments
+    ///
+    /// - `window`: The time after each connect in which duplicates are suppressed.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the duplicate window set.
+    ///
+    pub fn with_duplicate_window(mut self, window: Duration) -> Self {
+        self.duplicate_window = Some(window);
+        self
+    }
+
+    /// Keeps an idle standby connection (handshaked and subscribed, events suppressed) ready.
+    ///
+    /// When the primary connection fails the standby is promoted instantly and a new standby is
+    /// warmed, cutting the failover gap from a full reconnect to a few milliseconds. Only applies
+    /// when reconnecting is enabled.
+    pub fn with_warm_standby(mut self) -> Self {
+        self.warm_standby = true;
+        self
+    }
+
+    /// Runs every public connection with a redundant twin connection subscribed to the same
+    /// streams, for users who cannot tolerate reconnect gaps. Both connections are read, the copy
+    /// of every frame that arrives first is forwarded and the other is suppressed (book tickers,
+    /// aggregated trades and depth updates by their update id), so a failing connection causes
+    /// no gap while the other is up.
+    ///
+    /// The connection of the user data stream is not duplicated, runtime subscription changes
+    /// (`FuturesUsdStream::subscribe`) are only sent on the first connection.
+    pub fn with_redundant_connections(mut self) -> Self {
+        self.redundant_connections = true;
+        self
+    }
+
+    /// Runs the redundant twin connections (see `with_redundant_connections`, which this enables)
+    /// against another endpoint, e.g. a regional host, so an outage of a single endpoint causes no
+    /// gap either.
+    ///
+    /// # Arguments
+    ///
+    /// - `endpoint`: The endpoint of the twin connections.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the redundant endpoint set.
+    ///
+    pub fn with_redundant_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.redundant_connections = true;
+        self.redundant_endpoint = Some(endpoint);
+        self
+    }
+
+    /// Enables the event time skew monitor, which tracks the difference between the event time and
+    /// the local receive time per stream and emits a `SkewAlertEvent` when a threshold is exceeded.
+    pub fn with_skew_monitor(mut self, skew_thresholds: SkewThresholds) -> Self {
+        self.skew_thresholds = Some(skew_thresholds);
+        self
+    }
+
+    /// Enables the receive lag measurement, the difference between the (server synced) local
+    /// receive time and the event time of every market data event, reported as `receive_lag` of
+    /// the `ConnectionStatsSnapshot`s returned by `FuturesUsdStream::stats`.
+    pub fn with_receive_latency(mut self) -> Self {
+        self.receive_latency = true;
+        self
+    }
+
+    /// Attaches the received JSON to every decoded event, which is then emitted wrapped in a
+    /// `WithRawEvent` (see `Event::into_parts`), e.g. for audit logging. The JSON is the frame
+    /// as received, before symbol aliases or event scripts are applied.
+    pub fn with_raw_json(mut self) -> Self {
+        self.raw_json = true;
+        self
+    }
+
+    /// Enables the funding tracker, which tracks the funding rate, next funding time and funding
+    /// interval per symbol from the mark price streams and emits a `FundingAlertEvent` when the
+    /// funding rate crosses the alert threshold of the tracker, see `FundingTracker`.
+    pub fn with_funding_tracker(mut self, funding_tracker: FundingTracker) -> Self {
+        self.funding_tracker = Some(funding_tracker);
+        self
+    }
+
+    /// Enables the alert engine, which evaluates its conditions against the price, funding rate
+    /// and mark price events and emits an `AlertEvent` whenever a condition is triggered, see
+    /// `AlertEngine`. Conditions can be registered later with `FuturesUsdStream::register_alert`.
+    pub fn with_alert_engine(mut self, alert_engine: AlertEngine) -> Self {
+        self.alert_engine = Some(alert_engine);
+        self
+    }
+
+    /// Enables the latest-value cache, holding the latest payload per stream, see `LatestCache`.
+    ///
+    /// # Arguments
+    ///
+    /// - `ttl`: The event age after which cached values are reported as expired.
+    ///
+    pub fn with_latest_cache(mut self, ttl: Duration) -> Self {
+        self.latest_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Enables the account state store, maintaining the balances, positions, leverage and open
+    /// orders from the user data events, see `StateStore`. Combine with `with_account_snapshot`
+    /// to initialize it.
+    pub fn with_state_store(mut self) -> Self {
+        self.state_store = true;
+        self
+    }
+
+    /// Enables the PnL tracking, accumulating the realized PnL of the fills and computing the
+    /// unrealized PnL from the mark prices, see `PnlTracker`.
+    pub fn with_pnl_tracker(mut self) -> Self {
+        self.pnl_tracker = true;
+        self
+    }
+
+    /// Enables the quote cache, maintaining the best bid and ask per symbol from the book ticker
+    /// events, see `QuoteCache`.
+    pub fn with_quote_cache(mut self) -> Self {
+        self.quote_cache = true;
+        self
+    }
+
+    /// Enables the ticker cache, maintaining the most recent ticker and mini ticker per symbol
+    /// from the ticker events, see `TickerCache`.
+    pub fn with_ticker_cache(mut self) -> Self {
+        self.ticker_cache = true;
+        self
+    }
+
+    /// Records every received event payload with its receive time to a JSONL file, see `Recorder`.
+    ///
+    /// # Arguments
+    ///
+    /// - `recorder`: The file and rotation configuration.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the recorder set.
+    ///
+    pub fn with_recorder(mut self, recorder: RecorderConfig) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Sets the symbol aliases, aliases can then be used instead of Binance symbols in the stream
+    /// builders and the symbols of emitted events are replaced by their alias, see `SymbolAliases`.
+    pub fn with_symbol_aliases(mut self, symbol_aliases: SymbolAliases) -> Self {
+        self.symbol_aliases = Some(Arc::new(symbol_aliases));
+        self
+    }
+
+    /// Sets an urgent callback invoked with the symbol, order id and status of every order update,
+    /// extracted by a lightweight scanner before the frame is deserialized. The full
+    /// `OrderTradeUpdateEvent` follows on the normal channel. The symbol is the alias of an
+    /// aliased symbol, see `SymbolAliases`.
+    ///
+    /// # Arguments
+    ///
+    /// - `callback`: The callback, it runs on the connection thread and should return quickly.
+    ///
+    pub fn with_fill_callback(
+        mut self,
+        callback: impl Fn(&FillNotice) + Send + Sync + 'static,
+    ) -> Self {
+        self.fill_callback = Some(FillCallback::new(callback));
+        self
+    }
+
+    /// Sets the maximum number of messages (client pings and runtime subscription changes) sent to
+    /// Binance per second and connection, messages above the limit are queued. Defaults to 10, the
+    /// limit Binance enforces.
+    pub fn with_outgoing_rate_limit(mut self, messages_per_second: u32) -> Self {
+        self.messages_per_second = messages_per_second.max(1);
+        self
+    }
+
+    /// Sets the number of symbols from which a multi-symbol subscription uses the all-market
+    /// stream instead of one stream per symbol, see `MultiSymbolStream`. Defaults to 50.
+    pub fn with_all_market_threshold(mut self, all_market_threshold: usize) -> Self {
+        self.all_market_threshold = all_market_threshold;
+        self
+    }
+
+    /// Sets the maximum number of streams per connection, more streams are transparently spread
+    /// over multiple connections whose events arrive on the same receiver. Defaults to 200, the
+    /// limit Binance enforces.
+    pub fn with_max_streams_per_connection(mut self, max_streams_per_connection: usize) -> Self {
+        self.max_streams_per_connection = max_streams_per_connection.max(1);
+        self
+    }
+
+    /// Enables downtime reports: when the connection is re-established after a connectivity
+    /// incident, the gap per stream, the estimated number of missed events and the recovery
+    /// actions are emitted as a `DowntimeReportEvent` and kept for
+    /// `FuturesUsdStream::downtime_reports`. The missed events are not backfilled.
+    pub fn with_downtime_reports(mut self) -> Self {
+        self.downtime_reports = true;
+        self
+    }
+
+    /// Conflates the stream type: only the most recent value per symbol is kept and the pending
+    /// values are emitted at the interval, so slow consumers get fresh data instead of an
+    /// ever-growing backlog. Values of all-market streams are emitted as one array event.
+    ///
+    /// # Arguments
+    ///
+    /// - `stream`: The stream type to conflate.
+    /// - `interval`: The interval at which the latest values are emitted.
+    ///
+    pub fn with_conflation(mut self, stream: ConflatedStream, interval: Duration) -> Self {
+        self.conflation
+            .retain(|(conflated, _)| *conflated != stream);
+        self.conflation.push((stream, interval));
+        self
+    }
+
+    /// Emits a `CandleBoundaryEvent` at the close of every candle of the interval, aligned to the
+    /// (synchronized) server time, whether or not a kline update was received, as a reliable "on
+    /// candle close" trigger. The events are sent from a scheduler thread of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// - `interval`: The interval of the candles.
+    ///
+    pub fn with_candle_boundaries(mut self, interval: KlineInterval) -> Self {
+        if !self.candle_boundaries.contains(&interval) {
+            self.candle_boundaries.push(interval);
+        }
+        self
+    }
+
+    /// Sets the filter applied on the connection thread before events are sent over the channel,
+    /// see `EventFilter`.
+    pub fn with_event_filter(mut self, event_filter: EventFilter) -> Self {
+        self.event_filter = Some(event_filter);
+        self
+    }
+
+    /// Sets a user-defined filter/transform expression applied to every event, see `EventScript`.
+    ///
+    /// # Arguments
+    ///
+    /// - `expression`: The `evalexpr` expression, e.g. `s == "BTCUSDT" && p * q > 10000.0`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the event script set or a `BinanceConnectError`
+    /// when the expression does not compile.
+    ///
+    #[cfg(feature = "scripting")]
+    pub fn with_event_script(mut self, expression: &str) -> Result<Self, BinanceConnectError> {
+        self.event_script = Some(EventScript::new(expression)?);
+        Ok(self)
+    }
+
+    /// Returns the `ExchangeInfo` of the environment of the endpoint. It is cached process-wide
+    /// and requested again once older than the TTL, see `SharedContext::set_exchange_info_ttl`.
+    ///
+    /// # Returns
+    ///
+    /// The shared `ExchangeInfo` or a `BinanceConnectError` if the request failed.
+    ///
+    pub fn exchange_info(&self) -> Result<Arc<ExchangeInfo>, BinanceConnectError> {
+        shared().exchange_info(self.testnet())
+    }
+
+    /// Returns the symbols of the contracts that are currently trading, the tradable universe.
+    ///
+    /// # Returns
+    ///
+    /// The symbols or a `BinanceConnectError` if the `ExchangeInfo` request failed.
+    ///
+    pub fn symbols(&self) -> Result<Vec<String>, BinanceConnectError> {
+        Ok(self
+            .exchange_info()?
+            .symbols
+            .iter()
+            .filter(|symbol_info| symbol_info.status == ContractStatus::Trading)
+            .map(|symbol_info| symbol_info.symbol.clone())
+            .collect())
+    }
+
+    /// Returns the symbols of the perpetual contracts that are currently trading.
+    ///
+    /// # Returns
+    ///
+    /// The symbols or a `BinanceConnectError` if the `ExchangeInfo` request failed.
+    ///
+    pub fn perpetuals(&self) -> Result<Vec<String>, BinanceConnectError> {
+        Ok(self
+            .exchange_info()?
+            .symbols
+            .iter()
+            .filter(|symbol_info| {
+                symbol_info.status == ContractStatus::Trading
+                    && symbol_info.contract_type == ContractType::Perpetual
+            })
+            .map(|symbol_info| symbol_info.symbol.clone())
+            .collect())
+    }
+
+    /// Returns the filters of the symbol, e.g. its `PRICE_FILTER` and `LOT_SIZE`.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: The symbol, case-insensitive.
+    ///
+    /// # Returns
+    ///
+    /// The filters, `None` when the symbol is unknown, or a `BinanceConnectError` if the
+    /// `ExchangeInfo` request failed.
+    ///
+    pub fn filters(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<Vec<response::SymbolFilter>>, BinanceConnectError> {
+        Ok(self
+            .exchange_info()?
+            .symbol(symbol)
+            .map(|symbol_info| symbol_info.filters.clone()))
+    }
+
+    /// Returns the price and quantity precision of the symbol.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: The symbol, case-insensitive.
+    ///
+    /// # Returns
+    ///
+    /// The (price precision, quantity precision), `None` when the symbol is unknown, or a
+    /// `BinanceConnectError` if the `ExchangeInfo` request failed.
+    ///
+    pub fn precision(&self, symbol: &str) -> Result<Option<(i64, i64)>, BinanceConnectError> {
+        Ok(self
+            .exchange_info()?
+            .symbol(symbol)
+            .map(|symbol_info| (symbol_info.price_precision, symbol_info.quantity_precision)))
+    }
+
+    /// Returns the maximum number of streams of a connection, at least 1.
+    pub(crate) fn max_streams(&self) -> usize {
+        self.max_streams_per_connection.max(1)
+    }
+
+    /// Generates the WebSocket URL of a connection to the stream (or listen key).
+    pub(crate) fn stream_url(&self, stream: &str) -> Url {
+        Url::parse(format!("{}ws/{}", self.get_url(), stream).as_str()).unwrap()
+    }
+
+    /// Retrieves the WebSocket URL of the endpoint, replaced by that of `with_url` or
+    /// `with_url_testnet` when set.
+    fn get_url(&self) -> Url {
+        match (&self.endpoint, &self.url, &self.url_testnet) {
+            (Endpoint::Production, Some(url), _) | (Endpoint::Testnet, _, Some(url)) => url.clone(),
+            (endpoint, _, _) => endpoint.ws_url(),
+        }
+    }
+
+    /// Returns true when the endpoint belongs to the testnet environment.
+    fn testnet(&self) -> bool {
+        self.endpoint.is_testnet()
+    }
+
+    /// Retrieves the WebSocket URL followed by the fallback URLs.
+    fn get_endpoints(&self) -> Vec<Url> {
+        let mut endpoints: Vec<Url> = vec![self.get_url()];
+        endpoints.extend(self.fallback_urls.iter().cloned());
+        endpoints
+    }
+}
+
+#[derive(Debug)]
+pub struct FuturesUsdStream {
+    config: FuturesWebSocketConfig,
+    sender: EventSender,
+    receiver: Receiver<Event>,
+    /// The thread refreshing the listen key, stopped and joined on drop.
+    listen_key_refresh: Option<thread::JoinHandle<()>>,
+    /// The fresh listen key the user data connection reconnects with.
+    listen_key_renewal: Arc<ListenKeyRenewal>,
+    /// The migration requests of the connections.
+    migration: Arc<MigrationRequest>,
+    streams_public: Vec<Streams>,
+    authenticated: bool,
+    stop_signal: Arc<AtomicBool>,
+    /// The status of the stream, aggregating the status of every connection.
+    status: Arc<ConnectorStatus>,
+    /// The statistics of the connections, in connection order, filled on start.
+    stats: Arc<Mutex<Vec<Arc<ConnectionStats>>>>,
+    skew_monitor: Option<Arc<Mutex<SkewMonitor>>>,
+    funding_tracker: Option<Arc<Mutex<FundingTracker>>>,
+    alert_engine: Option<Arc<Mutex<AlertEngine>>>,
+    listing_detector: Option<Arc<Mutex<ListingDetector>>>,
+    /// The streams subscribed to for every new listing, the detector is created on start.
+    listing_streams: Option<Vec<ListingStream>>,
+    /// The handle keeping the shared clock synchronization running, see `with_time_sync`.
+    time_sync: Option<Arc<TimeSyncHandle>>,
+    latest_cache: Option<Arc<LatestCache>>,
+    state_store: Option<Arc<StateStore>>,
+    pnl_tracker: Option<Arc<PnlTracker>>,
+    quote_cache: Option<Arc<QuoteCache>>,
+    ticker_cache: Option<Arc<TickerCache>>,
+    recorder: Option<Arc<Recorder>>,
+    conflator: Option<Arc<Conflator>>,
+    /// The outbox of the first connection, queuing the requests made before the stream is started.
+    outbox: Arc<Outbox>,
+    /// The connections opened by the stream itself once started, see `Shards`.
+    shards: Option<Arc<Shards>>,
+    symbol_filter: SymbolFilter,
+    kline_filter: ClosedKlineFilter,
+    downtime_reports: Arc<Mutex<Vec<DowntimeReport>>>,
+    #[cfg(feature = "market-data")]
+    force_order_min_notional: Option<f64>,
+    /// The manager sharing its connections and rate limit, with the id of this stream once attached.
+    connection_manager: Option<(ConnectionManager, Option<u64>)>,
+    #[cfg(feature = "healthcheck")]
+    healthcheck_addr: Option<String>,
+}
+
+impl Default for FuturesUsdStream {
+    fn default() -> Self {
+        Self::with_config(FuturesWebSocketConfig::default())
+    }
+}
+
+impl Drop for FuturesUsdStream {
+    /// Stops the connection threads, which close their connections with a Close frame, and the
+    /// listen key refresh without waiting for them. The listen key is kept, see `close`.
+    fn drop(&mut self) {
+        self.stop();
+        if let Some((connection_manager, Some(id))) = &self.connection_manager {
+            connection_manager.detach(*id);
+        }
+    }
+}
+
+impl FuturesUsdStream {
+    /// Creates a new instance of the WebSocket client with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The WebSocket configuration to use for the client.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of the WebSocket client configured with the given settings.
+    ///
+    pub fn with_config(config: FuturesWebSocketConfig) -> Self {
+        let (sender, receiver) = channel();
+        let skew_monitor: Option<Arc<Mutex<SkewMonitor>>> = config
+            .skew_thresholds
+            .clone()
+            .map(|thresholds| Arc::new(Mutex::new(SkewMonitor::new(thresholds))));
+        let funding_tracker: Option<Arc<Mutex<FundingTracker>>> = config
+            .funding_tracker
+            .clone()
+            .map(|funding_tracker| Arc::new(Mutex::new(funding_tracker)));
+        let alert_engine: Option<Arc<Mutex<AlertEngine>>> = config
+            .alert_engine
+            .clone()
+            .map(|alert_engine| Arc::new(Mutex::new(alert_engine)));
+        let latest_cache: Option<Arc<LatestCache>> = config
+            .latest_cache_ttl
+            .map(|ttl| Arc::new(LatestCache::new(ttl)));
+        let state_store: Option<Arc<StateStore>> = match config.state_store {
+            true => Some(Arc::new(StateStore::new())),
+            false => None,
+        };
+        let pnl_tracker: Option<Arc<PnlTracker>> = match config.pnl_tracker {
+            true => Some(Arc::new(PnlTracker::new())),
+            false => None,
+        };
+        let quote_cache: Option<Arc<QuoteCache>> = match config.quote_cache {
+            true => Some(Arc::new(QuoteCache::new())),
+            false => None,
+        };
+        let ticker_cache: Option<Arc<TickerCache>> = match config.ticker_cache {
+            true => Some(Arc::new(TickerCache::new())),
+            false => None,
+        };
+        let recorder: Option<Arc<Recorder>> = config
+            .recorder
+            .clone()
+            .map(|recorder| Arc::new(Recorder::new(recorder)));
+        let conflator: Option<Arc<Conflator>> = match config.conflation.is_empty() {
+            true => None,
+            false => Some(Arc::new(Conflator::new(config.conflation.clone()))),
+        };
+        Self {
+            config,
+            sender: EventSender::new(sender),
+            receiver,
+            listen_key_refresh: None,
+            listen_key_renewal: Arc::new(ListenKeyRenewal::default()),
+            migration: Arc::new(MigrationRequest::default()),
+            streams_public: Vec::new(),
+            authenticated: false,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            status: Arc::new(ConnectorStatus::default()),
+            stats: Arc::new(Mutex::new(Vec::new())),
+            skew_monitor,
+            funding_tracker,
+            alert_engine,
+            listing_detector: None,
+            listing_streams: None,
+            time_sync: None,
+            latest_cache,
+            state_store,
+            pnl_tracker,
+            quote_cache,
+            ticker_cache,
+            recorder,
+            conflator,
+            outbox: Arc::new(Outbox::default()),
+            shards: None,
+            symbol_filter: SymbolFilter::default(),
+            kline_filter: ClosedKlineFilter::default(),
+            downtime_reports: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(feature = "market-data")]
+            force_order_min_notional: None,
+            connection_manager: None,
+            #[cfg(feature = "healthcheck")]
+            healthcheck_addr: None,
+        }
+    }
+
+    /// Starts the WebSocket connection and background thread for event handling.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the WebSocket connection started.
+    ///
+    pub fn start(mut self) -> Self {
+        #[cfg(feature = "healthcheck")]
+        if let Some(addr) = &self.healthcheck_addr {
+            // The stream runs without the endpoint, e.g. when the port is taken.
+            if let Err(err) = crate::futures_usd::healthcheck::serve(addr, Arc::clone(&self.status))
+            {
+                error!(addr = %addr, error = %err, "healthcheck not started");
+            }
+        }
+        self.time_sync();
+        self.listen_key();
+        self.listing_detector();
+        if let Some(conflator) = &self.conflator {
+            Conflator::start(
+                conflator,
+                self.sender.clone(),
+                Arc::clone(&self.stop_signal),
+            );
+        }
+        if !self.config.candle_boundaries.is_empty() {
+            let intervals: Vec<KlineInterval> = self.config.candle_boundaries.clone();
+            let sender: EventSender = self.sender.clone();
+            let stop_signal: Arc<AtomicBool> = Arc::clone(&self.stop_signal);
+            thread::spawn(move || Self::schedule_candle_boundaries(intervals, sender, stop_signal));
+        }
+        let mut listen_key: Option<ListenKeyRequest> = self.listen_key_request();
+        let shards: Arc<Shards> = Arc::new(Shards::new(
+            self.config.clone(),
+            self.connection_context(),
+            Arc::clone(&self.status),
+            Arc::clone(&self.stats),
+            Arc::clone(&self.downtime_reports),
+            Arc::clone(&self.listen_key_renewal),
+        ));
+        // The public streams are received on the pooled connections of the manager.
+        if let Some((connection_manager, _)) = &self.connection_manager {
+            let streams: Vec<Streams> = std::mem::take(&mut self.streams_public);
+            let mut context: ConnectionContext = shards.context();
+            // New listings are not subscribed to by attached streams, see `with_new_listings`.
+            context.shards = None;
+            let attached: u64 = connection_manager.attach(self.stream_names_of(&streams), context);
+            self.connection_manager = Some((connection_manager.clone(), Some(attached)));
+            if !self.authenticated {
+                return self;
+            }
+        }
+        for (index, (url_stream, streams)) in self.shards().into_iter().enumerate() {
+            // Requests made before the start go to the first connection.
+            let outbox: Arc<Outbox> = match index {
+                0 => Arc::clone(&self.outbox),
+                _ => Arc::new(Outbox::default()),
+            };
+            // The user data stream is the first connection.
+            shards.open(&url_stream, streams, outbox, listen_key.take());
+        }
+        self.shards = Some(shards);
+        self
+    }
+
+    /// Registers a sink every event is published to besides the receiver, e.g. `FileSink` or an
+    /// adapter to Kafka or Redis. Sinks must be registered before the stream is started.
+    ///
+    /// # Arguments
+    ///
+    /// - `sink`: The `EventSink` to publish to.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the sink registered.
+    ///
+    pub fn with_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sender.add_sink(Arc::new(Mutex::new(sink)));
+        self
+    }
+
+    /// Stamps every event with the next number of the persistent sequence before it is published,
+    /// the events are emitted as `SequencedEvent`. The numbers increase across restarts, so
+    /// exactly-once sinks can deduplicate on them. The sequence must be set before the stream is
+    /// started.
+    ///
+    /// # Arguments
+    ///
+    /// - `sequence_wal`: The opened `SequenceWal`, e.g. `SequenceWal::open(path)?`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the sequence set.
+    ///
+    pub fn with_sequence_wal(mut self, sequence_wal: SequenceWal) -> Self {
+        self.sender.set_sequence_wal(sequence_wal);
+        self
+    }
+
+    /// Attaches the stream to a `ConnectionManager` on start: the public streams are received on
+    /// the pooled connections of the manager, shared with the other attached streams, and every
+    /// connection of the stream is held to the global rate limit of the manager. The user data
+    /// connection of an authenticated stream is opened by the stream itself. Runtime subscription
+    /// changes go to the pooled connections, their confirmations are not sent to the stream.
+    ///
+    /// # Arguments
+    ///
+    /// - `connection_manager`: The manager to share the connections of.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct attached to the manager once started.
+    ///
+    pub fn with_connection_manager(mut self, connection_manager: &ConnectionManager) -> Self {
+        self.connection_manager = Some((connection_manager.clone(), None));
+        self
+    }
+
+    /// Exposes the connector status as JSON on a tiny HTTP endpoint once the stream is started.
+    ///
+    /// # Arguments
+    ///
+    /// - `addr`: The local address to bind the endpoint to, e.g. `0.0.0.0:8080`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the healthcheck endpoint configured.
+    ///
+    #[cfg(feature = "healthcheck")]
+    pub fn with_healthcheck(mut self, addr: &str) -> Self {
+        self.healthcheck_addr = Some(addr.to_string());
+        self
+    }
+
+    /// Runs a burn-in self-test against the testnet: subscribes to one instance of every supported
+    /// stream type and waits for an acknowledgement and event of each, catching stream-grammar and
+    /// deserializer regressions before deploying.
+    ///
+    /// # Arguments
+    ///
+    /// - `timeout`: The maximum time to wait for the acknowledgements and events.
+    ///
+    /// # Returns
+    ///
+    /// A `SelfTestReport` with the result per stream or a `BinanceConnectError` if the connection failed.
+    ///
+    pub fn self_test(timeout: Duration) -> Result<SelfTestReport, BinanceConnectError> {
+        self_test::run(timeout)
+    }
+
+    /// Returns the statistics of every connection (messages per second, bytes received, last
+    /// message time, reconnects and uptime), empty before the stream is started.
+    pub fn stats(&self) -> Vec<ConnectionStatsSnapshot> {
+        self.stats
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|stats| stats.snapshot())
+            .collect()
+    }
+
+    /// Returns a snapshot of the current connector status, aggregated over the connections: the
+    /// stream is connected while every connection is, see `ConnectorStatus::snapshot`.
+    pub fn status(&self) -> StatusSnapshot {
+        self.status.snapshot()
+    }
+
+    /// Returns the event time skew statistics per stream, empty when the skew monitor is not configured.
+    pub fn skew_stats(&self) -> HashMap<String, SkewStats> {
+        self.skew_monitor
+            .as_ref()
+            .map(|skew_monitor| skew_monitor.lock().unwrap().stats().clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the funding state per symbol, empty when the funding tracker is not configured.
+    pub fn funding_states(&self) -> HashMap<String, FundingState> {
+        self.funding_tracker
+            .as_ref()
+            .map(|funding_tracker| funding_tracker.lock().unwrap().states().clone())
+            .unwrap_or_default()
+    }
+
+    /// Registers an alert condition while the stream is running, see `AlertEngine::register`.
+    ///
+    /// # Arguments
+    ///
+    /// - `condition`: The condition to evaluate.
+    ///
+    /// # Returns
+    ///
+    /// The id of the condition, `None` when the alert engine is not configured.
+    ///
+    pub fn register_alert(&self, condition: AlertCondition) -> Option<usize> {
+        self.alert_engine
+            .as_ref()
+            .map(|alert_engine| alert_engine.lock().unwrap().register(condition))
+    }
+
+    /// Returns the latest-value cache, `None` when it is not configured.
+    pub fn latest_cache(&self) -> Option<Arc<LatestCache>> {
+        self.latest_cache.clone()
+    }
+
+    /// Returns the account state store, `None` when it is not configured.
+    pub fn state_store(&self) -> Option<Arc<StateStore>> {
+        self.state_store.clone()
+    }
+
+    /// Returns the PnL tracker, `None` when it is not configured.
+    pub fn pnl_tracker(&self) -> Option<Arc<PnlTracker>> {
+        self.pnl_tracker.clone()
+    }
+
+    /// Returns the quote cache, `None` when it is not configured.
+    pub fn quote_cache(&self) -> Option<Arc<QuoteCache>> {
+        self.quote_cache.clone()
+    }
+
+    /// Returns the ticker cache, `None` when it is not configured.
+    pub fn ticker_cache(&self) -> Option<Arc<TickerCache>> {
+        self.ticker_cache.clone()
+    }
+
+    /// Returns the event recorder, `None` when it is not configured.
+    pub fn recorder(&self) -> Option<Arc<Recorder>> {
+        self.recorder.clone()
+    }
+
+    /// Returns the downtime reports of the last incidents, oldest first, empty when downtime
+    /// reports are not enabled (see `FuturesWebSocketConfig::with_downtime_reports`).
+    pub fn downtime_reports(&self) -> Vec<DowntimeReport> {
+        self.downtime_reports.lock().unwrap().clone()
+    }
+
+    /// Subscribes to the streams on the running connection, the subscription is queued by the
+    /// outgoing rate limiter, sent when the connection next wakes up (on an incoming message) and
+    /// renewed after a reconnect.
+    ///
+    /// The streams are subscribed to on the first connection with capacity for all of them, see
+    /// `with_max_streams_per_connection`, or on a new connection when every connection is full.
+    /// Streams the stream is already subscribed to are not subscribed to again.
+    ///
+    /// # Arguments
+    ///
+    /// - `streams`: The Binance stream names, e.g. `btcusdt@bookTicker`.
+    ///
+    /// # Returns
+    ///
+    /// The id of the request, matching the `SubscriptionConfirmedEvent` once Binance confirmed it.
+    ///
+    pub fn subscribe(&self, streams: &[&str]) -> u64 {
+        let stream_names: Vec<String> = self.stream_names(streams);
+        info!(streams = ?stream_names, "futures_usd subscribing");
+        match (&self.connection_manager, &self.shards) {
+            (Some((connection_manager, Some(id))), _) => {
+                connection_manager.subscribe(*id, stream_names)
+            }
+            (_, Some(shards)) => shards
+                .subscribe(stream_names)
+                .unwrap_or_else(|| self.outbox.subscribe(Vec::new())),
+            // Before the start the subscription is sent by the first connection once connected.
+            _ => self.outbox.subscribe(stream_names),
+        }
+    }
+
+    /// Unsubscribes from the streams on the running connection, see `subscribe`. The streams are
+    /// unsubscribed from on the connections subscribed to them, with a request per connection.
+    ///
+    /// # Arguments
+    ///
+    /// - `streams`: The Binance stream names, e.g. `btcusdt@bookTicker`.
+    ///
+    /// # Returns
+    ///
+    /// The id of the (first) request, matching the `SubscriptionConfirmedEvent` once Binance
+    /// confirmed it.
+    ///
+    pub fn unsubscribe(&self, streams: &[&str]) -> u64 {
+        let stream_names: Vec<String> = self.stream_names(streams);
+        info!(streams = ?stream_names, "futures_usd unsubscribing");
+        if let Some((connection_manager, Some(id))) = &self.connection_manager {
+            return connection_manager.unsubscribe(*id, stream_names);
+        }
+        self.shards
+            .as_ref()
+            .and_then(|shards| shards.unsubscribe(&stream_names))
+            .unwrap_or_else(|| self.outbox.unsubscribe(stream_names))
+    }
+
+    /// Sets a property of the running connection, e.g. `StreamProperty::Combined` to receive every
+    /// payload wrapped with its stream name. The request is queued like `subscribe`.
+    ///
+    /// # Arguments
+    ///
+    /// - `property`: The property to set.
+    /// - `value`: The value of the property.
+    ///
+    /// # Returns
+    ///
+    /// The id of the request, matching the `PropertyEvent` once Binance confirmed it.
+    ///
+    pub fn set_property(&self, property: StreamProperty, value: bool) -> u64 {
+        info!(
+            property = property.to_str(),
+            value = value,
+            "futures_usd setting property"
+        );
+        self.outbox.set_property(property, value)
+    }
+
+    /// Requests the value of a property of the running connection, see `set_property`.
+    ///
+    /// # Arguments
+    ///
+    /// - `property`: The property to get.
+    ///
+    /// # Returns
+    ///
+    /// The id of the request, matching the `PropertyEvent` holding the value.
+    ///
+    pub fn get_property(&self, property: StreamProperty) -> u64 {
+        self.outbox.get_property(property)
+    }
+
+    /// Returns the stream names with aliased symbols replaced by their Binance symbol.
+    fn stream_names(&self, streams: &[&str]) -> Vec<String> {
+        streams
+            .iter()
+            .map(|stream| match &self.config.symbol_aliases {
+                Some(symbol_aliases) => symbol_aliases.stream_to_binance(stream),
+                None => stream.to_string(),
+            })
+            .collect()
+    }
+
+    /// Validates the symbols of the streams added so far against the cached `ExchangeInfo`, so a
+    /// misspelled or delisted symbol fails before connecting instead of leaving its stream silent.
+    /// All-market streams, continuous kline pairs and asset indexes carry no contract symbol and
+    /// are not validated. Call it before `start`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` or a `BinanceConnectError::InvalidStreamConfig` naming the first stream with an
+    /// unknown symbol, or the error of the `ExchangeInfo` request.
+    ///
+    pub fn validate_symbols(&self) -> Result<(), BinanceConnectError> {
+        let exchange_info: Arc<ExchangeInfo> = self.config.exchange_info()?;
+        for stream in self.stream_names_of(&self.streams_public) {
+            let symbol: &str = match stream.split_once('@') {
+                Some((symbol, kind))
+                    if !symbol.starts_with('!')
+                        && !symbol.contains('_')
+                        && kind != "assetIndex" =>
+                {
+                    symbol
+                }
+                _ => continue,
+            };
+            if exchange_info.symbol(symbol).is_none() {
+                return Err(BinanceConnectError::InvalidStreamConfig {
+                    reason: format!("unknown symbol {}", symbol),
+                    stream,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Migrates every connection "make-before-break", e.g. ahead of an announced maintenance: a
+    /// replacement is connected and subscribed while the events are still read from the old
+    /// connection, which is closed after the migration overlap. Consumers see neither a gap nor a
+    /// duplicate, unlike a reconnect.
+    pub fn migrate(&self) {
+        info!("futures_usd migrating connections");
+        self.migration.request();
+    }
+
+    // Stops the Websocket thread and drops the sender
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        let sender_clone = self.sender.clone();
+        drop(sender_clone);
+    }
+
+    /// Stops the stream, waits for the listen key refresh to finish and deletes the listen key.
+    ///
+    /// Binance hands out a single listen key per API key, so deleting it ends the user data
+    /// stream of every other consumer of the key, in this or another process. Only close a
+    /// stream that is the sole user of its API key, otherwise drop it.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` when the stream is stopped and its listen key (if any) deleted, or a
+    /// `BinanceConnectError` if the deletion failed.
+    ///
+    pub fn close(mut self) -> Result<(), BinanceConnectError> {
+        self.stop();
+        if let Some(listen_key_refresh) = self.listen_key_refresh.take() {
+            let _ = listen_key_refresh.join();
+        }
+        if let (true, Some(api_auth)) = (self.authenticated, &self.config.api_auth) {
+            self.config
+                .listen_key_provider
+                .delete(api_auth, self.config.testnet())?;
+            info!("futures_usd listen key deleted");
+        }
+        Ok(())
+    }
+
+    /// Creates the `ConnectionContext` shared by the connections of this instance, completed per
+    /// connection by `Shards::context`.
+    fn connection_context(&self) -> ConnectionContext {
+        ConnectionContext {
+            sender: self.sender.clone(),
+            stop_signal: Arc::clone(&self.stop_signal),
+            status: Arc::new(ConnectorStatus::default()),
+            stats: Arc::new(ConnectionStats::default()),
+            would_block_config: self.config.would_block_config.clone(),
+            stale_timeout: self.config.stale_timeout,
+            client_ping: self.config.client_ping.clone(),
+            rotation: None,
+            migration: Arc::clone(&self.migration),
+            skew_monitor: self.skew_monitor.clone(),
+            receive_latency: self.config.receive_latency,
+            raw_json: self.config.raw_json,
+            funding_tracker: self.funding_tracker.clone(),
+            alert_engine: self.alert_engine.clone(),
+            listing_detector: self.listing_detector.clone(),
+            latest_cache: self.latest_cache.clone(),
+            state_store: self.state_store.clone(),
+            pnl_tracker: self.pnl_tracker.clone(),
+            quote_cache: self.quote_cache.clone(),
+            ticker_cache: self.ticker_cache.clone(),
+            recorder: self.recorder.clone(),
+            conflator: self.conflator.clone(),
+            symbol_filter: match self.symbol_filter.is_empty() {
+                true => None,
+                false => Some(Arc::new(self.symbol_filter.clone())),
+            },
+            kline_filter: match self.kline_filter.is_empty() {
+                true => None,
+                false => Some(Arc::new(self.kline_filter.clone())),
+            },
+            symbol_aliases: self.config.symbol_aliases.clone(),
+            fill_callback: self.config.fill_callback.clone(),
+            // Every connection tracks its own streams.
+            downtime: match self.config.downtime_reports {
+                true => Some(Arc::new(DowntimeTracker::new(Arc::clone(
+                    &self.downtime_reports,
+                )))),
+                false => None,
+            },
+            // Every connection queues its own messages, see `Outbox`.
+            outbox: Arc::new(Outbox::default()),
+            messages_per_second: self.config.messages_per_second,
+            global_rate_limit: self
+                .connection_manager
+                .as_ref()
+                .map(|(connection_manager, _)| connection_manager.rate_limit()),
+            #[cfg(feature = "market-data")]
+            force_order_min_notional: self.force_order_min_notional,
+            event_filter: self.config.event_filter.clone().map(Arc::new),
+            update_id_filter: self
+                .config
+                .duplicate_window
+                .map(|window| Arc::new(Mutex::new(UpdateIdFilter::new(window)))),
+            redundant_filter: None,
+            router: None,
+            listen_key_renewal: None,
+            account_snapshot: None,
+            shards: None,
+            transport: Arc::new(self.config.transport.clone()),
+            #[cfg(feature = "scripting")]
+            event_script: self.config.event_script.clone(),
+        }
+    }
+
+    /// Spawns a new thread for establishing a WebSocket connection.
+    ///
+    /// This function spawns a new thread to handle the WebSocket connection using the provided URL,
+    /// WebSocket configuration, and optional subscription payload.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The WebSocket URL to connect to.
+    /// - `config`: The WebSocket configuration.
+    /// - `subscribe_payload`: An optional subscription payload to send upon connection.
+    /// - `context`: The `ConnectionContext` used to process and forward events.
+    ///
+    pub(crate) fn ws_conn_thread(
+        mut url: Url,
+        config: FuturesWebSocketConfig,
+        subscribe_payload: Option<String>,
+        context: ConnectionContext,
+        listen_key: Option<ListenKeyRequest>,
+    ) {
+        thread::spawn(move || {
+            // The user data connection creates its listen key here, not blocking the caller.
+            if let Some(listen_key) = listen_key {
+                let listen_key: ListenKey = match listen_key.create() {
+                    Ok(listen_key) => listen_key,
+                    Err(err) => {
+                        error!(error = %err, "futures_usd listen key creation failed");
+                        let attempts: ReconnectAttempts = ReconnectAttempts::new(None, None);
+                        let failed: ConnectionFailed = attempts.failed(&failure_reason(&err), &url);
+                        let _ = context.sender.send(Event::ConnectionFailedEvent(failed));
+                        return;
+                    }
+                };
+                info!(
+                    test_net = config.testnet(),
+                    "futures_usd listen key created"
+                );
+                set_listen_key(&mut url, &listen_key.key);
+            }
+            let standby: Option<Standby> =
+                Self::warm_standby(&url, &config, &subscribe_payload, &context);
+            Self::open_ws_con(url, config, subscribe_payload, context, standby);
+        });
+    }
+
+    /// Opens a pooled connection of a `ConnectionManager` to the combined stream of the stream name,
+    /// with the context completed by the manager.
+    pub(crate) fn open_pooled(
+        config: FuturesWebSocketConfig,
+        stream: &str,
+        complete: impl FnOnce(&mut ConnectionContext),
+    ) {
+        let url: Url =
+            Url::parse(format!("{}stream?streams={}", config.get_url(), stream).as_str()).unwrap();
+        // The context of a stream that is never started, dropping it sets its stop signal, which
+        // the manager replaces by its own.
+        let mut context: ConnectionContext = Self::with_config(config.clone()).connection_context();
+        complete(&mut context);
+        Self::ws_conn_thread(url, config, None, context, None);
+    }
+
+    /// Warms a standby connection when configured to do so.
+    fn warm_standby(
+        url: &Url,
+        config: &FuturesWebSocketConfig,
+        subscribe_payload: &Option<String>,
+        context: &ConnectionContext,
+    ) -> Option<Standby> {
+        match config.warm_standby && config.reconnect {
+            true => Some(Standby::warm(
+                url.clone(),
+                subscribe_payload.clone(),
+                Arc::clone(&context.stop_signal),
+                Arc::clone(&context.transport),
+            )),
+            false => None,
+        }
+    }
+
+    /// Opens a WebSocket connection and handles reconnection in case of errors.
+    ///
+    /// This function establishes a WebSocket connection using the provided URL and WebSocket configuration.
+    /// It also handles automatic reconnection in the case of connection errors, if the `reconnect` option
+    /// is enabled in the configuration. Reconnecting is done iteratively, so the stack does not grow
+    /// however often the connection drops.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The WebSocket URL to connect to.
+    /// - `config`: The WebSocket configuration, including options for reconnecting.
+    /// - `subscribe_payload`: An optional subscription payload to send upon connection.
+    /// - `context`: The `ConnectionContext` used to process and forward events.
+    /// - `standby`: The warm standby connection, if configured.
+    ///
+    fn open_ws_con(
+        mut url: Url,
+        config: FuturesWebSocketConfig,
+        subscribe_payload: Option<String>,
+        mut context: ConnectionContext,
+        mut standby: Option<Standby>,
+    ) {
+        let mut attempts: ReconnectAttempts =
+            ReconnectAttempts::new(config.max_reconnect_attempts, config.max_reconnect_time);
+        // An already established (promoted standby) connection to use instead of connecting.
+        let mut socket: Option<Socket> = None;
+        loop {
+            context.stats.set_url(url.as_str());
+            context.rotation = Some(Rotation::new(
+                url.clone(),
+                subscribe_payload.clone(),
+                config.max_connection_age,
+                config.migration_overlap,
+                Arc::clone(&context.migration),
+                Arc::clone(&context.transport),
+            ));
+            let established: u64 = context.stats.established();
+            let result: Result<(), BinanceConnectError> =
+                info_span!("futures_usd_connection", url = %url).in_scope(|| match socket.take() {
+                    Some(socket) => run(socket, &context),
+                    None => client(url.clone(), subscribe_payload.clone(), &context),
+                });
+            let err: BinanceConnectError = match result {
+                Ok(()) => return,
+                Err(err) => err,
+            };
+            // The receiver is gone, there is nobody left to notify.
+            if let BinanceConnectError::MpscSendError(_) = err {
+                info!(url = %url, "futures_usd receiver dropped, connection closed");
+                return;
+            }
+            let reason: DisconnectReason = failure_reason(&err);
+            if !config.reconnect || !config.reconnect_triggers.triggers(&reason) {
+                error!(url = %url, reason = ?reason, "futures_usd connection ended");
+                let _ = context
+                    .sender
+                    .send(Event::ConnectionFailedEvent(attempts.failed(&reason, &url)));
+                return;
+            }
+            if context.stats.established() > established {
+                attempts.established();
+            }
+            // Give up when the limits are exceeded, the application decides what follows.
+            if let Some(failed) = attempts.attempt(&reason, &url) {
+                error!(
+                    url = %url,
+                    attempts = failed.attempts,
+                    elapsed_ms = failed.elapsed_ms,
+                    "futures_usd reconnect limit exceeded"
+                );
+                let _ = context.sender.send(Event::ConnectionFailedEvent(failed));
+                return;
+            }
+            info!(url = %url, reason = ?reason, "futures_usd reconnecting");
+            let decision: ReconnectDecision = reason.decision();
+            let mut next_url: Url = match decision {
+                ReconnectDecision::RotateEndpoint => rotate_endpoint(&url, &config.get_endpoints()),
+                _ => url.clone(),
+            };
+            // The user data connection reconnects with the fresh listen key, the standby connection
+            // still uses the expired one.
+            let listen_key: Option<String> = match reason {
+                DisconnectReason::ListenKeyExpired => context
+                    .listen_key_renewal
+                    .as_ref()
+                    .and_then(|listen_key_renewal| listen_key_renewal.take()),
+                _ => None,
+            };
+            if let Some(listen_key) = &listen_key {
+                set_listen_key(&mut next_url, listen_key);
+                standby = None;
+            }
+            let trace: ReconnectTrace =
+                ReconnectTrace::new(reason, decision.clone(), &url, &next_url);
+            if let Some(downtime) = &context.downtime {
+                downtime.disconnected(trace.reason.clone());
+                downtime.action_taken(RecoveryAction::Reconnect(decision.clone()));
+            }
+            let _ = context.sender.send(Event::ReconnectEvent(trace));
+            // Values received before the disconnect must not be read as current.
+            if let Some(latest_cache) = &context.latest_cache {
+                latest_cache.invalidate_all();
+                if let Some(downtime) = &context.downtime {
+                    downtime.action_taken(RecoveryAction::LatestCacheInvalidated);
+                }
+            }
+            // Promote the standby connection when available, otherwise follow the decision.
+            socket = standby
+                .as_ref()
+                .and_then(|standby| standby.promote(Duration::from_millis(100)));
+            match socket {
+                Some(_) => {
+                    if let Some(downtime) = &context.downtime {
+                        downtime.action_taken(RecoveryAction::StandbyPromoted);
+                    }
+                    standby = Self::warm_standby(&next_url, &config, &subscribe_payload, &context);
+                }
+                None => {
+                    // A stop during the backoff (10 seconds when rate limited) ends the connection.
+                    if let ReconnectDecision::Backoff(duration) = decision {
+                        if !wait(duration, &context.stop_signal) {
+                            info!(url = %url, "futures_usd stopped while backing off");
+                            return;
+                        }
+                    }
+                    if listen_key.is_some() {
+                        standby =
+                            Self::warm_standby(&next_url, &config, &subscribe_payload, &context);
+                    }
+                }
+            }
+            context.status.reconnected();
+            context.stats.reconnected();
+            url = next_url;
+        }
+    }
+
+    /// Consumes the current instance and returns the event receiver.
+    ///
+    /// This function transfers ownership of the current instance to the caller and provides
+    /// access to the event receiver, allowing the caller to receive WebSocket events.
+    ///
+    /// # Returns
+    ///
+    /// A `Receiver<Event>` that can be used to receive WebSocket events.
+    ///
+    pub fn consume(&self) -> &Receiver<Event> {
+        &self.receiver
+    }
+
+    /// Returns the event receiver wrapped for polling consumers.
+    ///
+    /// Unlike `consume().recv()`, `next_event(timeout)` and `try_events()` never block forever
+    /// when the feed quiets down.
+    ///
+    /// # Returns
+    ///
+    /// An `EventReceiver` borrowing the event receiver.
+    ///
+    pub fn events(&self) -> EventReceiver<'_> {
+        EventReceiver::new(&self.receiver)
+    }
+
+    /// Sets up the listen key used for WebSocket authentication.
+    ///
+    /// The listen key itself is created on the thread of the user data connection, so `start`
+    /// does not block on the request; this function sets up the automatic refreshes at the
+    /// configured interval to maintain WebSocket authentication.
+    ///
+    fn listen_key(&mut self) {
+        if let Some(listen_key) = self.listen_key_request() {
+            self.authenticated = true;
+            let test_net: bool = self.config.testnet();
+            let interval: Duration = self.config.listen_key_refresh_interval;
+            let jitter: Duration = self.config.listen_key_refresh_jitter;
+            let sender: EventSender = self.sender.clone();
+            let listen_key_renewal: Arc<ListenKeyRenewal> = Arc::clone(&self.listen_key_renewal);
+            let stop_signal: Arc<AtomicBool> = Arc::clone(&self.stop_signal);
+            self.listen_key_refresh = Some(thread::spawn(move || {
+                Self::refresh_listen_key(
+                    listen_key,
+                    test_net,
+                    interval,
+                    jitter,
+                    sender,
+                    listen_key_renewal,
+                    stop_signal,
+                )
+            }));
+        }
+    }
+
+    /// Returns the `ListenKeyRequest` of the API key, `None` when not authenticated.
+    fn listen_key_request(&self) -> Option<ListenKeyRequest> {
+        self.config.api_auth.as_ref().map(|api_auth| {
+            ListenKeyRequest::new(
+                Arc::clone(&self.config.listen_key_provider),
+                api_auth.clone(),
+                self.config.testnet(),
+            )
+        })
+    }
+
+    /// Joins the shared clock synchronization when configured, see
+    /// `FuturesWebSocketConfig::with_time_sync`.
+    fn time_sync(&mut self) {
+        if let Some(interval) = self.config.time_sync {
+            match shared().time_sync(self.config.testnet(), interval) {
+                Ok(time_sync) => self.time_sync = Some(time_sync),
+                Err(err) => {
+                    warn!(error = ?err, "futures_usd time sync failed, using the local clock")
+                }
+            }
+        }
     }
 
-    /// Spawns a new thread for establishing a WebSocket connection.
+    /// Creates the detector of new listings from the trading contracts when configured, see
+    /// `with_new_listings`.
+    fn listing_detector(&mut self) {
+        if let Some(streams) = self.listing_streams.take() {
+            match self.config.symbols() {
+                Ok(trading) => {
+                    self.listing_detector =
+                        Some(Arc::new(Mutex::new(ListingDetector::new(trading, streams))));
+                }
+                Err(err) => {
+                    error!(error = ?err, "futures_usd new listing detection disabled")
+                }
+            }
+        }
+    }
+
+    /// Refreshes the listen key used for WebSocket authentication on its own thread.
     ///
-    /// This function spawns a new thread to handle the WebSocket connection using the provided URL,
-    /// WebSocket configuration, and optional subscription payload.
+    /// This function continually refreshes the listen key at the configured interval, brought
+    /// forward by a random part of the jitter, to ensure the WebSocket connection remains
+    /// authenticated. A failed refresh is retried with backoff; when the retries
+    /// are exhausted a `ListenKeyRefreshFailedEvent` is sent and the user data connection reconnects
+    /// with a fresh listen key. The thread returns once the stop signal is set.
     ///
     /// # Arguments
     ///
-    /// - `url`: The WebSocket URL to connect to.
-    /// - `sender`: A `Sender<Event>` for sending WebSocket events to the calling code.
-    /// - `config`: The WebSocket configuration.
-    /// - `subscribe_payload`: An optional subscription payload to send upon connection.
-    ///
-    fn ws_conn_thread(
-        url: Url,
-        sender: Sender<Event>,
-        config: FuturesWebSocketConfig,
-        subscribe_payload: Option<String>,
+    /// - `listen_key`: The `ListenKeyRequest` refreshing the listen key.
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    /// - `interval`: The interval between the refreshes.
+    /// - `jitter`: The maximum random amount a refresh is brought forward.
+    /// - `sender`: The `EventSender` the failures are sent to.
+    /// - `listen_key_renewal`: Hands the fresh listen key to the user data connection.
+    /// - `stop_signal`: The stop signal of the stream.
+    ///
+    fn refresh_listen_key(
+        listen_key: ListenKeyRequest,
+        test_net: bool,
+        interval: Duration,
+        jitter: Duration,
+        sender: EventSender,
+        listen_key_renewal: Arc<ListenKeyRenewal>,
         stop_signal: Arc<AtomicBool>,
     ) {
-        thread::spawn(move || {
-            Self::open_ws_con(url, sender, config, subscribe_payload, stop_signal);
-        });
+        while wait(refresh_delay(interval, jitter), &stop_signal) {
+            let _span = info_span!("futures_usd_listen_key_refresh", test_net).entered();
+            let mut backoff: Duration = LISTEN_KEY_REFRESH_BACKOFF;
+            let mut refreshed: bool = false;
+            for attempt in 1..=LISTEN_KEY_REFRESH_ATTEMPTS {
+                match listen_key.create() {
+                    Ok(_) => {
+                        info!("futures_usd listen key refreshed");
+                        refreshed = true;
+                        break;
+                    }
+                    Err(err) if attempt < LISTEN_KEY_REFRESH_ATTEMPTS => {
+                        warn!(attempt, backoff = ?backoff, error = %err, "futures_usd listen key refresh failed, retrying");
+                        if !wait(backoff, &stop_signal) {
+                            return;
+                        }
+                        backoff *= 2;
+                    }
+                    Err(err) => {
+                        error!(attempt, error = %err, "futures_usd listen key refresh failed");
+                        let _ = sender.send(Event::ListenKeyRefreshFailedEvent(
+                            ListenKeyRefreshFailed {
+                                attempts: attempt,
+                                error: err.to_string(),
+                            },
+                        ));
+                    }
+                }
+            }
+            if refreshed {
+                continue;
+            }
+            // The listen key expires without refresh, reconnect with a fresh one.
+            match listen_key.create() {
+                Ok(listen_key) => {
+                    info!("futures_usd listen key renewed");
+                    listen_key_renewal.renew(listen_key.key);
+                }
+                Err(err) => error!(error = %err, "futures_usd listen key renewal failed"),
+            }
+        }
     }
 
-    /// Opens a WebSocket connection and handles reconnection in case of errors.
-    ///
-    /// This function establishes a WebSocket connection using the provided URL and WebSocket configuration.
-    /// It also handles automatic reconnection in the case of connection errors, if the `reconnect` option
-    /// is enabled in the configuration.
+    /// Emits a `CandleBoundaryEvent` at every boundary of the intervals on its own thread, until
+    /// the stop signal is set or the receiver is dropped.
     ///
     /// # Arguments
     ///
-    /// - `url`: The WebSocket URL to connect to.
-    /// - `sender`: A `Sender<Event>` for sending WebSocket events to the calling code.
-    /// - `config`: The WebSocket configuration, including options for reconnecting.
-    /// - `subscribe_payload`: An optional subscription payload to send upon connection.
+    /// - `intervals`: The intervals of the candles.
+    /// - `sender`: The `EventSender` the boundaries are sent to.
+    /// - `stop_signal`: The stop signal of the stream.
     ///
-    fn open_ws_con(
-        url: Url,
-        sender: Sender<Event>,
-        config: FuturesWebSocketConfig,
-        subscribe_payload: Option<String>,
+    fn schedule_candle_boundaries(
+        intervals: Vec<KlineInterval>,
+        sender: EventSender,
         stop_signal: Arc<AtomicBool>,
     ) {
-        let result: Result<(), BinanceConnectError> = client(
-            sender.clone(),
-            url.clone(),
-            Arc::clone(&stop_signal),
-            config.would_block_config.clone(),
-            subscribe_payload.clone(),
-        );
-        if let Err(err) = result {
-            if config.reconnect && matches!(err, BinanceConnectError::SocketError(_)) {
-                info!("Reconnecting on SocketError: {:?}", err.to_string());
-                thread::sleep(Duration::from_millis(100));
-                Self::open_ws_con(
-                    url,
-                    sender,
-                    config,
-                    subscribe_payload,
-                    Arc::clone(&stop_signal),
-                );
-            } else {
-                panic!("futures_usd thread panicked {:?}", err.to_string());
+        let now: i64 = server_now() as i64;
+        let mut pending: Vec<CandleBoundary> = intervals
+            .into_iter()
+            .map(|interval| CandleBoundary::after(interval, now))
+            .collect();
+        loop {
+            let close_time: i64 = match pending.iter().map(|boundary| boundary.close_time).min() {
+                Some(close_time) => close_time,
+                None => return,
+            };
+            // The clock offset may change while waiting, the server time is checked again after.
+            let now: i64 = server_now() as i64;
+            if now < close_time {
+                if !wait(
+                    Duration::from_millis((close_time - now) as u64),
+                    &stop_signal,
+                ) {
+                    return;
+                }
+                continue;
+            }
+            for boundary in pending.iter_mut() {
+                if boundary.close_time <= now {
+                    let closed: CandleBoundary =
+                        std::mem::replace(boundary, CandleBoundary::after(boundary.interval, now));
+                    if sender.send(Event::CandleBoundaryEvent(closed)).is_err() {
+                        return;
+                    }
+                }
             }
         }
     }
 
-    /// Consumes the current instance and returns the event receiver.
+    /// Spreads the streams over connections of at most `max_streams_per_connection` streams.
     ///
-    /// This function transfers ownership of the current instance to the caller and provides
-    /// access to the event receiver, allowing the caller to receive WebSocket events.
+    /// The user data stream (listen key) counts as a stream of the first connection. Every other
+    /// connection uses its first stream in the URL and subscribes to the rest.
     ///
     /// # Returns
     ///
-    /// A `Receiver<Event>` that can be used to receive WebSocket events.
+    /// The stream in the WebSocket URL (empty for the user data connection) and the streams
+    /// subscribed to after connecting of every connection.
+    ///
+    fn shards(&mut self) -> Vec<(String, Vec<String>)> {
+        let mut streams: Vec<Streams> = std::mem::take(&mut self.streams_public);
+        let max_streams: usize = self.config.max_streams();
+        let mut shards: Vec<(String, Vec<String>)> = Vec::new();
+        if self.authenticated {
+            let shard: Vec<Streams> = streams
+                .drain(..streams.len().min(max_streams - 1))
+                .collect();
+            // The listen key is set on the connection thread once created.
+            shards.push((String::new(), self.stream_names_of(&shard)));
+        }
+        while !streams.is_empty() {
+            let mut shard: Vec<Streams> = streams.drain(..streams.len().min(max_streams)).collect();
+            if let Some(stream) = shard.pop() {
+                shards.push((self.stream_name(&stream), self.stream_names_of(&shard)));
+            }
+        }
+        if shards.is_empty() {
+            panic!("Can't start unauthenticated ws connection without at least 1 futures_usd")
+        }
+        shards
+    }
+
+    /// Returns the Binance stream names of the streams, subscribed to after connecting.
+    fn stream_names_of(&self, streams: &[Streams]) -> Vec<String> {
+        streams
+            .iter()
+            .map(|stream| self.stream_name(stream))
+            .collect()
+    }
+
+    /// Returns the Binance symbol of an aliased symbol, or the symbol itself.
+    fn binance_symbol(&self, symbol: &str) -> String {
+        match &self.config.symbol_aliases {
+            Some(symbol_aliases) => symbol_aliases.to_binance(symbol),
+            None => symbol.to_string(),
+        }
+    }
+
+    /// Returns the name of the stream with an aliased symbol replaced by its Binance symbol.
+    fn stream_name(&self, stream: &Streams) -> String {
+        match &self.config.symbol_aliases {
+            Some(symbol_aliases) => symbol_aliases.stream_to_binance(stream.to_str()),
+            None => stream.to_str().to_string(),
+        }
+    }
+
+    /// Adds the streams to the current instance, e.g. a list built from a config file.
     ///
-    pub fn consume(&self) -> &Receiver<Event> {
-        &self.receiver
+    /// # Arguments
+    ///
+    /// - `streams`: The streams, e.g. `vec![Streams::book_ticker("btcusdt"), Streams::tickers()]`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the streams added.
+    ///
+    pub fn with_streams(mut self, streams: Vec<Streams>) -> Self {
+        self.streams_public.extend(streams);
+        self
     }
 
-    /// Retrieves and manages the listen key used for WebSocket authentication.
+    /// Adds a stream of every perpetual contract that is currently trading, resolved from the
+    /// cached `ExchangeInfo`. The streams are spread over connections like any other, see
+    /// `with_max_streams_per_connection`.
+    ///
+    /// # Arguments
     ///
-    /// This function is responsible for obtaining the listen key and setting up automatic
-    /// refreshes at a fixed interval to maintain WebSocket authentication.
+    /// - `stream`: Creates the stream of a symbol, e.g. `Streams::book_ticker`.
     ///
-    fn listen_key(&mut self) {
-        let api_auth: &Option<ApiAuth> = &self.config.api_auth;
-        if let Some(api_auth) = api_auth {
-            self.authenticated = true;
-            let listen_key = get_listen_key(api_auth, self.config.testnet)
-                .unwrap_or_else(|err| panic!("{:?}", err));
-            self.listen_key = listen_key;
-            info!("{:?}", self.listen_key);
-            task::spawn(Self::refresh_listen_key(
-                api_auth.clone(),
-                self.config.testnet,
-            ));
-        }
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the streams added or a `BinanceConnectError` if the
+    /// `ExchangeInfo` request failed.
+    ///
+    pub fn with_all_perpetuals(
+        mut self,
+        stream: impl Fn(&str) -> Streams,
+    ) -> Result<Self, BinanceConnectError> {
+        let perpetuals: Vec<String> = self.config.perpetuals()?;
+        info!(
+            perpetuals = perpetuals.len(),
+            "futures_usd subscribing to all perpetuals"
+        );
+        self.streams_public
+            .extend(perpetuals.iter().map(|symbol| stream(symbol)));
+        Ok(self)
     }
 
-    /// Asynchronously refreshes the listen key used for WebSocket authentication.
+    /// Adds an aggregated trade stream of every perpetual contract, see `with_all_perpetuals`.
     ///
-    /// This function continually refreshes the listen key at a fixed interval to ensure the WebSocket
-    /// connection remains authenticated.
+    /// # Returns
     ///
-    /// # Arguments
+    /// A modified instance of the struct with the streams added or a `BinanceConnectError` if the
+    /// `ExchangeInfo` request failed.
     ///
-    /// - `api_auth`: An `ApiAuth` struct containing API authentication information.
-    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    pub fn with_agg_trades_for_all_perpetuals(self) -> Result<Self, BinanceConnectError> {
+        self.with_all_perpetuals(Streams::agg_trade)
+    }
+
+    /// Adds a book ticker stream of every perpetual contract, see `with_all_perpetuals`.
     ///
-    async fn refresh_listen_key(api_auth: ApiAuth, test_net: bool) {
-        loop {
-            sleep(Duration::from_secs(3000)).await;
-            get_listen_key(&api_auth, test_net)
-                .map(|listen_key| {
-                    info!("listen_key refreshed {}", listen_key.key);
-                })
-                .unwrap_or_else(|err| {
-                    error!("could not refresh listen_key {:?}", err);
-                });
-        }
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the streams added or a `BinanceConnectError` if the
+    /// `ExchangeInfo` request failed.
+    ///
+    pub fn with_book_tickers_for_all_perpetuals(self) -> Result<Self, BinanceConnectError> {
+        self.with_all_perpetuals(Streams::book_ticker)
     }
 
-    /// Generates the WebSocket URL for establishing a connection to the Binance WebSocket API.
+    /// Adds a kline stream of every perpetual contract, see `with_all_perpetuals`.
     ///
-    /// This function constructs the WebSocket URL based on the current configuration and the selected streams.
-    /// If the connection is authenticated, it uses the listen key as part of the URL. If not authenticated,
-    /// it requires at least one public stream to be selected.
+    /// # Arguments
+    ///
+    /// - `kline_interval`: The interval of the klines.
     ///
     /// # Returns
     ///
-    /// A `Url` instance representing the WebSocket URL.
+    /// A modified instance of the struct with the streams added or a `BinanceConnectError` if the
+    /// `ExchangeInfo` request failed.
     ///
-    fn url(&mut self) -> Url {
-        match self.authenticated {
-            true => Url::parse(&format!(
-                "{}ws/{}",
-                self.config.get_url(),
-                self.listen_key.key,
-            ))
-            .unwrap(),
-            false => {
-                let stream: Option<Streams> = self.streams_public.pop();
-                match stream {
-                    Some(stream) => Url::parse(
-                        format!("{}ws/{}", self.config.get_url(), stream.to_str()).as_str(),
-                    )
-                    .unwrap(),
-                    None => panic!(
-                        "Can't start unauthenticated ws connection without at least 1 futures_usd"
-                    ),
-                }
-            }
-        }
+    pub fn with_klines_for_all_perpetuals(
+        self,
+        kline_interval: KlineInterval,
+    ) -> Result<Self, BinanceConnectError> {
+        self.with_all_perpetuals(|symbol| Streams::kline(symbol, kline_interval))
     }
 
-    /// Generates a subscription payload for the current instance.
+    /// Subscribes to the given streams of every contract listed at runtime, detected from the
+    /// contract info stream (added when missing): a `NewListingEvent` is emitted for every contract
+    /// whose status changes to `TRADING` that was not trading when the stream was started or
+    /// since. The streams are spread over the connections like runtime subscriptions, see
+    /// `subscribe`, which is not supported for streams attached to a `ConnectionManager`.
+    ///
+    /// The trading contracts are taken from the cached `ExchangeInfo` on start, new listing
+    /// detection is disabled (and an error logged) if the `ExchangeInfo` request fails.
+    ///
+    /// # Arguments
+    ///
+    /// - `streams`: The streams subscribed to for every new listing, e.g. `Streams::agg_trade`.
     ///
     /// # Returns
     ///
-    /// - `Some(String)`: A JSON payload for subscribing to the streams.
-    /// - `None`: If there are no streams to subscribe to.
-    fn subscribe_payload(&self) -> Option<String> {
-        if self.streams_public.is_empty() {
-            return None;
+    /// A modified instance of the struct with new listing detection enabled.
+    ///
+    pub fn with_new_listings(mut self, streams: Vec<ListingStream>) -> Self {
+        self.listing_streams = Some(streams);
+        if !self
+            .streams_public
+            .iter()
+            .any(|stream| matches!(stream, Streams::ContractInfo(_)))
+        {
+            self.streams_public.push(Streams::contract_info());
         }
-        Some(format!(
-            "{{\"method\": \"SUBSCRIBE\",\"params\":[{}],\"id\": 1}}",
-            self.streams_public
-                .iter()
-                .map(|item| format!("\"{}\"", item.to_str()))
-                .collect::<Vec<String>>()
-                .join(",")
-        ))
+        self
     }
 
     /// Adds a book ticker stream to the current instance.
@@ -481,6 +2465,25 @@ impl FuturesUsdStream {
         self
     }
 
+    /// Adds a Kline/candlestick chart stream for a specific symbol and interval to the current
+    /// instance, delivering only the closed klines (`is_kline_closed == true`). The updates of
+    /// open klines are dropped before they are deserialized.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: A string representing the trading symbol for which the Kline stream should be added.
+    /// - `kline_interval`: A `KlineInterval` enum value specifying the interval for Kline data.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the closed-only Kline stream added.
+    ///
+    pub fn with_kline_closed_only(mut self, symbol: &str, kline_interval: KlineInterval) -> Self {
+        self.kline_filter
+            .allow(&self.binance_symbol(symbol), &kline_interval);
+        self.with_kline(symbol, kline_interval)
+    }
+
     /// Adds a continuous Kline/candlestick chart stream for a specific symbol, contract type, and interval to the current instance.
     ///
     /// # Arguments
@@ -585,13 +2588,60 @@ impl FuturesUsdStream {
         self
     }
 
+    /// Drops force order (liquidation) events with a notional (price × quantity) below the threshold.
+    ///
+    /// The filter applies to the streams added by `with_force_order` and `with_force_orders`.
+    ///
+    /// # Arguments
+    ///
+    /// - `min_notional`: The minimum notional in USD a force order needs to be forwarded.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the force order notional filter set.
+    ///
+    #[cfg(feature = "market-data")]
+    pub fn with_force_orders_min_notional(mut self, min_notional: f64) -> Self {
+        self.force_order_min_notional = Some(min_notional);
+        self
+    }
+
+    /// Adds a stream type for multiple symbols, using the all-market stream (filtered to the
+    /// symbols) from the configured threshold on and one stream per symbol below it, see
+    /// `MultiSymbolStream`.
+    ///
+    /// # Arguments
+    ///
+    /// - `stream`: A `MultiSymbolStream` enum value specifying the stream type.
+    /// - `symbols`: The trading symbols for which the stream should be added.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the streams added.
+    ///
+    pub fn with_multi_symbol(mut self, stream: MultiSymbolStream, symbols: &[&str]) -> Self {
+        let symbols: Vec<String> = symbols
+            .iter()
+            .map(|symbol| self.binance_symbol(symbol))
+            .collect();
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let streams: Vec<Streams> = stream.expand(
+            &symbols,
+            self.config.all_market_threshold,
+            &mut self.symbol_filter,
+        );
+        self.streams_public.extend(streams);
+        self
+    }
+
     /// Adds a partial book depth stream for a specific trading symbol with specified depth level and update speed to the current instance.
     ///
     /// # Arguments
     ///
     /// - `symbol`: A string representing the trading symbol for which the partial book depth stream should be added.
     /// - `book_depth_level`: A `PartialBookDepthLevel` enum value specifying the depth level for the partial book depth data.
-    /// - `book_depth_update_speed`: A `BookDepthUpdateSpeed` enum value specifying the update speed for the book depth data.
+    /// - `book_depth_update_speed`: A `BookDepthUpdateSpeed` enum value specifying the update speed for the book depth data,
+    ///   `BookDepthUpdateSpeed::default()` (250ms) subscribes to the stream without a speed suffix.
     ///
     /// # Returns
     ///
@@ -616,7 +2666,8 @@ impl FuturesUsdStream {
     /// # Arguments
     ///
     /// - `symbol`: A string representing the trading symbol for which the full book depth stream should be added.
-    /// - `book_depth_update_speed`: A `BookDepthUpdateSpeed` enum value specifying the update speed for the book depth data.
+    /// - `book_depth_update_speed`: A `BookDepthUpdateSpeed` enum value specifying the update speed for the book depth data,
+    ///   `BookDepthUpdateSpeed::default()` (250ms) subscribes to the stream without a speed suffix.
     ///
     /// # Returns
     ///
@@ -632,6 +2683,59 @@ impl FuturesUsdStream {
         self
     }
 
+    /// Adds a partial book depth stream after validating the combination against the levels and
+    /// update speeds Binance accepts, instead of letting Binance silently ignore the subscription.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: A string representing the trading symbol for which the partial book depth stream should be added.
+    /// - `levels`: The number of bids and asks, 5, 10 or 20.
+    /// - `update_speed_millis`: The update speed in milliseconds, 100, 250 (the default) or 500.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the partial book depth stream added, or a
+    /// `BinanceConnectError::InvalidStreamConfig` if the combination is not accepted by Binance.
+    ///
+    pub fn try_with_partial_book_depth(
+        self,
+        symbol: &str,
+        levels: u16,
+        update_speed_millis: u64,
+    ) -> Result<Self, BinanceConnectError> {
+        validate_symbol(&self.binance_symbol(symbol), "partial book depth")?;
+        Ok(self.with_partial_book_depth(
+            symbol,
+            PartialBookDepthLevel::from_levels(levels)?,
+            BookDepthUpdateSpeed::from_millis(update_speed_millis)?,
+        ))
+    }
+
+    /// Adds a full book depth stream after validating the update speed against the update speeds
+    /// Binance accepts.
+    ///
+    /// # Arguments
+    ///
+    /// - `symbol`: A string representing the trading symbol for which the full book depth stream should be added.
+    /// - `update_speed_millis`: The update speed in milliseconds, 100, 250 (the default) or 500.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the full book depth stream added, or a
+    /// `BinanceConnectError::InvalidStreamConfig` if the update speed is not accepted by Binance.
+    ///
+    pub fn try_with_book_depth(
+        self,
+        symbol: &str,
+        update_speed_millis: u64,
+    ) -> Result<Self, BinanceConnectError> {
+        validate_symbol(&self.binance_symbol(symbol), "book depth")?;
+        Ok(self.with_book_depth(
+            symbol,
+            BookDepthUpdateSpeed::from_millis(update_speed_millis)?,
+        ))
+    }
+
     /// Adds a composite index stream for a specific trading symbol to the current instance.
     ///
     /// # Arguments
@@ -684,4 +2788,299 @@ impl FuturesUsdStream {
         self.streams_public.push(Streams::asset_index_updates());
         self
     }
+
+    /// Adds a stream by its Binance name, for streams without first-class type in this crate.
+    /// Its payloads are emitted as `Event::Raw`, the stream name is only known when the connection
+    /// uses the combined format (see `set_property`).
+    ///
+    /// # Arguments
+    ///
+    /// - `stream`: The Binance stream name, e.g. `btcusdt@someNewStream`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the raw stream added.
+    ///
+    pub fn with_raw_stream(mut self, stream: &str) -> Self {
+        self.streams_public.push(Streams::raw(stream));
+        self
+    }
+}
+
+/// A connection opened by a stream with the streams it is subscribed to.
+#[derive(Debug)]
+struct Shard {
+    outbox: Arc<Outbox>,
+    /// The Binance stream names the connection is subscribed to.
+    streams: Vec<String>,
+    /// The maximum number of streams, the user data stream takes one of the first connection.
+    capacity: usize,
+}
+
+/// The connections opened by a started `FuturesUsdStream`, shared with the connections so the
+/// streams of new listings are spread like the runtime subscriptions.
+///
+/// Every connection has its own status, statistics and outbox. A subscription goes to the first
+/// connection with capacity for all of its streams, see
+/// `FuturesWebSocketConfig::with_max_streams_per_connection`, or to a new connection when every
+/// connection is full.
+#[derive(Debug)]
+pub(crate) struct Shards {
+    config: FuturesWebSocketConfig,
+    /// The context the contexts of the connections are created from.
+    context: ConnectionContext,
+    status: Arc<ConnectorStatus>,
+    stats: Arc<Mutex<Vec<Arc<ConnectionStats>>>>,
+    downtime_reports: Arc<Mutex<Vec<DowntimeReport>>>,
+    listen_key_renewal: Arc<ListenKeyRenewal>,
+    connections: Mutex<Vec<Shard>>,
+}
+
+impl Shards {
+    /// Creates the shards of a stream without connections.
+    ///
+    /// # Arguments
+    ///
+    /// - `config`: The configuration of the stream.
+    /// - `context`: The context the contexts of the connections are created from.
+    /// - `status`: The status of the stream, aggregating the status of every connection.
+    /// - `stats`: The statistics of the connections, in connection order.
+    /// - `downtime_reports`: The downtime reports of the stream.
+    /// - `listen_key_renewal`: Hands the fresh listen key to the user data connection.
+    ///
+    pub(crate) fn new(
+        config: FuturesWebSocketConfig,
+        context: ConnectionContext,
+        status: Arc<ConnectorStatus>,
+        stats: Arc<Mutex<Vec<Arc<ConnectionStats>>>>,
+        downtime_reports: Arc<Mutex<Vec<DowntimeReport>>>,
+        listen_key_renewal: Arc<ListenKeyRenewal>,
+    ) -> Shards {
+        Shards {
+            config,
+            context,
+            status,
+            stats,
+            downtime_reports,
+            listen_key_renewal,
+            connections: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Creates the context of a new connection, registering the status and statistics of the
+    /// connection.
+    pub(crate) fn context(self: &Arc<Self>) -> ConnectionContext {
+        let mut context: ConnectionContext = self.context.clone();
+        // Every connection has its own status, aggregated by the status of the stream.
+        context.status = self.status.connection();
+        let mut stats = self.stats.lock().unwrap();
+        context.stats = Arc::new(ConnectionStats::new(stats.len()));
+        stats.push(Arc::clone(&context.stats));
+        // Every connection queues its own messages and tracks its own streams.
+        context.outbox = Arc::new(Outbox::default());
+        if self.context.downtime.is_some() {
+            context.downtime = Some(Arc::new(DowntimeTracker::new(Arc::clone(
+                &self.downtime_reports,
+            ))));
+        }
+        context.update_id_filter = self
+            .config
+            .duplicate_window
+            .map(|window| Arc::new(Mutex::new(UpdateIdFilter::new(window))));
+        context.shards = Some(Arc::clone(self));
+        context
+    }
+
+    /// Opens a connection and, unless it is the user data connection, its redundant twin when
+    /// configured.
+    ///
+    /// # Arguments
+    ///
+    /// - `url_stream`: The stream in the WebSocket URL, empty for the user data connection.
+    /// - `streams`: The streams subscribed to after connecting.
+    /// - `outbox`: The outbox of the connection.
+    /// - `listen_key`: The request creating the listen key of the user data connection.
+    ///
+    pub(crate) fn open(
+        self: &Arc<Self>,
+        url_stream: &str,
+        streams: Vec<String>,
+        outbox: Arc<Outbox>,
+        listen_key: Option<ListenKeyRequest>,
+    ) {
+        let user_data: bool = listen_key.is_some();
+        let mut shard: Shard = Shard {
+            outbox: Arc::clone(&outbox),
+            streams: streams.clone(),
+            capacity: self.config.max_streams() - usize::from(user_data),
+        };
+        if !user_data {
+            shard.streams.push(url_stream.to_string());
+        }
+        self.connections.lock().unwrap().push(shard);
+        let subscribe_payload: Option<String> = outbox.connect_payload(streams.clone());
+        self.connect(
+            self.config.stream_url(url_stream),
+            streams,
+            outbox,
+            subscribe_payload,
+            listen_key,
+        );
+    }
+
+    /// Subscribes to the streams on the first connection with capacity for all of them, or on a
+    /// new connection when every connection is full. Streams already subscribed to on a
+    /// connection are not subscribed to again.
+    ///
+    /// # Arguments
+    ///
+    /// - `streams`: The Binance stream names.
+    ///
+    /// # Returns
+    ///
+    /// The id of the request, `None` when no streams are given.
+    ///
+    pub(crate) fn subscribe(self: &Arc<Self>, streams: Vec<String>) -> Option<u64> {
+        if streams.is_empty() {
+            return None;
+        }
+        let mut connections = self.connections.lock().unwrap();
+        let new_streams: Vec<String> = streams
+            .iter()
+            .filter(|stream| {
+                !connections
+                    .iter()
+                    .any(|connection| connection.streams.contains(stream))
+            })
+            .cloned()
+            .collect();
+        // Every stream is subscribed to already, the connection of the first one answers.
+        if new_streams.is_empty() {
+            return connections
+                .iter()
+                .find(|connection| connection.streams.contains(&streams[0]))
+                .map(|connection| connection.outbox.subscribe(streams));
+        }
+        if let Some(connection) = connections
+            .iter_mut()
+            .find(|connection| connection.streams.len() + new_streams.len() <= connection.capacity)
+        {
+            connection.streams.extend(new_streams.iter().cloned());
+            return Some(connection.outbox.subscribe(new_streams));
+        }
+        if new_streams.len() > self.config.max_streams() {
+            warn!(
+                streams = new_streams.len(),
+                "futures_usd subscribing to more streams than allowed per connection"
+            );
+        }
+        // The stream in the URL is subscribed to again, so the request covers every stream.
+        let outbox: Arc<Outbox> = Arc::new(Outbox::default());
+        let id: u64 = outbox.subscribe(new_streams.clone());
+        self.connect(
+            self.config.stream_url(&new_streams[0]),
+            new_streams[1..].to_vec(),
+            Arc::clone(&outbox),
+            None,
+            None,
+        );
+        connections.push(Shard {
+            outbox,
+            streams: new_streams,
+            capacity: self.config.max_streams(),
+        });
+        Some(id)
+    }
+
+    /// Unsubscribes from the streams on the connections subscribed to them, with a request per
+    /// connection.
+    ///
+    /// # Arguments
+    ///
+    /// - `streams`: The Binance stream names.
+    ///
+    /// # Returns
+    ///
+    /// The id of the first request, `None` when no connection is subscribed to the streams.
+    ///
+    pub(crate) fn unsubscribe(&self, streams: &[String]) -> Option<u64> {
+        let mut request_id: Option<u64> = None;
+        for connection in self.connections.lock().unwrap().iter_mut() {
+            let subscribed: Vec<String> = streams
+                .iter()
+                .filter(|stream| connection.streams.contains(stream))
+                .cloned()
+                .collect();
+            if subscribed.is_empty() {
+                continue;
+            }
+            connection
+                .streams
+                .retain(|stream| !subscribed.contains(stream));
+            let id: u64 = connection.outbox.unsubscribe(subscribed);
+            request_id.get_or_insert(id);
+        }
+        request_id
+    }
+
+    /// Spawns the connection thread (and that of the redundant twin of a public connection).
+    fn connect(
+        self: &Arc<Self>,
+        url: Url,
+        streams: Vec<String>,
+        outbox: Arc<Outbox>,
+        subscribe_payload: Option<String>,
+        listen_key: Option<ListenKeyRequest>,
+    ) {
+        let mut context: ConnectionContext = self.context();
+        context.outbox = outbox;
+        if listen_key.is_some() {
+            context.listen_key_renewal = Some(Arc::clone(&self.listen_key_renewal));
+            if self.config.account_snapshot {
+                let base_url: String = self
+                    .config
+                    .rest_base_url
+                    .clone()
+                    .unwrap_or_else(|| base_url(self.config.testnet()).to_string());
+                context.account_snapshot = self.config.api_auth.clone().map(|api_auth| {
+                    Arc::new(SnapshotRequest {
+                        api_auth,
+                        base_url,
+                        last_snapshot: Mutex::new(None),
+                    })
+                });
+            }
+        }
+        // The public connections are paired with a redundant twin, the first copy is forwarded.
+        if self.config.redundant_connections && listen_key.is_none() {
+            let mut twin: ConnectionContext = self.context();
+            let redundant_filter: Arc<Mutex<RedundantFilter>> =
+                Arc::new(Mutex::new(RedundantFilter::default()));
+            let update_id_filter: Arc<Mutex<UpdateIdFilter>> =
+                Arc::new(Mutex::new(UpdateIdFilter::redundant()));
+            context.redundant_filter = Some((Arc::clone(&redundant_filter), 0));
+            context.update_id_filter = Some(Arc::clone(&update_id_filter));
+            twin.redundant_filter = Some((redundant_filter, 1));
+            twin.update_id_filter = Some(update_id_filter);
+            let twin_url: Url = match &self.config.redundant_endpoint {
+                Some(endpoint) => with_endpoint(&url, &endpoint.ws_url()),
+                None => url.clone(),
+            };
+            let twin_payload: Option<String> = twin.outbox.connect_payload(streams);
+            FuturesUsdStream::ws_conn_thread(
+                twin_url,
+                self.config.clone(),
+                twin_payload,
+                twin,
+                None,
+            );
+        }
+        FuturesUsdStream::ws_conn_thread(
+            url,
+            self.config.clone(),
+            subscribe_payload,
+            context,
+            listen_key,
+        );
+    }
 }
@@ -0,0 +1,222 @@
+use crate::error::BinanceConnectError;
+use crate::futures_usd::deserializer::deserialize_slice;
+use crate::futures_usd::enums::binance::*;
+use crate::futures_usd::enums::events::{Event, EventType};
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::{AggTrade, BookTicker, MarkPriceUpdate, MiniTicker, Ticker};
+#[cfg(feature = "klines")]
+use crate::futures_usd::response::{Kline, KlineData};
+#[cfg(feature = "user-data")]
+use crate::futures_usd::response::{OrderData, OrderTradeUpdate};
+
+/// The fixture of the `BookTicker` builder.
+#[cfg(feature = "market-data")]
+const BOOK_TICKER: &str = r#"{"e":"bookTicker","u":400900217,"E":1568014460893,"T":1568014460891,"s":"BTCUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+/// The fixture of the `AggTrade` builder.
+#[cfg(feature = "market-data")]
+const AGG_TRADE: &str = r#"{"e":"aggTrade","E":123456789,"s":"BTCUSDT","a":5933014,"p":"0.001","q":"100","f":100,"l":105,"T":123456785,"m":true}"#;
+/// The fixture of the `MarkPriceUpdate` builder.
+#[cfg(feature = "market-data")]
+const MARK_PRICE_UPDATE: &str = r#"{"e":"markPriceUpdate","E":1562305380000,"s":"BTCUSDT","p":"11794.15000000","i":"11784.62659091","P":"11784.25641265","r":"0.00038167","T":1562306400000}"#;
+/// The fixture of the `KlineData` builder.
+#[cfg(feature = "klines")]
+const KLINE_DATA: &str = r#"{"t":1638747660000,"T":1638747719999,"s":"BTCUSDT","i":"1m","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}"#;
+/// The fixture of the `Kline` builder, the kline data is set from the `KlineData` fixture.
+#[cfg(feature = "klines")]
+const KLINE: &str = r#"{"e":"kline","E":1638747660000,"s":"BTCUSDT","k":{"t":1638747660000,"T":1638747719999,"s":"BTCUSDT","i":"1m","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}}"#;
+/// The fixture of the `MiniTicker` builder.
+#[cfg(feature = "market-data")]
+const MINI_TICKER: &str = r#"{"e":"24hrMiniTicker","E":123456789,"s":"BTCUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"}"#;
+/// The fixture of the `Ticker` builder.
+#[cfg(feature = "market-data")]
+const TICKER: &str = r#"{"e":"24hrTicker","E":123456789,"s":"BTCUSDT","p":"0.0015","P":"250.00","w":"0.0018","c":"0.0025","Q":"10","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18","O":0,"C":86400000,"F":0,"L":18150,"n":18151}"#;
+/// The fixture of the `OrderData` builder.
+#[cfg(feature = "user-data")]
+const ORDER_DATA: &str = r#"{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}"#;
+/// The fixture of the `OrderTradeUpdate` builder.
+#[cfg(feature = "user-data")]
+const ORDER_TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}}"#;
+
+/// Generates a builder starting from a fixture, with a setter per field.
+macro_rules! builder {
+    ($builder:ident, $target:ident, $fixture:expr, { $($field:ident: $ty:ty),* $(,)? }) => {
+        #[doc = concat!("A builder of a `", stringify!($target), "`, starting from a realistic fixture.")]
+        #[derive(Debug)]
+        pub struct $builder($target);
+
+        impl $target {
+            #[doc = concat!("Returns a builder of a `", stringify!($target), "` for unit tests.")]
+            pub fn builder() -> $builder {
+                $builder(serde_json::from_str($fixture).expect("the fixture is valid"))
+            }
+        }
+
+        impl $builder {
+            $(
+                #[doc = concat!("Sets the `", stringify!($field), "`.")]
+                pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+                    self.0.$field = $field.into();
+                    self
+                }
+            )*
+
+            #[doc = concat!("Returns the `", stringify!($target), "`.")]
+            pub fn build(self) -> $target {
+                self.0
+            }
+        }
+    };
+}
+
+#[cfg(feature = "market-data")]
+builder!(BookTickerBuilder, BookTicker, BOOK_TICKER, {
+    event_type: EventType,
+    event_time: u64,
+    symbol: String,
+    update_id: u64,
+    bid_price: f64,
+    bid_quantity: f64,
+    ask_price: f64,
+    ask_quantity: f64,
+    transaction_time: u64,
+});
+
+#[cfg(feature = "market-data")]
+builder!(AggTradeBuilder, AggTrade, AGG_TRADE, {
+    event_type: EventType,
+    event_time: u64,
+    symbol: String,
+    agg_trade_id: u64,
+    price: f64,
+    quantity: f64,
+    first_trade_id: u64,
+    last_trade_id: u64,
+    trade_time: u64,
+    buyer_is_market_maker: bool,
+});
+
+#[cfg(feature = "market-data")]
+builder!(MarkPriceUpdateBuilder, MarkPriceUpdate, MARK_PRICE_UPDATE, {
+    event_type: EventType,
+    event_time: i64,
+    symbol: String,
+    mark_price: f64,
+    index_price: f64,
+    estimated_settle_price: f64,
+    funding_rate: f64,
+    next_funding_time: i64,
+});
+
+#[cfg(feature = "klines")]
+builder!(KlineDataBuilder, KlineData, KLINE_DATA, {
+    kline_start_time: i64,
+    kline_close_time: i64,
+    kline_symbol: String,
+    interval: KlineInterval,
+    first_trade_id: i64,
+    last_trade_id: i64,
+    open_price: f64,
+    close_price: f64,
+    high_price: f64,
+    low_price: f64,
+    base_asset_volume: f64,
+    number_of_trades: i64,
+    is_kline_closed: bool,
+    quote_asset_volume: f64,
+    taker_buy_base_asset_volume: f64,
+    taker_buy_quote_asset_volume: f64,
+});
+
+#[cfg(feature = "klines")]
+builder!(KlineBuilder, Kline, KLINE, {
+    event_type: EventType,
+    event_time: i64,
+    symbol: String,
+    kline_data: KlineData,
+});
+
+#[cfg(feature = "market-data")]
+builder!(MiniTickerBuilder, MiniTicker, MINI_TICKER, {
+    event_type: EventType,
+    event_time: u64,
+    symbol: String,
+    close_price: f64,
+    open_price: f64,
+    high_price: f64,
+    low_price: f64,
+    total_traded_base_asset_volume: f64,
+    total_traded_quote_asset_volume: f64,
+});
+
+#[cfg(feature = "market-data")]
+builder!(TickerBuilder, Ticker, TICKER, {
+    event_type: EventType,
+    event_time: u64,
+    symbol: String,
+    price_change: f64,
+    price_change_percent: f64,
+    weighted_avg_price: f64,
+    last_price: f64,
+    last_quantity: f64,
+    open_price: f64,
+    high_price: f64,
+    low_price: f64,
+    total_traded_base_asset_volume: f64,
+    total_traded_quote_asset_volume: f64,
+    statistics_open_time: u64,
+    statistics_close_time: u64,
+    first_trade_id: u64,
+    last_trade_id: u64,
+    total_number_of_trades: u64,
+});
+
+#[cfg(feature = "user-data")]
+builder!(OrderDataBuilder, OrderData, ORDER_DATA, {
+    symbol: String,
+    client_order_id: String,
+    side: Side,
+    order_type: OrderType,
+    time_in_force: TimeInForce,
+    original_quantity: f64,
+    original_price: f64,
+    average_price: f64,
+    stop_price: f64,
+    execution_type: ExecutionType,
+    order_status: OrderStatus,
+    order_id: i64,
+    order_last_filled_quantity: f64,
+    order_filled_accumulated_quantity: f64,
+    last_filled_price: f64,
+    commission_asset: String,
+    commission: f64,
+    order_trade_time: i64,
+    trade_id: i64,
+    is_trade_maker_side: bool,
+    is_reduce_only: bool,
+    position_side: PositionSide,
+    realized_profit: f64,
+});
+
+#[cfg(feature = "user-data")]
+builder!(OrderTradeUpdateBuilder, OrderTradeUpdate, ORDER_TRADE_UPDATE, {
+    event_type: EventType,
+    event_time: i64,
+    transaction_time: i64,
+    order_data: OrderData,
+});
+
+impl Event {
+    /// Deserializes a raw Binance payload into an `Event`, like the streams do.
+    ///
+    /// # Arguments
+    ///
+    /// - `json`: The JSON payload, e.g. `{"e":"bookTicker",...}`.
+    ///
+    /// # Returns
+    ///
+    /// The `Event` or a `BinanceConnectError` if the payload is invalid.
+    ///
+    pub fn from_json(json: &str) -> Result<Event, BinanceConnectError> {
+        deserialize_slice(json.as_bytes())
+    }
+}
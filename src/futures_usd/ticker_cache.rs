@@ -0,0 +1,103 @@
+#[cfg(feature = "market-data")]
+use std::collections::HashMap;
+#[cfg(feature = "market-data")]
+use std::sync::RwLock;
+
+use crate::futures_usd::enums::events::Event;
+#[cfg(feature = "market-data")]
+use crate::futures_usd::response::{MiniTicker, Ticker};
+
+/// Maintains the most recent `Ticker` and `MiniTicker` per symbol from the (all-market) ticker
+/// streams, so monitoring dashboards can read a snapshot instead of processing every update.
+///
+/// Updates with an event time older than the cached ticker are ignored.
+#[derive(Debug, Default)]
+pub struct TickerCache {
+    #[cfg(feature = "market-data")]
+    tickers: RwLock<HashMap<String, Ticker>>,
+    #[cfg(feature = "market-data")]
+    mini_tickers: RwLock<HashMap<String, MiniTicker>>,
+}
+
+impl TickerCache {
+    /// Creates an empty cache.
+    pub fn new() -> TickerCache {
+        Self::default()
+    }
+
+    /// Applies the ticker and mini ticker events, other events are ignored.
+    pub fn apply(&self, event: &Event) {
+        match event {
+            #[cfg(feature = "market-data")]
+            Event::TickerEvent(ticker) => self.update_tickers(std::slice::from_ref(ticker)),
+            #[cfg(feature = "market-data")]
+            Event::TickersEvent(tickers) => self.update_tickers(&tickers.data),
+            #[cfg(feature = "market-data")]
+            Event::MiniTickerEvent(mini_ticker) => {
+                self.update_mini_tickers(std::slice::from_ref(mini_ticker))
+            }
+            #[cfg(feature = "market-data")]
+            Event::MiniTickersEvent(mini_tickers) => self.update_mini_tickers(&mini_tickers.data),
+            _ => {}
+        }
+    }
+
+    /// Returns the most recent ticker of the symbol.
+    #[cfg(feature = "market-data")]
+    pub fn ticker(&self, symbol: &str) -> Option<Ticker> {
+        self.tickers
+            .read()
+            .unwrap()
+            .get(&symbol.to_uppercase())
+            .cloned()
+    }
+
+    /// Returns the most recent mini ticker of the symbol.
+    #[cfg(feature = "market-data")]
+    pub fn mini_ticker(&self, symbol: &str) -> Option<MiniTicker> {
+        self.mini_tickers
+            .read()
+            .unwrap()
+            .get(&symbol.to_uppercase())
+            .cloned()
+    }
+
+    /// Returns the most recent ticker of every symbol, keyed by symbol.
+    #[cfg(feature = "market-data")]
+    pub fn snapshot(&self) -> HashMap<String, Ticker> {
+        self.tickers.read().unwrap().clone()
+    }
+
+    /// Returns the most recent mini ticker of every symbol, keyed by symbol.
+    #[cfg(feature = "market-data")]
+    pub fn mini_snapshot(&self) -> HashMap<String, MiniTicker> {
+        self.mini_tickers.read().unwrap().clone()
+    }
+
+    /// Stores the tickers unless a newer ticker of the symbol is cached.
+    #[cfg(feature = "market-data")]
+    fn update_tickers(&self, tickers: &[Ticker]) {
+        let mut cached = self.tickers.write().unwrap();
+        for ticker in tickers {
+            if cached
+                .get(&ticker.symbol)
+                .map_or(true, |previous| previous.event_time <= ticker.event_time)
+            {
+                cached.insert(ticker.symbol.clone(), ticker.clone());
+            }
+        }
+    }
+
+    /// Stores the mini tickers unless a newer mini ticker of the symbol is cached.
+    #[cfg(feature = "market-data")]
+    fn update_mini_tickers(&self, mini_tickers: &[MiniTicker]) {
+        let mut cached = self.mini_tickers.write().unwrap();
+        for mini_ticker in mini_tickers {
+            if cached.get(&mini_ticker.symbol).map_or(true, |previous| {
+                previous.event_time <= mini_ticker.event_time
+            }) {
+                cached.insert(mini_ticker.symbol.clone(), mini_ticker.clone());
+            }
+        }
+    }
+}
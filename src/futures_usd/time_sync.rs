@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::listen_key::base_url;
+use crate::futures_usd::rest::get_server_time_from;
+use crate::futures_usd::status::now_millis;
+
+/// The number of `/fapi/v1/time` requests per synchronization, the sample with the lowest
+/// round-trip is used since its offset estimate has the smallest error.
+const SAMPLES_PER_SYNC: usize = 3;
+
+/// The granularity with which the synchronization thread checks its stop signal.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The estimated clock offset (server time - local time) in milliseconds.
+static CLOCK_OFFSET_MS: AtomicI64 = AtomicI64::new(0);
+/// The round-trip in milliseconds of the last synchronization, 0 when never synchronized.
+static ROUND_TRIP_MS: AtomicU64 = AtomicU64::new(0);
+/// The local time (UNIX millis) of the last synchronization, 0 when never synchronized.
+static LAST_SYNC: AtomicU64 = AtomicU64::new(0);
+
+/// The result of a single clock synchronization.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeSample {
+    /// The server time reported by Binance.
+    pub server_time: i64,
+    /// The estimated clock offset (server time - local time) in milliseconds.
+    pub offset_ms: i64,
+    /// The round-trip of the request in milliseconds.
+    pub round_trip_ms: u64,
+}
+
+/// A handle to the periodic synchronization thread, the thread stops when the handle is dropped.
+#[derive(Debug)]
+pub struct TimeSyncHandle {
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl TimeSyncHandle {
+    /// Stops the synchronization thread, the last estimated offset stays in use.
+    pub fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for TimeSyncHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Synchronizes the clock once and spawns a thread that keeps synchronizing it periodically.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `interval`: The time between two synchronizations.
+///
+/// # Returns
+///
+/// A `TimeSyncHandle` controlling the thread, or a `BinanceConnectError` if the initial
+/// synchronization failed.
+///
+pub fn start(test_net: bool, interval: Duration) -> Result<TimeSyncHandle, BinanceConnectError> {
+    sync(test_net)?;
+    let stop_signal: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    let thread_stop_signal: Arc<AtomicBool> = Arc::clone(&stop_signal);
+    thread::spawn(move || {
+        let mut next_sync: Instant = Instant::now() + interval;
+        while !thread_stop_signal.load(Ordering::Relaxed) {
+            if Instant::now() < next_sync {
+                thread::sleep(STOP_CHECK_INTERVAL);
+                continue;
+            }
+            if let Err(err) = sync(test_net) {
+                warn!(error = %err, "futures_usd time sync failed");
+            }
+            next_sync = Instant::now() + interval;
+        }
+    });
+    Ok(TimeSyncHandle { stop_signal })
+}
+
+/// Queries the Binance server time and updates the estimated clock offset and round-trip.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// The `TimeSample` that was used, or a `BinanceConnectError` if no request succeeded.
+///
+pub fn sync(test_net: bool) -> Result<TimeSample, BinanceConnectError> {
+    sync_with_base_url(base_url(test_net))
+}
+
+/// Queries the server time of the base URL (e.g. of a proxy or a mock server) and updates the
+/// estimated clock offset and round-trip.
+///
+/// # Arguments
+///
+/// - `base_url`: The REST base URL, without trailing slash, e.g. `https://fapi.binance.com`.
+///
+/// # Returns
+///
+/// The `TimeSample` that was used, or a `BinanceConnectError` if no request succeeded.
+///
+pub fn sync_with_base_url(base_url: &str) -> Result<TimeSample, BinanceConnectError> {
+    let mut best: Option<TimeSample> = None;
+    let mut last_err: Option<BinanceConnectError> = None;
+    for _ in 0..SAMPLES_PER_SYNC {
+        match sample(base_url) {
+            Ok(time_sample) => {
+                if best
+                    .as_ref()
+                    .map_or(true, |best| time_sample.round_trip_ms < best.round_trip_ms)
+                {
+                    best = Some(time_sample);
+                }
+            }
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match best {
+        Some(time_sample) => {
+            CLOCK_OFFSET_MS.store(time_sample.offset_ms, Ordering::Relaxed);
+            ROUND_TRIP_MS.store(time_sample.round_trip_ms, Ordering::Relaxed);
+            LAST_SYNC.store(now_millis(), Ordering::Relaxed);
+            debug!(
+                offset_ms = time_sample.offset_ms,
+                round_trip_ms = time_sample.round_trip_ms,
+                "futures_usd time synced"
+            );
+            Ok(time_sample)
+        }
+        None => Err(last_err.unwrap_or_else(|| {
+            BinanceConnectError::Other("No time sync samples taken".to_string())
+        })),
+    }
+}
+
+/// Takes a single server time sample, assuming the server time was taken halfway the round-trip.
+fn sample(base_url: &str) -> Result<TimeSample, BinanceConnectError> {
+    let sent: u64 = now_millis();
+    let started: Instant = Instant::now();
+    let server_time: i64 = get_server_time_from(base_url)?.server_time;
+    let round_trip_ms: u64 = started.elapsed().as_millis() as u64;
+    Ok(TimeSample {
+        server_time,
+        offset_ms: server_time - (sent + round_trip_ms / 2) as i64,
+        round_trip_ms,
+    })
+}
+
+/// Returns the current Binance server time in milliseconds, being the local time corrected by the
+/// estimated clock offset. Equals the local time until the clock has been synchronized.
+pub fn server_now() -> u64 {
+    (now_millis() as i64 + CLOCK_OFFSET_MS.load(Ordering::Relaxed)) as u64
+}
+
+/// Returns the estimated clock offset (server time - local time) in milliseconds.
+pub fn clock_offset_ms() -> i64 {
+    CLOCK_OFFSET_MS.load(Ordering::Relaxed)
+}
+
+/// Returns the round-trip in milliseconds of the last synchronization, `None` when never synchronized.
+pub fn round_trip_ms() -> Option<u64> {
+    match LAST_SYNC.load(Ordering::Relaxed) {
+        0 => None,
+        _ => Some(ROUND_TRIP_MS.load(Ordering::Relaxed)),
+    }
+}
+
+/// Returns the local time (UNIX millis) of the last synchronization, `None` when never synchronized.
+pub fn last_sync() -> Option<u64> {
+    match LAST_SYNC.load(Ordering::Relaxed) {
+        0 => None,
+        time => Some(time),
+    }
+}
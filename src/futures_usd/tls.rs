@@ -0,0 +1,90 @@
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use reqwest::blocking::Client;
+use reqwest::Certificate;
+use tungstenite::Connector;
+
+use crate::error::BinanceConnectError;
+
+// The TLS backend is selected with the `native-tls` (default) or `rustls` cargo feature and is
+// used for both the WebSocket connections and the REST client. When both features are enabled
+// the WebSocket connections use native-tls.
+
+/// The PEM encoded root certificates trusted in addition to the default roots of the backend.
+static ROOT_CERTIFICATES: Lazy<RwLock<Vec<Vec<u8>>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Trusts the PEM encoded root certificate for all connections (WebSocket and REST) created
+/// afterwards, e.g. for a TLS intercepting proxy or a private relay.
+///
+/// # Arguments
+///
+/// - `pem`: The PEM encoded root certificate.
+///
+/// # Returns
+///
+/// `Ok(())` or a `BinanceConnectError` if the certificate can not be parsed.
+///
+pub fn add_root_certificate(pem: &[u8]) -> Result<(), BinanceConnectError> {
+    Certificate::from_pem(pem)?;
+    ROOT_CERTIFICATES.write().unwrap().push(pem.to_vec());
+    Ok(())
+}
+
+/// Creates a blocking HTTP client trusting the added root certificates.
+pub(crate) fn http_client() -> Result<Client, BinanceConnectError> {
+    let mut builder = Client::builder();
+    for pem in ROOT_CERTIFICATES.read().unwrap().iter() {
+        builder = builder.add_root_certificate(Certificate::from_pem(pem)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Creates a WebSocket TLS connector trusting the added root certificates, `None` when no root
+/// certificates were added and the default connector can be used.
+pub(crate) fn connector() -> Result<Option<Connector>, BinanceConnectError> {
+    let root_certificates = ROOT_CERTIFICATES.read().unwrap();
+    if root_certificates.is_empty() {
+        return Ok(None);
+    }
+    backend_connector(&root_certificates).map(Some)
+}
+
+/// Creates a native-tls connector trusting the root certificates.
+#[cfg(feature = "native-tls")]
+fn backend_connector(root_certificates: &[Vec<u8>]) -> Result<Connector, BinanceConnectError> {
+    let tls_error =
+        |err: native_tls::Error| BinanceConnectError::Other(format!("TLS error: {}", err));
+    let mut builder = native_tls::TlsConnector::builder();
+    for pem in root_certificates {
+        builder.add_root_certificate(native_tls::Certificate::from_pem(pem).map_err(tls_error)?);
+    }
+    Ok(Connector::NativeTls(builder.build().map_err(tls_error)?))
+}
+
+/// Creates a rustls connector trusting the webpki roots and the root certificates.
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+fn backend_connector(root_certificates: &[Vec<u8>]) -> Result<Connector, BinanceConnectError> {
+    use std::sync::Arc;
+
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|trust_anchor| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            trust_anchor.subject,
+            trust_anchor.spki,
+            trust_anchor.name_constraints,
+        )
+    }));
+    for pem in root_certificates {
+        for der in rustls_pemfile::certs(&mut pem.as_slice())? {
+            root_store
+                .add(&rustls::Certificate(der))
+                .map_err(|err| BinanceConnectError::Other(format!("TLS error: {}", err)))?;
+        }
+    }
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    Ok(Connector::Rustls(Arc::new(config)))
+}
@@ -0,0 +1,161 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use socket2::{Domain, Protocol, SockAddr, Socket as RawSocket, Type};
+use tracing::{debug, warn};
+use tungstenite::protocol::WebSocketConfig;
+
+/// The time a connection attempt to a single address may take before the next address is tried.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The IP versions of the resolved addresses that are connected to, and their order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpPreference {
+    /// Every address.
+    #[default]
+    System,
+    /// Every address, IPv4 addresses first.
+    Ipv4First,
+    /// Every address, IPv6 addresses first.
+    Ipv6First,
+    /// Only IPv4 addresses.
+    Ipv4Only,
+    /// Only IPv6 addresses.
+    Ipv6Only,
+}
+
+impl IpPreference {
+    /// Returns the rank of the address, `None` when it is excluded.
+    fn rank(&self, addr: &SocketAddr) -> Option<u8> {
+        match (self, addr.is_ipv4()) {
+            (IpPreference::System, _) => Some(0),
+            (IpPreference::Ipv4First, ipv4) => Some(u8::from(!ipv4)),
+            (IpPreference::Ipv6First, ipv4) => Some(u8::from(ipv4)),
+            (IpPreference::Ipv4Only, true) | (IpPreference::Ipv6Only, false) => Some(0),
+            (IpPreference::Ipv4Only, false) | (IpPreference::Ipv6Only, true) => None,
+        }
+    }
+}
+
+/// The options of the outbound TCP connections of the WebSocket connections, set with
+/// `FuturesWebSocketConfig::with_local_address`, `with_interface` and `with_ip_preference`, and
+/// the WebSocket limits set with `with_max_message_size`, `with_max_frame_size` and
+/// `with_write_buffer_size`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Transport {
+    /// The local address the connections are bound to, chosen by the OS when `None`.
+    pub local_address: Option<IpAddr>,
+    /// The network interface the connections are bound to (`SO_BINDTODEVICE`, Linux only).
+    pub interface: Option<String>,
+    /// The IP versions of the resolved addresses that are connected to, and their order.
+    pub ip_preference: IpPreference,
+    /// The message, frame and write buffer limits of the WebSocket connections.
+    pub websocket_config: WebSocketConfig,
+    /// The index of the resolved address the next connection starts with, moved past an address
+    /// when connecting to it fails.
+    next_address: Arc<AtomicUsize>,
+}
+
+impl Transport {
+    /// Connects to a reachable address of the host, bound to the local address and interface when
+    /// set.
+    ///
+    /// The host is resolved to all its addresses. The attempts start with the address the last
+    /// connection used, an address that fails is moved past, so a reconnect during a partial
+    /// outage tries the other addresses first.
+    ///
+    /// # Arguments
+    ///
+    /// - `host`: The host name or IP address to connect to.
+    /// - `port`: The port to connect to.
+    ///
+    /// # Returns
+    ///
+    /// The connected `TcpStream` or the error of the last attempt.
+    ///
+    pub(crate) fn connect(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        let addrs: Vec<SocketAddr> = self.resolve(host, port)?;
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                format!("no address of {} matches the IP preference", host),
+            ));
+        }
+        let start: usize = self.next_address.load(Ordering::Relaxed);
+        let mut last_err: Option<io::Error> = None;
+        for offset in 0..addrs.len() {
+            let index: usize = (start + offset) % addrs.len();
+            debug!(addr = %addrs[index], "futures_usd connecting to address");
+            match self.connect_addr(addrs[index]) {
+                Ok(stream) => {
+                    self.next_address.store(index, Ordering::Relaxed);
+                    return Ok(stream);
+                }
+                Err(err) => {
+                    warn!(addr = %addrs[index], error = %err, "futures_usd address unreachable");
+                    self.next_address.store(index + 1, Ordering::Relaxed);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.expect("at least one address was tried"))
+    }
+
+    /// Resolves the host to all its addresses that match the IP preference and the local address,
+    /// in a stable order so the index of the next address stays meaningful between connections.
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        // IPv6 hosts are bracketed in URLs.
+        let host: &str = host.trim_start_matches('[').trim_end_matches(']');
+        let mut addrs: Vec<(u8, SocketAddr)> = (host, port)
+            .to_socket_addrs()?
+            // A socket bound to an IPv4 address can not reach IPv6 addresses and vice versa.
+            .filter(|addr| {
+                self.local_address.map_or(true, |local_address| {
+                    local_address.is_ipv4() == addr.is_ipv4()
+                })
+            })
+            .filter_map(|addr| Some((self.ip_preference.rank(&addr)?, addr)))
+            .collect();
+        addrs.sort();
+        addrs.dedup();
+        Ok(addrs.into_iter().map(|(_, addr)| addr).collect())
+    }
+
+    /// Connects to the address, bound to the local address and interface when set.
+    fn connect_addr(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        if self.local_address.is_none() && self.interface.is_none() {
+            return TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT);
+        }
+        let socket: RawSocket =
+            RawSocket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+        if let Some(interface) = &self.interface {
+            bind_device(&socket, interface)?;
+        }
+        if let Some(local_address) = self.local_address {
+            socket.bind(&SockAddr::from(SocketAddr::new(local_address, 0)))?;
+        }
+        socket.connect_timeout(&SockAddr::from(addr), CONNECT_TIMEOUT)?;
+        Ok(socket.into())
+    }
+}
+
+/// Binds the socket to the network interface.
+#[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+fn bind_device(socket: &RawSocket, interface: &str) -> io::Result<()> {
+    socket.bind_device(Some(interface.as_bytes()))
+}
+
+/// Binding to a network interface is only supported on Linux, bind to its address instead.
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+fn bind_device(_: &RawSocket, interface: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "binding to interface {} is not supported on this platform",
+            interface
+        ),
+    ))
+}
@@ -0,0 +1,242 @@
+#[cfg(feature = "native")]
+use std::sync::Arc;
+
+use crate::error::BinanceConnectError;
+use crate::futures_usd::enums::binance::OrderType;
+use crate::futures_usd::response::{
+    round_to_step, ContractInfo, ContractInfoBracket, ExchangeInfo, SymbolInfo,
+};
+#[cfg(feature = "native")]
+use crate::futures_usd::shared::shared;
+
+/// The relative tolerance used when checking whether a value is a multiple of a step.
+const STEP_TOLERANCE: f64 = 1e-6;
+
+/// Represents the parameters of an order that is validated before it is sent to Binance.
+#[derive(Debug, Clone)]
+pub struct OrderParams {
+    pub symbol: String,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    /// The (limit) price of the order, for market orders a reference price can be set to validate
+    /// the notional.
+    pub price: Option<f64>,
+    pub leverage: Option<u8>,
+}
+
+impl OrderParams {
+    /// Creates new `OrderParams` without price and leverage.
+    pub fn new(symbol: &str, order_type: OrderType, quantity: f64) -> OrderParams {
+        OrderParams {
+            symbol: symbol.to_string(),
+            order_type,
+            quantity,
+            price: None,
+            leverage: None,
+        }
+    }
+
+    /// Sets the (limit or reference) price of the order.
+    pub fn with_price(mut self, price: f64) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Sets the leverage the order is placed with, validated against the leverage bracket of the
+    /// notional, which requires a (limit or reference) price.
+    pub fn with_leverage(mut self, leverage: u8) -> Self {
+        self.leverage = Some(leverage);
+        self
+    }
+
+    /// Returns whether the price of the order is sent to Binance and thus bound by the `PRICE_FILTER`.
+    fn has_limit_price(&self) -> bool {
+        matches!(
+            self.order_type,
+            OrderType::Limit | OrderType::Stop | OrderType::TakeProfit
+        )
+    }
+}
+
+/// Validates the order like `validate_order` against the shared cached `ExchangeInfo` of the
+/// environment, see `SharedContext::exchange_info`.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+/// - `contract_info`: The optional contract info containing the leverage brackets of the symbol.
+/// - `order`: The order to validate.
+///
+/// # Returns
+///
+/// `Ok(())` when the order passes all filters, otherwise a `BinanceConnectError::FilterViolation`
+/// describing the first violated filter or the error of the `ExchangeInfo` request.
+///
+#[cfg(feature = "native")]
+pub fn validate_order_shared(
+    test_net: bool,
+    contract_info: Option<&ContractInfo>,
+    order: &OrderParams,
+) -> Result<(), BinanceConnectError> {
+    let exchange_info: Arc<ExchangeInfo> = shared().exchange_info(test_net)?;
+    validate_order(&exchange_info, contract_info, order)
+}
+
+/// Validates the order against the symbol filters of the exchange info and, when given, against
+/// the leverage brackets of the contract info.
+///
+/// # Arguments
+///
+/// - `exchange_info`: The (cached) exchange info containing the symbol filters.
+/// - `contract_info`: The optional contract info containing the leverage brackets of the symbol.
+/// - `order`: The order to validate.
+///
+/// # Returns
+///
+/// `Ok(())` when the order passes all filters, otherwise a `BinanceConnectError::FilterViolation`
+/// describing the first violated filter.
+///
+pub fn validate_order(
+    exchange_info: &ExchangeInfo,
+    contract_info: Option<&ContractInfo>,
+    order: &OrderParams,
+) -> Result<(), BinanceConnectError> {
+    let symbol_info: &SymbolInfo = exchange_info
+        .symbol(&order.symbol)
+        .ok_or_else(|| violation(order, "SYMBOL", "unknown symbol".to_string()))?;
+
+    if let (true, Some(price), Some((min_price, max_price, tick_size))) = (
+        order.has_limit_price(),
+        order.price,
+        symbol_info.price_filter(),
+    ) {
+        check_range(order, "PRICE_FILTER", "price", price, min_price, max_price)?;
+        check_step(order, "PRICE_FILTER", "price", price, tick_size)?;
+    }
+
+    let (filter, lot_size) = match (&order.order_type, symbol_info.market_lot_size()) {
+        (OrderType::Market, Some(market_lot_size)) => ("MARKET_LOT_SIZE", Some(market_lot_size)),
+        _ => ("LOT_SIZE", symbol_info.lot_size()),
+    };
+    if let Some((min_qty, max_qty, step_size)) = lot_size {
+        check_range(order, filter, "quantity", order.quantity, min_qty, max_qty)?;
+        check_step(order, filter, "quantity", order.quantity, step_size)?;
+    }
+
+    if let (Some(price), Some(min_notional)) = (order.price, symbol_info.min_notional()) {
+        let notional: f64 = price * order.quantity;
+        if notional < min_notional {
+            return Err(violation(
+                order,
+                "MIN_NOTIONAL",
+                format!("notional {} is below {}", notional, min_notional),
+            ));
+        }
+    }
+
+    if let (Some(leverage), Some(contract_info)) = (order.leverage, contract_info) {
+        check_leverage(order, contract_info, leverage)?;
+    }
+    Ok(())
+}
+
+/// Checks that the leverage is allowed by the bracket the notional of the order falls into. The
+/// bracket is unknown without a price, so a reference price is required.
+fn check_leverage(
+    order: &OrderParams,
+    contract_info: &ContractInfo,
+    leverage: u8,
+) -> Result<(), BinanceConnectError> {
+    let price: f64 = match order.price {
+        Some(price) => price,
+        None => {
+            return Err(violation(
+                order,
+                "LEVERAGE_BRACKET",
+                "a (reference) price is required to determine the bracket".to_string(),
+            ))
+        }
+    };
+    let notional: f64 = price * order.quantity;
+    let bracket: Option<&ContractInfoBracket> = contract_info
+        .brackets
+        .iter()
+        .find(|bracket| notional < bracket.cap_notional as f64)
+        .or(contract_info.brackets.last());
+    match bracket {
+        Some(bracket) if i32::from(leverage) > bracket.max_leverage => Err(violation(
+            order,
+            "LEVERAGE_BRACKET",
+            format!(
+                "leverage {} exceeds the max leverage {} of bracket {}",
+                leverage, bracket.max_leverage, bracket.notional_bracket
+            ),
+        )),
+        Some(bracket) if i32::from(leverage) < bracket.min_leverage => Err(violation(
+            order,
+            "LEVERAGE_BRACKET",
+            format!(
+                "leverage {} is below the min leverage {} of bracket {}",
+                leverage, bracket.min_leverage, bracket.notional_bracket
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the value is within the bounds, a bound of zero means the bound is disabled.
+fn check_range(
+    order: &OrderParams,
+    filter: &str,
+    name: &str,
+    value: f64,
+    min: f64,
+    max: f64,
+) -> Result<(), BinanceConnectError> {
+    if min > 0.0 && value < min {
+        return Err(violation(
+            order,
+            filter,
+            format!("{} {} is below {}", name, value, min),
+        ));
+    }
+    if max > 0.0 && value > max {
+        return Err(violation(
+            order,
+            filter,
+            format!("{} {} is above {}", name, value, max),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the value is a multiple of the step, a step of zero means the check is disabled.
+fn check_step(
+    order: &OrderParams,
+    filter: &str,
+    name: &str,
+    value: f64,
+    step: f64,
+) -> Result<(), BinanceConnectError> {
+    let rounded: f64 = round_to_step(value, step, f64::round);
+    if step > 0.0 && (rounded - value).abs() > step * STEP_TOLERANCE {
+        return Err(violation(
+            order,
+            filter,
+            format!(
+                "{} {} is not a multiple of {}, nearest valid value is {}",
+                name, value, step, rounded
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a `BinanceConnectError::FilterViolation` for the order.
+fn violation(order: &OrderParams, filter: &str, reason: String) -> BinanceConnectError {
+    BinanceConnectError::FilterViolation {
+        symbol: order.symbol.clone(),
+        filter: filter.to_string(),
+        reason,
+    }
+}
@@ -0,0 +1,245 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{BinaryType, CloseEvent, MessageEvent, Response, WebSocket};
+
+use crate::constants;
+use crate::error::BinanceConnectError;
+use crate::futures_usd::deserializer::deserialize;
+use crate::futures_usd::enums::events::Event;
+use crate::futures_usd::response::{ExchangeInfo, ServerTime};
+
+/// The callback receiving the events (or errors) of a `BrowserFuturesStream`.
+type EventCallback = Rc<RefCell<dyn FnMut(Result<Event, BinanceConnectError>)>>;
+
+/// A Binance USD-M Futures market data stream for the browser, using the `web-sys` WebSocket
+/// instead of tungstenite and emitting the same typed `Event`s as the `FuturesUsdStream`.
+///
+/// Browsers do not allow blocking, so events are pushed to a callback instead of a receiver.
+/// The stream does not reconnect, a `ConnectionClosed` error is passed to the callback when the
+/// connection ends and a new stream can be started from there.
+pub struct BrowserFuturesStream {
+    test_net: bool,
+    streams: Vec<String>,
+    socket: Option<WebSocket>,
+    /// The handlers registered on the socket, they must live as long as the socket.
+    on_open: Option<Closure<dyn FnMut()>>,
+    on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
+    on_error: Option<Closure<dyn FnMut(web_sys::Event)>>,
+    on_close: Option<Closure<dyn FnMut(CloseEvent)>>,
+}
+
+impl BrowserFuturesStream {
+    /// Creates a new instance without streams.
+    ///
+    /// # Arguments
+    ///
+    /// - `test_net`: A boolean indicating whether the testnet environment should be used.
+    ///
+    pub fn new(test_net: bool) -> Self {
+        Self {
+            test_net,
+            streams: Vec::new(),
+            socket: None,
+            on_open: None,
+            on_message: None,
+            on_error: None,
+            on_close: None,
+        }
+    }
+
+    /// Adds a market data stream to the current instance.
+    ///
+    /// # Arguments
+    ///
+    /// - `stream`: The Binance stream name, e.g. `btcusdt@bookTicker` or `!markPrice@arr`.
+    ///
+    /// # Returns
+    ///
+    /// A modified instance of the struct with the stream added.
+    ///
+    pub fn with_stream(mut self, stream: &str) -> Self {
+        self.streams.push(stream.to_string());
+        self
+    }
+
+    /// Opens the WebSocket connection and subscribes to the streams once it is open.
+    ///
+    /// # Arguments
+    ///
+    /// - `on_event`: The callback receiving every deserialized `Event`, or the error when a
+    ///   payload could not be deserialized or the connection failed.
+    ///
+    /// # Returns
+    ///
+    /// The started instance or a `BinanceConnectError` if there are no streams or the WebSocket
+    /// could not be created.
+    ///
+    pub fn start(
+        mut self,
+        on_event: impl FnMut(Result<Event, BinanceConnectError>) + 'static,
+    ) -> Result<Self, BinanceConnectError> {
+        if self.streams.is_empty() {
+            return Err(BinanceConnectError::Other(
+                "Can't start a ws connection without at least 1 stream".to_string(),
+            ));
+        }
+        let url: String = format!(
+            "{}/ws",
+            match self.test_net {
+                true => constants::WS_URL_FUTURES_TESTNET,
+                false => constants::WS_URL_FUTURES,
+            }
+        );
+        let socket: WebSocket = WebSocket::new(&url).map_err(js_error)?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        let on_event: EventCallback = Rc::new(RefCell::new(on_event));
+
+        let subscribe_payload: String = subscribe_payload(&self.streams);
+        let open_socket: WebSocket = socket.clone();
+        let on_open = Closure::<dyn FnMut()>::new(move || {
+            let _ = open_socket.send_with_str(&subscribe_payload);
+        });
+        let message_callback: EventCallback = Rc::clone(&on_event);
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |message: MessageEvent| {
+            if let Some(json_response) = message_text(message.data()) {
+                (message_callback.borrow_mut())(deserialize(json_response));
+            }
+        });
+        let error_callback: EventCallback = Rc::clone(&on_event);
+        let on_error = Closure::<dyn FnMut(web_sys::Event)>::new(move |_: web_sys::Event| {
+            (error_callback.borrow_mut())(Err(BinanceConnectError::Other(
+                "WebSocket error".to_string(),
+            )));
+        });
+        let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |close: CloseEvent| {
+            (on_event.borrow_mut())(Err(BinanceConnectError::ConnectionClosed {
+                code: close.code(),
+                reason: close.reason(),
+            }));
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        self.socket = Some(socket);
+        self.on_open = Some(on_open);
+        self.on_message = Some(on_message);
+        self.on_error = Some(on_error);
+        self.on_close = Some(on_close);
+        Ok(self)
+    }
+
+    /// Closes the WebSocket connection.
+    pub fn stop(&self) {
+        if let Some(socket) = &self.socket {
+            let _ = socket.close();
+        }
+    }
+}
+
+impl Drop for BrowserFuturesStream {
+    /// Detaches the handlers and closes the connection before the handlers are dropped.
+    fn drop(&mut self) {
+        if let Some(socket) = &self.socket {
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onerror(None);
+            socket.set_onclose(None);
+            let _ = socket.close();
+        }
+    }
+}
+
+/// Retrieves the current exchange trading rules and symbol information using `fetch`.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// The `ExchangeInfo` or a `BinanceConnectError` if the request failed.
+///
+pub async fn get_exchange_info(test_net: bool) -> Result<ExchangeInfo, BinanceConnectError> {
+    let json_response: String = fetch(test_net, constants::FUTURES_EXCHANGE_INFO).await?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Retrieves the current server time using `fetch`.
+///
+/// # Arguments
+///
+/// - `test_net`: A boolean indicating whether the testnet environment should be used.
+///
+/// # Returns
+///
+/// The `ServerTime` or a `BinanceConnectError` if the request failed.
+///
+pub async fn get_server_time(test_net: bool) -> Result<ServerTime, BinanceConnectError> {
+    let json_response: String = fetch(test_net, constants::FUTURES_TIME).await?;
+    serde_json::from_str(&json_response)
+        .map_err(|err| BinanceConnectError::json_error(err, &json_response))
+}
+
+/// Sends a public GET request with `fetch` and returns the response body.
+async fn fetch(test_net: bool, endpoint: &str) -> Result<String, BinanceConnectError> {
+    let base_url: &str = match test_net {
+        true => constants::BASE_URL_FUTURES_TESTNET,
+        false => constants::BASE_URL_FUTURES,
+    };
+    let window = web_sys::window()
+        .ok_or_else(|| BinanceConnectError::Other("No browser window".to_string()))?;
+    let response: Response =
+        JsFuture::from(window.fetch_with_str(&format!("{}{}", base_url, endpoint)))
+            .await
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(js_error)?;
+    let text: String = JsFuture::from(response.text().map_err(js_error)?)
+        .await
+        .map_err(js_error)?
+        .as_string()
+        .unwrap_or_default();
+    match response.ok() {
+        true => Ok(text),
+        false => Err(BinanceConnectError::HttpResponseError(format!(
+            "Not-OK status code received {}: {}",
+            response.status(),
+            text
+        ))),
+    }
+}
+
+/// Generates the subscription payload for the streams.
+fn subscribe_payload(streams: &[String]) -> String {
+    format!(
+        "{{\"method\": \"SUBSCRIBE\",\"params\":[{}],\"id\": 1}}",
+        streams
+            .iter()
+            .map(|stream| format!("\"{}\"", stream))
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
+
+/// Returns the text of a message, binary frames are decoded as UTF-8 like the text frames.
+fn message_text(data: JsValue) -> Option<String> {
+    match data.as_string() {
+        Some(text) => Some(text),
+        None => data
+            .dyn_into::<js_sys::ArrayBuffer>()
+            .ok()
+            .and_then(|buffer| String::from_utf8(js_sys::Uint8Array::new(&buffer).to_vec()).ok()),
+    }
+}
+
+/// Maps a JavaScript exception to a `BinanceConnectError`.
+fn js_error(err: JsValue) -> BinanceConnectError {
+    BinanceConnectError::Other(format!("JS error: {:?}", err))
+}
@@ -1,19 +1,115 @@
 extern crate core;
 
+// Binance is only reachable over TLS, in the browser TLS is provided by the `wasm` transport.
+#[cfg(not(any(feature = "native-tls", feature = "rustls", feature = "wasm")))]
+compile_error!("Enable the `native-tls`, `rustls` or `wasm` feature to select a transport");
+
 mod constants;
 
 pub mod error;
 
 pub mod futures_usd {
+    pub mod alert;
+    pub mod alias;
+    pub mod analytics;
+    pub mod auth;
+    #[cfg(feature = "binary")]
+    pub mod binary;
+    pub mod candle;
+    #[cfg(feature = "native")]
     mod client;
-    mod deserializer;
+    #[cfg(feature = "native")]
+    pub mod conflation;
+    #[cfg(feature = "native")]
+    mod dedup;
+    pub mod deserializer;
+    pub mod downtime;
+    #[cfg(feature = "native")]
+    pub mod endpoint;
+    pub mod event_filter;
+    #[cfg(feature = "native")]
+    pub mod event_source;
+    pub mod fill_notice;
+    pub mod funding;
+    #[cfg(all(feature = "healthcheck", feature = "native"))]
+    pub mod healthcheck;
+    #[cfg(all(feature = "historical", feature = "native"))]
+    pub mod historical;
+    #[cfg(feature = "native")]
+    mod kline_filter;
+    #[cfg(feature = "native")]
+    pub mod latest_cache;
+    #[cfg(feature = "native")]
     pub mod listen_key;
+    pub mod listing;
+    pub mod market_event;
+    pub mod migration;
+    #[cfg(all(feature = "test-support", feature = "native"))]
+    pub mod mock_server;
+    #[cfg(feature = "native")]
+    pub mod multi_account;
+    pub mod multi_symbol;
+    pub mod ordering;
+    #[cfg(feature = "native")]
+    mod outbox;
+    #[cfg(feature = "native")]
+    pub mod ping;
+    pub mod pnl;
+    #[cfg(feature = "native")]
+    pub mod poller;
+    #[cfg(feature = "native")]
+    pub mod pool;
+    pub mod quote_cache;
+    #[cfg(feature = "native")]
+    pub mod rate_limit;
+    #[cfg(feature = "native")]
+    pub mod receiver;
+    pub mod reconnect;
+    #[cfg(feature = "native")]
+    pub mod recorder;
+    #[cfg(feature = "native")]
+    pub mod replay;
     pub mod response;
+    #[cfg(feature = "native")]
+    pub mod rest;
+    #[cfg(feature = "native")]
+    pub mod rotation;
+    #[cfg(feature = "scripting")]
+    pub mod script;
+    #[cfg(feature = "native")]
+    pub mod self_test;
+    pub mod sequence;
+    #[cfg(feature = "native")]
+    pub mod settings;
+    #[cfg(feature = "native")]
+    pub mod shared;
+    pub mod signer;
+    #[cfg(feature = "native")]
+    pub mod sink;
+    pub mod skew;
+    #[cfg(feature = "native")]
+    mod standby;
+    pub mod state_store;
+    pub mod stats;
+    pub mod status;
+    #[cfg(feature = "native")]
     pub mod stream;
+    #[cfg(feature = "test-support")]
+    pub mod test_utils;
+    pub mod ticker_cache;
+    #[cfg(feature = "native")]
+    pub mod time_sync;
+    #[cfg(feature = "native")]
+    pub mod tls;
+    #[cfg(feature = "native")]
+    pub mod transport;
+    pub mod validation;
+    #[cfg(feature = "wasm")]
+    pub mod wasm;
 
     pub mod enums {
         pub mod binance;
         pub mod events;
-        pub(crate) mod streams;
+        pub mod streams;
     }
 }
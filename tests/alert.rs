@@ -0,0 +1,87 @@
+#![cfg(feature = "market-data")]
+
+use binance_connect::futures_usd::alert::{Alert, AlertCondition, AlertEngine};
+use binance_connect::futures_usd::enums::events::{Event, EventType};
+use binance_connect::futures_usd::response::{AggTrade, MarkPriceUpdate};
+
+fn agg_trade(price: f64) -> Event {
+    Event::AggTradeEvent(AggTrade {
+        event_type: EventType::AggTradeEventType,
+        event_time: 1,
+        symbol: "BTCUSDT".to_string(),
+        agg_trade_id: 1,
+        price,
+        quantity: 1.0,
+        first_trade_id: 1,
+        last_trade_id: 1,
+        trade_time: 1,
+        buyer_is_market_maker: false,
+    })
+}
+
+fn mark_price_update(mark_price: f64, funding_rate: f64) -> Event {
+    Event::MarkPriceUpdateEvent(MarkPriceUpdate {
+        event_type: EventType::MarkPriceUpdateEventType,
+        event_time: 2,
+        symbol: "BTCUSDT".to_string(),
+        mark_price,
+        index_price: 100.0,
+        estimated_settle_price: 100.0,
+        funding_rate,
+        next_funding_time: 0,
+    })
+}
+
+/// Returns the ids and directions of the alerts.
+fn triggered(alerts: Vec<Alert>) -> Vec<(usize, bool)> {
+    alerts.iter().map(|alert| (alert.id, alert.above)).collect()
+}
+
+#[test]
+fn conditions_are_edge_triggered() {
+    let mut alert_engine: AlertEngine = AlertEngine::new()
+        .with_condition(AlertCondition::PriceCrosses {
+            symbol: "btcusdt".to_string(),
+            price: 100.0,
+        })
+        .with_condition(AlertCondition::FundingRateAbove {
+            symbol: "btcusdt".to_string(),
+            funding_rate: 0.001,
+        });
+    let spread: usize = alert_engine.register(AlertCondition::MarkIndexSpreadAbove {
+        symbol: "BTCUSDT".to_string(),
+        spread: 0.01,
+    });
+
+    // The first price only sets the side.
+    assert!(alert_engine.observe(&agg_trade(99.0)).is_empty());
+    assert!(alert_engine.observe(&agg_trade(99.5)).is_empty());
+    assert_eq!(
+        triggered(alert_engine.observe(&agg_trade(100.0))),
+        [(0, true)]
+    );
+    assert!(alert_engine.observe(&agg_trade(101.0)).is_empty());
+    let alerts: Vec<Alert> = alert_engine.observe(&agg_trade(98.0));
+    assert_eq!(triggered(alerts.clone()), [(0, false)]);
+    assert_eq!(alerts[0].value, 98.0);
+    assert_eq!(alerts[0].event_time, 1);
+
+    assert!(alert_engine
+        .observe(&mark_price_update(100.5, 0.0005))
+        .is_empty());
+    assert_eq!(
+        triggered(alert_engine.observe(&mark_price_update(102.0, 0.002))),
+        [(1, true), (spread, true)]
+    );
+    assert!(alert_engine
+        .observe(&mark_price_update(102.0, 0.002))
+        .is_empty());
+    // Re-armed once the values fall back.
+    assert!(alert_engine
+        .observe(&mark_price_update(100.0, 0.0))
+        .is_empty());
+    assert_eq!(
+        triggered(alert_engine.observe(&mark_price_update(98.0, 0.0))),
+        [(spread, true)]
+    );
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "market-data")]
+
+use std::time::Duration;
+
+use binance_connect::futures_usd::analytics::{RollingStats, VolumeProfile};
+use binance_connect::futures_usd::enums::events::EventType;
+use binance_connect::futures_usd::response::AggTrade;
+
+fn agg_trade(trade_time: u64, price: f64, quantity: f64, trade_ids: (u64, u64)) -> AggTrade {
+    AggTrade {
+        event_type: EventType::AggTradeEventType,
+        event_time: trade_time,
+        symbol: "BTCUSDT".to_string(),
+        agg_trade_id: 1,
+        price,
+        quantity,
+        first_trade_id: trade_ids.0,
+        last_trade_id: trade_ids.1,
+        trade_time,
+        buyer_is_market_maker: false,
+    }
+}
+
+#[test]
+fn zero_window_is_rejected() {
+    assert!(RollingStats::new(Duration::ZERO).is_err());
+    assert!(VolumeProfile::new(0.1, Duration::ZERO).is_err());
+    assert!(VolumeProfile::new(0.0, Duration::from_secs(60)).is_err());
+}
+
+#[test]
+fn samples_are_evicted_from_the_window() {
+    let mut rolling_stats: RollingStats = RollingStats::new(Duration::from_secs(60)).unwrap();
+    rolling_stats.update_agg_trade(&agg_trade(0, 100.0, 1.0, (1, 2)));
+    rolling_stats.update_agg_trade(&agg_trade(30_000, 200.0, 1.0, (3, 3)));
+    assert_eq!(rolling_stats.vwap(), Some(150.0));
+    assert_eq!(rolling_stats.trade_count(), 3);
+
+    rolling_stats.update_agg_trade(&agg_trade(60_000, 300.0, 2.0, (4, 4)));
+    assert_eq!(rolling_stats.volume(), 3.0);
+    assert_eq!(rolling_stats.vwap(), Some(800.0 / 3.0));
+    assert_eq!(rolling_stats.trade_count(), 2);
+}
+
+#[test]
+fn inverted_trade_ids_count_one_trade() {
+    let mut rolling_stats: RollingStats = RollingStats::new(Duration::from_secs(60)).unwrap();
+    rolling_stats.update_agg_trade(&agg_trade(0, 100.0, 1.0, (7, 5)));
+    assert_eq!(rolling_stats.trade_count(), 1);
+}
+
+#[test]
+fn volume_is_grouped_per_level() {
+    let mut volume_profile: VolumeProfile =
+        VolumeProfile::new(10.0, Duration::from_secs(60)).unwrap();
+    volume_profile.update_agg_trade(&agg_trade(0, 101.0, 1.0, (1, 1)));
+    volume_profile.update_agg_trade(&agg_trade(1_000, 109.0, 2.0, (2, 2)));
+    volume_profile.update_agg_trade(&agg_trade(2_000, 110.0, 1.0, (3, 3)));
+    assert_eq!(volume_profile.levels(), [(100.0, 3.0), (110.0, 1.0)]);
+    assert_eq!(volume_profile.point_of_control(), Some(100.0));
+
+    volume_profile.update_agg_trade(&agg_trade(61_000, 110.0, 1.0, (4, 4)));
+    assert_eq!(volume_profile.volume_at(105.0), 0.0);
+    assert_eq!(volume_profile.volume_at(110.0), 2.0);
+}
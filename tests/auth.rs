@@ -0,0 +1,21 @@
+#![cfg(feature = "native")]
+
+use binance_connect::futures_usd::auth::StaticAuthProvider;
+use binance_connect::futures_usd::listen_key::ApiAuth;
+use binance_connect::futures_usd::signer::HmacSigner;
+
+#[test]
+#[allow(deprecated)]
+fn api_key_field_is_kept() {
+    let api_auth: ApiAuth = ApiAuth::new("key".to_string(), "secret".to_string());
+    assert_eq!(api_auth.api_key, "key");
+    assert_eq!(api_auth.api_key().unwrap().as_str(), "key");
+
+    // The key of a provider is only supplied on demand.
+    let api_auth: ApiAuth = ApiAuth::with_provider(StaticAuthProvider::new(
+        "key".to_string(),
+        HmacSigner::new("secret".to_string()),
+    ));
+    assert!(api_auth.api_key.is_empty());
+    assert_eq!(api_auth.api_key().unwrap().as_str(), "key");
+}
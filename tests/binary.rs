@@ -0,0 +1,54 @@
+#![cfg(all(feature = "binary", feature = "market-data", feature = "user-data"))]
+
+use std::time::Duration;
+
+use binance_connect::futures_usd::binary::{decode, encode, BinaryFileSink};
+use binance_connect::futures_usd::deserializer::deserialize;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::replay::{ReplaySpeed, ReplayStream};
+use binance_connect::futures_usd::sink::EventSink;
+
+const BOOK_TICKER: &str = r#"{"e":"bookTicker","u":400900217,"E":1568014460893,"T":1568014460891,"s":"BTCUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+const ORDER_TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}}"#;
+
+#[test]
+fn events_round_trip() {
+    for json in [BOOK_TICKER, ORDER_TRADE_UPDATE] {
+        let event: Event = deserialize(json.to_string()).unwrap();
+        let bytes: Vec<u8> = encode(&event).unwrap();
+        assert!(bytes.len() < json.len());
+        assert_eq!(decode(&bytes).unwrap(), event);
+    }
+}
+
+#[test]
+fn binary_frames_are_replayed() {
+    let path = std::env::temp_dir().join(format!("binance_connect_{}.bin", std::process::id()));
+    let events: Vec<Event> = [BOOK_TICKER, ORDER_TRADE_UPDATE]
+        .iter()
+        .map(|json| deserialize(json.to_string()).unwrap())
+        .collect();
+    {
+        let mut sink: BinaryFileSink = BinaryFileSink::create(&path).unwrap();
+        for event in &events {
+            sink.publish(event);
+        }
+    }
+
+    let replay: ReplayStream = ReplayStream::new(&path)
+        .with_binary_frames()
+        .with_speed(ReplaySpeed::AsFastAsPossible)
+        .start();
+    for event in &events {
+        let replayed: Event = replay
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(&replayed, event);
+    }
+    assert!(replay
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .is_err());
+    std::fs::remove_file(&path).unwrap();
+}
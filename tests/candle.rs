@@ -0,0 +1,49 @@
+use binance_connect::futures_usd::candle::CandleBoundary;
+use binance_connect::futures_usd::enums::binance::KlineInterval;
+
+/// 2024-02-15T12:34:56Z, a Thursday.
+const TIME: i64 = 1708000496000;
+
+fn candle(interval: KlineInterval, time: i64) -> (i64, i64) {
+    let boundary: CandleBoundary = CandleBoundary::after(interval, time);
+    assert_eq!(boundary.interval, interval);
+    (boundary.open_time, boundary.close_time)
+}
+
+#[test]
+fn boundaries_are_aligned() {
+    // 12:30 - 12:45.
+    assert_eq!(
+        candle(KlineInterval::Minutes15, TIME),
+        (1708000200000, 1708001100000)
+    );
+    // A time on the boundary opens the next candle.
+    assert_eq!(
+        candle(KlineInterval::Minutes15, 1708001100000).0,
+        1708001100000
+    );
+    // Monday 2024-02-12 - Monday 2024-02-19.
+    assert_eq!(
+        candle(KlineInterval::Weeks1, TIME),
+        (1707696000000, 1708300800000)
+    );
+    // 2024-02-01 - 2024-03-01, a leap year.
+    assert_eq!(
+        candle(KlineInterval::Months1, TIME),
+        (1706745600000, 1709251200000)
+    );
+    // 2023-12-01 - 2024-01-01.
+    assert_eq!(
+        candle(KlineInterval::Months1, 1704067140000),
+        (1701388800000, 1704067200000)
+    );
+}
+
+#[test]
+fn every_interval_contains_the_time() {
+    for interval in KlineInterval::iter() {
+        let (open_time, close_time): (i64, i64) = candle(interval, TIME);
+        assert!(open_time <= TIME && TIME < close_time, "{:?}", interval);
+        assert_eq!(candle(interval, close_time).0, close_time, "{:?}", interval);
+    }
+}
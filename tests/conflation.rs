@@ -0,0 +1,84 @@
+#![cfg(all(feature = "test-support", feature = "market-data"))]
+
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use binance_connect::futures_usd::conflation::ConflatedStream;
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::mock_server::{MockServer, BOOK_TICKER};
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+/// Returns the book ticker of the symbol with the update id.
+fn book_ticker(symbol: &str, update_id: u64) -> String {
+    BOOK_TICKER
+        .replace("BTCUSDT", symbol)
+        .replace("400900217", &update_id.to_string())
+}
+
+/// Returns a config of the server conflating the book tickers at the interval.
+fn config(server: &MockServer, interval: Duration) -> FuturesWebSocketConfig {
+    FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+        .with_stale_timeout(Duration::from_secs(5))
+        .with_conflation(ConflatedStream::BookTicker, interval)
+}
+
+#[test]
+fn latest_value_per_symbol_is_emitted() {
+    let server: MockServer = MockServer::new()
+        .with_frame(&book_ticker("BTCUSDT", 1))
+        .with_frame(&book_ticker("ETHUSDT", 2))
+        .with_frame(&book_ticker("BTCUSDT", 3))
+        .start()
+        .unwrap();
+    let stream: FuturesUsdStream =
+        FuturesUsdStream::with_config(config(&server, Duration::from_millis(500)))
+            .with_book_ticker("btcusdt")
+            .start();
+
+    let mut update_ids: Vec<(String, u64)> = Vec::new();
+    while update_ids.len() < 2 {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(book_ticker) => {
+                update_ids.push((book_ticker.symbol, book_ticker.update_id))
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    update_ids.sort();
+    assert_eq!(
+        update_ids,
+        [("BTCUSDT".to_string(), 3), ("ETHUSDT".to_string(), 2)]
+    );
+    // Nothing is pending after the values were emitted.
+    assert_eq!(
+        stream
+            .consume()
+            .recv_timeout(Duration::from_secs(1))
+            .unwrap_err(),
+        RecvTimeoutError::Timeout
+    );
+    stream.stop();
+}
+
+#[test]
+fn emit_thread_stops_within_the_interval() {
+    let server: MockServer = MockServer::new().start().unwrap();
+    let (sink, worker) = channel();
+    let stream: FuturesUsdStream =
+        FuturesUsdStream::with_config(config(&server, Duration::from_secs(3600)))
+            .with_book_ticker("btcusdt")
+            .with_sink(sink)
+            .start();
+
+    // The sink is dropped with the last sender, after the emit thread noticed the stop.
+    drop(stream);
+    assert_eq!(
+        worker.recv_timeout(Duration::from_secs(10)).unwrap_err(),
+        RecvTimeoutError::Disconnected
+    );
+}
@@ -0,0 +1,66 @@
+#[cfg(feature = "market-data")]
+use binance_connect::futures_usd::deserializer::deserialize;
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::events::Event;
+
+#[cfg(feature = "market-data")]
+const BOOK_TICKER: &str = r#"{"e":"bookTicker","u":400900217,"E":1568014460893,"T":1568014460891,"s":"BTCUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+const KLINE: &str = r#"{"e":"kline","E":1638747660000,"s":"BTCUSDT","k":{"t":1638747660000,"T":1638747719999,"s":"BTCUSDT","i":"1m","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}}"#;
+#[cfg(feature = "market-data")]
+const MINI_TICKERS: &str = r#"[{"e":"24hrMiniTicker","E":123456789,"s":"BTCUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"},{"e":"24hrMiniTicker","E":123456790,"s":"ETHUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"}]"#;
+
+#[test]
+#[cfg(feature = "market-data")]
+fn slice_matches_string_deserialization() {
+    let combined: String = format!(
+        r#"{{"stream":"btcusdt@bookTicker","data":{}}}"#,
+        BOOK_TICKER
+    );
+    let raw: &str = r#"{"stream":"custom@stream","data":{"x":1}}"#;
+    for json in [BOOK_TICKER, MINI_TICKERS, combined.as_str(), raw] {
+        let event: Event = deserialize_slice(json.as_bytes()).unwrap();
+        assert_eq!(event, deserialize(json.to_string()).unwrap());
+    }
+    assert!(matches!(
+        deserialize_slice(combined.as_bytes()).unwrap(),
+        Event::BookTickerEvent(_)
+    ));
+    assert!(matches!(
+        deserialize_slice(MINI_TICKERS.as_bytes()).unwrap(),
+        Event::MiniTickersEvent(tickers) if tickers.data.len() == 2
+    ));
+    assert_eq!(
+        deserialize_slice(raw.as_bytes()).unwrap(),
+        Event::Raw {
+            stream: Some("custom@stream".to_string()),
+            json: r#"{"x":1}"#.to_string(),
+        }
+    );
+}
+
+#[test]
+fn invalid_slice_is_an_error() {
+    assert!(deserialize_slice(b"{\"e\":").is_err());
+}
+
+#[test]
+#[cfg(feature = "klines")]
+fn enabled_family_is_deserialized() {
+    assert!(matches!(
+        deserialize_slice(KLINE.as_bytes()).unwrap(),
+        Event::KlineEvent(_)
+    ));
+}
+
+#[test]
+#[cfg(not(feature = "klines"))]
+fn disabled_family_is_emitted_raw() {
+    let combined: String = format!(r#"{{"stream":"btcusdt@kline_1m","data":{}}}"#, KLINE);
+    assert_eq!(
+        deserialize_slice(combined.as_bytes()).unwrap(),
+        Event::Raw {
+            stream: Some("btcusdt@kline_1m".to_string()),
+            json: KLINE.to_string(),
+        }
+    );
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "native")]
+
+use binance_connect::futures_usd::endpoint::Endpoint;
+
+#[test]
+fn endpoints_are_parsed() {
+    let auth_portal: Endpoint = "auth_portal".parse().unwrap();
+    assert_eq!(auth_portal, Endpoint::AuthPortal);
+    assert_eq!(
+        auth_portal.ws_url().as_str(),
+        "wss://fstream-auth.binance.com/"
+    );
+    assert!(!auth_portal.is_testnet());
+    assert!("testnet".parse::<Endpoint>().unwrap().is_testnet());
+
+    let custom: Endpoint = "wss://fstream.example.com/".parse().unwrap();
+    assert_eq!(custom.ws_url().as_str(), "wss://fstream.example.com/");
+    assert!(!custom.is_testnet());
+    assert!("https://fapi.binance.com".parse::<Endpoint>().is_err());
+}
@@ -0,0 +1,202 @@
+#![cfg(feature = "native")]
+
+use binance_connect::error::BinanceConnectError;
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::binance::OrderType;
+use binance_connect::futures_usd::response::{ContractInfo, ExchangeInfo, SymbolFilter};
+use binance_connect::futures_usd::shared::shared;
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+use binance_connect::futures_usd::validation::{
+    validate_order, validate_order_shared, OrderParams,
+};
+
+const EXCHANGE_INFO: &str = r#"{"timezone":"UTC","serverTime":1700000000000,"rateLimits":[],"symbols":[
+{"symbol":"BTCUSDT","pair":"BTCUSDT","contractType":"PERPETUAL","deliveryDate":4133404800000,"onboardDate":1569398400000,"status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","marginAsset":"USDT","pricePrecision":2,"quantityPrecision":3,
+"filters":[{"filterType":"PRICE_FILTER","minPrice":"0.10","maxPrice":"1000000","tickSize":"0.10"}]},
+{"symbol":"OLDUSDT","pair":"OLDUSDT","contractType":"PERPETUAL","deliveryDate":4133404800000,"onboardDate":1569398400000,"status":"SETTLING","baseAsset":"OLD","quoteAsset":"USDT","marginAsset":"USDT","pricePrecision":4,"quantityPrecision":0,"filters":[]}]}"#;
+
+#[test]
+fn values_are_rounded_to_steps() {
+    let exchange_info: ExchangeInfo = serde_json::from_str(&EXCHANGE_INFO.replace(
+        r#""tickSize":"0.10"}]"#,
+        r#""tickSize":"0.25"},{"filterType":"LOT_SIZE","minQty":"0.001","maxQty":"1000","stepSize":"0.001"}]"#,
+    ))
+    .unwrap();
+    let round_price = |price: f64| exchange_info.round_price("BTCUSDT", price).unwrap();
+    let round_qty = |quantity: f64| exchange_info.round_qty("BTCUSDT", quantity).unwrap();
+
+    assert_eq!(round_price(100.13), 100.25);
+    assert_eq!(round_price(100.12), 100.0);
+    assert_eq!(round_price(100.75), 100.75);
+    // An exact multiple stays, also when the division is noisy.
+    assert_eq!(round_qty(0.3), 0.3);
+    assert_eq!(round_qty(0.1 + 0.2), 0.3);
+    assert_eq!(round_qty(0.0019), 0.001);
+    assert_eq!(round_qty(1.2345), 1.234);
+    assert_eq!(exchange_info.round_qty("UNKNOWN", 1.0), None);
+}
+
+#[test]
+fn leverage_is_validated_against_the_bracket_of_the_notional() {
+    let exchange_info: ExchangeInfo = serde_json::from_str(EXCHANGE_INFO).unwrap();
+    let contract_info: ContractInfo = serde_json::from_str(r#"{"e":"contractInfo","E":1700000000000,"s":"BTCUSDT","ps":"BTCUSDT","ct":"PERPETUAL","dt":4133404800000,"ot":1569398400000,"cs":"TRADING","bks":[{"bs":1,"bnf":0,"bnc":50000,"mmr":"0.004","cf":0,"mi":1,"ma":125},{"bs":2,"bnf":50000,"bnc":250000,"mmr":"0.005","cf":50,"mi":1,"ma":100}]}"#).unwrap();
+    let validate =
+        |order: OrderParams| validate_order(&exchange_info, Some(&contract_info), &order);
+
+    let order = |price: f64| {
+        OrderParams::new("BTCUSDT", OrderType::Limit, 1.0)
+            .with_price(price)
+            .with_leverage(125)
+    };
+    assert!(validate(order(40000.0)).is_ok());
+    assert!(matches!(
+        validate(order(60000.0)),
+        Err(BinanceConnectError::FilterViolation { .. })
+    ));
+    // Without a price the bracket is unknown, the first (highest leverage) bracket is not assumed.
+    let market: OrderParams =
+        OrderParams::new("BTCUSDT", OrderType::Market, 1.0).with_leverage(125);
+    match validate(market) {
+        Err(BinanceConnectError::FilterViolation { filter, .. }) => {
+            assert_eq!(filter, "LEVERAGE_BRACKET")
+        }
+        result => panic!("unexpected {:?}", result),
+    }
+    let market: OrderParams = OrderParams::new("BTCUSDT", OrderType::Market, 1.0)
+        .with_price(60000.0)
+        .with_leverage(100);
+    assert!(validate(market).is_ok());
+}
+
+/// Seeds the exchange info of the production environment, to which custom endpoints belong, the
+/// same for every test as they share the cache.
+#[cfg(feature = "test-support")]
+fn seed_production_exchange_info() {
+    use binance_connect::futures_usd::enums::binance::ContractType;
+
+    let mut exchange_info: ExchangeInfo = serde_json::from_str(EXCHANGE_INFO).unwrap();
+    let mut quarterly = exchange_info.symbols[0].clone();
+    quarterly.symbol = "BTCUSDT_240628".to_string();
+    quarterly.contract_type = ContractType::CurrentQuarter;
+    let mut ethusdt = exchange_info.symbols[0].clone();
+    ethusdt.symbol = "ETHUSDT".to_string();
+    exchange_info.symbols.extend([quarterly, ethusdt]);
+    shared().set_exchange_info(false, exchange_info);
+}
+
+#[cfg(feature = "test-support")]
+#[test]
+fn all_perpetuals_are_subscribed() {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use binance_connect::futures_usd::mock_server::MockServer;
+
+    seed_production_exchange_info();
+    let server: MockServer = MockServer::new().start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_max_streams_per_connection(1);
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trades_for_all_perpetuals()
+        .unwrap()
+        .start();
+
+    let started: Instant = Instant::now();
+    while server.connections() < 2 {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(10));
+    }
+    let mut paths: Vec<String> = server.paths();
+    paths.sort();
+    assert_eq!(paths, vec!["/ws/btcusdt@aggTrade", "/ws/ethusdt@aggTrade"]);
+    stream.stop();
+}
+
+#[cfg(all(feature = "test-support", feature = "market-data"))]
+#[test]
+fn new_listing_is_subscribed() {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use binance_connect::futures_usd::enums::events::Event;
+    use binance_connect::futures_usd::enums::streams::Streams;
+    use binance_connect::futures_usd::mock_server::MockServer;
+
+    const NEW_LISTING: &str = r#"{"e":"contractInfo","E":1700000000000,"s":"NEWUSDT","ps":"NEWUSDT","ct":"PERPETUAL","dt":4133404800000,"ot":1700000000000,"cs":"TRADING","bks":[]}"#;
+    const LISTED: &str = r#"{"e":"contractInfo","E":1700000000001,"s":"BTCUSDT","ps":"BTCUSDT","ct":"PERPETUAL","dt":4133404800000,"ot":1569398400000,"cs":"TRADING","bks":[]}"#;
+
+    seed_production_exchange_info();
+    let server: MockServer = MockServer::new()
+        .with_frame(NEW_LISTING)
+        .with_frame(LISTED)
+        .start()
+        .unwrap();
+    // The contract info connection is full, the listing is subscribed to on a new connection.
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_max_streams_per_connection(1);
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_new_listings(vec![Streams::agg_trade])
+        .start();
+
+    let receiver = stream.consume();
+    let mut listings: Vec<Vec<String>> = Vec::new();
+    while let Ok(event) = receiver.recv_timeout(Duration::from_millis(500)) {
+        if let Event::NewListingEvent(listing) = event {
+            assert_eq!(listing.symbol, "NEWUSDT");
+            listings.push(listing.streams);
+        }
+    }
+    assert_eq!(listings, vec![vec!["newusdt@aggTrade".to_string()]]);
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while server.messages().is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    stream.stop();
+    assert_eq!(
+        server.paths(),
+        vec!["/ws/!contractInfo", "/ws/newusdt@aggTrade"]
+    );
+    assert!(server
+        .messages_on("/ws/newusdt@aggTrade")
+        .iter()
+        .any(|message| message.contains("SUBSCRIBE") && message.contains("newusdt@aggTrade")));
+}
+
+#[test]
+fn cached_exchange_info_is_exposed() {
+    let exchange_info: ExchangeInfo = serde_json::from_str(EXCHANGE_INFO).unwrap();
+    shared().set_exchange_info(true, exchange_info);
+    let config: FuturesWebSocketConfig = FuturesWebSocketConfig::from_endpoint(Endpoint::Testnet);
+
+    assert_eq!(config.symbols().unwrap(), vec!["BTCUSDT".to_string()]);
+    assert_eq!(config.precision("btcusdt").unwrap(), Some((2, 3)));
+    assert!(matches!(
+        config.filters("BTCUSDT").unwrap().unwrap()[..],
+        [SymbolFilter::PriceFilter { .. }]
+    ));
+    assert_eq!(config.filters("ETHUSDT").unwrap(), None);
+
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config.clone())
+        .with_agg_trade("btcusdt")
+        .with_book_tickers();
+    assert!(stream.validate_symbols().is_ok());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config).with_agg_trade("btcusdtt");
+    match stream.validate_symbols() {
+        Err(BinanceConnectError::InvalidStreamConfig { stream, .. }) => {
+            assert_eq!(stream, "btcusdtt@aggTrade")
+        }
+        result => panic!("unexpected {:?}", result),
+    }
+
+    // Orders are validated against the same cached `ExchangeInfo`.
+    let order: OrderParams = OrderParams::new("BTCUSDT", OrderType::Limit, 1.0).with_price(100.05);
+    assert!(matches!(
+        validate_order_shared(true, None, &order),
+        Err(BinanceConnectError::FilterViolation { .. })
+    ));
+    let order: OrderParams = OrderParams::new("BTCUSDT", OrderType::Limit, 1.0).with_price(100.1);
+    assert!(validate_order_shared(true, None, &order).is_ok());
+}
@@ -0,0 +1,66 @@
+use binance_connect::futures_usd::enums::binance::OrderStatus;
+use binance_connect::futures_usd::fill_notice::FillNotice;
+
+const ORDER_TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"7103.04","sp":"0","x":"TRADE","X":"PARTIALLY_FILLED","i":8886774,"l":"0.0005","z":"0.0005","L":"7103.04","N":"USDT","n":"0.001","T":1568879465650,"t":1,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}}"#;
+
+#[test]
+fn order_update_is_scanned() {
+    assert_eq!(
+        FillNotice::scan(ORDER_TRADE_UPDATE),
+        Some(FillNotice {
+            symbol: "BTCUSDT".to_string(),
+            order_id: 8886774,
+            status: OrderStatus::PartiallyFilled,
+        })
+    );
+}
+
+#[test]
+fn combined_order_update_is_scanned() {
+    let combined: String = format!(r#"{{"stream":"listenKey","data":{}}}"#, ORDER_TRADE_UPDATE);
+    let fill_notice: FillNotice = FillNotice::scan(&combined).unwrap();
+    assert_eq!(fill_notice.symbol, "BTCUSDT");
+    assert_eq!(fill_notice.order_id, 8886774);
+}
+
+#[test]
+fn fields_are_read_from_the_order_object() {
+    // The symbol of the event precedes the order object in some payloads.
+    let json: String =
+        ORDER_TRADE_UPDATE.replace(r#""T":1568879465650,"o":{"#, r#""s":"ETHUSDT","i":1,"o":{"#);
+    let fill_notice: FillNotice = FillNotice::scan(&json).unwrap();
+    assert_eq!(fill_notice.symbol, "BTCUSDT");
+    assert_eq!(fill_notice.order_id, 8886774);
+}
+
+#[test]
+fn unknown_status_is_tolerated() {
+    let json: String = ORDER_TRADE_UPDATE.replace("PARTIALLY_FILLED", "EXPIRED_IN_MATCH");
+    assert_eq!(
+        FillNotice::scan(&json).unwrap().status,
+        OrderStatus::Unknown
+    );
+}
+
+#[test]
+fn other_frames_are_not_scanned() {
+    let book_ticker: &str = r#"{"e":"bookTicker","u":400900217,"E":1568014460893,"T":1568014460891,"s":"BTCUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}"#;
+    assert_eq!(FillNotice::scan(book_ticker), None);
+    assert_eq!(FillNotice::scan(""), None);
+}
+
+#[test]
+fn incomplete_order_update_is_not_scanned() {
+    for field in [
+        r#""X":"PARTIALLY_FILLED","#,
+        r#""i":8886774,"#,
+        r#""s":"BTCUSDT","#,
+    ] {
+        assert_eq!(
+            FillNotice::scan(&ORDER_TRADE_UPDATE.replace(field, "")),
+            None
+        );
+    }
+    // A truncated frame ends inside the order object.
+    assert_eq!(FillNotice::scan(&ORDER_TRADE_UPDATE[..150]), None);
+}
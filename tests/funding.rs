@@ -0,0 +1,57 @@
+#![cfg(feature = "market-data")]
+
+use std::time::Duration;
+
+use binance_connect::futures_usd::enums::events::{Event, EventType};
+use binance_connect::futures_usd::funding::{FundingCountdown, FundingTracker};
+use binance_connect::futures_usd::response::MarkPriceUpdate;
+
+const FUNDING_TIME: i64 = 1708012800000;
+
+fn mark_price_update(event_time: i64, next_funding_time: i64) -> Event {
+    Event::MarkPriceUpdateEvent(MarkPriceUpdate {
+        event_type: EventType::MarkPriceUpdateEventType,
+        event_time,
+        symbol: "BTCUSDT".to_string(),
+        mark_price: 100.0,
+        index_price: 100.0,
+        estimated_settle_price: 100.0,
+        funding_rate: 0.0001,
+        next_funding_time,
+    })
+}
+
+/// Feeds the update, returning the countdowns (in seconds) raised by it.
+fn countdowns(funding_tracker: &mut FundingTracker, seconds_before: i64) -> Vec<i64> {
+    let event: Event = mark_price_update(FUNDING_TIME - seconds_before * 1000, FUNDING_TIME);
+    assert!(funding_tracker.observe(&event).is_empty());
+    funding_tracker
+        .drain_countdowns()
+        .iter()
+        .map(|countdown: &FundingCountdown| {
+            assert_eq!(countdown.next_funding_time, FUNDING_TIME);
+            assert_eq!(countdown.remaining_ms, seconds_before * 1000);
+            countdown.countdown_ms / 1000
+        })
+        .collect()
+}
+
+#[test]
+fn countdowns_are_raised_once_per_funding() {
+    let mut funding_tracker: FundingTracker =
+        FundingTracker::new().with_countdowns(&[Duration::from_secs(30), Duration::from_secs(300)]);
+
+    assert!(countdowns(&mut funding_tracker, 301).is_empty());
+    assert_eq!(countdowns(&mut funding_tracker, 299), [300]);
+    assert!(countdowns(&mut funding_tracker, 296).is_empty());
+    assert_eq!(countdowns(&mut funding_tracker, 29), [30]);
+    assert!(countdowns(&mut funding_tracker, 1).is_empty());
+
+    // The next funding is hours away, a tracker started within both countdowns raises both.
+    let next: Event = mark_price_update(FUNDING_TIME, FUNDING_TIME + 8 * 3600 * 1000);
+    funding_tracker.observe(&next);
+    assert!(funding_tracker.drain_countdowns().is_empty());
+    let mut late: FundingTracker =
+        FundingTracker::new().with_countdowns(&[Duration::from_secs(300), Duration::from_secs(30)]);
+    assert_eq!(countdowns(&mut late, 10), [300, 30]);
+}
@@ -0,0 +1,103 @@
+#![cfg(all(
+    feature = "healthcheck",
+    feature = "test-support",
+    feature = "market-data"
+))]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::mock_server::{MockServer, BOOK_TICKER};
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+/// Returns a local address with a free port.
+fn free_addr() -> String {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+/// Requests the endpoint, returning the status line of the response.
+fn probe(addr: &str) -> String {
+    let mut stream: TcpStream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+        .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response: String = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn status_is_served() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let addr: String = free_addr();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_stale_timeout(Duration::from_secs(5))
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_healthcheck(&addr)
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+    assert_eq!(probe(&addr), "HTTP/1.1 200 OK");
+
+    stream.stop();
+    let started: Instant = Instant::now();
+    while probe(&addr) != "HTTP/1.1 503 Service Unavailable"
+        && started.elapsed() < Duration::from_secs(10)
+    {
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(probe(&addr), "HTTP/1.1 503 Service Unavailable");
+}
+
+#[test]
+fn idle_connection_does_not_block_probes() {
+    let addr: String = free_addr();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom("ws://127.0.0.1:1").unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_healthcheck(&addr)
+        .start();
+
+    // A TCP probe connects without sending a request.
+    let _idle: TcpStream = TcpStream::connect(&addr).unwrap();
+    let started: Instant = Instant::now();
+    assert_eq!(probe(&addr), "HTTP/1.1 503 Service Unavailable");
+    assert!(started.elapsed() < Duration::from_secs(5));
+    stream.stop();
+}
+
+#[test]
+fn taken_port_does_not_fail_the_stream() {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr: String = listener.local_addr().unwrap().to_string();
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_healthcheck(&addr)
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
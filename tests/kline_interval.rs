@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use binance_connect::futures_usd::enums::binance::KlineInterval;
+
+#[test]
+fn durations_round_trip() {
+    for interval in KlineInterval::iter() {
+        assert_eq!(
+            KlineInterval::from_duration(interval.as_duration()),
+            Some(interval)
+        );
+    }
+    assert_eq!(
+        KlineInterval::Minutes15.as_duration(),
+        Duration::from_secs(900)
+    );
+    assert_eq!(KlineInterval::from_duration(Duration::from_secs(7)), None);
+}
+
+#[test]
+fn intervals_are_ordered() {
+    let intervals: Vec<KlineInterval> = KlineInterval::iter().collect();
+    assert_eq!(intervals.len(), 15);
+    assert_eq!(intervals.first(), Some(&KlineInterval::Minutes1));
+    assert_eq!(intervals.last(), Some(&KlineInterval::Months1));
+    assert!(intervals
+        .windows(2)
+        .all(|pair| pair[0] < pair[1] && pair[0].as_duration() < pair[1].as_duration()));
+}
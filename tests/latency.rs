@@ -0,0 +1,111 @@
+#![cfg(feature = "market-data")]
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+use tungstenite::{accept, Message, WebSocket};
+
+/// The frames pumped per second by the loopback server.
+const RATE: u64 = 2_000;
+/// The number of frames pumped per run.
+const FRAMES: u64 = 4_000;
+/// The p99 socket-to-receiver latency budget, generous enough for unoptimized CI builds.
+const P99_BUDGET: Duration = Duration::from_millis(10);
+
+/// Returns the local time in microseconds since the UNIX epoch.
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
+
+/// A synthetic `bookTicker` frame carrying the send time (micros) as update id.
+fn book_ticker(sent: u64) -> String {
+    format!(
+        r#"{{"e":"bookTicker","u":{},"E":{},"T":{},"s":"BTCUSDT","b":"25000.10","B":"1.5","a":"25000.20","A":"2.5"}}"#,
+        sent,
+        sent / 1_000,
+        sent / 1_000
+    )
+}
+
+/// Starts a loopback WebSocket server pumping `FRAMES` frames at `RATE` to the first client.
+fn loopback_server() -> u16 {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port: u16 = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nodelay(true).unwrap();
+        let mut socket: WebSocket<TcpStream> = accept(stream).unwrap();
+        let interval: Duration = Duration::from_nanos(1_000_000_000 / RATE);
+        let started: Instant = Instant::now();
+        for frame in 0..FRAMES {
+            let due: Instant = started + interval * frame as u32;
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            }
+            socket
+                .send(Message::Text(book_ticker(now_micros())))
+                .unwrap();
+        }
+        // Keep the connection open until the client went through all frames.
+        thread::sleep(Duration::from_secs(5));
+    });
+    port
+}
+
+/// Receives the frames of the loopback server, returning the latency (micros) of every frame.
+fn receive_latencies() -> Vec<u64> {
+    let port: u16 = loopback_server();
+    let url: String = format!("ws://127.0.0.1:{}/", port);
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&url).unwrap()).do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut latencies: Vec<u64> = Vec::new();
+    let mut last_update_id: u64 = 0;
+    while (latencies.len() as u64) < FRAMES {
+        match stream.consume().recv_timeout(Duration::from_secs(5)) {
+            Ok(Event::BookTickerEvent(book_ticker)) => {
+                latencies.push(now_micros().saturating_sub(book_ticker.update_id));
+                assert!(book_ticker.update_id > last_update_id, "out of order");
+                last_update_id = book_ticker.update_id;
+            }
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+    stream.stop();
+    latencies
+}
+
+#[test]
+fn book_tickers_are_not_dropped() {
+    assert_eq!(receive_latencies().len() as u64, FRAMES, "frames dropped");
+}
+
+/// Measures the wall clock latency, which depends on the load of the machine, run it with
+/// `cargo test --release --test latency -- --ignored` on an otherwise idle machine.
+#[test]
+#[ignore]
+fn book_ticker_p99_latency() {
+    let mut latencies: Vec<u64> = receive_latencies();
+    assert_eq!(latencies.len() as u64, FRAMES, "frames dropped");
+    latencies.sort_unstable();
+    let p99: Duration = Duration::from_micros(latencies[latencies.len() * 99 / 100]);
+    assert!(
+        p99 <= P99_BUDGET,
+        "p99 latency {:?} above {:?}",
+        p99,
+        P99_BUDGET
+    );
+}
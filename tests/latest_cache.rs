@@ -0,0 +1,97 @@
+#![cfg(all(feature = "test-support", feature = "market-data"))]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::latest_cache::{LatestCache, Staleness};
+use binance_connect::futures_usd::mock_server::MockServer;
+use binance_connect::futures_usd::response::BookTicker;
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+use binance_connect::futures_usd::time_sync::server_now;
+
+fn book_ticker(symbol: &str, update_id: u64, event_time: u64, bid: f64) -> String {
+    format!(
+        r#"{{"e":"bookTicker","u":{},"E":{},"T":{},"s":"{}","b":"{}","B":"1.5","a":"25000.20","A":"2.5"}}"#,
+        update_id, event_time, event_time, symbol, bid
+    )
+}
+
+/// Streams the frames through a stream with a latest-value cache and returns the cache once all
+/// frames were received.
+fn cache_frames(frames: &[String], ttl: Duration) -> Arc<LatestCache> {
+    let server: MockServer = frames
+        .iter()
+        .fold(MockServer::new(), |server, frame| server.with_frame(frame))
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_latest_cache(ttl)
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let receiver = stream.consume();
+    for _ in frames {
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+    let latest_cache: Arc<LatestCache> = stream.latest_cache().unwrap();
+    stream.stop();
+    latest_cache
+}
+
+#[test]
+fn latest_value_per_stream_wins() {
+    let now: u64 = server_now();
+    let latest_cache: Arc<LatestCache> = cache_frames(
+        &[
+            book_ticker("BTCUSDT", 1, now, 25000.0),
+            book_ticker("ETHUSDT", 1, now, 1600.0),
+            book_ticker("BTCUSDT", 2, now, 25001.0),
+            book_ticker("BTCUSDT", 3, now, 25002.0),
+        ],
+        Duration::from_secs(60),
+    );
+
+    let mut streams: Vec<String> = latest_cache.streams();
+    streams.sort();
+    assert_eq!(streams, vec!["BTCUSDT@bookTicker", "ETHUSDT@bookTicker"]);
+    let btcusdt: BookTicker = latest_cache
+        .get::<BookTicker>("BTCUSDT@bookTicker")
+        .fresh()
+        .unwrap();
+    assert_eq!((btcusdt.update_id, btcusdt.bid_price), (3, 25002.0));
+    let ethusdt: BookTicker = latest_cache
+        .get::<BookTicker>("ETHUSDT@bookTicker")
+        .fresh()
+        .unwrap();
+    assert_eq!((ethusdt.update_id, ethusdt.bid_price), (1, 1600.0));
+    assert_eq!(
+        latest_cache.get::<BookTicker>("XRPUSDT@bookTicker"),
+        Staleness::Missing
+    );
+}
+
+#[test]
+fn old_value_is_expired_and_invalidated() {
+    let event_time: u64 = server_now() - 60_000;
+    let latest_cache: Arc<LatestCache> = cache_frames(
+        &[book_ticker("BTCUSDT", 1, event_time, 25000.0)],
+        Duration::from_secs(10),
+    );
+
+    match latest_cache.get::<BookTicker>("BTCUSDT@bookTicker") {
+        Staleness::Expired { value, age } => {
+            assert_eq!(value.bid_price, 25000.0);
+            assert!(age >= Duration::from_secs(60));
+        }
+        staleness => panic!("unexpected {:?}", staleness),
+    }
+    latest_cache.invalidate_all();
+    assert_eq!(
+        latest_cache.get::<BookTicker>("BTCUSDT@bookTicker"),
+        Staleness::Invalidated
+    );
+}
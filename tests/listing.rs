@@ -0,0 +1,56 @@
+#![cfg(feature = "market-data")]
+
+use binance_connect::futures_usd::enums::streams::Streams;
+use binance_connect::futures_usd::listing::{ListingDetector, NewListing};
+use binance_connect::futures_usd::response::ContractInfo;
+
+fn contract_info(symbol: &str, status: &str) -> ContractInfo {
+    serde_json::from_str(&format!(
+        r#"{{"e":"contractInfo","E":1700000000000,"s":"{}","ps":"{}","ct":"PERPETUAL","dt":4133404800000,"ot":1700000000000,"cs":"{}","bks":[]}}"#,
+        symbol, symbol, status
+    ))
+    .unwrap()
+}
+
+/// Returns the symbols of the listings raised by the status updates.
+fn listings(listing_detector: &mut ListingDetector, updates: &[(&str, &str)]) -> Vec<String> {
+    updates
+        .iter()
+        .filter_map(|(symbol, status)| listing_detector.update(&contract_info(symbol, status)))
+        .map(|listing: NewListing| listing.symbol)
+        .collect()
+}
+
+#[test]
+fn contracts_are_listed_once() {
+    let mut listing_detector: ListingDetector =
+        ListingDetector::new(vec!["BTCUSDT".to_string()], vec![Streams::agg_trade]);
+
+    assert_eq!(
+        listings(
+            &mut listing_detector,
+            &[
+                ("BTCUSDT", "TRADING"),
+                ("NEWUSDT", "PENDING_TRADING"),
+                ("NEWUSDT", "TRADING"),
+                ("NEWUSDT", "TRADING"),
+            ]
+        ),
+        ["NEWUSDT"]
+    );
+    // Trading again after settling is no new listing.
+    assert!(listings(
+        &mut listing_detector,
+        &[
+            ("BTCUSDT", "SETTLING"),
+            ("BTCUSDT", "TRADING"),
+            ("NEWUSDT", "SETTLING"),
+            ("NEWUSDT", "TRADING"),
+        ]
+    )
+    .is_empty());
+    let listing: NewListing = listing_detector
+        .update(&contract_info("ETHUSDT", "TRADING"))
+        .unwrap();
+    assert_eq!(listing.streams, ["ethusdt@aggTrade"]);
+}
@@ -0,0 +1,1420 @@
+#![cfg(all(
+    feature = "test-support",
+    feature = "market-data",
+    feature = "user-data"
+))]
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use binance_connect::error::BinanceConnectError;
+use binance_connect::futures_usd::alias::SymbolAliases;
+use binance_connect::futures_usd::downtime::{DowntimeReport, RecoveryAction};
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::binance::{OrderType, StreamProperty};
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::enums::streams::Streams;
+use binance_connect::futures_usd::event_filter::EventFilter;
+use binance_connect::futures_usd::listen_key::{ApiAuth, ListenKey, ListenKeyProvider};
+use binance_connect::futures_usd::mock_server::{
+    MockServer, AGG_TRADE, BOOK_TICKER, MARK_PRICE_UPDATE, ORDER_TRADE_UPDATE,
+};
+use binance_connect::futures_usd::multi_account::{AccountEvent, MultiAccountStream};
+use binance_connect::futures_usd::ping::ClientPing;
+use binance_connect::futures_usd::pool::ConnectionManager;
+use binance_connect::futures_usd::reconnect::{
+    DisconnectReason, ReconnectDecision, ReconnectTriggers,
+};
+use binance_connect::futures_usd::sequence::SequenceWal;
+use binance_connect::futures_usd::sink::NoopSink;
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+use binance_connect::futures_usd::transport::IpPreference;
+
+#[test]
+fn book_ticker_is_deserialized() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_frame(AGG_TRADE)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_agg_trade("btcusdt")
+        .start();
+
+    let receiver = stream.consume();
+    match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::BookTickerEvent(book_ticker) => assert_eq!(book_ticker.symbol, "BTCUSDT"),
+        event => panic!("unexpected {:?}", event),
+    }
+    match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::AggTradeEvent(agg_trade) => assert_eq!(agg_trade.agg_trade_id, 5933014),
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+    assert_eq!(server.connections(), 1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_urls_are_connected_to() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let configs: [FuturesWebSocketConfig; 2] = [
+        FuturesWebSocketConfig::new(&server.url(), "wss://stream.binancefuture.com/").unwrap(),
+        FuturesWebSocketConfig::default()
+            .with_url_testnet(&server.url())
+            .unwrap()
+            .use_testnet(),
+    ];
+    for config in configs {
+        let stream: FuturesUsdStream = FuturesUsdStream::with_config(config.do_not_reconnect())
+            .with_book_ticker("btcusdt")
+            .start();
+        assert!(matches!(
+            stream
+                .consume()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap(),
+            Event::BookTickerEvent(_)
+        ));
+        stream.stop();
+    }
+    assert_eq!(server.connections(), 2);
+    assert!(FuturesWebSocketConfig::new("https://fstream.binance.com", &server.url()).is_err());
+}
+
+#[test]
+fn aliases_are_seen_after_the_subscription_filters() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .with_frame(BOOK_TICKER)
+        .start()
+        .unwrap();
+    let (fill_sender, fill_receiver) = channel();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_symbol_aliases(SymbolAliases::new().with_alias("INST-42", "BTCUSDT"))
+            .with_fill_callback(move |fill| fill_sender.send(fill.symbol.clone()).unwrap())
+            .with_event_filter(EventFilter::new().with_symbols(&["INST-42"]))
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("INST-42")
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::OrderTradeUpdateEvent(order_trade_update)) => {
+            assert_eq!(order_trade_update.order_data.symbol, "INST-42")
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(book_ticker)) => assert_eq!(book_ticker.symbol, "INST-42"),
+        event => panic!("unexpected {:?}", event),
+    }
+    assert_eq!(fill_receiver.try_recv().unwrap(), "INST-42");
+    assert_eq!(server.paths(), ["/ws/btcusdt@bookTicker"]);
+    stream.stop();
+}
+
+#[test]
+fn events_are_polled_with_timeouts() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_frame(AGG_TRADE)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_agg_trade("btcusdt")
+        .start();
+
+    let events = stream.events();
+    match events.next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(book_ticker)) => assert_eq!(book_ticker.symbol, "BTCUSDT"),
+        event => panic!("unexpected {:?}", event),
+    }
+    thread::sleep(Duration::from_millis(200));
+    let pending: Vec<Event> = events.try_events().collect();
+    assert_eq!(pending.len(), 1);
+    assert!(matches!(pending[0], Event::AggTradeEvent(_)));
+    assert!(events.try_events().next().is_none());
+    assert!(events.next_event(Duration::from_millis(100)).is_none());
+    stream.stop();
+}
+
+#[test]
+fn dropped_stream_closes_connection() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    // Bounded reads notice the stop signal without waiting for the next frame.
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_stale_timeout(Duration::from_secs(5));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+    drop(stream);
+    let started: Instant = Instant::now();
+    while server.closes() == 0 && started.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.closes(), 1);
+    assert_eq!(server.connections(), 1);
+}
+
+#[test]
+fn stream_list_is_subscribed() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_streams(vec![
+            Streams::agg_trade("btcusdt"),
+            Streams::book_ticker("btcusdt"),
+        ])
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::BookTickerEvent(book_ticker) => assert_eq!(book_ticker.symbol, "BTCUSDT"),
+        event => panic!("unexpected {:?}", event),
+    }
+    // The server reads the subscription of the rest of the list after sending its frame.
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while server.messages().is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    stream.stop();
+    assert_eq!(server.paths(), vec!["/ws/btcusdt@bookTicker"]);
+    assert!(server
+        .messages()
+        .iter()
+        .any(|message| message.contains("btcusdt@aggTrade")));
+}
+
+#[test]
+fn raw_json_is_attached() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_raw_json();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let event: Event = stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(event.raw(), Some(BOOK_TICKER));
+    match event.into_parts() {
+        (Event::BookTickerEvent(book_ticker), Some(raw)) => {
+            assert_eq!(book_ticker.symbol, "BTCUSDT");
+            assert_eq!(&*raw, BOOK_TICKER);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn events_are_published_to_sinks() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let (sink, worker) = channel();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_sink(sink)
+        .with_sink(NoopSink)
+        .start();
+
+    let event: Event = stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(worker.recv_timeout(Duration::from_secs(5)).unwrap(), event);
+    stream.stop();
+}
+
+#[test]
+fn events_are_stamped_with_the_sequence() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_frame(AGG_TRADE)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("binance_connect_stream_{}.wal", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let (sink, worker) = channel();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_agg_trade("btcusdt")
+        .with_sequence_wal(SequenceWal::open(&path).unwrap())
+        .with_sink(sink)
+        .start();
+
+    let receiver = stream.consume();
+    for expected in 0..2 {
+        let event: Event = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event.sequence(), Some(expected));
+        // The sinks see the same stamped event.
+        assert_eq!(worker.recv_timeout(Duration::from_secs(5)).unwrap(), event);
+    }
+    stream.stop();
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn binary_frame_is_deserialized() {
+    let server: MockServer = MockServer::new()
+        .with_binary_frame(BOOK_TICKER)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::BookTickerEvent(book_ticker) => assert_eq!(book_ticker.symbol, "BTCUSDT"),
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn raw_stream_is_emitted() {
+    let payload: &str = r#"{"e":"someNewEvent","E":1568014460893,"s":"BTCUSDT"}"#;
+    let server: MockServer = MockServer::new()
+        .with_frame(payload)
+        .with_frame(&format!(
+            r#"{{"stream":"btcusdt@someNewStream","data":{}}}"#,
+            payload
+        ))
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_raw_stream("btcusdt@someNewStream")
+        .start();
+
+    let receiver = stream.consume();
+    match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::Raw { stream, json } => {
+            assert_eq!(stream, None);
+            assert_eq!(json, payload);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::Raw { stream, .. } => assert_eq!(stream.as_deref(), Some("btcusdt@someNewStream")),
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+    assert_eq!(server.paths(), vec!["/ws/btcusdt@someNewStream"]);
+}
+
+#[test]
+fn unknown_enum_value_is_tolerated() {
+    let order_trade_update: String =
+        ORDER_TRADE_UPDATE.replace(r#""o":"LIMIT""#, r#""o":"SOME_NEW_TYPE""#);
+    let server: MockServer = MockServer::new()
+        .with_frame(&order_trade_update)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::OrderTradeUpdateEvent(update) => {
+            assert_eq!(update.order_data.order_type, OrderType::Unknown)
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn error_message_is_deserialized() {
+    let server: MockServer = MockServer::new()
+        .with_frame(r#"{"error":{"code":2,"msg":"Invalid request"},"id":1}"#)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::ErrorMessageEvent(error_message) => {
+            assert_eq!(error_message.code, 2);
+            assert_eq!(error_message.msg, "Invalid request");
+            assert_eq!(error_message.id, Some(1));
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn subscription_is_confirmed() {
+    let server: MockServer = MockServer::new().with_confirmed_requests().start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    // The last stream is connected to by URL, the others are subscribed to.
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .with_agg_trade("btcusdt")
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::SubscriptionConfirmedEvent(confirmed) => {
+            assert_eq!(confirmed.method, "SUBSCRIBE");
+            assert_eq!(confirmed.streams, vec!["btcusdt@bookTicker".to_string()]);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn combined_property_is_set() {
+    let combined: String = format!(
+        r#"{{"stream":"btcusdt@bookTicker","data":{}}}"#,
+        BOOK_TICKER
+    );
+    let mut server: MockServer = MockServer::new()
+        .with_confirmed_requests()
+        .with_interval(Duration::from_millis(20));
+    for _ in 0..50 {
+        server = server.with_frame(&combined);
+    }
+    let server: MockServer = server.start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    // Set once the connection runs, a reconnect re-sends the property under a new id.
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::BookTickerEvent(_) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+    let id: u64 = stream.set_property(StreamProperty::Combined, true);
+    let mut book_tickers: usize = 1;
+    loop {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(_) => book_tickers += 1,
+            Event::PropertyEvent(property) => {
+                assert_eq!(property.id, id);
+                assert_eq!(property.property, Some(StreamProperty::Combined));
+                assert!(property.value);
+                break;
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    assert!(book_tickers >= 1);
+    assert!(server
+        .messages()
+        .iter()
+        .any(|message| message.contains("SET_PROPERTY")));
+    stream.stop();
+}
+
+#[test]
+fn stream_reconnects_after_close() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_close_after_frames()
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut book_tickers: usize = 0;
+    let mut closed: bool = false;
+    while book_tickers < 2 {
+        let event: Event = stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        match event {
+            Event::BookTickerEvent(_) => book_tickers += 1,
+            Event::ConnectionClosedEvent(close) => {
+                assert_eq!(close.code, 1000);
+                assert_eq!(close.reason, "mock server closed");
+                closed = true;
+            }
+            _ => {}
+        }
+    }
+    assert!(closed);
+    let stats = stream.stats();
+    assert_eq!(stats.len(), 1);
+    assert!(stats[0].reconnects >= 1);
+    assert!(stats[0].messages_received >= 2);
+    stream.stop();
+    assert!(server.connections() >= 2);
+}
+
+#[test]
+fn incident_is_reported_on_reconnect() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_close_after_frames()
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_downtime_reports();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut book_tickers: usize = 0;
+    let report: DowntimeReport = loop {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(_) => book_tickers += 1,
+            Event::DowntimeReportEvent(report) => break report,
+            _ => {}
+        }
+    };
+    // The incident is closed by the reconnect, not by the next event.
+    assert_eq!(book_tickers, 1);
+    assert_eq!(report.reasons.len(), 1);
+    assert!(matches!(report.actions[0], RecoveryAction::Reconnect(_)));
+    assert_eq!(report.gaps.len(), 1);
+    assert_eq!(report.gaps[0].stream, "BTCUSDT@bookTicker");
+    assert!(report.gaps[0].gap_start <= report.gaps[0].gap_end);
+    assert_eq!(report.gaps[0].gap_end, report.ended);
+    assert_eq!(stream.downtime_reports()[0], report);
+    stream.stop();
+}
+
+#[test]
+fn stream_survives_many_reconnects() {
+    let server: MockServer = MockServer::new()
+        .with_frame(BOOK_TICKER)
+        .with_close_after_frames()
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut reconnects: usize = 0;
+    while reconnects < 50 {
+        let event: Event = stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        if let Event::ReconnectEvent(_) = event {
+            reconnects += 1;
+        }
+    }
+    stream.stop();
+    assert!(server.connections() >= 50);
+}
+
+#[test]
+fn stale_connection_is_reconnected() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_stale_timeout(Duration::from_millis(200));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut reconnected: bool = false;
+    loop {
+        let event: Event = stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        match event {
+            Event::ReconnectEvent(trace) => {
+                assert_eq!(trace.decision, ReconnectDecision::Immediate);
+                assert!(matches!(trace.reason, DisconnectReason::Stale(_)));
+                reconnected = true;
+            }
+            // The frame of the new connection.
+            Event::BookTickerEvent(_) if reconnected => break,
+            _ => {}
+        }
+    }
+    stream.stop();
+    assert!(server.connections() >= 2);
+}
+
+#[test]
+fn standby_takes_over_a_dead_primary() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    // The primary goes stale after its frame, the standby only discards and is never declared
+    // stale.
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_warm_standby()
+            .with_stale_timeout(Duration::from_millis(300))
+            .with_downtime_reports();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut book_tickers: usize = 0;
+    let mut reconnected: bool = false;
+    let report: DowntimeReport = loop {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(_) => book_tickers += 1,
+            Event::ReconnectEvent(trace) => {
+                assert!(matches!(trace.reason, DisconnectReason::Stale(_)));
+                reconnected = true;
+            }
+            Event::DowntimeReportEvent(report) => break report,
+            _ => {}
+        }
+    };
+    assert!(reconnected);
+    // The frame the standby received while idle is not delivered.
+    assert_eq!(book_tickers, 1);
+    assert!(report.actions.contains(&RecoveryAction::StandbyPromoted));
+    stream.stop();
+}
+
+#[test]
+fn connection_is_rotated_without_reconnect() {
+    let mut server: MockServer = MockServer::new().with_interval(Duration::from_millis(20));
+    for _ in 0..50 {
+        server = server.with_frame(BOOK_TICKER);
+    }
+    let server: MockServer = server.start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_max_connection_age(Duration::from_millis(300));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    for _ in 0..30 {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(_) => {}
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    assert_eq!(stream.stats()[0].reconnects, 0);
+    stream.stop();
+    assert!(server.connections() >= 2);
+}
+
+#[test]
+fn migration_has_no_gap_or_duplicate() {
+    let mut server: MockServer = MockServer::new()
+        .with_interval(Duration::from_millis(10))
+        .with_live_frames();
+    for id in 0..200 {
+        server = server.with_frame(&AGG_TRADE.replace("5933014", &id.to_string()));
+    }
+    let server: MockServer = server.start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_migration_overlap(Duration::from_millis(200));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .start();
+
+    let mut ids: Vec<u64> = Vec::new();
+    while ids.last() != Some(&199) {
+        match stream.events().next_event(Duration::from_secs(5)) {
+            Some(Event::AggTradeEvent(agg_trade)) => ids.push(agg_trade.agg_trade_id),
+            event => panic!("unexpected {:?}", event),
+        }
+        if ids.len() == 20 {
+            stream.migrate();
+        }
+    }
+    let first: u64 = ids[0];
+    assert_eq!(ids, (first..200).collect::<Vec<u64>>());
+    assert_eq!(server.connections(), 2);
+    assert_eq!(stream.stats()[0].reconnects, 0);
+    stream.stop();
+}
+
+#[test]
+fn events_received_again_after_reconnect_are_suppressed() {
+    let mut server: MockServer = MockServer::new().with_close_after_frames();
+    for id in 0..10 {
+        server = server.with_frame(&AGG_TRADE.replace("5933014", &id.to_string()));
+    }
+    let server: MockServer = server.start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_duplicate_window(Duration::from_secs(10));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .start();
+
+    // Every connection receives the same trades, only the first connection forwards them.
+    let mut ids: Vec<u64> = Vec::new();
+    let mut reconnects: usize = 0;
+    while reconnects < 3 {
+        match stream.events().next_event(Duration::from_secs(5)) {
+            Some(Event::AggTradeEvent(agg_trade)) => ids.push(agg_trade.agg_trade_id),
+            Some(Event::ConnectionClosedEvent(_)) => {}
+            Some(Event::ReconnectEvent(_)) => reconnects += 1,
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    assert_eq!(ids, (0..10).collect::<Vec<u64>>());
+    stream.stop();
+}
+
+#[cfg(feature = "depth")]
+#[test]
+fn older_partial_depth_is_not_suppressed() {
+    let book_depth = |final_update_id: u64| {
+        format!(
+            r#"{{"e":"depthUpdate","E":1571889248277,"T":1571889248276,"s":"BTCUSDT","U":{},"u":{},"pu":{},"b":[],"a":[]}}"#,
+            final_update_id,
+            final_update_id,
+            final_update_id - 1
+        )
+    };
+    // A diff. update followed by the partial depth of an older update, within the window.
+    let server: MockServer = MockServer::new()
+        .with_frame(&book_depth(10))
+        .with_frame(&book_depth(8))
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_duplicate_window(Duration::from_secs(10));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    for expected in [10, 8] {
+        match stream.events().next_event(Duration::from_secs(5)) {
+            Some(Event::BookDepthEvent(book_depth)) => {
+                assert_eq!(book_depth.final_update_id, expected)
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    stream.stop();
+}
+
+#[test]
+fn redundant_connection_covers_outage() {
+    let live_server = || {
+        let mut server: MockServer = MockServer::new()
+            .with_interval(Duration::from_millis(10))
+            .with_live_frames();
+        for id in 0..100 {
+            server = server
+                .with_frame(&AGG_TRADE.replace("5933014", &id.to_string()))
+                .with_frame(&MARK_PRICE_UPDATE.replace("1562305380000", &id.to_string()));
+        }
+        server.start().unwrap()
+    };
+    let (primary, secondary) = (live_server(), live_server());
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&primary.url()).unwrap())
+            .with_redundant_endpoint(Endpoint::custom(&secondary.url()).unwrap());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .start();
+
+    let (mut trade_ids, mut mark_price_times): (Vec<u64>, Vec<i64>) = (Vec::new(), Vec::new());
+    while trade_ids.last() != Some(&99) {
+        match stream.events().next_event(Duration::from_secs(5)) {
+            Some(Event::AggTradeEvent(agg_trade)) => trade_ids.push(agg_trade.agg_trade_id),
+            Some(Event::MarkPriceUpdateEvent(mark_price)) => {
+                mark_price_times.push(mark_price.event_time)
+            }
+            Some(Event::ReconnectEvent(_)) | Some(Event::ConnectionClosedEvent(_)) => {}
+            event => panic!("unexpected {:?}", event),
+        }
+        // The outage of the primary endpoint is covered by the secondary.
+        if trade_ids.len() == 20 {
+            primary.stop();
+        }
+    }
+    let first: u64 = trade_ids[0];
+    assert_eq!(trade_ids, (first..100).collect::<Vec<u64>>());
+    let mut unique: Vec<i64> = mark_price_times.clone();
+    unique.dedup();
+    assert_eq!(unique, mark_price_times);
+    assert_eq!(stream.stats().len(), 2);
+    stream.stop();
+}
+
+#[test]
+fn streams_share_pooled_connection() {
+    let combined =
+        |stream: &str, data: &str| format!(r#"{{"stream":"{}","data":{}}}"#, stream, data);
+    let server: MockServer = MockServer::new()
+        .with_interval(Duration::from_millis(100))
+        .with_frame(&combined("btcusdt@aggTrade", AGG_TRADE))
+        .with_frame(&combined("btcusdt@bookTicker", BOOK_TICKER))
+        .with_frame(&combined("btcusdt@aggTrade", AGG_TRADE))
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let connection_manager: ConnectionManager = ConnectionManager::new(config.clone(), 5);
+    let trades: FuturesUsdStream = FuturesUsdStream::with_config(config.clone())
+        .with_connection_manager(&connection_manager)
+        .with_agg_trade("btcusdt")
+        .start();
+    let quotes: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_connection_manager(&connection_manager)
+        .with_agg_trade("btcusdt")
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let received = |stream: &FuturesUsdStream, count: usize| -> Vec<Event> {
+        (0..count)
+            .map(|_| stream.events().next_event(Duration::from_secs(5)).unwrap())
+            .collect()
+    };
+    let trade_events: Vec<Event> = received(&trades, 2);
+    assert!(trade_events
+        .iter()
+        .all(|event| matches!(event, Event::AggTradeEvent(_))));
+    let quote_events: Vec<Event> = received(&quotes, 3);
+    assert!(matches!(quote_events[1], Event::BookTickerEvent(_)));
+    assert_eq!(server.connections(), 1);
+    assert_eq!(connection_manager.stats().len(), 1);
+    assert!(server
+        .messages()
+        .iter()
+        .any(|message| message.contains("btcusdt@bookTicker")));
+    connection_manager.stop();
+}
+
+#[test]
+fn connections_answer_their_own_pings() {
+    let server: MockServer = MockServer::new()
+        .with_ping("keepalive")
+        .with_frame(BOOK_TICKER)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_max_streams_per_connection(1)
+            // Bounded reads flush the runtime subscription on the idle connection.
+            .with_stale_timeout(Duration::from_secs(30));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while server.pongs().len() < 2 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    stream.unsubscribe(&["btcusdt@aggTrade"]);
+    while server.messages().is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    // A pong answered twice or on the other connection would show up by now.
+    thread::sleep(Duration::from_millis(200));
+    stream.stop();
+    let mut pongs: Vec<(String, String)> = server.pongs();
+    pongs.sort();
+    assert_eq!(
+        pongs,
+        vec![
+            ("/ws/btcusdt@aggTrade".to_string(), "keepalive".to_string()),
+            (
+                "/ws/btcusdt@bookTicker".to_string(),
+                "keepalive".to_string()
+            ),
+        ]
+    );
+    // The unsubscription is sent once, on the connection of the stream only.
+    assert_eq!(server.messages().len(), 1);
+    assert_eq!(server.messages_on("/ws/btcusdt@aggTrade").len(), 1);
+    assert!(server.messages()[0].contains("UNSUBSCRIBE"));
+}
+
+/// Waits until the messages sent on the connection to the path satisfy the condition.
+fn wait_for_messages(server: &MockServer, path: &str, condition: impl Fn(&[String]) -> bool) {
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while !condition(&server.messages_on(path)) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn runtime_subscriptions_go_to_connection_with_capacity() {
+    let server: MockServer = MockServer::new().start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_max_streams_per_connection(2)
+            // Bounded reads flush the runtime subscriptions on the idle connections.
+            .with_stale_timeout(Duration::from_secs(30));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .with_book_ticker("btcusdt")
+        .start();
+    wait_for_messages(&server, "/ws/btcusdt@bookTicker", |messages| {
+        !messages.is_empty()
+    });
+
+    // The first connection is full, the subscription opens a new one.
+    stream.subscribe(&["ethusdt@aggTrade"]);
+    wait_for_messages(&server, "/ws/ethusdt@aggTrade", |messages| {
+        !messages.is_empty()
+    });
+    assert_eq!(server.connections(), 2);
+    stream.subscribe(&["ethusdt@bookTicker"]);
+    wait_for_messages(&server, "/ws/ethusdt@aggTrade", |messages| {
+        messages.len() == 2
+    });
+    // Freed capacity is used again, a subscribed stream is not subscribed to again.
+    stream.unsubscribe(&["btcusdt@aggTrade"]);
+    stream.subscribe(&["solusdt@aggTrade", "ethusdt@bookTicker"]);
+    wait_for_messages(&server, "/ws/btcusdt@bookTicker", |messages| {
+        messages.len() == 3
+    });
+    // Requests without new streams open no connection, an empty one is answered by the first.
+    stream.subscribe(&[]);
+    stream.subscribe(&["ethusdt@aggTrade", "ethusdt@bookTicker"]);
+    wait_for_messages(&server, "/ws/ethusdt@aggTrade", |messages| {
+        messages.len() == 3
+    });
+    wait_for_messages(&server, "/ws/btcusdt@bookTicker", |messages| {
+        messages.len() == 4
+    });
+    stream.stop();
+
+    assert_eq!(server.connections(), 2);
+    let first: Vec<String> = server.messages_on("/ws/btcusdt@bookTicker");
+    assert_eq!(first.len(), 4);
+    assert!(first[0].contains("\"SUBSCRIBE\"") && first[0].contains("btcusdt@aggTrade"));
+    assert!(first[1].contains("UNSUBSCRIBE") && first[1].contains("btcusdt@aggTrade"));
+    assert!(first[2].contains("solusdt@aggTrade") && !first[2].contains("ethusdt@bookTicker"));
+    assert!(first[3].contains("\"params\":[]"));
+    let second: Vec<String> = server.messages_on("/ws/ethusdt@aggTrade");
+    assert_eq!(second.len(), 3);
+    assert!(second[0].contains("ethusdt@aggTrade"));
+    assert!(second[1].contains("ethusdt@bookTicker"));
+}
+
+#[test]
+fn failed_connection_marks_stream_disconnected() {
+    let server: MockServer = MockServer::new()
+        .with_frame(AGG_TRADE)
+        .with_close_after_frames_on("/ws/btcusdt@aggTrade")
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_max_streams_per_connection(1);
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    loop {
+        match stream
+            .consume()
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .unwrap()
+        {
+            Event::ConnectionFailedEvent(_) => break,
+            _ => continue,
+        }
+    }
+    while stream
+        .stats()
+        .iter()
+        .filter(|stats| stats.connected)
+        .count()
+        != 1
+        && Instant::now() < deadline
+    {
+        thread::sleep(Duration::from_millis(10));
+    }
+    // The other connection is still up, the stream is not.
+    assert!(!stream.status().connected);
+    assert!(stream.status().last_event_age_ms.is_some());
+    assert_eq!(
+        stream
+            .stats()
+            .iter()
+            .filter(|stats| stats.connected)
+            .count(),
+        1
+    );
+    stream.stop();
+}
+
+#[test]
+fn client_ping_round_trip_is_measured() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_client_ping(ClientPing {
+                interval: Duration::from_millis(50),
+                pong_timeout: Duration::from_secs(1),
+            });
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    let started: Instant = Instant::now();
+    while stream.stats()[0].ping_rtt_ms.is_none() {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_millis(10));
+    }
+    stream.stop();
+}
+
+#[test]
+fn reconnect_limit_emits_connection_failed() {
+    // A port nothing listens on, every attempt is refused.
+    let url: String = {
+        let listener: std::net::TcpListener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("ws://{}/", listener.local_addr().unwrap())
+    };
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&url).unwrap())
+            .with_max_reconnect_attempts(2);
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    let mut reconnects: usize = 0;
+    loop {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::ReconnectEvent(_) => reconnects += 1,
+            Event::ConnectionFailedEvent(failed) => {
+                assert_eq!(failed.attempts, 2);
+                assert!(matches!(failed.reason, DisconnectReason::SocketError(_)));
+                assert!(failed.url.starts_with(&url));
+                break;
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    assert_eq!(reconnects, 2);
+    stream.stop();
+}
+
+#[test]
+fn json_error_triggers_reconnect_when_enabled() {
+    let server: MockServer = MockServer::new()
+        .with_frame(r#"{"e":"bookTicker","s":"BTCUSDT"}"#)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_reconnect_triggers(ReconnectTriggers::all());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::ReconnectEvent(trace) => {
+            assert!(matches!(trace.reason, DisconnectReason::InvalidPayload(_)));
+            assert_eq!(trace.decision, ReconnectDecision::Immediate);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn reason_without_trigger_emits_connection_failed() {
+    let server: MockServer = MockServer::new()
+        .with_frame(r#"{"e":"bookTicker","s":"BTCUSDT"}"#)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    // JSON errors are no reconnect trigger by default, the connection ends.
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::ConnectionFailedEvent(failed) => {
+            assert_eq!(failed.attempts, 0);
+            assert!(matches!(failed.reason, DisconnectReason::InvalidPayload(_)));
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    assert!(stream
+        .consume()
+        .recv_timeout(Duration::from_millis(500))
+        .is_err());
+    assert_eq!(server.connections(), 1);
+    stream.stop();
+}
+
+#[test]
+fn expired_listen_key_always_reconnects() {
+    let reason: DisconnectReason =
+        DisconnectReason::from_error(&BinanceConnectError::ListenKeyExpired).unwrap();
+    assert_eq!(reason, DisconnectReason::ListenKeyExpired);
+    assert_eq!(reason.decision(), ReconnectDecision::Immediate);
+    let triggers: ReconnectTriggers = ReconnectTriggers {
+        socket_errors: false,
+        json_errors: false,
+        server_close: false,
+        stale_connection: false,
+    };
+    assert!(triggers.triggers(&reason));
+}
+
+/// Hands out a fixed listen key after the delay and counts the deletions.
+#[derive(Debug, Default)]
+struct FixedListenKeyProvider {
+    delay: Duration,
+    deletes: Arc<AtomicUsize>,
+}
+
+impl ListenKeyProvider for FixedListenKeyProvider {
+    fn create(&self, _: &ApiAuth, _: bool) -> Result<ListenKey, BinanceConnectError> {
+        thread::sleep(self.delay);
+        Ok(ListenKey {
+            key: "fixedlistenkey".to_string(),
+        })
+    }
+
+    fn delete(&self, _: &ApiAuth, _: bool) -> Result<(), BinanceConnectError> {
+        self.deletes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[test]
+fn listen_key_provider_is_used() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .start()
+        .unwrap();
+    let listen_key_provider: FixedListenKeyProvider = FixedListenKeyProvider::default();
+    let deletes: Arc<AtomicUsize> = Arc::clone(&listen_key_provider.deletes);
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_api_auth(ApiAuth::new("key".to_string(), "secret".to_string()))
+            .with_listen_key_provider(listen_key_provider)
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config).start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::OrderTradeUpdateEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+    assert_eq!(server.paths(), vec!["/ws/fixedlistenkey".to_string()]);
+    stream.close().unwrap();
+    assert_eq!(deletes.load(Ordering::Relaxed), 1);
+}
+
+/// Hands out the listen key `<api key>listenkey`, so every account gets its own connection path.
+#[derive(Debug)]
+struct AccountListenKeyProvider;
+
+impl ListenKeyProvider for AccountListenKeyProvider {
+    fn create(&self, api_auth: &ApiAuth, _: bool) -> Result<ListenKey, BinanceConnectError> {
+        Ok(ListenKey {
+            key: format!("{}listenkey", api_auth.api_key()?.as_str()),
+        })
+    }
+
+    fn delete(&self, _: &ApiAuth, _: bool) -> Result<(), BinanceConnectError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn account_events_are_tagged_with_the_account() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_listen_key_provider(AccountListenKeyProvider)
+            .do_not_reconnect();
+    let stream: MultiAccountStream = MultiAccountStream::with_config(config)
+        .with_account(
+            "main",
+            ApiAuth::new("main".to_string(), "secret".to_string()),
+        )
+        .with_account("sub", ApiAuth::new("sub".to_string(), "secret".to_string()))
+        .start();
+
+    let mut accounts: Vec<String> = Vec::new();
+    for _ in 0..2 {
+        let account_event: AccountEvent = stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert!(matches!(
+            account_event.event,
+            Event::OrderTradeUpdateEvent(_)
+        ));
+        accounts.push(account_event.account);
+    }
+    stream.stop();
+    accounts.sort();
+    assert_eq!(accounts, vec!["main", "sub"]);
+    let mut paths: Vec<String> = server.paths();
+    paths.sort();
+    assert_eq!(paths, vec!["/ws/mainlistenkey", "/ws/sublistenkey"]);
+}
+
+/// A `/fapi/v2/account` response with a USDT balance and no positions.
+const ACCOUNT: &str = r#"{"totalInitialMargin":"0","totalMaintMargin":"0","totalWalletBalance":"100","totalUnrealizedProfit":"0","totalMarginBalance":"100","totalPositionInitialMargin":"0","totalOpenOrderInitialMargin":"0","totalCrossWalletBalance":"100","totalCrossUnPnl":"0","availableBalance":"100","maxWithdrawAmount":"100","assets":[{"asset":"USDT","walletBalance":"100","unrealizedProfit":"0","marginBalance":"100","maintMargin":"0","initialMargin":"0","positionInitialMargin":"0","openOrderInitialMargin":"0","crossWalletBalance":"100","crossUnPnl":"0","availableBalance":"100","maxWithdrawAmount":"100","marginAvailable":true,"updateTime":1568879465000}],"positions":[]}"#;
+
+#[test]
+fn identical_account_snapshot_is_sent_once() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .with_close_after_frames()
+        .with_http_response("/fapi/v2/account", ACCOUNT)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_api_auth(ApiAuth::new("key".to_string(), "secret".to_string()))
+            .with_listen_key_provider(FixedListenKeyProvider::default())
+            .with_account_snapshot()
+            .with_rest_base_url(&server.http_url());
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config).start();
+
+    let mut snapshots: usize = 0;
+    let mut order_updates: usize = 0;
+    while order_updates < 3 {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::AccountSnapshotEvent(account_snapshot) => {
+                assert_eq!(account_snapshot.assets[0].wallet_balance, 100.0);
+                snapshots += 1;
+            }
+            Event::OrderTradeUpdateEvent(_) => order_updates += 1,
+            _ => {}
+        }
+    }
+    stream.stop();
+    // Every connection requested the snapshot, only the first was sent.
+    let requests: usize = server
+        .paths()
+        .iter()
+        .filter(|path| path.as_str() == "/fapi/v2/account")
+        .count();
+    assert!(requests >= 3);
+    assert_eq!(snapshots, 1);
+}
+
+#[test]
+fn dropped_stream_keeps_listen_key() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .start()
+        .unwrap();
+    let listen_key_provider: FixedListenKeyProvider = FixedListenKeyProvider::default();
+    let deletes: Arc<AtomicUsize> = Arc::clone(&listen_key_provider.deletes);
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_api_auth(ApiAuth::new("key".to_string(), "secret".to_string()))
+            .with_listen_key_provider(listen_key_provider)
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config).start();
+
+    assert!(stream.events().next_event(Duration::from_secs(5)).is_some());
+    drop(stream);
+    // The listen key is shared by every consumer of the API key.
+    assert_eq!(deletes.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn start_does_not_wait_for_listen_key() {
+    let server: MockServer = MockServer::new()
+        .with_frame(ORDER_TRADE_UPDATE)
+        .start()
+        .unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_api_auth(ApiAuth::new("key".to_string(), "secret".to_string()))
+            .with_listen_key_provider(FixedListenKeyProvider {
+                delay: Duration::from_secs(1),
+                ..FixedListenKeyProvider::default()
+            })
+            .do_not_reconnect();
+    let started: Instant = Instant::now();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config).start();
+    assert!(started.elapsed() < Duration::from_millis(500));
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::OrderTradeUpdateEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+}
+
+#[test]
+fn connection_is_bound_to_local_address() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_local_address("127.0.0.1".parse().unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+}
+
+#[test]
+fn oversized_message_fails_connection() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .with_max_message_size(Some(16));
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::ReconnectEvent(trace)) => match trace.reason {
+            DisconnectReason::SocketError(err) => assert!(err.contains("Message too long")),
+            reason => panic!("unexpected {:?}", reason),
+        },
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+}
+
+#[test]
+fn ip_preference_selects_resolved_addresses() {
+    let server: MockServer = MockServer::new().with_frame(BOOK_TICKER).start().unwrap();
+    // The mock server only listens on IPv4, `localhost` may resolve to `::1` first.
+    let url: String = server.url().replace("127.0.0.1", "localhost");
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&url).unwrap())
+            .with_ip_preference(IpPreference::Ipv4Only)
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_book_ticker("btcusdt")
+        .start();
+
+    match stream.events().next_event(Duration::from_secs(5)) {
+        Some(Event::BookTickerEvent(_)) => {}
+        event => panic!("unexpected {:?}", event),
+    }
+}
@@ -0,0 +1,127 @@
+#![cfg(all(feature = "test-support", feature = "market-data"))]
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::mock_server::{MockServer, BOOK_TICKER};
+use binance_connect::futures_usd::multi_symbol::MultiSymbolStream;
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+const MINI_TICKERS: &str = r#"[{"e":"24hrMiniTicker","E":123456789,"s":"BTCUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"},{"e":"24hrMiniTicker","E":123456789,"s":"XRPUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"},{"e":"24hrMiniTicker","E":123456789,"s":"ETHUSDT","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"}]"#;
+
+/// Returns the book ticker of the symbol.
+fn book_ticker(symbol: &str) -> String {
+    BOOK_TICKER.replace("BTCUSDT", symbol)
+}
+
+/// Returns a stream of the server, subscribing to the all-market stream from two symbols on.
+fn stream(server: &MockServer, stream: MultiSymbolStream, symbols: &[&str]) -> FuturesUsdStream {
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect()
+            .with_all_market_threshold(2);
+    FuturesUsdStream::with_config(config)
+        .with_multi_symbol(stream, symbols)
+        .start()
+}
+
+#[test]
+fn all_market_stream_is_filtered_to_the_symbols() {
+    let server: MockServer = MockServer::new()
+        .with_frame(&book_ticker("XRPUSDT"))
+        .with_frame(&book_ticker("ETHUSDT"))
+        .with_frame(&book_ticker("BTCUSDT"))
+        .start()
+        .unwrap();
+    let stream: FuturesUsdStream = stream(
+        &server,
+        MultiSymbolStream::BookTicker,
+        &["btcusdt", "ethusdt"],
+    );
+
+    for symbol in ["ETHUSDT", "BTCUSDT"] {
+        match stream
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+        {
+            Event::BookTickerEvent(book_ticker) => assert_eq!(book_ticker.symbol, symbol),
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    stream.stop();
+    assert_eq!(server.paths(), vec!["/ws/!bookTicker"]);
+}
+
+#[test]
+fn all_market_array_keeps_the_symbols() {
+    let server: MockServer = MockServer::new().with_frame(MINI_TICKERS).start().unwrap();
+    let stream: FuturesUsdStream = stream(
+        &server,
+        MultiSymbolStream::MiniTicker,
+        &["btcusdt", "ethusdt"],
+    );
+
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::MiniTickersEvent(mini_tickers) => {
+            let symbols: Vec<&str> = mini_tickers
+                .data
+                .iter()
+                .map(|mini_ticker| mini_ticker.symbol.as_str())
+                .collect();
+            assert_eq!(symbols, ["BTCUSDT", "ETHUSDT"]);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+    assert_eq!(server.paths(), vec!["/ws/!miniTicker@arr"]);
+}
+
+#[test]
+fn streams_per_symbol_below_the_threshold() {
+    let server: MockServer = MockServer::new()
+        .with_frame(&book_ticker("XRPUSDT"))
+        .start()
+        .unwrap();
+    let stream: FuturesUsdStream = stream(&server, MultiSymbolStream::BookTicker, &["xrpusdt"]);
+
+    // Nothing is filtered without the all-market stream.
+    match stream
+        .consume()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+    {
+        Event::BookTickerEvent(book_ticker) => assert_eq!(book_ticker.symbol, "XRPUSDT"),
+        event => panic!("unexpected {:?}", event),
+    }
+    stream.stop();
+    assert_eq!(server.paths(), vec!["/ws/xrpusdt@bookTicker"]);
+}
+
+#[test]
+fn streams_without_all_market_stream_are_per_symbol() {
+    let server: MockServer = MockServer::new().start().unwrap();
+    let stream: FuturesUsdStream = stream(
+        &server,
+        MultiSymbolStream::AggTrade,
+        &["btcusdt", "ethusdt", "xrpusdt"],
+    );
+
+    // The server reads the subscription of the other symbols after the connection.
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while server.messages().is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    stream.stop();
+    let subscribed: String = format!("{:?}{:?}", server.paths(), server.messages());
+    assert_eq!(server.paths().len(), 1);
+    assert!(["btcusdt@aggTrade", "ethusdt@aggTrade", "xrpusdt@aggTrade"]
+        .iter()
+        .all(|stream| subscribed.contains(stream)));
+}
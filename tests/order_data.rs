@@ -0,0 +1,46 @@
+#![cfg(all(feature = "test-support", feature = "user-data"))]
+
+use binance_connect::futures_usd::enums::binance::{PriceMatch, StpMode};
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::response::OrderTradeUpdate;
+
+/// An `ORDER_TRADE_UPDATE` of 2020, before price protection and self-trade prevention.
+const ORDER_TRADE_UPDATE_2020: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"TRAILING_STOP_MARKET","f":"GTC","q":"0.001","p":"0","ap":"0","sp":"7103.04","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"TRAILING_STOP_MARKET","ps":"LONG","cp":false,"AP":"7476.89","cr":"5.0","rp":"0"}}"#;
+/// An `ORDER_TRADE_UPDATE` of 2022, with price protection but before self-trade prevention.
+const ORDER_TRADE_UPDATE_2022: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"pP":true,"si":0,"ss":0,"rp":"0"}}"#;
+/// An `ORDER_TRADE_UPDATE` of 2023 onwards, with self-trade prevention, price match and GTD.
+const ORDER_TRADE_UPDATE_2023: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTD","q":"0.001","p":"7103.04","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"9.91","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"EXPIRE_TAKER","pm":"OPPONENT","gtd":1568880000000}}"#;
+
+fn order_trade_update(json: &str) -> OrderTradeUpdate {
+    match Event::from_json(json).unwrap() {
+        Event::OrderTradeUpdateEvent(update) => update,
+        event => panic!("unexpected {:?}", event),
+    }
+}
+
+#[test]
+fn order_data_of_2020_is_deserialized() {
+    let order_data = order_trade_update(ORDER_TRADE_UPDATE_2020).order_data;
+    assert_eq!(order_data.activation_price, 7476.89);
+    assert!(!order_data.is_price_protection_enabled);
+    assert_eq!(order_data.stp_mode, StpMode::None);
+    assert_eq!(order_data.price_match_mode, PriceMatch::None);
+    assert_eq!(order_data.gtd_order_auto_cancel_time, 0);
+}
+
+#[test]
+fn order_data_of_2022_is_deserialized() {
+    let order_data = order_trade_update(ORDER_TRADE_UPDATE_2022).order_data;
+    assert!(order_data.is_price_protection_enabled);
+    assert_eq!(order_data.commission_asset, "");
+    assert_eq!(order_data.stp_mode, StpMode::None);
+    assert_eq!(order_data.gtd_order_auto_cancel_time, 0);
+}
+
+#[test]
+fn order_data_of_2023_is_deserialized() {
+    let order_data = order_trade_update(ORDER_TRADE_UPDATE_2023).order_data;
+    assert_eq!(order_data.stp_mode, StpMode::ExpireTaker);
+    assert_eq!(order_data.price_match_mode, PriceMatch::Opponent);
+    assert_eq!(order_data.gtd_order_auto_cancel_time, 1568880000000);
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "market-data")]
+
+#[cfg(feature = "test-support")]
+use std::time::Duration;
+
+#[cfg(feature = "test-support")]
+use binance_connect::futures_usd::endpoint::Endpoint;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::enums::events::EventType;
+#[cfg(feature = "test-support")]
+use binance_connect::futures_usd::mock_server::{MockServer, AGG_TRADE};
+use binance_connect::futures_usd::ordering::{OrderingGuard, Sequence, SequenceGenerator};
+use binance_connect::futures_usd::response::AggTrade;
+#[cfg(feature = "depth")]
+use binance_connect::futures_usd::response::BookDepth;
+#[cfg(feature = "test-support")]
+use binance_connect::futures_usd::stream::{FuturesUsdStream, FuturesWebSocketConfig};
+
+/// Small deterministic xorshift generator so the property tests are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn agg_trade(symbol: &str, agg_trade_id: u64) -> Event {
+    Event::AggTradeEvent(AggTrade {
+        event_type: EventType::AggTradeEventType,
+        event_time: 0,
+        symbol: symbol.to_string(),
+        agg_trade_id,
+        price: 1.0,
+        quantity: 1.0,
+        first_trade_id: agg_trade_id,
+        last_trade_id: agg_trade_id,
+        trade_time: 0,
+        buyer_is_market_maker: false,
+    })
+}
+
+#[test]
+fn sequence_is_strictly_monotonic() {
+    let mut generator: SequenceGenerator = SequenceGenerator::default();
+    assert_eq!(generator.last(), None);
+    let mut previous: Sequence = generator.next_sequence();
+    assert_eq!(previous, Sequence::FIRST);
+    for _ in 0..10_000 {
+        let sequence: Sequence = generator.next_sequence();
+        assert!(sequence > previous);
+        assert_eq!(sequence.value(), previous.value() + 1);
+        previous = sequence;
+    }
+    assert_eq!(generator.last(), Some(previous));
+}
+
+#[test]
+fn guard_accepts_any_non_decreasing_ids_per_stream() {
+    let mut rng: Rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..100 {
+        let mut guard: OrderingGuard = OrderingGuard::default();
+        let mut ids: [u64; 3] = [0; 3];
+        let symbols: [&str; 3] = ["BTCUSDT", "ETHUSDT", "BNBUSDT"];
+        for _ in 0..500 {
+            let index: usize = (rng.next() % 3) as usize;
+            ids[index] += rng.next() % 4;
+            assert!(guard.check(&agg_trade(symbols[index], ids[index])).is_ok());
+        }
+    }
+}
+
+#[test]
+fn guard_rejects_any_decreasing_id() {
+    let mut rng: Rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..1_000 {
+        let mut guard: OrderingGuard = OrderingGuard::default();
+        let first: u64 = rng.next() % 1_000_000 + 1;
+        let second: u64 = rng.next() % first;
+        assert!(guard.check(&agg_trade("BTCUSDT", first)).is_ok());
+        let violation = guard.check(&agg_trade("BTCUSDT", second)).unwrap_err();
+        assert_eq!(violation.previous_id, first);
+        assert_eq!(violation.received_id, second);
+        // Other streams are unaffected.
+        assert!(guard.check(&agg_trade("ETHUSDT", second)).is_ok());
+    }
+}
+
+#[cfg(feature = "depth")]
+#[test]
+fn guard_ignores_depth_updates() {
+    let book_depth = |final_update_id: i64| {
+        Event::BookDepthEvent(BookDepth {
+            event_type: EventType::BookDepthEventType,
+            event_time: 0,
+            transaction_time: 0,
+            symbol: "BTCUSDT".to_string(),
+            first_update_id: final_update_id,
+            final_update_id,
+            previous_final_update_id: final_update_id - 1,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        })
+    };
+    // A diff. update followed by the partial depth of an older update.
+    let mut guard: OrderingGuard = OrderingGuard::default();
+    assert!(guard.check(&book_depth(10)).is_ok());
+    assert!(guard.check(&book_depth(8)).is_ok());
+}
+
+#[cfg(feature = "test-support")]
+#[test]
+fn events_are_delivered_in_network_order() {
+    let ids: [u64; 5] = [3, 1, 2, 5, 4];
+    let mut server: MockServer = MockServer::new();
+    for id in ids {
+        server = server.with_frame(&AGG_TRADE.replace("5933014", &id.to_string()));
+    }
+    let server: MockServer = server.start().unwrap();
+    let config: FuturesWebSocketConfig =
+        FuturesWebSocketConfig::from_endpoint(Endpoint::custom(&server.url()).unwrap())
+            .do_not_reconnect();
+    let stream: FuturesUsdStream = FuturesUsdStream::with_config(config)
+        .with_agg_trade("btcusdt")
+        .start();
+
+    // The ids going backwards are only logged, the events are forwarded as received.
+    let receiver = stream.consume();
+    for id in ids {
+        match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            Event::AggTradeEvent(agg_trade) => assert_eq!(agg_trade.agg_trade_id, id),
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+    stream.stop();
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "user-data")]
+
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::binance::PositionSide;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::pnl::{Pnl, PnlTracker};
+
+/// Returns a `TRADE` order update of the fill.
+fn fill(side: &str, quantity: f64, price: f64, realized: f64, commission: (&str, f64)) -> Event {
+    let json: String = format!(
+        r#"{{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{{"s":"BTCUSDT","c":"TEST","S":"{}","o":"MARKET","f":"GTC","q":"{}","p":"0","ap":"{}","sp":"0","x":"TRADE","X":"FILLED","i":8886774,"l":"{}","z":"{}","L":"{}","N":"{}","n":"{}","T":1568879465650,"t":1,"b":"0","a":"0","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"MARKET","ps":"BOTH","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"{}","V":"NONE","pm":"NONE","gtd":0}}}}"#,
+        side, quantity, price, quantity, quantity, price, commission.0, commission.1, realized
+    );
+    deserialize_slice(json.as_bytes()).unwrap()
+}
+
+fn position(pnl_tracker: &PnlTracker) -> (f64, f64) {
+    let pnl: Pnl = pnl_tracker.pnl("btcusdt", PositionSide::Both).unwrap();
+    (pnl.position_amount, pnl.entry_price)
+}
+
+#[test]
+fn partial_close_keeps_the_entry_price() {
+    let pnl_tracker: PnlTracker = PnlTracker::new();
+    pnl_tracker.apply(&fill("BUY", 1.0, 100.0, 0.0, ("USDT", 0.1)));
+    pnl_tracker.apply(&fill("BUY", 1.0, 200.0, 0.0, ("USDT", 0.1)));
+    assert_eq!(position(&pnl_tracker), (2.0, 150.0));
+
+    pnl_tracker.apply(&fill("SELL", 0.5, 250.0, 50.0, ("USDT", 0.1)));
+    assert_eq!(position(&pnl_tracker), (1.5, 150.0));
+    pnl_tracker.apply(&fill("SELL", 1.5, 100.0, -75.0, ("USDT", 0.1)));
+    assert_eq!(position(&pnl_tracker), (0.0, 0.0));
+    assert_eq!(pnl_tracker.realized_pnl(Some("BTCUSDT")), -25.0);
+}
+
+#[test]
+fn flip_opens_the_remainder_at_the_fill_price() {
+    let pnl_tracker: PnlTracker = PnlTracker::new();
+    pnl_tracker.apply(&fill("BUY", 1.0, 100.0, 0.0, ("USDT", 0.1)));
+    pnl_tracker.apply(&fill("SELL", 3.0, 120.0, 20.0, ("USDT", 0.3)));
+    assert_eq!(position(&pnl_tracker), (-2.0, 120.0));
+
+    pnl_tracker.apply(&fill("SELL", 2.0, 90.0, 0.0, ("USDT", 0.2)));
+    assert_eq!(position(&pnl_tracker), (-4.0, 105.0));
+    pnl_tracker.apply(&fill("BUY", 5.0, 100.0, 20.0, ("USDT", 0.5)));
+    assert_eq!(position(&pnl_tracker), (1.0, 100.0));
+}
+
+#[test]
+fn commissions_are_kept_per_asset() {
+    let pnl_tracker: PnlTracker = PnlTracker::new();
+    pnl_tracker.apply(&fill("BUY", 1.0, 100.0, 0.0, ("USDT", 0.04)));
+    pnl_tracker.apply(&fill("BUY", 1.0, 100.0, 0.0, ("BNB", 0.0001)));
+    pnl_tracker.apply(&fill("SELL", 2.0, 100.0, 0.0, ("USDT", 0.08)));
+
+    let pnl: Pnl = pnl_tracker.pnl("BTCUSDT", PositionSide::Both).unwrap();
+    assert_eq!(pnl.commissions.len(), 2);
+    assert!((pnl.commissions["USDT"] - 0.12).abs() < 1e-9);
+    assert_eq!(pnl.commissions["BNB"], 0.0001);
+}
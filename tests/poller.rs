@@ -0,0 +1,43 @@
+#![cfg(feature = "native")]
+
+use std::sync::mpsc::{RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use binance_connect::futures_usd::poller::PollerScheduler;
+use binance_connect::futures_usd::rate_limit;
+
+#[test]
+fn scheduler_without_polls_disconnects() {
+    let scheduler: PollerScheduler = PollerScheduler::new(true).start();
+    assert_eq!(
+        scheduler
+            .consume()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap_err(),
+        RecvTimeoutError::Disconnected
+    );
+}
+
+#[test]
+fn slowdown_follows_headroom_without_due_polls() {
+    // Without weight budget the polls wait for the reset, no request is made.
+    rate_limit::set_weight_limit(0);
+    let scheduler: PollerScheduler = PollerScheduler::new(true)
+        .with_open_interest(&["btcusdt", "ethusdt"], Duration::from_secs(3600))
+        .with_max_slowdown(4)
+        .start();
+
+    let started: Instant = Instant::now();
+    while scheduler.slowdown() < 4 && started.elapsed() < Duration::from_secs(10) {
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(scheduler.slowdown(), 4);
+
+    // The thread is joined by stop, after which the receiver is disconnected.
+    scheduler.stop();
+    assert_eq!(
+        scheduler.consume().try_recv().unwrap_err(),
+        TryRecvError::Disconnected
+    );
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "market-data")]
+
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::quote_cache::{Quote, QuoteCache};
+
+fn book_ticker(symbol: &str, update_id: u64, bid: f64, ask: f64) -> Event {
+    let json: String = format!(
+        r#"{{"e":"bookTicker","u":{},"E":1568014460893,"T":1568014460891,"s":"{}","b":"{}","B":"1.5","a":"{}","A":"2.5"}}"#,
+        update_id, symbol, bid, ask
+    );
+    deserialize_slice(json.as_bytes()).unwrap()
+}
+
+#[test]
+fn quote_is_looked_up_by_symbol() {
+    let quote_cache: QuoteCache = QuoteCache::new();
+    assert_eq!(quote_cache.quote("btcusdt"), None);
+
+    quote_cache.apply(&book_ticker("BTCUSDT", 1, 25000.0, 25001.0));
+    quote_cache.apply(&book_ticker("ETHUSDT", 1, 1600.0, 1600.5));
+    let quote: Quote = quote_cache.quote("btcusdt").unwrap();
+    assert_eq!((quote.bid_price, quote.bid_quantity), (25000.0, 1.5));
+    assert_eq!((quote.ask_price, quote.ask_quantity), (25001.0, 2.5));
+    assert_eq!(quote.transaction_time, 1568014460891);
+    assert_eq!(quote_cache.best_bid("ETHUSDT"), Some(1600.0));
+    assert_eq!(quote_cache.best_ask("ETHUSDT"), Some(1600.5));
+    assert_eq!(quote_cache.mid("ETHUSDT"), Some(1600.25));
+
+    let mut symbols: Vec<String> = quote_cache.symbols();
+    symbols.sort();
+    assert_eq!(symbols, vec!["BTCUSDT", "ETHUSDT"]);
+}
+
+#[test]
+fn newer_update_replaces_the_quote() {
+    let quote_cache: QuoteCache = QuoteCache::new();
+    quote_cache.apply(&book_ticker("BTCUSDT", 1, 25000.0, 25001.0));
+    quote_cache.apply(&book_ticker("BTCUSDT", 2, 25002.0, 25003.0));
+    assert_eq!(quote_cache.quote("BTCUSDT").unwrap().update_id, 2);
+    assert_eq!(quote_cache.best_bid("BTCUSDT"), Some(25002.0));
+}
+
+#[test]
+fn stale_update_is_ignored() {
+    let quote_cache: QuoteCache = QuoteCache::new();
+    quote_cache.apply(&book_ticker("BTCUSDT", 2, 25002.0, 25003.0));
+    quote_cache.apply(&book_ticker("BTCUSDT", 1, 25000.0, 25001.0));
+    assert_eq!(quote_cache.quote("BTCUSDT").unwrap().update_id, 2);
+    assert_eq!(quote_cache.best_bid("BTCUSDT"), Some(25002.0));
+}
+
+#[test]
+fn combined_book_tickers_are_applied() {
+    let quote_cache: QuoteCache = QuoteCache::new();
+    let json: &str = r#"[{"e":"bookTicker","u":1,"E":1,"T":1,"s":"BTCUSDT","b":"1","B":"1","a":"2","A":"1"},{"e":"bookTicker","u":1,"E":1,"T":1,"s":"ETHUSDT","b":"3","B":"1","a":"4","A":"1"}]"#;
+    quote_cache.apply(&deserialize_slice(json.as_bytes()).unwrap());
+    assert_eq!(quote_cache.mid("BTCUSDT"), Some(1.5));
+    assert_eq!(quote_cache.mid("ETHUSDT"), Some(3.5));
+}
@@ -0,0 +1,55 @@
+use binance_connect::futures_usd::enums::binance::{
+    MarginType, OrderStatus, OrderType, PositionSide, Side, WorkingType,
+};
+use binance_connect::futures_usd::response::{OpenOrder, PositionRisk};
+
+/// A `/fapi/v2/positionRisk` response with a cross and an isolated position.
+const POSITION_RISK: &str = r#"[{"entryPrice":"6563.66500","breakEvenPrice":"6565.97500","marginType":"cross","isAutoAddMargin":"false","isolatedMargin":"0.00000000","leverage":"10","liquidationPrice":"2379.51","markPrice":"6679.50671178","maxNotionalValue":"20000000","positionAmt":"0.020","notional":"133.59013423","isolatedWallet":"0","symbol":"BTCUSDT","unRealizedProfit":"2.31683423","positionSide":"BOTH","updateTime":1625474304765},
+{"entryPrice":"0.00000","breakEvenPrice":"0.0","marginType":"isolated","isAutoAddMargin":"true","isolatedMargin":"0.00000000","leverage":"20","liquidationPrice":"0","markPrice":"1876.51000000","maxNotionalValue":"5000000","positionAmt":"0.000","notional":"0","isolatedWallet":"0","symbol":"ETHUSDT","unRealizedProfit":"0.00000000","positionSide":"LONG","updateTime":0}]"#;
+
+/// A `/fapi/v1/openOrders` response with a trailing stop order.
+const OPEN_ORDERS: &str = r#"[{"avgPrice":"0.00000","clientOrderId":"abc","cumQuote":"0","executedQty":"0","orderId":1917641,"origQty":"0.40","origType":"TRAILING_STOP_MARKET","price":"0","reduceOnly":false,"side":"BUY","positionSide":"SHORT","status":"NEW","stopPrice":"9300","closePosition":false,"symbol":"BTCUSDT","time":1579276756075,"timeInForce":"GTC","type":"TRAILING_STOP_MARKET","activatePrice":"9020","priceRate":"0.3","updateTime":1579276756075,"workingType":"CONTRACT_PRICE","priceProtect":false,"priceMatch":"NONE","selfTradePreventionMode":"NONE","goodTillDate":0}]"#;
+
+#[test]
+fn position_risk_is_deserialized() {
+    let positions: Vec<PositionRisk> = serde_json::from_str(POSITION_RISK).unwrap();
+    assert_eq!(positions.len(), 2);
+
+    let cross: &PositionRisk = &positions[0];
+    assert_eq!(cross.symbol, "BTCUSDT");
+    assert_eq!(cross.margin_type, MarginType::Crossed);
+    assert_eq!(cross.position_amount, 0.02);
+    assert_eq!(cross.leverage, 10);
+    assert!(!cross.is_auto_add_margin);
+    assert_eq!(cross.position_side, PositionSide::Both);
+    assert_eq!(cross.update_time, 1625474304765);
+
+    let isolated: &PositionRisk = &positions[1];
+    assert_eq!(isolated.margin_type, MarginType::Isolated);
+    assert!(isolated.is_auto_add_margin);
+    assert_eq!(isolated.position_side, PositionSide::Long);
+}
+
+#[test]
+fn open_orders_are_deserialized() {
+    let open_orders: Vec<OpenOrder> = serde_json::from_str(OPEN_ORDERS).unwrap();
+    assert_eq!(open_orders.len(), 1);
+
+    let open_order: &OpenOrder = &open_orders[0];
+    assert_eq!(open_order.order_id, 1917641);
+    assert_eq!(open_order.side, Side::Buy);
+    assert_eq!(open_order.order_type, OrderType::TrailingStopMarket);
+    assert_eq!(open_order.order_status, OrderStatus::New);
+    assert_eq!(open_order.original_quantity, 0.4);
+    assert_eq!(open_order.activation_price, 9020.0);
+    assert_eq!(open_order.callback_rate, 0.3);
+    assert_eq!(
+        open_order.stop_price_working_type,
+        WorkingType::ContractPrice
+    );
+
+    // Orders other than trailing stops come without activation price and callback rate.
+    let json: String = OPEN_ORDERS.replace(r#""activatePrice":"9020","priceRate":"0.3","#, "");
+    let open_orders: Vec<OpenOrder> = serde_json::from_str(&json).unwrap();
+    assert_eq!(open_orders[0].activation_price, 0.0);
+}
@@ -0,0 +1,68 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use binance_connect::futures_usd::sequence::{SequenceWal, Sequenced};
+
+fn wal_path(name: &str) -> PathBuf {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "binance_connect_{}_{}.wal",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn sequence_continues_after_crash() {
+    let path: PathBuf = wal_path("crash");
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap().with_block_size(10);
+    for expected in 0..15 {
+        assert_eq!(sequence_wal.next_sequence().unwrap(), expected);
+    }
+    assert_eq!(sequence_wal.last(), Some(14));
+    // The process dies without closing, the reserved block [10, 20) is skipped.
+    drop(sequence_wal);
+
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap().with_block_size(10);
+    assert_eq!(sequence_wal.last(), None);
+    let sequenced: Sequenced<&str> = sequence_wal.stamp("event").unwrap();
+    assert_eq!(sequenced.sequence, 20);
+    assert_eq!(sequence_wal.last(), Some(20));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn torn_record_is_ignored() {
+    let path: PathBuf = wal_path("torn");
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap().with_block_size(10);
+    sequence_wal.next_sequence().unwrap();
+    drop(sequence_wal);
+    // A crash during the write of the next reservation leaves a partial record.
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, "2").unwrap();
+    drop(file);
+
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap().with_block_size(10);
+    assert_eq!(sequence_wal.next_sequence().unwrap(), 10);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn wal_is_compacted() {
+    let path: PathBuf = wal_path("compact");
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap().with_block_size(1);
+    for expected in 0..1025 {
+        assert_eq!(sequence_wal.next_sequence().unwrap(), expected);
+    }
+    // The 1025th reservation replaced the 1024 records with a single one.
+    assert_eq!(fs::read_to_string(&path).unwrap(), "1025\n");
+
+    sequence_wal.next_sequence().unwrap();
+    drop(sequence_wal);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    let mut sequence_wal: SequenceWal = SequenceWal::open(&path).unwrap();
+    assert_eq!(sequence_wal.next_sequence().unwrap(), 1026);
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "native")]
+
+use std::env;
+
+use binance_connect::error::BinanceConnectError;
+use binance_connect::futures_usd::settings::ConfigSettings;
+use binance_connect::futures_usd::stream::FuturesWebSocketConfig;
+
+#[test]
+fn settings_are_read_from_env() {
+    env::set_var("BINANCE_MAX_RECONNECT_ATTEMPTS", "7");
+    env::set_var(
+        "BINANCE_FALLBACK_URLS",
+        "wss://fstream1.example.com/, wss://fstream2.example.com/",
+    );
+    let settings: ConfigSettings = ConfigSettings::from_env().unwrap();
+    assert_eq!(settings.max_reconnect_attempts, Some(7));
+    assert_eq!(settings.fallback_urls.len(), 2);
+    assert!(FuturesWebSocketConfig::from_env().is_ok());
+
+    env::set_var("BINANCE_MAX_RECONNECT_ATTEMPTS", "seven");
+    match ConfigSettings::from_env() {
+        Err(BinanceConnectError::InvalidConfig { key, .. }) => {
+            assert_eq!(key, "BINANCE_MAX_RECONNECT_ATTEMPTS")
+        }
+        result => panic!("unexpected {:?}", result),
+    }
+    env::remove_var("BINANCE_MAX_RECONNECT_ATTEMPTS");
+    env::remove_var("BINANCE_FALLBACK_URLS");
+}
+
+#[test]
+fn api_key_requires_secret() {
+    let settings: ConfigSettings = ConfigSettings {
+        api_key: Some("key".to_string()),
+        ..ConfigSettings::default()
+    };
+    match settings.apply(FuturesWebSocketConfig::default()) {
+        Err(BinanceConnectError::InvalidConfig { key, .. }) => assert_eq!(key, "api_secret"),
+        result => panic!("unexpected {:?}", result),
+    }
+}
+
+#[cfg(feature = "config-file")]
+#[test]
+fn settings_are_parsed_from_toml() {
+    let settings: ConfigSettings = ConfigSettings::from_toml_str(
+        r#"
+        endpoint = "testnet"
+        reconnect_on_json_errors = true
+        stale_timeout_secs = 300
+        "#,
+    )
+    .unwrap();
+    assert_eq!(settings.endpoint, Some("testnet".to_string()));
+    assert_eq!(settings.reconnect_on_json_errors, Some(true));
+    assert_eq!(settings.stale_timeout_secs, Some(300));
+    assert!(settings.apply(FuturesWebSocketConfig::default()).is_ok());
+
+    assert!(ConfigSettings::from_toml_str("unknown_setting = 1").is_err());
+}
@@ -0,0 +1,81 @@
+#![cfg(all(feature = "native", feature = "market-data"))]
+
+use std::time::Duration;
+
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::skew::{SkewAlert, SkewMonitor, SkewStats, SkewThresholds};
+
+/// Returns a book ticker of the symbol with the event time.
+fn book_ticker(symbol: &str, event_time: u64) -> Event {
+    let json: String = format!(
+        r#"{{"e":"bookTicker","u":400900217,"E":{},"T":{},"s":"{}","b":"25.35","B":"31.21","a":"25.36","A":"40.66"}}"#,
+        event_time, event_time, symbol
+    );
+    deserialize_slice(json.as_bytes()).unwrap()
+}
+
+fn thresholds(max_skew_ms: u64, max_jitter_ms: u64) -> SkewThresholds {
+    SkewThresholds {
+        max_skew: Duration::from_millis(max_skew_ms),
+        max_jitter: Duration::from_millis(max_jitter_ms),
+    }
+}
+
+#[test]
+fn skew_is_estimated_per_stream() {
+    let mut skew_monitor: SkewMonitor = SkewMonitor::new(SkewThresholds::default());
+    assert!(skew_monitor
+        .observe(&book_ticker("BTCUSDT", 1_000), 1_010)
+        .is_empty());
+    skew_monitor.observe(&book_ticker("BTCUSDT", 2_000), 2_030);
+    skew_monitor.observe(&book_ticker("ETHUSDT", 2_000), 2_004);
+
+    let stats: &SkewStats = &skew_monitor.stats()["BTCUSDT@bookTicker"];
+    assert_eq!(stats.samples, 2);
+    assert_eq!(stats.last_skew_ms, 30);
+    // The mean starts at the first skew and moves a tenth towards every next one.
+    assert!((stats.mean_skew_ms - 12.0).abs() < 1e-9);
+    assert!((stats.jitter_ms - 2.0).abs() < 1e-9);
+    // 10ms falls into the `<= 10` bucket, 30ms into the `<= 50` bucket.
+    assert_eq!(stats.histogram.counts[3], 1);
+    assert_eq!(stats.histogram.counts[5], 1);
+    assert_eq!(skew_monitor.stats()["ETHUSDT@bookTicker"].last_skew_ms, 4);
+}
+
+#[test]
+fn alert_is_raised_once_per_excess() {
+    let mut skew_monitor: SkewMonitor = SkewMonitor::new(thresholds(100, 1_000));
+    let alerts: Vec<SkewAlert> = skew_monitor.observe(&book_ticker("BTCUSDT", 0), 500);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].stream, "BTCUSDT@bookTicker");
+    assert_eq!(alerts[0].skew_ms, 500.0);
+    // Still above the threshold, no second alert.
+    assert!(skew_monitor
+        .observe(&book_ticker("BTCUSDT", 1_000), 1_500)
+        .is_empty());
+
+    // Back below the threshold, then above it again.
+    let mut time: u64 = 2_000;
+    while skew_monitor.stats()["BTCUSDT@bookTicker"].mean_skew_ms > 100.0 {
+        assert!(skew_monitor
+            .observe(&book_ticker("BTCUSDT", time), time)
+            .is_empty());
+        time += 1_000;
+    }
+    let alerts: Vec<SkewAlert> = skew_monitor.observe(&book_ticker("BTCUSDT", time), time + 5_000);
+    assert_eq!(alerts.len(), 1);
+}
+
+#[test]
+fn jitter_threshold_raises_alert() {
+    let mut skew_monitor: SkewMonitor = SkewMonitor::new(thresholds(10_000, 20));
+    assert!(skew_monitor
+        .observe(&book_ticker("BTCUSDT", 0), 0)
+        .is_empty());
+    // A single 500ms outlier moves the mean by 50ms and the jitter by 50ms.
+    let alerts: Vec<SkewAlert> = skew_monitor.observe(&book_ticker("BTCUSDT", 1_000), 1_500);
+    assert_eq!(alerts.len(), 1);
+    assert!((alerts[0].jitter_ms - 50.0).abs() < 1e-9);
+    assert!((alerts[0].skew_ms - 50.0).abs() < 1e-9);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "user-data")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use binance_connect::error::BinanceConnectError;
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::binance::PositionSide;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::state_store::StateStore;
+
+const ACCOUNT_UPDATE: &str = r#"{"e":"ACCOUNT_UPDATE","E":1564745798939,"T":1564745798938,"a":{"m":"ORDER","B":[{"a":"USDT","wb":"122624.12345678","cw":"100.12345678","bc":"50.12345678"}],"P":[{"s":"BTCUSDT","pa":"0.5","ep":"30000.0","bep":"30010.0","cr":"200","up":"12.5","mt":"isolated","iw":"1500.0","ps":"LONG"}]}}"#;
+const ORDER_TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"LIMIT","f":"GTC","q":"0.5","p":"35000","ap":"0","sp":"0","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","N":"USDT","n":"0","T":1568879465650,"t":0,"b":"0","a":"17500","m":false,"R":true,"wt":"CONTRACT_PRICE","ot":"LIMIT","ps":"LONG","cp":false,"AP":"0","cr":"0","pP":false,"si":0,"ss":0,"rp":"0","V":"NONE","pm":"NONE","gtd":0}}"#;
+const ACCOUNT_CONFIG_UPDATE: &str = r#"{"e":"ACCOUNT_CONFIG_UPDATE","E":1611646737479,"T":1611646737476,"ac":{"s":"BTCUSDT","l":25},"ai":{"j":true}}"#;
+
+fn state_path(name: &str) -> PathBuf {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "binance_connect_{}_{}.json",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn saved_state_loads_unchanged() {
+    let path: PathBuf = state_path("state_round_trip");
+    let state_store: StateStore = StateStore::new();
+    for json in [ACCOUNT_UPDATE, ORDER_TRADE_UPDATE, ACCOUNT_CONFIG_UPDATE] {
+        let event: Event = deserialize_slice(json.as_bytes()).unwrap();
+        state_store.apply(&event);
+    }
+    state_store.save(&path).unwrap();
+
+    let loaded: StateStore = StateStore::load(&path).unwrap();
+    assert_eq!(loaded.balances(), state_store.balances());
+    assert_eq!(
+        loaded.position("BTCUSDT", PositionSide::Long),
+        state_store.position("BTCUSDT", PositionSide::Long)
+    );
+    assert_eq!(loaded.open_order(8886774), state_store.open_order(8886774));
+    assert_eq!(loaded.leverage("BTCUSDT"), Some(25));
+    assert_eq!(loaded.multi_assets_mode(), Some(true));
+
+    // Sanity check that the events were applied at all.
+    assert_eq!(
+        loaded.balance("USDT").unwrap().wallet_balance,
+        122624.12345678
+    );
+    assert_eq!(
+        loaded
+            .position("BTCUSDT", PositionSide::Long)
+            .unwrap()
+            .position_amount,
+        0.5
+    );
+    assert!(loaded.open_order(8886774).is_some());
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn corrupt_state_is_an_error() {
+    let path: PathBuf = state_path("state_corrupt");
+    fs::write(&path, r#"{"balances":[{"asset":"USDT","wallet_b"#).unwrap();
+    assert!(matches!(
+        StateStore::load(&path),
+        Err(BinanceConnectError::JsonError { .. })
+    ));
+
+    let _ = fs::remove_file(&path);
+    assert!(matches!(
+        StateStore::load(&path),
+        Err(BinanceConnectError::IoError(_))
+    ));
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "market-data")]
+
+use binance_connect::futures_usd::deserializer::deserialize_slice;
+use binance_connect::futures_usd::enums::events::Event;
+use binance_connect::futures_usd::ticker_cache::TickerCache;
+
+fn ticker(symbol: &str, event_time: u64, last_price: f64) -> Event {
+    let json: String = format!(
+        r#"{{"e":"24hrTicker","E":{},"s":"{}","p":"0.0015","P":"250.00","w":"0.0018","c":"{}","Q":"10","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18","O":0,"C":86400000,"F":0,"L":18150,"n":18151}}"#,
+        event_time, symbol, last_price
+    );
+    deserialize_slice(json.as_bytes()).unwrap()
+}
+
+fn mini_ticker(symbol: &str, event_time: u64, close_price: f64) -> Event {
+    let json: String = format!(
+        r#"{{"e":"24hrMiniTicker","E":{},"s":"{}","c":"{}","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"}}"#,
+        event_time, symbol, close_price
+    );
+    deserialize_slice(json.as_bytes()).unwrap()
+}
+
+#[test]
+fn ticker_is_looked_up_by_symbol() {
+    let ticker_cache: TickerCache = TickerCache::new();
+    assert!(ticker_cache.ticker("btcusdt").is_none());
+
+    ticker_cache.apply(&ticker("BTCUSDT", 1, 25000.0));
+    ticker_cache.apply(&ticker("ETHUSDT", 1, 1600.0));
+    assert_eq!(ticker_cache.ticker("btcusdt").unwrap().last_price, 25000.0);
+    assert_eq!(ticker_cache.ticker("ETHUSDT").unwrap().last_price, 1600.0);
+    assert_eq!(ticker_cache.snapshot().len(), 2);
+    assert!(ticker_cache.mini_ticker("BTCUSDT").is_none());
+}
+
+#[test]
+fn mini_ticker_is_looked_up_by_symbol() {
+    let ticker_cache: TickerCache = TickerCache::new();
+    ticker_cache.apply(&mini_ticker("BTCUSDT", 1, 25000.0));
+    assert_eq!(
+        ticker_cache.mini_ticker("btcusdt").unwrap().close_price,
+        25000.0
+    );
+    assert_eq!(ticker_cache.mini_snapshot().len(), 1);
+    assert!(ticker_cache.ticker("BTCUSDT").is_none());
+}
+
+#[test]
+fn older_ticker_is_ignored() {
+    let ticker_cache: TickerCache = TickerCache::new();
+    ticker_cache.apply(&ticker("BTCUSDT", 2, 25002.0));
+    ticker_cache.apply(&ticker("BTCUSDT", 1, 25001.0));
+    assert_eq!(ticker_cache.ticker("BTCUSDT").unwrap().last_price, 25002.0);
+    ticker_cache.apply(&ticker("BTCUSDT", 3, 25003.0));
+    assert_eq!(ticker_cache.ticker("BTCUSDT").unwrap().last_price, 25003.0);
+}
+
+#[test]
+fn all_market_mini_tickers_are_applied() {
+    let ticker_cache: TickerCache = TickerCache::new();
+    let json: &str = r#"[{"e":"24hrMiniTicker","E":1,"s":"BTCUSDT","c":"1","o":"1","h":"1","l":"1","v":"1","q":"1"},{"e":"24hrMiniTicker","E":1,"s":"ETHUSDT","c":"2","o":"1","h":"2","l":"1","v":"1","q":"1"}]"#;
+    ticker_cache.apply(&deserialize_slice(json.as_bytes()).unwrap());
+    assert_eq!(
+        ticker_cache.mini_ticker("BTCUSDT").unwrap().close_price,
+        1.0
+    );
+    assert_eq!(
+        ticker_cache.mini_ticker("ETHUSDT").unwrap().close_price,
+        2.0
+    );
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "test-support")]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use binance_connect::futures_usd::mock_server::MockServer;
+use binance_connect::futures_usd::time_sync::{self, server_now, TimeSample};
+
+/// The clock offset of the mock server.
+const OFFSET_MS: i64 = 60_000;
+
+fn local_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+#[test]
+fn offset_is_estimated_from_the_server_time() {
+    let server_time: i64 = local_now() + OFFSET_MS;
+    let server: MockServer = MockServer::new()
+        .with_http_response(
+            "/fapi/v1/time",
+            &format!(r#"{{"serverTime":{}}}"#, server_time),
+        )
+        .start()
+        .unwrap();
+
+    let time_sample: TimeSample = time_sync::sync_with_base_url(&server.http_url()).unwrap();
+    assert_eq!(time_sample.server_time, server_time);
+    // The canned server time does not advance, so the estimate falls short by the time elapsed.
+    assert!(time_sample.offset_ms <= OFFSET_MS);
+    assert!(time_sample.offset_ms > OFFSET_MS - 1_000);
+    // Every sample is a request, the one with the lowest round-trip is used.
+    assert_eq!(server.paths(), vec!["/fapi/v1/time"; 3]);
+    assert_eq!(time_sync::clock_offset_ms(), time_sample.offset_ms);
+
+    let estimated: i64 = server_now() as i64 - local_now();
+    assert!((estimated - time_sample.offset_ms).abs() <= 5);
+}
+
+#[test]
+fn failed_sync_is_an_error() {
+    let server: MockServer = MockServer::new()
+        .with_http_response("/fapi/v1/ping", "{}")
+        .start()
+        .unwrap();
+    assert!(time_sync::sync_with_base_url(&server.http_url()).is_err());
+    assert_eq!(server.paths().len(), 3);
+}